@@ -9,12 +9,19 @@ mod test;
 use stellai_lib::{
     audit::{create_audit_log, OperationType},
     errors::ContractError,
-    Agent, RoyaltyInfo, ADMIN_KEY, AGENT_COUNTER_KEY, APPROVED_MINTERS_KEY, MAX_ROYALTY_FEE,
+    Agent, RoyaltyInfo, ADMIN_KEY, AGENT_COUNTER_KEY, APPROVED_ESCROWS_KEY, APPROVED_MINTERS_KEY,
+    DEFAULT_MAX_ROYALTY_BPS, MAX_ROYALTY_FEE,
 };
 
 // Maximum lengths for validation
 const MAX_STRING_LENGTH: usize = 256;
 const MAX_CAPABILITIES: usize = 10;
+const MAX_BATCH_TRANSFER_SIZE: u32 = 50;
+const EXECUTION_HUB_KEY: &str = "execution_hub";
+const EVOLUTION_CONTRACT_KEY: &str = "evolution_contract";
+const MAX_ROYALTY_KEY: &str = "max_royalty";
+const CAPABILITY_REGISTRY_KEY: &str = "capabilities";
+const MAX_METADATA_HISTORY: u32 = 50;
 
 // ============================================================================
 // Event types
@@ -28,6 +35,14 @@ pub enum AgentEvent {
     LeaseStarted,
     LeaseEnded,
     BatchMintCompleted,
+    EscrowLocked,
+    EscrowReleased,
+    BatchTransferCompleted,
+    ReputationUpdated,
+    AgentBurned,
+    RoyaltySet,
+    MinterRemoved,
+    AgentEvolved,
 }
 
 // ============================================================================
@@ -41,7 +56,32 @@ pub struct AgentMintData {
     pub model_hash: String,
     pub metadata_cid: String,
     pub capabilities: Vec<String>,
-    pub royalty: Option<RoyaltyInfo>,
+    pub royalty_recipient: Option<Address>,
+    pub royalty_fee: Option<u32>,
+}
+
+// ============================================================================
+// Combined Agent View
+// ============================================================================
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct AgentFullView {
+    pub agent: Agent,
+    // Royalty is flattened: contract types cannot nest an optional struct
+    pub royalty_recipient: Option<Address>,
+    pub royalty_fee: Option<u32>,
+    pub is_leased: bool,
+}
+
+// ============================================================================
+// Metadata History
+// ============================================================================
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct MetadataChange {
+    pub field_changed: String,
+    pub timestamp: u64,
+    pub nonce: u64,
 }
 
 #[contract]
@@ -99,531 +139,709 @@ impl AgentNFT {
         Ok(())
     }
 
-    /// Helper to get storage key for an agent
-    fn get_agent_key(env: &Env, agent_id: u64) -> (Symbol, u64) {
-        (Symbol::new(env, "agent"), agent_id)
-    }
+    /// Remove an approved minter (admin only)
+    ///
+    /// # Errors
+    /// - ContractError::Unauthorized if caller is not admin
+    /// - ContractError::InvalidInput if the address is not an approved minter
+    pub fn remove_approved_minter(
+        env: Env,
+        admin: Address,
+        minter: Address,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin)?;
 
-    /// Helper to get storage key for agent lease status
-    fn get_agent_lease_key(env: &Env, agent_id: u64) -> (Symbol, u64) {
-        (Symbol::new(env, "lease"), agent_id)
-    }
+        let mut approved_minters = Self::get_approved_minters(env.clone());
+        if !approved_minters.contains(&minter) {
+            return Err(ContractError::InvalidInput);
+        }
+        // add_approved_minter does not dedupe, so drop every occurrence
+        while let Some(index) = approved_minters.first_index_of(&minter) {
+            approved_minters.remove(index);
+        }
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, APPROVED_MINTERS_KEY), &approved_minters);
 
-    /// Helper to get storage key for agent royalty info
-    fn get_royalty_key(env: &Env, agent_id: u64) -> (Symbol, u64) {
-        (Symbol::new(env, "royalty"), agent_id)
+        env.events().publish(
+            (Symbol::new(&env, "agent_nft"), AgentEvent::MinterRemoved),
+            minter,
+        );
+
+        Ok(())
     }
 
-    /// Validate royalty fee is within acceptable bounds
-    fn validate_royalty_fee(fee: u32) -> Result<(), ContractError> {
-        if fee > MAX_ROYALTY_FEE {
-            return Err(ContractError::InvalidRoyaltyFee);
-        }
+    /// Set how many agents a minter may mint in total (admin only)
+    ///
+    /// A quota of 0 means unlimited.
+    pub fn set_minter_quota(
+        env: Env,
+        admin: Address,
+        minter: Address,
+        quota: u64,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin)?;
+
+        env.storage()
+            .instance()
+            .set(&Self::get_minter_quota_key(&env, &minter), &quota);
+
         Ok(())
     }
 
-    /// Verify caller is admin
-    fn verify_admin(env: &Env, caller: &Address) -> Result<(), ContractError> {
-        let admin: Address = env
+    /// Get a minter's (used, quota); a quota of 0 means unlimited
+    pub fn get_minter_usage(env: Env, minter: Address) -> (u64, u64) {
+        let used: u64 = env
             .storage()
             .instance()
-            .get(&Symbol::new(env, ADMIN_KEY))
-            .ok_or(ContractError::Unauthorized)?;
+            .get(&Self::get_minter_usage_key(&env, &minter))
+            .unwrap_or(0);
+        let quota: u64 = env
+            .storage()
+            .instance()
+            .get(&Self::get_minter_quota_key(&env, &minter))
+            .unwrap_or(0);
+        (used, quota)
+    }
 
-        if caller != &admin {
-            // Log the authorization failure
-            let before_state = String::from_str(&env, "{}");
-            let after_state = String::from_str(&env, "{}");
-            let tx_hash = String::from_str(&env, "verify_admin_fail"); // Placeholder
-            let description = Some(String::from_str(&env, "Admin verification failed."));
+    /// Get the current list of approved minters
+    pub fn get_approved_minters(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&Symbol::new(&env, APPROVED_MINTERS_KEY))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
 
-            let _ = create_audit_log(
-                &env,
-                caller.clone(),
-                OperationType::AuthFailure,
-                before_state,
-                after_state,
-                tx_hash,
-                description,
-            );
-            return Err(ContractError::Unauthorized);
+    /// Register a canonical capability name (admin only)
+    ///
+    /// Once the registry is non-empty, agents may only carry registered
+    /// capabilities.
+    ///
+    /// # Errors
+    /// - ContractError::Unauthorized if caller is not admin
+    /// - ContractError::InvalidInput if the name is empty or too long
+    /// - ContractError::AlreadyExists if the name is already registered
+    pub fn register_capability(
+        env: Env,
+        admin: Address,
+        name: String,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin)?;
+
+        if name.is_empty() || name.len() > MAX_STRING_LENGTH as u32 {
+            return Err(ContractError::InvalidInput);
         }
+
+        let mut registry = Self::get_capabilities(env.clone());
+        if registry.contains(&name) {
+            return Err(ContractError::AlreadyExists);
+        }
+        registry.push_back(name);
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, CAPABILITY_REGISTRY_KEY), &registry);
+
         Ok(())
     }
 
-    /// Verify caller is admin or approved minter
-    fn verify_minter(env: &Env, caller: &Address) -> Result<(), ContractError> {
-        // Check if admin
-        if let Some(admin) = env
-            .storage()
+    /// Get the registered canonical capability names
+    pub fn get_capabilities(env: Env) -> Vec<String> {
+        env.storage()
             .instance()
-            .get::<_, Address>(&Symbol::new(env, ADMIN_KEY))
-        {
-            if caller == &admin {
-                return Ok(());
-            }
-        }
+            .get(&Symbol::new(&env, CAPABILITY_REGISTRY_KEY))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
 
-        // Check if approved minter
-        let approved_minters: Vec<Address> = env
+    /// Add an approved escrow contract (admin only)
+    ///
+    /// Approved escrow contracts may lock agents via `lock_in_escrow`, e.g. the
+    /// marketplace while a multi-sig sale is pending.
+    pub fn add_approved_escrow(
+        env: Env,
+        admin: Address,
+        escrow: Address,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin)?;
+
+        let mut approved_escrows: Vec<Address> = env
             .storage()
             .instance()
-            .get(&Symbol::new(env, APPROVED_MINTERS_KEY))
-            .unwrap_or_else(|| Vec::new(env));
+            .get(&Symbol::new(&env, APPROVED_ESCROWS_KEY))
+            .unwrap_or_else(|| Vec::new(&env));
 
-        for i in 0..approved_minters.len() {
-            if let Some(minter) = approved_minters.get(i) {
-                if &minter == caller {
-                    return Ok(());
-                }
-            }
+        if !approved_escrows.contains(&escrow) {
+            approved_escrows.push_back(escrow);
         }
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, APPROVED_ESCROWS_KEY), &approved_escrows);
 
-        // If we reach here, no match was found.
-        let before_state = String::from_str(&env, "{}");
-        let after_state = String::from_str(&env, "{}");
-        let tx_hash = String::from_str(&env, "verify_minter_fail"); // Placeholder
-        let description = Some(String::from_str(&env, "Minter verification failed."));
-
-        let _ = create_audit_log(
-            &env,
-            caller.clone(),
-            OperationType::AuthFailure,
-            before_state,
-            after_state,
-            tx_hash,
-            description,
-        );
-        Err(ContractError::Unauthorized)
+        Ok(())
     }
 
-    /// Safe addition with overflow checks
-    fn safe_add(a: u64, b: u64) -> Result<u64, ContractError> {
-        a.checked_add(b).ok_or(ContractError::OverflowError)
-    }
+    /// Register the ExecutionHub allowed to update agent reputation (admin only)
+    pub fn set_execution_hub(
+        env: Env,
+        admin: Address,
+        execution_hub: Address,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin)?;
 
-    /// Check if agent is currently leased
-    fn is_agent_leased(env: &Env, agent_id: u64) -> bool {
-        let lease_key = Self::get_agent_lease_key(env, agent_id);
         env.storage()
             .instance()
-            .get::<_, bool>(&lease_key)
-            .unwrap_or(false)
-    }
+            .set(&Symbol::new(&env, EXECUTION_HUB_KEY), &execution_hub);
 
-    /// Set agent lease status
-    fn set_agent_lease_status(env: &Env, agent_id: u64, is_leased: bool) {
-        let lease_key = Self::get_agent_lease_key(env, agent_id);
-        env.storage().instance().set(&lease_key, &is_leased);
+        Ok(())
     }
 
-    /// Check if agent ID already exists
-    fn agent_exists(env: &Env, agent_id: u64) -> bool {
-        let key = Self::get_agent_key(env, agent_id);
-        env.storage().instance().has(&key)
+    /// Register the Evolution contract allowed to apply agent upgrades (admin only)
+    pub fn set_evolution_contract(
+        env: Env,
+        admin: Address,
+        evolution: Address,
+    ) -> Result<(), ContractError> {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin)?;
+
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, EVOLUTION_CONTRACT_KEY), &evolution);
+
+        Ok(())
     }
 
-    /// Mint a new agent NFT - Implements requirement from issue
-    ///
-    /// # Arguments
-    /// * `agent_id` - Unique identifier for the agent (u128 in spec, using u64 for storage efficiency)
-    /// * `owner` - Address of the agent owner
-    /// * `metadata_cid` - IPFS CID for agent metadata
-    /// * `initial_evolution_level` - Starting evolution level
-    /// * `royalty_recipient` - Optional address to receive royalty payments (basis points)
-    /// * `royalty_fee` - Optional royalty fee in basis points (0-10000, where 10000 = 100%)
-    ///
-    /// # Returns
-    /// Result<(), ContractError>
+    /// Apply a completed evolution to an agent: set its evolution level and,
+    /// when given, its new model hash. Changes are recorded in the metadata
+    /// history.
     ///
     /// # Errors
-    /// - ContractError::Unauthorized if caller is not admin or approved minter
-    /// - ContractError::DuplicateAgentId if agent_id already exists
-    /// - ContractError::InvalidInput if validation fails
-    /// - ContractError::InvalidRoyaltyFee if royalty fee exceeds maximum (10000)
-    pub fn mint_agent(
+    /// - ContractError::Unauthorized if caller is not the registered Evolution contract
+    /// - ContractError::AgentNotFound if the agent does not exist
+    /// - ContractError::InvalidInput if the model hash is too long
+    pub fn apply_evolution(
         env: Env,
-        agent_id: u128,
-        owner: Address,
-        metadata_cid: String,
-        initial_evolution_level: u32,
-        royalty_recipient: Option<Address>,
-        royalty_fee: Option<u32>,
+        caller: Address,
+        agent_id: u64,
+        evolution_level: u32,
+        model_hash: Option<String>,
     ) -> Result<(), ContractError> {
-        owner.require_auth();
+        caller.require_auth();
 
-        // Validate caller authorization (admin or approved minter)
-        Self::verify_minter(&env, &owner)?;
+        let evolution: Option<Address> = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(&env, EVOLUTION_CONTRACT_KEY));
+        if evolution != Some(caller) {
+            return Err(ContractError::Unauthorized);
+        }
 
-        // Convert u128 to u64 for storage (validate it fits)
-        let agent_id_u64 = agent_id
-            .try_into()
-            .map_err(|_| ContractError::InvalidInput)?;
+        let key = Self::get_agent_key(&env, agent_id);
+        let mut agent: Agent = env
+            .storage()
+            .instance()
+            .get(&key)
+            .ok_or(ContractError::AgentNotFound)?;
+        let previous = agent.clone();
 
-        // Enforce uniqueness of agent_id
-        if Self::agent_exists(&env, agent_id_u64) {
-            return Err(ContractError::DuplicateAgentId);
+        agent.evolution_level = evolution_level;
+        if let Some(new_model_hash) = model_hash {
+            if new_model_hash.len() > MAX_STRING_LENGTH.try_into().unwrap() {
+                return Err(ContractError::InvalidInput);
+            }
+            agent.model_hash = new_model_hash;
         }
 
-        // Input validation
-        if metadata_cid.len() > MAX_STRING_LENGTH.try_into().unwrap() {
-            return Err(ContractError::InvalidInput);
-        }
+        agent.nonce = agent
+            .nonce
+            .checked_add(1)
+            .ok_or(ContractError::OverflowError)?;
+        agent.updated_at = env.ledger().timestamp();
 
-        // Validate and store royalty info if provided
-        if let (Some(recipient), Some(fee)) = (&royalty_recipient, royalty_fee) {
-            Self::validate_royalty_fee(fee)?;
-            let royalty_info = RoyaltyInfo {
-                recipient: recipient.clone(),
-                fee,
-            };
-            let royalty_key = Self::get_royalty_key(&env, agent_id_u64);
-            env.storage().instance().set(&royalty_key, &royalty_info);
-        } else if royalty_recipient.is_some() || royalty_fee.is_some() {
-            // Both must be provided together or neither
-            return Err(ContractError::InvalidInput);
+        env.storage().instance().set(&key, &agent);
+        Self::record_metadata_changes(&env, &previous, &agent);
+
+        env.events().publish(
+            (Symbol::new(&env, "agent_nft"), AgentEvent::AgentEvolved),
+            (agent_id, evolution_level),
+        );
+
+        Ok(())
+    }
+
+    /// Set the highest royalty fee, in basis points, new royalties may use (admin only)
+    ///
+    /// # Errors
+    /// - ContractError::Unauthorized if caller is not admin
+    /// - ContractError::InvalidRoyaltyFee if the cap exceeds 10000
+    pub fn set_max_royalty(env: Env, admin: Address, bps: u32) -> Result<(), ContractError> {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin)?;
+        if bps > MAX_ROYALTY_FEE {
+            return Err(ContractError::InvalidRoyaltyFee);
         }
 
-        // Create agent with metadata CID and evolution level
-        let agent = Agent {
-            id: agent_id_u64,
-            owner: owner.clone(),
-            name: String::from_str(&env, ""), // Can be set via update_agent
-            model_hash: String::from_str(&env, ""), // Can be set via update_agent
-            metadata_cid,
-            capabilities: Vec::new(&env),
-            evolution_level: initial_evolution_level,
-            created_at: env.ledger().timestamp(),
-            updated_at: env.ledger().timestamp(),
-            nonce: 0,
-            escrow_locked: false,
-            escrow_holder: None,
-        };
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, MAX_ROYALTY_KEY), &bps);
 
-        // Persist agent data
-        let key = Self::get_agent_key(&env, agent_id_u64);
-        env.storage().instance().set(&key, &agent);
+        Ok(())
+    }
 
-        // Initialize lease status to false (not leased)
-        Self::set_agent_lease_status(&env, agent_id_u64, false);
+    /// Get the royalty fee cap in basis points
+    pub fn get_max_royalty(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&Symbol::new(&env, MAX_ROYALTY_KEY))
+            .unwrap_or(DEFAULT_MAX_ROYALTY_BPS)
+    }
+
+    /// Record the outcome of an agent execution: a success raises reputation
+    /// by one, a failure lowers it by one (never below zero).
+    ///
+    /// # Errors
+    /// - ContractError::Unauthorized if caller is not the registered ExecutionHub
+    /// - ContractError::AgentNotFound if the agent does not exist
+    pub fn update_reputation(
+        env: Env,
+        caller: Address,
+        agent_id: u64,
+        success: bool,
+    ) -> Result<u64, ContractError> {
+        caller.require_auth();
+
+        let execution_hub: Option<Address> = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(&env, EXECUTION_HUB_KEY));
+        if execution_hub != Some(caller) {
+            return Err(ContractError::Unauthorized);
+        }
+
+        if !env
+            .storage()
+            .instance()
+            .has(&Self::get_agent_key(&env, agent_id))
+        {
+            return Err(ContractError::AgentNotFound);
+        }
+
+        let reputation_key = Self::get_reputation_key(&env, agent_id);
+        let reputation: u64 = env.storage().instance().get(&reputation_key).unwrap_or(0);
+        let reputation = if success {
+            reputation.saturating_add(1)
+        } else {
+            reputation.saturating_sub(1)
+        };
+        env.storage().instance().set(&reputation_key, &reputation);
 
-        // Emit AgentMinted event
         env.events().publish(
-            (Symbol::new(&env, "agent_nft"), AgentEvent::AgentMinted),
-            (agent_id_u64, owner.clone(), initial_evolution_level),
+            (
+                Symbol::new(&env, "agent_nft"),
+                AgentEvent::ReputationUpdated,
+            ),
+            (agent_id, reputation, success),
         );
 
-        // Log audit entry for admin mint operation
-        let before_state = String::from_str(&env, "{}");
-        let after_state = String::from_str(&env, "{\"created\":true}");
-        let tx_hash = String::from_str(&env, "mint_agent");
-        let description = Some(String::from_str(&env, "AgentNFT minted"));
+        Ok(reputation)
+    }
 
-        let _ = create_audit_log(
-            &env,
-            owner.clone(),
-            OperationType::AdminMint,
-            before_state,
-            after_state,
-            tx_hash,
-            description,
-        );
+    /// Get an agent's reputation score
+    pub fn get_agent_reputation(env: Env, agent_id: u64) -> u64 {
+        env.storage()
+            .instance()
+            .get(&Self::get_reputation_key(&env, agent_id))
+            .unwrap_or(0)
+    }
 
-        Ok(())
+    /// Helper to get storage key for agent reputation
+    fn get_reputation_key(env: &Env, agent_id: u64) -> (Symbol, u64) {
+        (Symbol::new(env, "reputation"), agent_id)
     }
 
-    /// Legacy mint function for backward compatibility
-    ///
-    /// # Arguments
-    /// * `owner` - Address of the agent owner
-    /// * `name` - Agent name
-    /// * `model_hash` - Hash of the agent model
-    /// * `capabilities` - List of agent capabilities
-    /// * `royalty_recipient` - Optional address to receive royalty payments
-    /// * `royalty_fee` - Optional royalty fee in basis points (0-10000, where 10000 = 100%)
-    ///
-    /// # Returns
-    /// Result<u64, ContractError> - The minted agent ID
-    ///
-    /// # Errors
-    /// - ContractError::Unauthorized if caller is not admin or approved minter
-    /// - ContractError::InvalidInput if validation fails
-    /// - ContractError::InvalidRoyaltyFee if royalty fee exceeds maximum (10000)
-    pub fn mint_agent_legacy(
-        env: Env,
-        owner: Address,
-        name: String,
-        model_hash: String,
-        capabilities: Vec<String>,
-        royalty_recipient: Option<Address>,
-        royalty_fee: Option<u32>,
-    ) -> Result<u64, ContractError> {
-        owner.require_auth();
+    /// Helper to get storage key for an agent
+    fn get_agent_key(env: &Env, agent_id: u64) -> (Symbol, u64) {
+        (Symbol::new(env, "agent"), agent_id)
+    }
 
-        // Validate caller authorization
-        Self::verify_minter(&env, &owner)?;
+    /// Helper to get storage key for agent lease status
+    fn get_agent_lease_key(env: &Env, agent_id: u64) -> (Symbol, u64) {
+        (Symbol::new(env, "lease"), agent_id)
+    }
 
-        // Input validation
-        if name.len() > MAX_STRING_LENGTH.try_into().unwrap() {
-            return Err(ContractError::InvalidInput);
+    /// Helper to get storage key for agent royalty info
+    fn get_royalty_key(env: &Env, agent_id: u64) -> (Symbol, u64) {
+        (Symbol::new(env, "royalty"), agent_id)
+    }
+
+    /// Helper to get storage key for agent metadata history
+    fn get_metadata_history_key(env: &Env, agent_id: u64) -> (Symbol, u64) {
+        (Symbol::new(env, "meta_hist"), agent_id)
+    }
+
+    /// Append a history entry for every tracked field that differs between
+    /// `before` and `after`, keeping at most MAX_METADATA_HISTORY entries
+    fn record_metadata_changes(env: &Env, before: &Agent, after: &Agent) {
+        let mut changed: Vec<String> = Vec::new(env);
+        if before.name != after.name {
+            changed.push_back(String::from_str(env, "name"));
         }
-        if model_hash.len() > MAX_STRING_LENGTH.try_into().unwrap() {
-            return Err(ContractError::InvalidInput);
+        if before.capabilities != after.capabilities {
+            changed.push_back(String::from_str(env, "capabilities"));
         }
-        if capabilities.len() > MAX_CAPABILITIES.try_into().unwrap() {
-            return Err(ContractError::InvalidInput);
+        if before.model_hash != after.model_hash {
+            changed.push_back(String::from_str(env, "model_hash"));
         }
-
-        // Validate each capability string
-        for i in 0..capabilities.len() {
-            if let Some(cap) = capabilities.get(i) {
-                if cap.len() > MAX_STRING_LENGTH.try_into().unwrap() {
-                    return Err(ContractError::InvalidInput);
-                }
-            }
+        if before.evolution_level != after.evolution_level {
+            changed.push_back(String::from_str(env, "evolution_level"));
         }
-
-        // Validate and store royalty info if provided
-        if let (Some(recipient), Some(fee)) = (&royalty_recipient, royalty_fee) {
-            Self::validate_royalty_fee(fee)?;
-        } else if royalty_recipient.is_some() || royalty_fee.is_some() {
-            // Both must be provided together or neither
-            return Err(ContractError::InvalidInput);
+        if changed.is_empty() {
+            return;
         }
 
-        // Increment agent counter safely
-        let counter: u64 = env
+        let key = Self::get_metadata_history_key(env, after.id);
+        let mut history: Vec<MetadataChange> = env
             .storage()
             .instance()
-            .get(&Symbol::new(&env, AGENT_COUNTER_KEY))
-            .unwrap_or(0);
-
-        let agent_id = Self::safe_add(counter, 1)?;
-
-        // Create agent
-        let agent = Agent {
-            id: agent_id,
-            owner: owner.clone(),
-            name,
-            model_hash,
-            metadata_cid: String::from_str(&env, ""),
-            capabilities,
-            evolution_level: 0,
-            created_at: env.ledger().timestamp(),
-            updated_at: env.ledger().timestamp(),
-            nonce: 0,
-            escrow_locked: false,
-            escrow_holder: None,
-        };
+            .get(&key)
+            .unwrap_or_else(|| Vec::new(env));
+        for field_changed in changed.iter() {
+            history.push_back(MetadataChange {
+                field_changed,
+                timestamp: after.updated_at,
+                nonce: after.nonce,
+            });
+        }
+        while history.len() > MAX_METADATA_HISTORY {
+            history.pop_front();
+        }
+        env.storage().instance().set(&key, &history);
+    }
 
-        // Store agent
-        let key = Self::get_agent_key(&env, agent_id);
-        env.storage().instance().set(&key, &agent);
+    /// Helper to get storage key for a minter's mint quota
+    fn get_minter_quota_key(env: &Env, minter: &Address) -> (Symbol, Address) {
+        (Symbol::new(env, "mint_quota"), minter.clone())
+    }
 
-        // Initialize lease status
-        Self::set_agent_lease_status(&env, agent_id, false);
+    /// Helper to get storage key for a minter's running mint count
+    fn get_minter_usage_key(env: &Env, minter: &Address) -> (Symbol, Address) {
+        (Symbol::new(env, "mint_used"), minter.clone())
+    }
 
-        // Store royalty info if provided
-        if let (Some(recipient), Some(fee)) = (royalty_recipient, royalty_fee) {
-            let royalty_info = RoyaltyInfo { recipient, fee };
-            let royalty_key = Self::get_royalty_key(&env, agent_id);
-            env.storage().instance().set(&royalty_key, &royalty_info);
+    /// Count `amount` mints against the minter, rejecting them past the quota
+    fn consume_mint_quota(env: &Env, minter: &Address, amount: u64) -> Result<(), ContractError> {
+        let (used, quota) = Self::get_minter_usage(env.clone(), minter.clone());
+        let new_used = used
+            .checked_add(amount)
+            .ok_or(ContractError::OverflowError)?;
+        if quota > 0 && new_used > quota {
+            return Err(ContractError::RateLimitExceeded);
         }
-
-        // Update counter
         env.storage()
             .instance()
-            .set(&Symbol::new(&env, AGENT_COUNTER_KEY), &agent_id);
+            .set(&Self::get_minter_usage_key(env, minter), &new_used);
+        Ok(())
+    }
 
-        // Emit event
-        env.events().publish(
-            (Symbol::new(&env, "agent_nft"), AgentEvent::AgentMinted),
-            (agent_id, owner.clone()),
-        );
+    /// Helper to get storage key for the metadata CID -> agent ID index
+    fn get_cid_key(env: &Env, metadata_cid: &String) -> (Symbol, String) {
+        (Symbol::new(env, "cid_index"), metadata_cid.clone())
+    }
 
-        Ok(agent_id)
+    /// Check whether a (non-empty) metadata CID is already used by an agent
+    fn cid_in_use(env: &Env, metadata_cid: &String) -> bool {
+        !metadata_cid.is_empty()
+            && env
+                .storage()
+                .instance()
+                .has(&Self::get_cid_key(env, metadata_cid))
     }
 
-    /// Get agent metadata with bounds checking
-    pub fn get_agent(env: Env, agent_id: u64) -> Result<Agent, ContractError> {
-        if agent_id == 0 {
-            return Err(ContractError::InvalidAgentId);
+    /// Record the metadata CID of a newly minted agent (empty CIDs are not indexed)
+    fn index_metadata_cid(env: &Env, metadata_cid: &String, agent_id: u64) {
+        if !metadata_cid.is_empty() {
+            env.storage()
+                .instance()
+                .set(&Self::get_cid_key(env, metadata_cid), &agent_id);
         }
+    }
 
-        let key = Self::get_agent_key(&env, agent_id);
+    /// Store royalty info and emit RoyaltySet for off-chain indexing
+    fn store_royalty(env: &Env, agent_id: u64, recipient: Address, fee: u32) {
+        let royalty_info = RoyaltyInfo {
+            recipient: recipient.clone(),
+            fee,
+        };
         env.storage()
             .instance()
-            .get::<_, Agent>(&key)
-            .ok_or(ContractError::AgentNotFound)
+            .set(&Self::get_royalty_key(env, agent_id), &royalty_info);
+        env.events().publish(
+            (Symbol::new(env, "agent_nft"), AgentEvent::RoyaltySet),
+            (agent_id, recipient, fee),
+        );
     }
 
-    /// Update agent metadata with authorization check
-    pub fn update_agent(
-        env: Env,
-        agent_id: u64,
-        owner: Address,
-        name: Option<String>,
-        capabilities: Option<Vec<String>>,
+    /// Reject capabilities missing from the registry (when it is non-empty)
+    fn validate_registered_capabilities(
+        env: &Env,
+        capabilities: &Vec<String>,
     ) -> Result<(), ContractError> {
-        owner.require_auth();
+        let registry = Self::get_capabilities(env.clone());
+        if registry.is_empty() {
+            return Ok(());
+        }
+        for cap in capabilities.iter() {
+            if !registry.contains(&cap) {
+                return Err(ContractError::InvalidInput);
+            }
+        }
+        Ok(())
+    }
 
-        if agent_id == 0 {
-            return Err(ContractError::InvalidAgentId);
+    /// Validate royalty fee is within the configured cap
+    fn validate_royalty_fee(env: &Env, fee: u32) -> Result<(), ContractError> {
+        if fee > Self::get_max_royalty(env.clone()) {
+            return Err(ContractError::InvalidRoyaltyFee);
         }
+        Ok(())
+    }
 
-        let key = Self::get_agent_key(&env, agent_id);
-        let mut agent: Agent = env
+    /// Verify caller is admin
+    fn verify_admin(env: &Env, caller: &Address) -> Result<(), ContractError> {
+        let admin: Address = env
             .storage()
             .instance()
-            .get(&key)
-            .ok_or(ContractError::AgentNotFound)?;
+            .get(&Symbol::new(env, ADMIN_KEY))
+            .ok_or(ContractError::Unauthorized)?;
 
-        // Authorization check: only owner can update
-        if agent.owner != owner {
-            // Log the ownership failure
-            let before_state = String::from_str(&env, "{}");
-            let after_state = String::from_str(&env, "{}");
-            let tx_hash = String::from_str(&env, "update_agent_fail"); // Placeholder
-            let description = Some(String::from_str(
-                &env,
-                "NotOwner check failed during update.",
-            ));
+        if caller != &admin {
+            // Log the authorization failure
+            let before_state = String::from_str(env, "{}");
+            let after_state = String::from_str(env, "{}");
+            let tx_hash = String::from_str(env, "verify_admin_fail"); // Placeholder
+            let description = Some(String::from_str(env, "Admin verification failed."));
 
             let _ = create_audit_log(
-                &env,
-                owner.clone(), // 'owner' is the caller here
-                OperationType::UnauthorizedAttempt,
+                env,
+                caller.clone(),
+                OperationType::AuthFailure,
                 before_state,
                 after_state,
                 tx_hash,
                 description,
             );
-            return Err(ContractError::NotOwner);
+            return Err(ContractError::Unauthorized);
         }
+        Ok(())
+    }
 
-        // Check if agent is leased
-        if Self::is_agent_leased(&env, agent_id) {
-            return Err(ContractError::AgentLeased);
+    /// Verify caller is admin or approved minter
+    fn verify_minter(env: &Env, caller: &Address) -> Result<(), ContractError> {
+        // Check if admin
+        if let Some(admin) = env
+            .storage()
+            .instance()
+            .get::<_, Address>(&Symbol::new(env, ADMIN_KEY))
+        {
+            if caller == &admin {
+                return Ok(());
+            }
         }
 
-        // Update fields with validation
-        if let Some(new_name) = name {
-            if new_name.len() > MAX_STRING_LENGTH.try_into().unwrap() {
-                return Err(ContractError::InvalidInput);
-            }
-            agent.name = new_name;
-        }
+        // Check if approved minter
+        let approved_minters: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(env, APPROVED_MINTERS_KEY))
+            .unwrap_or_else(|| Vec::new(env));
 
-        if let Some(new_capabilities) = capabilities {
-            if new_capabilities.len() > MAX_CAPABILITIES.try_into().unwrap() {
-                return Err(ContractError::InvalidInput);
-            }
-            for i in 0..new_capabilities.len() {
-                if let Some(cap) = new_capabilities.get(i) {
-                    if cap.len() > MAX_STRING_LENGTH.try_into().unwrap() {
-                        return Err(ContractError::InvalidInput);
-                    }
+        for i in 0..approved_minters.len() {
+            if let Some(minter) = approved_minters.get(i) {
+                if &minter == caller {
+                    return Ok(());
                 }
             }
-            agent.capabilities = new_capabilities;
         }
 
-        // Increment nonce for replay protection
-        agent.nonce = agent
-            .nonce
-            .checked_add(1)
-            .ok_or(ContractError::OverflowError)?;
-        agent.updated_at = env.ledger().timestamp();
+        // If we reach here, no match was found.
+        let before_state = String::from_str(env, "{}");
+        let after_state = String::from_str(env, "{}");
+        let tx_hash = String::from_str(env, "verify_minter_fail"); // Placeholder
+        let description = Some(String::from_str(env, "Minter verification failed."));
 
-        env.storage().instance().set(&key, &agent);
+        let _ = create_audit_log(
+            env,
+            caller.clone(),
+            OperationType::AuthFailure,
+            before_state,
+            after_state,
+            tx_hash,
+            description,
+        );
+        Err(ContractError::Unauthorized)
+    }
 
-        env.events().publish(
-            (Symbol::new(&env, "agent_nft"), AgentEvent::AgentUpdated),
-            (agent_id, owner),
+    /// Verify caller is an approved escrow contract
+    fn verify_escrow(env: &Env, caller: &Address) -> Result<(), ContractError> {
+        let approved_escrows: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(env, APPROVED_ESCROWS_KEY))
+            .unwrap_or_else(|| Vec::new(env));
+
+        if approved_escrows.contains(caller) {
+            return Ok(());
+        }
+
+        let before_state = String::from_str(env, "{}");
+        let after_state = String::from_str(env, "{}");
+        let tx_hash = String::from_str(env, "verify_escrow_fail"); // Placeholder
+        let description = Some(String::from_str(env, "Escrow verification failed."));
+
+        let _ = create_audit_log(
+            env,
+            caller.clone(),
+            OperationType::AuthFailure,
+            before_state,
+            after_state,
+            tx_hash,
+            description,
         );
+        Err(ContractError::Unauthorized)
+    }
 
-        Ok(())
+    /// Safe addition with overflow checks
+    fn safe_add(a: u64, b: u64) -> Result<u64, ContractError> {
+        a.checked_add(b).ok_or(ContractError::OverflowError)
     }
 
-    /// Get total agents minted
-    pub fn total_agents(env: Env) -> u64 {
+    /// Check if agent is currently leased
+    fn is_agent_leased(env: &Env, agent_id: u64) -> bool {
+        let lease_key = Self::get_agent_lease_key(env, agent_id);
         env.storage()
             .instance()
-            .get(&Symbol::new(&env, AGENT_COUNTER_KEY))
-            .unwrap_or(0)
+            .get::<_, bool>(&lease_key)
+            .unwrap_or(false)
     }
 
-    /// Get nonce for replay protection
-    pub fn get_nonce(env: Env, agent_id: u64) -> Result<u64, ContractError> {
-        if agent_id == 0 {
-            return Err(ContractError::InvalidAgentId);
-        }
+    /// Set agent lease status
+    fn set_agent_lease_status(env: &Env, agent_id: u64, is_leased: bool) {
+        let lease_key = Self::get_agent_lease_key(env, agent_id);
+        env.storage().instance().set(&lease_key, &is_leased);
+    }
 
-        let key = Self::get_agent_key(&env, agent_id);
-        env.storage()
-            .instance()
-            .get::<_, Agent>(&key)
-            .map(|agent| agent.nonce)
-            .ok_or(ContractError::AgentNotFound)
+    /// Check if agent ID already exists
+    fn agent_exists(env: &Env, agent_id: u64) -> bool {
+        let key = Self::get_agent_key(env, agent_id);
+        env.storage().instance().has(&key)
     }
 
-    /// Transfer ownership of an Agent NFT
-    pub fn transfer_agent(
+    /// Mint a new agent NFT - Implements requirement from issue
+    ///
+    /// # Arguments
+    /// * `agent_id` - Unique identifier for the agent (u128 in spec, using u64 for storage efficiency)
+    /// * `owner` - Address of the agent owner
+    /// * `metadata_cid` - IPFS CID for agent metadata
+    /// * `initial_evolution_level` - Starting evolution level
+    /// * `royalty_recipient` - Optional address to receive royalty payments (basis points)
+    /// * `royalty_fee` - Optional royalty fee in basis points (0-10000, where 10000 = 100%)
+    ///
+    /// # Returns
+    /// Result<(), ContractError>
+    ///
+    /// # Errors
+    /// - ContractError::Unauthorized if caller is not admin or approved minter
+    /// - ContractError::DuplicateAgentId if agent_id already exists
+    /// - ContractError::InvalidInput if validation fails
+    /// - ContractError::InvalidRoyaltyFee if royalty fee exceeds the configured cap
+    pub fn mint_agent(
         env: Env,
-        agent_id: u64,
-        from: Address,
-        to: Address,
+        agent_id: u128,
+        owner: Address,
+        metadata_cid: String,
+        initial_evolution_level: u32,
+        royalty_recipient: Option<Address>,
+        royalty_fee: Option<u32>,
     ) -> Result<(), ContractError> {
-        if agent_id == 0 {
-            return Err(ContractError::InvalidAgentId);
-        }
+        owner.require_auth();
 
-        from.require_auth();
+        // Validate caller authorization (admin or approved minter)
+        Self::verify_minter(&env, &owner)?;
+        Self::consume_mint_quota(&env, &owner, 1)?;
 
-        if from == to {
-            return Err(ContractError::SameAddressTransfer);
+        // Convert u128 to u64 for storage (validate it fits)
+        let agent_id_u64 = agent_id
+            .try_into()
+            .map_err(|_| ContractError::InvalidInput)?;
+
+        // Enforce uniqueness of agent_id
+        if Self::agent_exists(&env, agent_id_u64) {
+            return Err(ContractError::DuplicateAgentId);
         }
 
-        let key = Self::get_agent_key(&env, agent_id);
-        let mut agent: Agent = env
-            .storage()
-            .instance()
-            .get(&key)
-            .ok_or(ContractError::AgentNotFound)?;
+        // Input validation
+        if metadata_cid.len() > MAX_STRING_LENGTH.try_into().unwrap() {
+            return Err(ContractError::InvalidInput);
+        }
 
-        if agent.owner != from {
-            return Err(ContractError::NotOwner);
+        // Enforce uniqueness of metadata_cid across the contract
+        if Self::cid_in_use(&env, &metadata_cid) {
+            return Err(ContractError::AlreadyExists);
         }
 
-        if Self::is_agent_leased(&env, agent_id) {
-            return Err(ContractError::AgentLeased);
+        // Validate and store royalty info if provided
+        if let (Some(recipient), Some(fee)) = (&royalty_recipient, royalty_fee) {
+            Self::validate_royalty_fee(&env, fee)?;
+            Self::store_royalty(&env, agent_id_u64, recipient.clone(), fee);
+        } else if royalty_recipient.is_some() || royalty_fee.is_some() {
+            // Both must be provided together or neither
+            return Err(ContractError::InvalidInput);
         }
 
-        let previous_owner = agent.owner.clone();
-        agent.owner = to.clone();
-        agent.nonce = agent
-            .nonce
-            .checked_add(1)
-            .ok_or(ContractError::OverflowError)?;
-        agent.updated_at = env.ledger().timestamp();
+        // Create agent with metadata CID and evolution level
+        let agent = Agent {
+            id: agent_id_u64,
+            owner: owner.clone(),
+            name: String::from_str(&env, ""), // Can be set via update_agent
+            model_hash: String::from_str(&env, ""), // Can be set via update_agent
+            metadata_cid,
+            capabilities: Vec::new(&env),
+            evolution_level: initial_evolution_level,
+            created_at: env.ledger().timestamp(),
+            updated_at: env.ledger().timestamp(),
+            nonce: 0,
+            escrow_locked: false,
+            escrow_holder: None,
+        };
 
+        // Persist agent data
+        let key = Self::get_agent_key(&env, agent_id_u64);
         env.storage().instance().set(&key, &agent);
+        Self::index_metadata_cid(&env, &agent.metadata_cid, agent_id_u64);
+
+        // Initialize lease status to false (not leased)
+        Self::set_agent_lease_status(&env, agent_id_u64, false);
 
+        // Emit AgentMinted event
         env.events().publish(
-            (Symbol::new(&env, "agent_nft"), AgentEvent::AgentTransferred),
-            (agent_id, previous_owner.clone(), to.clone()),
+            (Symbol::new(&env, "agent_nft"), AgentEvent::AgentMinted),
+            (agent_id_u64, owner.clone(), initial_evolution_level),
         );
 
-        // Audit log for transfer
-        let before_state = String::from_str(&env, "{\"transferred\":false}");
-        let after_state = String::from_str(&env, "{\"transferred\":true}");
-        let tx_hash = String::from_str(&env, "transfer_agent");
-        let description = Some(String::from_str(&env, "Agent NFT transferred"));
+        // Log audit entry for admin mint operation
+        let before_state = String::from_str(&env, "{}");
+        let after_state = String::from_str(&env, "{\"created\":true}");
+        let tx_hash = String::from_str(&env, "mint_agent");
+        let description = Some(String::from_str(&env, "AgentNFT minted"));
 
         let _ = create_audit_log(
             &env,
-            from,
-            OperationType::AdminTransfer,
+            owner.clone(),
+            OperationType::AdminMint,
             before_state,
             after_state,
             tx_hash,
@@ -633,122 +851,120 @@ impl AgentNFT {
         Ok(())
     }
 
-    pub fn batch_mint(
+    /// Legacy mint function for backward compatibility
+    ///
+    /// # Arguments
+    /// * `owner` - Address of the agent owner
+    /// * `name` - Agent name
+    /// * `model_hash` - Hash of the agent model
+    /// * `capabilities` - List of agent capabilities
+    /// * `royalty_recipient` - Optional address to receive royalty payments
+    /// * `royalty_fee` - Optional royalty fee in basis points (0-10000, where 10000 = 100%)
+    ///
+    /// # Returns
+    /// Result<u64, ContractError> - The minted agent ID
+    ///
+    /// # Errors
+    /// - ContractError::Unauthorized if caller is not admin or approved minter
+    /// - ContractError::InvalidInput if validation fails
+    /// - ContractError::InvalidRoyaltyFee if royalty fee exceeds the configured cap
+    pub fn mint_agent_legacy(
         env: Env,
-        admin: Address,
-        agents: Vec<AgentMintData>,
-    ) -> Result<Vec<u64>, ContractError> {
-        // 1. Authorization: Only admin or approved minters
-        admin.require_auth();
-        Self::verify_minter(&env, &admin)?;
+        owner: Address,
+        name: String,
+        model_hash: String,
+        capabilities: Vec<String>,
+        royalty_recipient: Option<Address>,
+        royalty_fee: Option<u32>,
+    ) -> Result<u64, ContractError> {
+        owner.require_auth();
 
-        // 2. Batch Size Validation
-        let count = agents.len();
-        if count == 0 {
+        // Validate caller authorization
+        Self::verify_minter(&env, &owner)?;
+        Self::consume_mint_quota(&env, &owner, 1)?;
+
+        // Input validation
+        if name.len() > MAX_STRING_LENGTH.try_into().unwrap() {
             return Err(ContractError::InvalidInput);
         }
-        if count > 50 {
+        if model_hash.len() > MAX_STRING_LENGTH.try_into().unwrap() {
             return Err(ContractError::InvalidInput);
-        } // Limit to 50
+        }
+        if capabilities.len() > MAX_CAPABILITIES.try_into().unwrap() {
+            return Err(ContractError::InvalidInput);
+        }
 
-        // 3. Duplicate Metadata Validation (Internal to Batch)
-        // We use a temporary map to ensure no CID is repeated in this single call
-        let mut seen_cids = Vec::new(&env);
-        for i in 0..agents.len() {
-            let agent = agents.get(i).unwrap();
-            if seen_cids.contains(agent.metadata_cid.clone()) {
-                return Err(ContractError::InvalidInput);
+        // Validate each capability string
+        for i in 0..capabilities.len() {
+            if let Some(cap) = capabilities.get(i) {
+                if cap.len() > MAX_STRING_LENGTH.try_into().unwrap() {
+                    return Err(ContractError::InvalidInput);
+                }
             }
-            seen_cids.push_back(agent.metadata_cid.clone());
         }
+        Self::validate_registered_capabilities(&env, &capabilities)?;
 
-        // 4. Execution Logic
-        let mut minted_ids = Vec::new(&env);
-        let mut current_counter: u64 = env
+        // Validate and store royalty info if provided
+        if let (Some(_recipient), Some(fee)) = (&royalty_recipient, royalty_fee) {
+            Self::validate_royalty_fee(&env, fee)?;
+        } else if royalty_recipient.is_some() || royalty_fee.is_some() {
+            // Both must be provided together or neither
+            return Err(ContractError::InvalidInput);
+        }
+
+        // Increment agent counter safely
+        let counter: u64 = env
             .storage()
             .instance()
             .get(&Symbol::new(&env, AGENT_COUNTER_KEY))
             .unwrap_or(0);
 
-        for i in 0..agents.len() {
-            let data = agents.get(i).unwrap();
-
-            // Increment ID
-            current_counter = Self::safe_add(current_counter, 1)?;
-            let agent_id = current_counter;
-
-            // Create Agent object
-            let agent = Agent {
-                id: agent_id,
-                owner: data.owner.clone(),
-                name: data.name,
-                model_hash: data.model_hash,
-                metadata_cid: data.metadata_cid,
-                capabilities: data.capabilities,
-                evolution_level: 0,
-                created_at: env.ledger().timestamp(),
-                updated_at: env.ledger().timestamp(),
-                nonce: 0,
-                escrow_locked: false,
-                escrow_holder: None,
-            };
+        let agent_id = Self::safe_add(counter, 1)?;
 
-            // Persist Agent
-            let key = Self::get_agent_key(&env, agent_id);
-            env.storage().instance().set(&key, &agent);
-            Self::set_agent_lease_status(&env, agent_id, false);
+        // Create agent
+        let agent = Agent {
+            id: agent_id,
+            owner: owner.clone(),
+            name,
+            model_hash,
+            metadata_cid: String::from_str(&env, ""),
+            capabilities,
+            evolution_level: 0,
+            created_at: env.ledger().timestamp(),
+            updated_at: env.ledger().timestamp(),
+            nonce: 0,
+            escrow_locked: false,
+            escrow_holder: None,
+        };
 
-            // Handle Royalty if present
-            if let Some(royalty) = data.royalty {
-                Self::validate_royalty_fee(royalty.fee)?;
-                let royalty_key = Self::get_royalty_key(&env, agent_id);
-                env.storage().instance().set(&royalty_key, &royalty);
-            }
+        // Store agent
+        let key = Self::get_agent_key(&env, agent_id);
+        env.storage().instance().set(&key, &agent);
 
-            // Emit Individual Event
-            env.events().publish(
-                (Symbol::new(&env, "agent_nft"), AgentEvent::AgentMinted),
-                (agent_id, data.owner.clone()),
-            );
+        // Initialize lease status
+        Self::set_agent_lease_status(&env, agent_id, false);
 
-            minted_ids.push_back(agent_id);
+        // Store royalty info if provided
+        if let (Some(recipient), Some(fee)) = (royalty_recipient, royalty_fee) {
+            Self::store_royalty(&env, agent_id, recipient, fee);
         }
 
-        // 5. Update Global Counter
+        // Update counter
         env.storage()
             .instance()
-            .set(&Symbol::new(&env, AGENT_COUNTER_KEY), &current_counter);
+            .set(&Symbol::new(&env, AGENT_COUNTER_KEY), &agent_id);
 
-        // 6. Emit Batch Summary Event
-        // We use the timestamp + admin address hash as a pseudo batch_id
+        // Emit event
         env.events().publish(
-            (
-                Symbol::new(&env, "agent_nft"),
-                AgentEvent::BatchMintCompleted,
-            ),
-            (count, admin),
+            (Symbol::new(&env, "agent_nft"), AgentEvent::AgentMinted),
+            (agent_id, owner.clone()),
         );
 
-        Ok(minted_ids)
+        Ok(agent_id)
     }
 
-    fn validate_agent_data(
-        env: &Env,
-        name: &String,
-        metadata_cid: &String,
-        capabilities: &Vec<String>,
-    ) -> Result<(), ContractError> {
-        if name.len() > MAX_STRING_LENGTH as u32
-            || metadata_cid.len() > MAX_STRING_LENGTH as u32
-            || capabilities.len() > MAX_CAPABILITIES as u32
-        {
-            return Err(ContractError::InvalidInput);
-        }
-        Ok(())
-    }
-    /// Get current owner of an agent
-    /// Read-only query function for off-chain consumers (Issue #6)
-    pub fn get_agent_owner(env: Env, agent_id: u64) -> Result<Address, ContractError> {
+    /// Get agent metadata with bounds checking
+    pub fn get_agent(env: Env, agent_id: u64) -> Result<Agent, ContractError> {
         if agent_id == 0 {
             return Err(ContractError::InvalidAgentId);
         }
@@ -757,268 +973,1801 @@ impl AgentNFT {
         env.storage()
             .instance()
             .get::<_, Agent>(&key)
-            .map(|agent| agent.owner)
             .ok_or(ContractError::AgentNotFound)
     }
 
-    /// Get agent metadata CID (IPFS hash)
-    /// Read-only query function for off-chain consumers (Issue #6)
-    /// Returns the IPFS CID for the agent's metadata
-    pub fn get_agent_metadata(env: Env, agent_id: u64) -> Result<String, ContractError> {
+    /// Update agent metadata with authorization check
+    pub fn update_agent(
+        env: Env,
+        agent_id: u64,
+        owner: Address,
+        name: Option<String>,
+        capabilities: Option<Vec<String>>,
+    ) -> Result<(), ContractError> {
+        owner.require_auth();
+
         if agent_id == 0 {
             return Err(ContractError::InvalidAgentId);
         }
 
         let key = Self::get_agent_key(&env, agent_id);
-        env.storage()
+        let mut agent: Agent = env
+            .storage()
             .instance()
-            .get::<_, Agent>(&key)
-            .map(|agent| agent.metadata_cid)
-            .ok_or(ContractError::AgentNotFound)
+            .get(&key)
+            .ok_or(ContractError::AgentNotFound)?;
+        let previous = agent.clone();
+
+        // Authorization check: only owner can update
+        if agent.owner != owner {
+            // Log the ownership failure
+            let before_state = String::from_str(&env, "{}");
+            let after_state = String::from_str(&env, "{}");
+            let tx_hash = String::from_str(&env, "update_agent_fail"); // Placeholder
+            let description = Some(String::from_str(
+                &env,
+                "NotOwner check failed during update.",
+            ));
+
+            let _ = create_audit_log(
+                &env,
+                owner.clone(), // 'owner' is the caller here
+                OperationType::UnauthorizedAttempt,
+                before_state,
+                after_state,
+                tx_hash,
+                description,
+            );
+            return Err(ContractError::NotOwner);
+        }
+
+        // Check if agent is leased
+        if Self::is_agent_leased(&env, agent_id) {
+            return Err(ContractError::AgentLeased);
+        }
+
+        // Check if agent is held in escrow
+        if agent.escrow_locked {
+            return Err(ContractError::AgentEscrowLocked);
+        }
+
+        // Update fields with validation
+        if let Some(new_name) = name {
+            if new_name.len() > MAX_STRING_LENGTH.try_into().unwrap() {
+                return Err(ContractError::InvalidInput);
+            }
+            agent.name = new_name;
+        }
+
+        if let Some(new_capabilities) = capabilities {
+            if new_capabilities.len() > MAX_CAPABILITIES.try_into().unwrap() {
+                return Err(ContractError::InvalidInput);
+            }
+            for i in 0..new_capabilities.len() {
+                if let Some(cap) = new_capabilities.get(i) {
+                    if cap.len() > MAX_STRING_LENGTH.try_into().unwrap() {
+                        return Err(ContractError::InvalidInput);
+                    }
+                }
+            }
+            Self::validate_registered_capabilities(&env, &new_capabilities)?;
+            agent.capabilities = new_capabilities;
+        }
+
+        // Increment nonce for replay protection
+        agent.nonce = agent
+            .nonce
+            .checked_add(1)
+            .ok_or(ContractError::OverflowError)?;
+        agent.updated_at = env.ledger().timestamp();
+
+        env.storage().instance().set(&key, &agent);
+        Self::record_metadata_changes(&env, &previous, &agent);
+
+        env.events().publish(
+            (Symbol::new(&env, "agent_nft"), AgentEvent::AgentUpdated),
+            (agent_id, owner),
+        );
+
+        Ok(())
     }
 
-    /// Get agent evolution level
-    /// Read-only query function for off-chain consumers (Issue #6)
-    /// Returns the current evolution level of the agent
-    pub fn get_agent_evolution_level(env: Env, agent_id: u64) -> Result<u32, ContractError> {
+    /// Burn an agent NFT, removing it and its metadata CID from the index
+    ///
+    /// # Errors
+    /// - ContractError::AgentNotFound if the agent does not exist
+    /// - ContractError::NotOwner if caller is not the owner
+    /// - ContractError::AgentLeased / AgentEscrowLocked if the agent is in use
+    pub fn burn_agent(env: Env, agent_id: u64, owner: Address) -> Result<(), ContractError> {
+        owner.require_auth();
+
         if agent_id == 0 {
             return Err(ContractError::InvalidAgentId);
         }
 
         let key = Self::get_agent_key(&env, agent_id);
+        let agent: Agent = env
+            .storage()
+            .instance()
+            .get(&key)
+            .ok_or(ContractError::AgentNotFound)?;
+
+        if agent.owner != owner {
+            return Err(ContractError::NotOwner);
+        }
+        if Self::is_agent_leased(&env, agent_id) {
+            return Err(ContractError::AgentLeased);
+        }
+        if agent.escrow_locked {
+            return Err(ContractError::AgentEscrowLocked);
+        }
+
+        env.storage().instance().remove(&key);
         env.storage()
             .instance()
-            .get::<_, Agent>(&key)
-            .map(|agent| agent.evolution_level)
-            .ok_or(ContractError::AgentNotFound)
+            .remove(&Self::get_agent_lease_key(&env, agent_id));
+        env.storage()
+            .instance()
+            .remove(&Self::get_royalty_key(&env, agent_id));
+        env.storage()
+            .instance()
+            .remove(&Self::get_reputation_key(&env, agent_id));
+        env.storage()
+            .instance()
+            .remove(&Self::get_metadata_history_key(&env, agent_id));
+        if !agent.metadata_cid.is_empty() {
+            env.storage()
+                .instance()
+                .remove(&Self::get_cid_key(&env, &agent.metadata_cid));
+        }
+
+        env.events().publish(
+            (Symbol::new(&env, "agent_nft"), AgentEvent::AgentBurned),
+            (agent_id, owner),
+        );
+
+        Ok(())
+    }
+
+    /// Get the most recent metadata changes for an agent, oldest first
+    pub fn get_metadata_history(env: Env, agent_id: u64, limit: u32) -> Vec<MetadataChange> {
+        let history: Vec<MetadataChange> = env
+            .storage()
+            .instance()
+            .get(&Self::get_metadata_history_key(&env, agent_id))
+            .unwrap_or_else(|| Vec::new(&env));
+        let start = history.len().saturating_sub(limit);
+        history.slice(start..)
+    }
+
+    /// Resolve a metadata CID back to the agent minted with it
+    pub fn get_agent_by_cid(env: Env, metadata_cid: String) -> Option<u64> {
+        env.storage()
+            .instance()
+            .get(&Self::get_cid_key(&env, &metadata_cid))
+    }
+
+    /// Get total agents minted
+    pub fn total_agents(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&Symbol::new(&env, AGENT_COUNTER_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Get nonce for replay protection
+    pub fn get_nonce(env: Env, agent_id: u64) -> Result<u64, ContractError> {
+        if agent_id == 0 {
+            return Err(ContractError::InvalidAgentId);
+        }
+
+        let key = Self::get_agent_key(&env, agent_id);
+        env.storage()
+            .instance()
+            .get::<_, Agent>(&key)
+            .map(|agent| agent.nonce)
+            .ok_or(ContractError::AgentNotFound)
+    }
+
+    /// Transfer ownership of an Agent NFT
+    pub fn transfer_agent(
+        env: Env,
+        agent_id: u64,
+        from: Address,
+        to: Address,
+    ) -> Result<(), ContractError> {
+        if agent_id == 0 {
+            return Err(ContractError::InvalidAgentId);
+        }
+
+        from.require_auth();
+
+        if from == to {
+            return Err(ContractError::SameAddressTransfer);
+        }
+
+        let key = Self::get_agent_key(&env, agent_id);
+        let mut agent: Agent = env
+            .storage()
+            .instance()
+            .get(&key)
+            .ok_or(ContractError::AgentNotFound)?;
+
+        if agent.owner != from {
+            return Err(ContractError::NotOwner);
+        }
+
+        if Self::is_agent_leased(&env, agent_id) {
+            return Err(ContractError::AgentLeased);
+        }
+
+        if agent.escrow_locked {
+            return Err(ContractError::AgentEscrowLocked);
+        }
+
+        let previous_owner = agent.owner.clone();
+        agent.owner = to.clone();
+        agent.nonce = agent
+            .nonce
+            .checked_add(1)
+            .ok_or(ContractError::OverflowError)?;
+        agent.updated_at = env.ledger().timestamp();
+
+        env.storage().instance().set(&key, &agent);
+
+        env.events().publish(
+            (Symbol::new(&env, "agent_nft"), AgentEvent::AgentTransferred),
+            (agent_id, previous_owner.clone(), to.clone()),
+        );
+
+        // Audit log for transfer
+        let before_state = String::from_str(&env, "{\"transferred\":false}");
+        let after_state = String::from_str(&env, "{\"transferred\":true}");
+        let tx_hash = String::from_str(&env, "transfer_agent");
+        let description = Some(String::from_str(&env, "Agent NFT transferred"));
+
+        let _ = create_audit_log(
+            &env,
+            from,
+            OperationType::AdminTransfer,
+            before_state,
+            after_state,
+            tx_hash,
+            description,
+        );
+
+        Ok(())
+    }
+
+    /// Transfer several agents from one owner in a single call
+    ///
+    /// Every agent is validated (owned by `from`, not leased, not escrow-locked)
+    /// before any transfer is applied, so the batch is all-or-nothing.
+    ///
+    /// # Errors
+    /// - ContractError::InvalidInput if the batch is empty, too large or repeats an agent
+    /// - ContractError::SameAddressTransfer if any recipient is `from`
+    /// - ContractError::NotOwner, AgentLeased or AgentEscrowLocked for any failing agent
+    pub fn batch_transfer(
+        env: Env,
+        from: Address,
+        transfers: Vec<(u64, Address)>,
+    ) -> Result<(), ContractError> {
+        from.require_auth();
+
+        let count = transfers.len();
+        if count == 0 || count > MAX_BATCH_TRANSFER_SIZE {
+            return Err(ContractError::InvalidInput);
+        }
+
+        // Validate the full batch before mutating any state
+        let mut agents: Vec<Agent> = Vec::new(&env);
+        let mut seen_ids: Vec<u64> = Vec::new(&env);
+        for (agent_id, to) in transfers.iter() {
+            if agent_id == 0 {
+                return Err(ContractError::InvalidAgentId);
+            }
+            if seen_ids.contains(agent_id) {
+                return Err(ContractError::InvalidInput);
+            }
+            seen_ids.push_back(agent_id);
+
+            if from == to {
+                return Err(ContractError::SameAddressTransfer);
+            }
+
+            let key = Self::get_agent_key(&env, agent_id);
+            let agent: Agent = env
+                .storage()
+                .instance()
+                .get(&key)
+                .ok_or(ContractError::AgentNotFound)?;
+
+            if agent.owner != from {
+                return Err(ContractError::NotOwner);
+            }
+            if Self::is_agent_leased(&env, agent_id) {
+                return Err(ContractError::AgentLeased);
+            }
+            if agent.escrow_locked {
+                return Err(ContractError::AgentEscrowLocked);
+            }
+
+            agents.push_back(agent);
+        }
+
+        // Apply transfers
+        for (i, (agent_id, to)) in transfers.iter().enumerate() {
+            let mut agent = agents.get(i as u32).unwrap();
+            agent.owner = to.clone();
+            agent.nonce = agent
+                .nonce
+                .checked_add(1)
+                .ok_or(ContractError::OverflowError)?;
+            agent.updated_at = env.ledger().timestamp();
+
+            let key = Self::get_agent_key(&env, agent_id);
+            env.storage().instance().set(&key, &agent);
+
+            env.events().publish(
+                (Symbol::new(&env, "agent_nft"), AgentEvent::AgentTransferred),
+                (agent_id, from.clone(), to),
+            );
+        }
+
+        env.events().publish(
+            (
+                Symbol::new(&env, "agent_nft"),
+                AgentEvent::BatchTransferCompleted,
+            ),
+            (count, from.clone()),
+        );
+
+        let before_state = String::from_str(&env, "{\"transferred\":false}");
+        let after_state = String::from_str(&env, "{\"transferred\":true}");
+        let tx_hash = String::from_str(&env, "batch_transfer");
+        let description = Some(String::from_str(&env, "Agent NFT batch transferred"));
+
+        let _ = create_audit_log(
+            &env,
+            from,
+            OperationType::AdminTransfer,
+            before_state,
+            after_state,
+            tx_hash,
+            description,
+        );
+
+        Ok(())
+    }
+
+    pub fn batch_mint(
+        env: Env,
+        admin: Address,
+        agents: Vec<AgentMintData>,
+    ) -> Result<Vec<u64>, ContractError> {
+        // 1. Authorization: Only admin or approved minters
+        admin.require_auth();
+        Self::verify_minter(&env, &admin)?;
+
+        // 2. Batch Size Validation
+        let count = agents.len();
+        if count == 0 {
+            return Err(ContractError::InvalidInput);
+        }
+        if count > 50 {
+            return Err(ContractError::InvalidInput);
+        } // Limit to 50
+        Self::consume_mint_quota(&env, &admin, count as u64)?;
+
+        // 3. Duplicate Metadata Validation (Internal to Batch)
+        // We use a temporary map to ensure no CID is repeated in this single call
+        let mut seen_cids = Vec::new(&env);
+        for i in 0..agents.len() {
+            let agent = agents.get(i).unwrap();
+            if seen_cids.contains(agent.metadata_cid.clone()) {
+                return Err(ContractError::InvalidInput);
+            }
+            if Self::cid_in_use(&env, &agent.metadata_cid) {
+                return Err(ContractError::AlreadyExists);
+            }
+            Self::validate_registered_capabilities(&env, &agent.capabilities)?;
+            seen_cids.push_back(agent.metadata_cid.clone());
+        }
+
+        // 4. Execution Logic
+        let mut minted_ids = Vec::new(&env);
+        let mut current_counter: u64 = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(&env, AGENT_COUNTER_KEY))
+            .unwrap_or(0);
+
+        for i in 0..agents.len() {
+            let data = agents.get(i).unwrap();
+
+            // Increment ID
+            current_counter = Self::safe_add(current_counter, 1)?;
+            let agent_id = current_counter;
+
+            // Create Agent object
+            let agent = Agent {
+                id: agent_id,
+                owner: data.owner.clone(),
+                name: data.name,
+                model_hash: data.model_hash,
+                metadata_cid: data.metadata_cid,
+                capabilities: data.capabilities,
+                evolution_level: 0,
+                created_at: env.ledger().timestamp(),
+                updated_at: env.ledger().timestamp(),
+                nonce: 0,
+                escrow_locked: false,
+                escrow_holder: None,
+            };
+
+            // Persist Agent
+            let key = Self::get_agent_key(&env, agent_id);
+            env.storage().instance().set(&key, &agent);
+            Self::index_metadata_cid(&env, &agent.metadata_cid, agent_id);
+            Self::set_agent_lease_status(&env, agent_id, false);
+
+            // Handle Royalty if present
+            if let (Some(recipient), Some(fee)) = (data.royalty_recipient, data.royalty_fee) {
+                Self::validate_royalty_fee(&env, fee)?;
+                Self::store_royalty(&env, agent_id, recipient, fee);
+            }
+
+            // Emit Individual Event
+            env.events().publish(
+                (Symbol::new(&env, "agent_nft"), AgentEvent::AgentMinted),
+                (agent_id, data.owner.clone()),
+            );
+
+            minted_ids.push_back(agent_id);
+        }
+
+        // 5. Update Global Counter
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, AGENT_COUNTER_KEY), &current_counter);
+
+        // 6. Emit Batch Summary Event
+        // We use the timestamp + admin address hash as a pseudo batch_id
+        env.events().publish(
+            (
+                Symbol::new(&env, "agent_nft"),
+                AgentEvent::BatchMintCompleted,
+            ),
+            (count, admin),
+        );
+
+        Ok(minted_ids)
+    }
+
+    /// Get current owner of an agent
+    /// Read-only query function for off-chain consumers (Issue #6)
+    pub fn get_agent_owner(env: Env, agent_id: u64) -> Result<Address, ContractError> {
+        if agent_id == 0 {
+            return Err(ContractError::InvalidAgentId);
+        }
+
+        let key = Self::get_agent_key(&env, agent_id);
+        env.storage()
+            .instance()
+            .get::<_, Agent>(&key)
+            .map(|agent| agent.owner)
+            .ok_or(ContractError::AgentNotFound)
+    }
+
+    /// Get agent metadata CID (IPFS hash)
+    /// Read-only query function for off-chain consumers (Issue #6)
+    /// Returns the IPFS CID for the agent's metadata
+    pub fn get_agent_metadata(env: Env, agent_id: u64) -> Result<String, ContractError> {
+        if agent_id == 0 {
+            return Err(ContractError::InvalidAgentId);
+        }
+
+        let key = Self::get_agent_key(&env, agent_id);
+        env.storage()
+            .instance()
+            .get::<_, Agent>(&key)
+            .map(|agent| agent.metadata_cid)
+            .ok_or(ContractError::AgentNotFound)
+    }
+
+    /// Get agent evolution level
+    /// Read-only query function for off-chain consumers (Issue #6)
+    /// Returns the current evolution level of the agent
+    pub fn get_agent_evolution_level(env: Env, agent_id: u64) -> Result<u32, ContractError> {
+        if agent_id == 0 {
+            return Err(ContractError::InvalidAgentId);
+        }
+
+        let key = Self::get_agent_key(&env, agent_id);
+        env.storage()
+            .instance()
+            .get::<_, Agent>(&key)
+            .map(|agent| agent.evolution_level)
+            .ok_or(ContractError::AgentNotFound)
+    }
+
+    /// Check if agent can be transferred
+    pub fn can_transfer_agent(env: Env, agent_id: u64, caller: Address) -> bool {
+        if agent_id == 0 {
+            return false;
+        }
+
+        let key = Self::get_agent_key(&env, agent_id);
+        let agent = match env.storage().instance().get::<_, Agent>(&key) {
+            Some(agent) => agent,
+            None => return false,
+        };
+
+        if agent.owner != caller {
+            return false;
+        }
+
+        !agent.escrow_locked && !Self::is_agent_leased(&env, agent_id)
+    }
+
+    /// Lock an agent in escrow (approved escrow contracts only)
+    ///
+    /// While locked, the agent cannot be transferred or updated until the
+    /// holder calls `release_from_escrow`.
+    ///
+    /// # Errors
+    /// - ContractError::Unauthorized if holder is not an approved escrow contract
+    /// - ContractError::AgentNotFound if the agent does not exist
+    /// - ContractError::AgentEscrowLocked if the agent is already locked
+    pub fn lock_in_escrow(env: Env, agent_id: u64, holder: Address) -> Result<(), ContractError> {
+        if agent_id == 0 {
+            return Err(ContractError::InvalidAgentId);
+        }
+
+        holder.require_auth();
+        Self::verify_escrow(&env, &holder)?;
+
+        let key = Self::get_agent_key(&env, agent_id);
+        let mut agent: Agent = env
+            .storage()
+            .instance()
+            .get(&key)
+            .ok_or(ContractError::AgentNotFound)?;
+
+        if agent.escrow_locked {
+            return Err(ContractError::AgentEscrowLocked);
+        }
+
+        agent.escrow_locked = true;
+        agent.escrow_holder = Some(holder.clone());
+        agent.updated_at = env.ledger().timestamp();
+        env.storage().instance().set(&key, &agent);
+
+        env.events().publish(
+            (Symbol::new(&env, "agent_nft"), AgentEvent::EscrowLocked),
+            (agent_id, holder),
+        );
+
+        Ok(())
+    }
+
+    /// Release an agent from escrow (current escrow holder only)
+    ///
+    /// # Errors
+    /// - ContractError::AgentNotFound if the agent does not exist
+    /// - ContractError::InvalidInput if the agent is not locked
+    pub fn release_from_escrow(env: Env, agent_id: u64) -> Result<(), ContractError> {
+        if agent_id == 0 {
+            return Err(ContractError::InvalidAgentId);
+        }
+
+        let key = Self::get_agent_key(&env, agent_id);
+        let mut agent: Agent = env
+            .storage()
+            .instance()
+            .get(&key)
+            .ok_or(ContractError::AgentNotFound)?;
+
+        let holder = match (agent.escrow_locked, agent.escrow_holder.clone()) {
+            (true, Some(holder)) => holder,
+            _ => return Err(ContractError::InvalidInput),
+        };
+        holder.require_auth();
+
+        agent.escrow_locked = false;
+        agent.escrow_holder = None;
+        agent.updated_at = env.ledger().timestamp();
+        env.storage().instance().set(&key, &agent);
+
+        env.events().publish(
+            (Symbol::new(&env, "agent_nft"), AgentEvent::EscrowReleased),
+            (agent_id, holder),
+        );
+
+        Ok(())
+    }
+
+    /// Start leasing an agent
+    pub fn start_lease(env: Env, agent_id: u64) -> Result<(), ContractError> {
+        if agent_id == 0 {
+            return Err(ContractError::InvalidAgentId);
+        }
+
+        Self::set_agent_lease_status(&env, agent_id, true);
+
+        env.events().publish(
+            (Symbol::new(&env, "agent_nft"), AgentEvent::LeaseStarted),
+            (agent_id, env.ledger().timestamp()),
+        );
+
+        Ok(())
+    }
+
+    /// End leasing an agent
+    pub fn end_lease(env: Env, agent_id: u64) -> Result<(), ContractError> {
+        if agent_id == 0 {
+            return Err(ContractError::InvalidAgentId);
+        }
+
+        Self::set_agent_lease_status(&env, agent_id, false);
+
+        env.events().publish(
+            (Symbol::new(&env, "agent_nft"), AgentEvent::LeaseEnded),
+            (agent_id, env.ledger().timestamp()),
+        );
+
+        Ok(())
+    }
+
+    /// Check if agent is leased
+    pub fn is_leased(env: Env, agent_id: u64) -> Result<bool, ContractError> {
+        if agent_id == 0 {
+            return Err(ContractError::InvalidAgentId);
+        }
+        Ok(Self::is_agent_leased(&env, agent_id))
+    }
+
+    /// Get royalty info for an agent
+    ///
+    /// # Arguments
+    /// * `agent_id` - The agent ID to query
+    ///
+    /// # Returns
+    /// Result<Option<RoyaltyInfo>, ContractError> - Royalty info if set, None if not set
+    ///
+    /// # Errors
+    /// - ContractError::InvalidAgentId if agent_id is 0
+    pub fn get_royalty(env: Env, agent_id: u64) -> Result<Option<RoyaltyInfo>, ContractError> {
+        if agent_id == 0 {
+            return Err(ContractError::InvalidAgentId);
+        }
+
+        let royalty_key = Self::get_royalty_key(&env, agent_id);
+        Ok(env.storage().instance().get(&royalty_key))
+    }
+
+    /// Get the royalty owed on a sale at `sale_price` (ERC-2981 style)
+    ///
+    /// Returns `(recipient, sale_price * fee / 10000)`, or `(owner, 0)` when the
+    /// agent has no royalty configured.
+    ///
+    /// # Errors
+    /// - ContractError::InvalidAgentId if agent_id is 0
+    /// - ContractError::AgentNotFound if the agent does not exist
+    /// - ContractError::InvalidAmount if sale_price is negative
+    pub fn royalty_info(
+        env: Env,
+        agent_id: u64,
+        sale_price: i128,
+    ) -> Result<(Address, i128), ContractError> {
+        if sale_price < 0 {
+            return Err(ContractError::InvalidAmount);
+        }
+        let agent = Self::get_agent(env.clone(), agent_id)?;
+
+        match Self::get_royalty(env, agent_id)? {
+            Some(royalty) => {
+                // Split the price so the multiplication cannot overflow: the fee
+                // is capped at 10000 bps, so neither partial product can exceed i128
+                let fee = royalty.fee as i128;
+                let amount = (sale_price / 10000) * fee + (sale_price % 10000) * fee / 10000;
+                Ok((royalty.recipient, amount))
+            }
+            None => Ok((agent.owner, 0)),
+        }
+    }
+
+    /// Get an agent together with its royalty info and lease status in one read
+    ///
+    /// # Errors
+    /// - ContractError::InvalidAgentId if agent_id is 0
+    /// - ContractError::AgentNotFound if the agent does not exist
+    pub fn get_agent_full(env: Env, agent_id: u64) -> Result<AgentFullView, ContractError> {
+        let agent = Self::get_agent(env.clone(), agent_id)?;
+        let royalty = Self::get_royalty(env.clone(), agent_id)?;
+        let is_leased = Self::is_leased(env, agent_id)?;
+
+        Ok(AgentFullView {
+            agent,
+            royalty_recipient: royalty.as_ref().map(|info| info.recipient.clone()),
+            royalty_fee: royalty.map(|info| info.fee),
+            is_leased,
+        })
+    }
+
+    /// Set or replace royalty info for an agent after mint
+    ///
+    /// # Errors
+    /// - ContractError::AgentNotFound if the agent does not exist
+    /// - ContractError::NotOwner if caller is not the owner
+    /// - ContractError::InvalidRoyaltyFee if royalty fee exceeds the configured cap
+    pub fn set_royalty(
+        env: Env,
+        agent_id: u64,
+        owner: Address,
+        recipient: Address,
+        fee: u32,
+    ) -> Result<(), ContractError> {
+        owner.require_auth();
+
+        if agent_id == 0 {
+            return Err(ContractError::InvalidAgentId);
+        }
+
+        let agent: Agent = env
+            .storage()
+            .instance()
+            .get(&Self::get_agent_key(&env, agent_id))
+            .ok_or(ContractError::AgentNotFound)?;
+        if agent.owner != owner {
+            return Err(ContractError::NotOwner);
+        }
+
+        Self::validate_royalty_fee(&env, fee)?;
+        Self::store_royalty(&env, agent_id, recipient, fee);
+
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Tests for Issue #6: Read-only agent query functions
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::{
+        testutils::{Address as _, Events, Ledger},
+        Env, IntoVal, Val,
+    };
+
+    pub fn setup_contract(env: &Env) -> (AgentNFTClient<'_>, Address) {
+        let contract_id = env.register(AgentNFT, ());
+        let client = AgentNFTClient::new(env, &contract_id);
+        let admin = Address::generate(env);
+
+        env.mock_all_auths();
+        client.init_contract(&admin);
+
+        (client, admin)
+    }
+
+    pub fn mint_test_agent(
+        env: &Env,
+        client: &AgentNFTClient,
+        owner: &Address,
+        agent_id: u128,
+        metadata_cid: &str,
+        evolution_level: u32,
+    ) {
+        let metadata = String::from_str(env, metadata_cid);
+        // Added 'None' for royalty_recipient and 'None' for royalty_fee
+        client.mint_agent(
+            &agent_id,
+            owner,
+            &metadata,
+            &evolution_level,
+            &None, // royalty_recipient
+            &None, // royalty_fee
+        );
+    }
+
+    #[test]
+    fn test_get_agent_owner() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+
+        let owner = Address::generate(&env);
+        client.add_approved_minter(&admin, &owner);
+
+        env.mock_all_auths();
+        mint_test_agent(&env, &client, &owner, 1, "QmTestCID123", 1);
+
+        // Test get_agent_owner returns correct owner
+        let result = client.get_agent_owner(&1);
+        assert_eq!(result, owner);
+    }
+
+    #[test]
+    fn test_get_agent_metadata() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+
+        let owner = Address::generate(&env);
+        client.add_approved_minter(&admin, &owner);
+
+        let metadata_cid = "QmTestMetadataCID456";
+        env.mock_all_auths();
+        mint_test_agent(&env, &client, &owner, 2, metadata_cid, 5);
+
+        // Test get_agent_metadata returns correct CID
+        let result = client.get_agent_metadata(&2);
+        assert_eq!(result, String::from_str(&env, metadata_cid));
+    }
+
+    #[test]
+    fn test_get_agent_evolution_level() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+
+        let owner = Address::generate(&env);
+        client.add_approved_minter(&admin, &owner);
+
+        let evolution_level = 7u32;
+        env.mock_all_auths();
+        mint_test_agent(&env, &client, &owner, 3, "QmEvolutionTest", evolution_level);
+
+        // Test get_agent_evolution_level returns correct level
+        let result = client.get_agent_evolution_level(&3);
+        assert_eq!(result, evolution_level);
+    }
+
+    #[test]
+    fn test_query_non_existent_agent() {
+        let env = Env::default();
+        let (client, _admin) = setup_contract(&env);
+
+        // Try to query a non-existent agent - should return AgentNotFound
+        let result = client.try_get_agent_owner(&999);
+        assert!(result.is_err());
+
+        let result = client.try_get_agent_metadata(&999);
+        assert!(result.is_err());
+
+        let result = client.try_get_agent_evolution_level(&999);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_query_zero_agent_id() {
+        let env = Env::default();
+        let (client, _admin) = setup_contract(&env);
+
+        // Query with agent_id = 0 should return InvalidAgentId
+        let result = client.try_get_agent_owner(&0);
+        assert!(result.is_err());
+
+        let result = client.try_get_agent_metadata(&0);
+        assert!(result.is_err());
+
+        let result = client.try_get_agent_evolution_level(&0);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_query_functions_no_state_mutation() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+
+        let owner = Address::generate(&env);
+        client.add_approved_minter(&admin, &owner);
+
+        env.mock_all_auths();
+        mint_test_agent(&env, &client, &owner, 4, "QmImmutableTest", 3);
+
+        // Get initial state
+        let initial_owner = client.get_agent_owner(&4);
+        let initial_metadata = client.get_agent_metadata(&4);
+        let initial_level = client.get_agent_evolution_level(&4);
+
+        // Call query functions multiple times
+        for _ in 0..5 {
+            client.get_agent_owner(&4);
+            client.get_agent_metadata(&4);
+            client.get_agent_evolution_level(&4);
+        }
+
+        // Verify state remains unchanged
+        assert_eq!(client.get_agent_owner(&4), initial_owner);
+        assert_eq!(client.get_agent_metadata(&4), initial_metadata);
+        assert_eq!(client.get_agent_evolution_level(&4), initial_level);
+    }
+
+    #[test]
+    fn test_lock_in_escrow() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+
+        let owner = Address::generate(&env);
+        let escrow = Address::generate(&env);
+        client.add_approved_minter(&admin, &owner);
+        client.add_approved_escrow(&admin, &escrow);
+        mint_test_agent(&env, &client, &owner, 5, "QmEscrowTest", 1);
+
+        client.lock_in_escrow(&5, &escrow);
+
+        let agent = client.get_agent(&5);
+        assert!(agent.escrow_locked);
+        assert_eq!(agent.escrow_holder, Some(escrow.clone()));
+
+        // Locking twice is rejected
+        let result = client.try_lock_in_escrow(&5, &escrow);
+        assert_eq!(result, Err(Ok(ContractError::AgentEscrowLocked)));
+
+        // Unapproved contracts cannot lock
+        mint_test_agent(&env, &client, &owner, 6, "QmEscrowTest2", 1);
+        let result = client.try_lock_in_escrow(&6, &Address::generate(&env));
+        assert_eq!(result, Err(Ok(ContractError::Unauthorized)));
+    }
+
+    #[test]
+    fn test_escrow_blocks_transfer_and_update() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+
+        let owner = Address::generate(&env);
+        let buyer = Address::generate(&env);
+        let escrow = Address::generate(&env);
+        client.add_approved_minter(&admin, &owner);
+        client.add_approved_escrow(&admin, &escrow);
+        mint_test_agent(&env, &client, &owner, 7, "QmEscrowBlocked", 1);
+
+        client.lock_in_escrow(&7, &escrow);
+
+        assert!(!client.can_transfer_agent(&7, &owner));
+        let result = client.try_transfer_agent(&7, &owner, &buyer);
+        assert_eq!(result, Err(Ok(ContractError::AgentEscrowLocked)));
+
+        let result =
+            client.try_update_agent(&7, &owner, &Some(String::from_str(&env, "New")), &None);
+        assert_eq!(result, Err(Ok(ContractError::AgentEscrowLocked)));
+    }
+
+    #[test]
+    fn test_release_from_escrow_restores_transfer() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+
+        let owner = Address::generate(&env);
+        let buyer = Address::generate(&env);
+        let escrow = Address::generate(&env);
+        client.add_approved_minter(&admin, &owner);
+        client.add_approved_escrow(&admin, &escrow);
+        mint_test_agent(&env, &client, &owner, 8, "QmEscrowRelease", 1);
+
+        client.lock_in_escrow(&8, &escrow);
+        client.release_from_escrow(&8);
+
+        let agent = client.get_agent(&8);
+        assert!(!agent.escrow_locked);
+        assert_eq!(agent.escrow_holder, None);
+        assert!(client.can_transfer_agent(&8, &owner));
+
+        client.transfer_agent(&8, &owner, &buyer);
+        assert_eq!(client.get_agent_owner(&8), buyer);
+
+        // Releasing an unlocked agent is rejected
+        let result = client.try_release_from_escrow(&8);
+        assert_eq!(result, Err(Ok(ContractError::InvalidInput)));
+    }
+
+    #[test]
+    fn test_batch_transfer() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+
+        let owner = Address::generate(&env);
+        let new_owner = Address::generate(&env);
+        client.add_approved_minter(&admin, &owner);
+        mint_test_agent(&env, &client, &owner, 10, "QmBatch10", 1);
+        mint_test_agent(&env, &client, &owner, 11, "QmBatch11", 1);
+        mint_test_agent(&env, &client, &owner, 12, "QmBatch12", 1);
+
+        let mut transfers = Vec::new(&env);
+        transfers.push_back((10u64, new_owner.clone()));
+        transfers.push_back((11u64, new_owner.clone()));
+        transfers.push_back((12u64, new_owner.clone()));
+        client.batch_transfer(&owner, &transfers);
+
+        for agent_id in 10..=12u64 {
+            assert_eq!(client.get_agent_owner(&agent_id), new_owner);
+            assert_eq!(client.get_nonce(&agent_id), 1);
+        }
+    }
+
+    #[test]
+    fn test_batch_transfer_rejects_unowned_agent() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+
+        let owner = Address::generate(&env);
+        let other = Address::generate(&env);
+        let new_owner = Address::generate(&env);
+        client.add_approved_minter(&admin, &owner);
+        client.add_approved_minter(&admin, &other);
+        mint_test_agent(&env, &client, &owner, 13, "QmBatch13", 1);
+        mint_test_agent(&env, &client, &other, 14, "QmBatch14", 1);
+
+        let mut transfers = Vec::new(&env);
+        transfers.push_back((13u64, new_owner.clone()));
+        transfers.push_back((14u64, new_owner.clone()));
+        let result = client.try_batch_transfer(&owner, &transfers);
+        assert_eq!(result, Err(Ok(ContractError::NotOwner)));
+
+        // Nothing was transferred
+        assert_eq!(client.get_agent_owner(&13), owner);
+        assert_eq!(client.get_agent_owner(&14), other);
+        assert_eq!(client.get_nonce(&13), 0);
+    }
+
+    #[test]
+    fn test_batch_transfer_rejects_leased_agent() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+
+        let owner = Address::generate(&env);
+        let new_owner = Address::generate(&env);
+        client.add_approved_minter(&admin, &owner);
+        mint_test_agent(&env, &client, &owner, 15, "QmBatch15", 1);
+        mint_test_agent(&env, &client, &owner, 16, "QmBatch16", 1);
+        client.start_lease(&16);
+
+        let mut transfers = Vec::new(&env);
+        transfers.push_back((15u64, new_owner.clone()));
+        transfers.push_back((16u64, new_owner.clone()));
+        let result = client.try_batch_transfer(&owner, &transfers);
+        assert_eq!(result, Err(Ok(ContractError::AgentLeased)));
+
+        assert_eq!(client.get_agent_owner(&15), owner);
+        assert_eq!(client.get_agent_owner(&16), owner);
+    }
+
+    // Stands in for ExecutionHub reporting execution outcomes
+    #[contract]
+    pub struct MockExecutionHub;
+
+    #[contractimpl]
+    impl MockExecutionHub {
+        pub fn report(env: Env, agent_nft: Address, agent_id: u64, success: bool) -> u64 {
+            AgentNFTClient::new(&env, &agent_nft).update_reputation(
+                &env.current_contract_address(),
+                &agent_id,
+                &success,
+            )
+        }
+    }
+
+    #[test]
+    fn test_reputation_updated_by_execution_hub() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+
+        let owner = Address::generate(&env);
+        client.add_approved_minter(&admin, &owner);
+        mint_test_agent(&env, &client, &owner, 20, "QmRep20", 1);
+
+        let hub = env.register(MockExecutionHub, ());
+        client.set_execution_hub(&admin, &hub);
+        let hub_client = MockExecutionHubClient::new(&env, &hub);
+
+        assert_eq!(client.get_agent_reputation(&20), 0);
+        hub_client.report(&client.address, &20, &true);
+        hub_client.report(&client.address, &20, &true);
+        assert_eq!(client.get_agent_reputation(&20), 2);
+
+        hub_client.report(&client.address, &20, &false);
+        assert_eq!(client.get_agent_reputation(&20), 1);
+    }
+
+    #[test]
+    fn test_reputation_rejects_unauthorized_caller() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+
+        let owner = Address::generate(&env);
+        client.add_approved_minter(&admin, &owner);
+        mint_test_agent(&env, &client, &owner, 21, "QmRep21", 1);
+
+        let hub = env.register(MockExecutionHub, ());
+        client.set_execution_hub(&admin, &hub);
+
+        let result = client.try_update_reputation(&owner, &21, &true);
+        assert_eq!(result, Err(Ok(ContractError::Unauthorized)));
+        assert_eq!(client.get_agent_reputation(&21), 0);
+    }
+    #[test]
+    fn test_get_agent_by_cid() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+        let owner = Address::generate(&env);
+        client.add_approved_minter(&admin, &owner);
+
+        mint_test_agent(&env, &client, &owner, 30, "QmLookup30", 1);
+        mint_test_agent(&env, &client, &owner, 31, "QmLookup31", 1);
+
+        assert_eq!(
+            client.get_agent_by_cid(&String::from_str(&env, "QmLookup31")),
+            Some(31)
+        );
+        assert_eq!(
+            client.get_agent_by_cid(&String::from_str(&env, "QmUnknown")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_mint_rejects_duplicate_cid_across_calls() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+        let owner = Address::generate(&env);
+        client.add_approved_minter(&admin, &owner);
+
+        mint_test_agent(&env, &client, &owner, 32, "QmDuplicate", 1);
+
+        let result = client.try_mint_agent(
+            &33,
+            &owner,
+            &String::from_str(&env, "QmDuplicate"),
+            &1,
+            &None,
+            &None,
+        );
+        assert_eq!(result, Err(Ok(ContractError::AlreadyExists)));
+
+        let mut batch = Vec::new(&env);
+        batch.push_back(AgentMintData {
+            owner: owner.clone(),
+            name: String::from_str(&env, "dup"),
+            model_hash: String::from_str(&env, "hash"),
+            metadata_cid: String::from_str(&env, "QmDuplicate"),
+            capabilities: Vec::new(&env),
+            royalty_recipient: None,
+            royalty_fee: None,
+        });
+        let result = client.try_batch_mint(&admin, &batch);
+        assert_eq!(result, Err(Ok(ContractError::AlreadyExists)));
+    }
+
+    #[test]
+    fn test_cid_lookup_after_burn_returns_none() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+        let owner = Address::generate(&env);
+        client.add_approved_minter(&admin, &owner);
+
+        mint_test_agent(&env, &client, &owner, 34, "QmBurned", 1);
+        client.burn_agent(&34, &owner);
+
+        let cid = String::from_str(&env, "QmBurned");
+        assert_eq!(client.get_agent_by_cid(&cid), None);
+        assert_eq!(
+            client.try_get_agent(&34),
+            Err(Ok(ContractError::AgentNotFound))
+        );
+
+        // The CID can be reused once the original agent is burned
+        mint_test_agent(&env, &client, &owner, 35, "QmBurned", 1);
+        assert_eq!(client.get_agent_by_cid(&cid), Some(35));
+    }
+
+    #[test]
+    fn test_burn_clears_reputation() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+        let owner = Address::generate(&env);
+        client.add_approved_minter(&admin, &owner);
+
+        let hub = env.register(MockExecutionHub, ());
+        client.set_execution_hub(&admin, &hub);
+        mint_test_agent(&env, &client, &owner, 36, "QmRep36", 1);
+        MockExecutionHubClient::new(&env, &hub).report(&client.address, &36, &true);
+        assert_eq!(client.get_agent_reputation(&36), 1);
+
+        client.burn_agent(&36, &owner);
+        assert_eq!(client.get_agent_reputation(&36), 0);
+
+        // A re-minted agent under the same ID starts without the old score
+        mint_test_agent(&env, &client, &owner, 36, "QmRep36b", 1);
+        assert_eq!(client.get_agent_reputation(&36), 0);
+    }
+
+    fn last_royalty_event(env: &Env) -> Option<(u64, Address, u32)> {
+        let topics: Vec<Val> =
+            (Symbol::new(env, "agent_nft"), AgentEvent::RoyaltySet).into_val(env);
+        env.events()
+            .all()
+            .iter()
+            .filter(|(_, event_topics, _)| *event_topics == topics)
+            .last()
+            .map(|(_, _, data)| data.into_val(env))
+    }
+
+    #[test]
+    fn test_mint_with_royalty_emits_royalty_set() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+        let owner = Address::generate(&env);
+        let recipient = Address::generate(&env);
+        client.add_approved_minter(&admin, &owner);
+
+        client.mint_agent(
+            &36,
+            &owner,
+            &String::from_str(&env, "QmRoyalty36"),
+            &1,
+            &Some(recipient.clone()),
+            &Some(250),
+        );
+
+        assert_eq!(last_royalty_event(&env), Some((36, recipient, 250)));
+    }
+
+    #[test]
+    fn test_set_royalty_updates_info_and_emits_event() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+        let owner = Address::generate(&env);
+        let recipient = Address::generate(&env);
+        client.add_approved_minter(&admin, &owner);
+        mint_test_agent(&env, &client, &owner, 37, "QmRoyalty37", 1);
+
+        client.set_royalty(&37, &owner, &recipient, &500);
+        assert_eq!(last_royalty_event(&env), Some((37, recipient.clone(), 500)));
+
+        let royalty = client.get_royalty(&37).unwrap();
+        assert_eq!(royalty.recipient, recipient);
+        assert_eq!(royalty.fee, 500);
     }
 
-    /// Check if agent can be transferred
-    pub fn can_transfer_agent(env: Env, agent_id: u64, caller: Address) -> bool {
-        if agent_id == 0 {
-            return false;
-        }
+    #[test]
+    fn test_set_royalty_requires_owner() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+        let owner = Address::generate(&env);
+        let stranger = Address::generate(&env);
+        client.add_approved_minter(&admin, &owner);
+        mint_test_agent(&env, &client, &owner, 38, "QmRoyalty38", 1);
 
-        let key = Self::get_agent_key(&env, agent_id);
-        let agent = match env.storage().instance().get::<_, Agent>(&key) {
-            Some(agent) => agent,
-            None => return false,
-        };
+        let result = client.try_set_royalty(&38, &stranger, &stranger, &500);
+        assert_eq!(result, Err(Ok(ContractError::NotOwner)));
+        assert!(client.get_royalty(&38).is_none());
+    }
 
-        if agent.owner != caller {
-            return false;
-        }
+    #[test]
+    fn test_get_agent_full_without_royalty_or_lease() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+        let owner = Address::generate(&env);
+        client.add_approved_minter(&admin, &owner);
+        mint_test_agent(&env, &client, &owner, 39, "QmFullView39", 2);
+
+        let view = client.get_agent_full(&39);
+        let agent = client.get_agent(&39);
+        assert_eq!(view.agent.id, agent.id);
+        assert_eq!(view.agent.owner, agent.owner);
+        assert_eq!(view.agent.metadata_cid, agent.metadata_cid);
+        assert_eq!(view.agent.evolution_level, agent.evolution_level);
+        assert!(view.royalty_recipient.is_none());
+        assert!(view.royalty_fee.is_none());
+        assert_eq!(view.is_leased, client.is_leased(&39));
+        assert!(!view.is_leased);
+    }
 
-        !Self::is_agent_leased(&env, agent_id)
+    #[test]
+    fn test_get_agent_full_with_royalty_and_lease() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+        let owner = Address::generate(&env);
+        let recipient = Address::generate(&env);
+        client.add_approved_minter(&admin, &owner);
+        mint_test_agent(&env, &client, &owner, 40, "QmFullView40", 1);
+        client.set_royalty(&40, &owner, &recipient, &300);
+        client.start_lease(&40);
+
+        let view = client.get_agent_full(&40);
+        let expected = client.get_royalty(&40).unwrap();
+        assert_eq!(view.royalty_recipient, Some(expected.recipient));
+        assert_eq!(view.royalty_fee, Some(expected.fee));
+        assert_eq!(view.agent.owner, client.get_agent_owner(&40));
+        assert!(view.is_leased);
+
+        client.end_lease(&40);
+        assert!(!client.get_agent_full(&40).is_leased);
     }
 
-    /// Start leasing an agent
-    pub fn start_lease(env: Env, agent_id: u64) -> Result<(), ContractError> {
-        if agent_id == 0 {
-            return Err(ContractError::InvalidAgentId);
-        }
+    #[test]
+    fn test_get_agent_full_missing_agent() {
+        let env = Env::default();
+        let (client, _admin) = setup_contract(&env);
 
-        Self::set_agent_lease_status(&env, agent_id, true);
+        assert_eq!(
+            client.try_get_agent_full(&99).unwrap_err(),
+            Ok(ContractError::AgentNotFound)
+        );
+        assert_eq!(
+            client.try_get_agent_full(&0).unwrap_err(),
+            Ok(ContractError::InvalidAgentId)
+        );
+    }
 
-        env.events().publish(
-            (Symbol::new(&env, "agent_nft"), AgentEvent::LeaseStarted),
-            (agent_id, env.ledger().timestamp()),
+    #[test]
+    fn test_royalty_above_cap_rejected() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+        let owner = Address::generate(&env);
+        let recipient = Address::generate(&env);
+        client.add_approved_minter(&admin, &owner);
+
+        assert_eq!(client.get_max_royalty(), 1000);
+        let result = client.try_mint_agent(
+            &41,
+            &owner,
+            &String::from_str(&env, "QmRoyalty41"),
+            &1,
+            &Some(recipient.clone()),
+            &Some(1001),
         );
+        assert_eq!(result, Err(Ok(ContractError::InvalidRoyaltyFee)));
 
-        Ok(())
+        mint_test_agent(&env, &client, &owner, 42, "QmRoyalty42", 1);
+        let result = client.try_set_royalty(&42, &owner, &recipient, &1001);
+        assert_eq!(result, Err(Ok(ContractError::InvalidRoyaltyFee)));
+
+        let result = client.try_set_max_royalty(&admin, &10001);
+        assert_eq!(result, Err(Ok(ContractError::InvalidRoyaltyFee)));
     }
 
-    /// End leasing an agent
-    pub fn end_lease(env: Env, agent_id: u64) -> Result<(), ContractError> {
-        if agent_id == 0 {
-            return Err(ContractError::InvalidAgentId);
-        }
+    #[test]
+    fn test_lowering_royalty_cap_affects_later_mints() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+        let owner = Address::generate(&env);
+        let recipient = Address::generate(&env);
+        client.add_approved_minter(&admin, &owner);
 
-        Self::set_agent_lease_status(&env, agent_id, false);
+        client.mint_agent(
+            &43,
+            &owner,
+            &String::from_str(&env, "QmRoyalty43"),
+            &1,
+            &Some(recipient.clone()),
+            &Some(500),
+        );
 
-        env.events().publish(
-            (Symbol::new(&env, "agent_nft"), AgentEvent::LeaseEnded),
-            (agent_id, env.ledger().timestamp()),
+        client.set_max_royalty(&admin, &200);
+        let result = client.try_mint_agent(
+            &44,
+            &owner,
+            &String::from_str(&env, "QmRoyalty44"),
+            &1,
+            &Some(recipient.clone()),
+            &Some(500),
         );
+        assert_eq!(result, Err(Ok(ContractError::InvalidRoyaltyFee)));
 
-        Ok(())
+        // Existing royalties are untouched
+        assert_eq!(client.get_royalty(&43).unwrap().fee, 500);
     }
 
-    /// Check if agent is leased
-    pub fn is_leased(env: Env, agent_id: u64) -> Result<bool, ContractError> {
-        if agent_id == 0 {
-            return Err(ContractError::InvalidAgentId);
+    fn capability_list(env: &Env, names: &[&str]) -> Vec<String> {
+        let mut caps = Vec::new(env);
+        for name in names {
+            caps.push_back(String::from_str(env, name));
         }
-        Ok(Self::is_agent_leased(&env, agent_id))
+        caps
     }
 
-    /// Get royalty info for an agent
-    ///
-    /// # Arguments
-    /// * `agent_id` - The agent ID to query
-    ///
-    /// # Returns
-    /// Result<Option<RoyaltyInfo>, ContractError> - Royalty info if set, None if not set
-    ///
-    /// # Errors
-    /// - ContractError::InvalidAgentId if agent_id is 0
-    pub fn get_royalty(env: Env, agent_id: u64) -> Result<Option<RoyaltyInfo>, ContractError> {
-        if agent_id == 0 {
-            return Err(ContractError::InvalidAgentId);
-        }
-
-        let royalty_key = Self::get_royalty_key(&env, agent_id);
-        Ok(env.storage().instance().get(&royalty_key))
+    #[test]
+    fn test_mint_with_registered_capability() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+        let owner = Address::generate(&env);
+        client.add_approved_minter(&admin, &owner);
+        client.register_capability(&admin, &String::from_str(&env, "vision"));
+
+        let agent_id = client.mint_agent_legacy(
+            &owner,
+            &String::from_str(&env, "Agent"),
+            &String::from_str(&env, "Hash"),
+            &capability_list(&env, &["vision"]),
+            &None,
+            &None,
+        );
+        assert_eq!(client.get_agent(&agent_id).capabilities.len(), 1);
+        assert_eq!(
+            client.get_capabilities(),
+            capability_list(&env, &["vision"])
+        );
     }
-}
-
-// ============================================================================
-// Tests for Issue #6: Read-only agent query functions
-// ============================================================================
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use soroban_sdk::{testutils::Address as _, Env};
-
-    pub fn setup_contract(env: &Env) -> (AgentNFTClient, Address) {
-        let contract_id = env.register_contract(None, AgentNFT);
-        let client = AgentNFTClient::new(env, &contract_id);
-        let admin = Address::generate(env);
 
-        env.mock_all_auths();
-        client.init_contract(&admin);
+    #[test]
+    fn test_unregistered_capability_rejected() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+        let owner = Address::generate(&env);
+        client.add_approved_minter(&admin, &owner);
+        client.register_capability(&admin, &String::from_str(&env, "vision"));
+
+        let result = client.try_mint_agent_legacy(
+            &owner,
+            &String::from_str(&env, "Agent"),
+            &String::from_str(&env, "Hash"),
+            &capability_list(&env, &["Vision"]),
+            &None,
+            &None,
+        );
+        assert_eq!(result, Err(Ok(ContractError::InvalidInput)));
+
+        mint_test_agent(&env, &client, &owner, 45, "QmCaps45", 1);
+        let result = client.try_update_agent(
+            &45,
+            &owner,
+            &None,
+            &Some(capability_list(&env, &["vision", "audio"])),
+        );
+        assert_eq!(result, Err(Ok(ContractError::InvalidInput)));
 
-        (client, admin)
+        let result = client.try_register_capability(&admin, &String::from_str(&env, "vision"));
+        assert_eq!(result, Err(Ok(ContractError::AlreadyExists)));
     }
 
-    pub fn mint_test_agent(
-        env: &Env,
-        client: &AgentNFTClient,
-        owner: &Address,
-        agent_id: u128,
-        metadata_cid: &str,
-        evolution_level: u32,
-    ) {
-        let metadata = String::from_str(&env, metadata_cid);
-        // Added 'None' for royalty_recipient and 'None' for royalty_fee
-        client.mint_agent(
+    #[test]
+    fn test_empty_capability_registry_accepts_any() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+        let owner = Address::generate(&env);
+        client.add_approved_minter(&admin, &owner);
+        assert_eq!(client.get_capabilities().len(), 0);
+
+        let agent_id = client.mint_agent_legacy(
+            &owner,
+            &String::from_str(&env, "Agent"),
+            &String::from_str(&env, "Hash"),
+            &capability_list(&env, &["anything", "goes"]),
+            &None,
+            &None,
+        );
+        client.update_agent(
             &agent_id,
-            owner,
-            &metadata,
-            &evolution_level,
-            &None, // royalty_recipient
-            &None, // royalty_fee
+            &owner,
+            &None,
+            &Some(capability_list(&env, &["still", "fine"])),
         );
     }
 
     #[test]
-    fn test_get_agent_owner() {
+    fn test_metadata_history_tracks_updates() {
         let env = Env::default();
         let (client, admin) = setup_contract(&env);
-
         let owner = Address::generate(&env);
         client.add_approved_minter(&admin, &owner);
+        mint_test_agent(&env, &client, &owner, 46, "QmHistory46", 1);
+        assert_eq!(client.get_metadata_history(&46, &10).len(), 0);
+
+        env.ledger().with_mut(|li| li.timestamp = 100);
+        client.update_agent(&46, &owner, &Some(String::from_str(&env, "Renamed")), &None);
+
+        env.ledger().with_mut(|li| li.timestamp = 200);
+        client.update_agent(
+            &46,
+            &owner,
+            &Some(String::from_str(&env, "Renamed Again")),
+            &Some(capability_list(&env, &["planning"])),
+        );
 
-        env.mock_all_auths();
-        mint_test_agent(&env, &client, &owner, 1, "QmTestCID123", 1);
+        // Unchanged values do not add entries
+        client.update_agent(
+            &46,
+            &owner,
+            &Some(String::from_str(&env, "Renamed Again")),
+            &None,
+        );
 
-        // Test get_agent_owner returns correct owner
-        let result = client.get_agent_owner(&1);
-        assert_eq!(result, owner);
+        let history = client.get_metadata_history(&46, &10);
+        assert_eq!(history.len(), 3);
+        let first = history.get(0).unwrap();
+        assert_eq!(first.field_changed, String::from_str(&env, "name"));
+        assert_eq!(first.timestamp, 100);
+        assert_eq!(first.nonce, 1);
+        let second = history.get(1).unwrap();
+        assert_eq!(second.field_changed, String::from_str(&env, "name"));
+        assert_eq!(second.nonce, 2);
+        let third = history.get(2).unwrap();
+        assert_eq!(third.field_changed, String::from_str(&env, "capabilities"));
+        assert_eq!(third.timestamp, 200);
+        assert_eq!(third.nonce, 2);
+
+        let latest = client.get_metadata_history(&46, &1);
+        assert_eq!(latest.len(), 1);
+        assert_eq!(latest.get(0).unwrap(), third);
+    }
+
+    // Stands in for the Evolution contract completing an upgrade
+    #[contract]
+    pub struct MockEvolution;
+
+    #[contractimpl]
+    impl MockEvolution {
+        pub fn evolve(
+            env: Env,
+            agent_nft: Address,
+            agent_id: u64,
+            level: u32,
+            model_hash: Option<String>,
+        ) {
+            AgentNFTClient::new(&env, &agent_nft).apply_evolution(
+                &env.current_contract_address(),
+                &agent_id,
+                &level,
+                &model_hash,
+            );
+        }
     }
 
     #[test]
-    fn test_get_agent_metadata() {
+    fn test_metadata_history_tracks_evolution() {
         let env = Env::default();
         let (client, admin) = setup_contract(&env);
-
         let owner = Address::generate(&env);
         client.add_approved_minter(&admin, &owner);
+        mint_test_agent(&env, &client, &owner, 48, "QmHistory48", 1);
+
+        let evolution = env.register(MockEvolution, ());
+        client.set_evolution_contract(&admin, &evolution);
+        let evolution_client = MockEvolutionClient::new(&env, &evolution);
+
+        env.ledger().with_mut(|li| li.timestamp = 300);
+        evolution_client.evolve(&client.address, &48, &2, &None);
+        evolution_client.evolve(
+            &client.address,
+            &48,
+            &3,
+            &Some(String::from_str(&env, "model_v3")),
+        );
 
-        let metadata_cid = "QmTestMetadataCID456";
-        env.mock_all_auths();
-        mint_test_agent(&env, &client, &owner, 2, metadata_cid, 5);
+        let agent = client.get_agent(&48);
+        assert_eq!(agent.evolution_level, 3);
+        assert_eq!(agent.model_hash, String::from_str(&env, "model_v3"));
 
-        // Test get_agent_metadata returns correct CID
-        let result = client.get_agent_metadata(&2);
-        assert_eq!(result, String::from_str(&env, metadata_cid));
+        let history = client.get_metadata_history(&48, &10);
+        assert_eq!(history.len(), 3);
+        let first = history.get(0).unwrap();
+        assert_eq!(
+            first.field_changed,
+            String::from_str(&env, "evolution_level")
+        );
+        assert_eq!(first.timestamp, 300);
+        assert_eq!(first.nonce, 1);
+        let second = history.get(1).unwrap();
+        assert_eq!(second.field_changed, String::from_str(&env, "model_hash"));
+        assert_eq!(second.nonce, 2);
+        let third = history.get(2).unwrap();
+        assert_eq!(
+            third.field_changed,
+            String::from_str(&env, "evolution_level")
+        );
+        assert_eq!(third.nonce, 2);
     }
 
     #[test]
-    fn test_get_agent_evolution_level() {
+    fn test_apply_evolution_rejects_unauthorized_caller() {
         let env = Env::default();
         let (client, admin) = setup_contract(&env);
-
         let owner = Address::generate(&env);
         client.add_approved_minter(&admin, &owner);
+        mint_test_agent(&env, &client, &owner, 49, "QmHistory49", 1);
 
-        let evolution_level = 7u32;
-        env.mock_all_auths();
-        mint_test_agent(&env, &client, &owner, 3, "QmEvolutionTest", evolution_level);
+        let evolution = env.register(MockEvolution, ());
+        client.set_evolution_contract(&admin, &evolution);
 
-        // Test get_agent_evolution_level returns correct level
-        let result = client.get_agent_evolution_level(&3);
-        assert_eq!(result, evolution_level);
+        let result = client.try_apply_evolution(&owner, &49, &5, &None);
+        assert_eq!(result, Err(Ok(ContractError::Unauthorized)));
+        assert_eq!(client.get_agent_evolution_level(&49), 1);
+        assert_eq!(client.get_metadata_history(&49, &10).len(), 0);
     }
 
     #[test]
-    fn test_query_non_existent_agent() {
+    fn test_metadata_history_is_bounded() {
         let env = Env::default();
-        let (client, _admin) = setup_contract(&env);
+        let (client, admin) = setup_contract(&env);
+        let owner = Address::generate(&env);
+        client.add_approved_minter(&admin, &owner);
+        mint_test_agent(&env, &client, &owner, 47, "QmHistory47", 1);
 
-        // Try to query a non-existent agent - should return AgentNotFound
-        let result = client.try_get_agent_owner(&999);
-        assert!(result.is_err());
+        let names = ["a", "b"];
+        for i in 0..(MAX_METADATA_HISTORY + 5) {
+            let name = String::from_str(&env, names[(i % 2) as usize]);
+            client.update_agent(&47, &owner, &Some(name), &None);
+        }
 
-        let result = client.try_get_agent_metadata(&999);
-        assert!(result.is_err());
+        let history = client.get_metadata_history(&47, &(MAX_METADATA_HISTORY * 2));
+        assert_eq!(history.len(), MAX_METADATA_HISTORY);
+        assert_eq!(history.get(0).unwrap().nonce, 6);
+        assert_eq!(
+            history.get(MAX_METADATA_HISTORY - 1).unwrap().nonce,
+            (MAX_METADATA_HISTORY + 5) as u64
+        );
+    }
 
-        let result = client.try_get_agent_evolution_level(&999);
-        assert!(result.is_err());
+    #[test]
+    fn test_remove_approved_minter_revokes_minting() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+        let minter = Address::generate(&env);
+        client.add_approved_minter(&admin, &minter);
+        mint_test_agent(&env, &client, &minter, 48, "QmMinter48", 1);
+
+        client.remove_approved_minter(&admin, &minter);
+
+        let topics: Vec<Val> =
+            (Symbol::new(&env, "agent_nft"), AgentEvent::MinterRemoved).into_val(&env);
+        let removed: Option<Address> = env
+            .events()
+            .all()
+            .iter()
+            .filter(|(_, event_topics, _)| *event_topics == topics)
+            .last()
+            .map(|(_, _, data)| data.into_val(&env));
+        assert_eq!(removed, Some(minter.clone()));
+
+        let result = client.try_mint_agent(
+            &49,
+            &minter,
+            &String::from_str(&env, "QmMinter49"),
+            &1,
+            &None,
+            &None,
+        );
+        assert_eq!(result, Err(Ok(ContractError::Unauthorized)));
     }
 
     #[test]
-    fn test_query_zero_agent_id() {
+    fn test_remove_unknown_minter_fails() {
         let env = Env::default();
-        let (client, _admin) = setup_contract(&env);
+        let (client, admin) = setup_contract(&env);
+        let stranger = Address::generate(&env);
 
-        // Query with agent_id = 0 should return InvalidAgentId
-        let result = client.try_get_agent_owner(&0);
-        assert!(result.is_err());
+        let result = client.try_remove_approved_minter(&admin, &stranger);
+        assert_eq!(result, Err(Ok(ContractError::InvalidInput)));
+    }
 
-        let result = client.try_get_agent_metadata(&0);
-        assert!(result.is_err());
+    #[test]
+    fn test_get_approved_minters_lists_current_set() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+        let first = Address::generate(&env);
+        let second = Address::generate(&env);
+        assert_eq!(client.get_approved_minters().len(), 0);
 
-        let result = client.try_get_agent_evolution_level(&0);
-        assert!(result.is_err());
+        client.add_approved_minter(&admin, &first);
+        client.add_approved_minter(&admin, &second);
+        client.remove_approved_minter(&admin, &first);
+
+        let minters = client.get_approved_minters();
+        assert_eq!(minters.len(), 1);
+        assert_eq!(minters.get(0).unwrap(), second);
     }
 
     #[test]
-    fn test_query_functions_no_state_mutation() {
+    fn test_minter_quota_limits_mints() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+        let minter = Address::generate(&env);
+        client.add_approved_minter(&admin, &minter);
+        client.set_minter_quota(&admin, &minter, &2);
+
+        mint_test_agent(&env, &client, &minter, 50, "QmQuota50", 1);
+        client.mint_agent_legacy(
+            &minter,
+            &String::from_str(&env, "Agent"),
+            &String::from_str(&env, "Hash"),
+            &Vec::new(&env),
+            &None,
+            &None,
+        );
+        assert_eq!(client.get_minter_usage(&minter), (2, 2));
+
+        let result = client.try_mint_agent(
+            &51,
+            &minter,
+            &String::from_str(&env, "QmQuota51"),
+            &1,
+            &None,
+            &None,
+        );
+        assert_eq!(result, Err(Ok(ContractError::RateLimitExceeded)));
+        assert_eq!(client.get_minter_usage(&minter), (2, 2));
+    }
+
+    #[test]
+    fn test_minter_quota_applies_to_batch_and_can_be_raised() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+        let minter = Address::generate(&env);
+        client.add_approved_minter(&admin, &minter);
+        client.set_minter_quota(&admin, &minter, &1);
+
+        let mut batch = Vec::new(&env);
+        for cid in ["QmQuotaBatchA", "QmQuotaBatchB"] {
+            batch.push_back(AgentMintData {
+                owner: minter.clone(),
+                name: String::from_str(&env, "Agent"),
+                model_hash: String::from_str(&env, "Hash"),
+                metadata_cid: String::from_str(&env, cid),
+                capabilities: Vec::new(&env),
+                royalty_recipient: None,
+                royalty_fee: None,
+            });
+        }
+        let result = client.try_batch_mint(&minter, &batch);
+        assert_eq!(result, Err(Ok(ContractError::RateLimitExceeded)));
+
+        client.set_minter_quota(&admin, &minter, &3);
+        assert_eq!(client.batch_mint(&minter, &batch).len(), 2);
+        assert_eq!(client.get_minter_usage(&minter), (2, 3));
+    }
+
+    #[test]
+    fn test_unset_minter_quota_is_unlimited() {
         let env = Env::default();
         let (client, admin) = setup_contract(&env);
+        let minter = Address::generate(&env);
+        client.add_approved_minter(&admin, &minter);
+
+        for i in 0..3u128 {
+            let cid = ["QmFree0", "QmFree1", "QmFree2"][i as usize];
+            mint_test_agent(&env, &client, &minter, 52 + i, cid, 1);
+        }
+        assert_eq!(client.get_minter_usage(&minter), (3, 0));
+    }
 
+    #[test]
+    fn test_royalty_info_scales_with_sale_price() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
         let owner = Address::generate(&env);
+        let recipient = Address::generate(&env);
         client.add_approved_minter(&admin, &owner);
+        mint_test_agent(&env, &client, &owner, 60, "QmRoyaltyInfo60", 1);
+        client.set_royalty(&60, &owner, &recipient, &500);
 
-        env.mock_all_auths();
-        mint_test_agent(&env, &client, &owner, 4, "QmImmutableTest", 3);
+        assert_eq!(client.royalty_info(&60, &0), (recipient.clone(), 0));
+        assert_eq!(client.royalty_info(&60, &1_000), (recipient.clone(), 50));
+        assert_eq!(client.royalty_info(&60, &12_345), (recipient.clone(), 617));
 
-        // Get initial state
-        let initial_owner = client.get_agent_owner(&4);
-        let initial_metadata = client.get_agent_metadata(&4);
-        let initial_level = client.get_agent_evolution_level(&4);
+        // sale_price * fee would overflow i128 if computed directly
+        let (_, amount) = client.royalty_info(&60, &i128::MAX);
+        assert_eq!(amount, i128::MAX / 20);
 
-        // Call query functions multiple times
-        for _ in 0..5 {
-            client.get_agent_owner(&4);
-            client.get_agent_metadata(&4);
-            client.get_agent_evolution_level(&4);
-        }
+        assert_eq!(
+            client.try_royalty_info(&60, &-1),
+            Err(Ok(ContractError::InvalidAmount))
+        );
+    }
 
-        // Verify state remains unchanged
-        assert_eq!(client.get_agent_owner(&4), initial_owner);
-        assert_eq!(client.get_agent_metadata(&4), initial_metadata);
-        assert_eq!(client.get_agent_evolution_level(&4), initial_level);
+    #[test]
+    fn test_royalty_info_without_royalty_is_zero() {
+        let env = Env::default();
+        let (client, admin) = setup_contract(&env);
+        let owner = Address::generate(&env);
+        client.add_approved_minter(&admin, &owner);
+        mint_test_agent(&env, &client, &owner, 61, "QmRoyaltyInfo61", 1);
+
+        assert_eq!(client.royalty_info(&61, &1_000_000), (owner, 0));
+        assert_eq!(
+            client.try_royalty_info(&999, &1_000),
+            Err(Ok(ContractError::AgentNotFound))
+        );
     }
 }
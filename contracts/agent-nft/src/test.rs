@@ -1,39 +1,62 @@
 #[cfg(test)]
 mod prop_tests {
-    use super::*;
+    extern crate std;
+
+    use crate::tests::{mint_test_agent, setup_contract};
     use crate::{AgentNFT, AgentNFTClient, ContractError};
     use proptest::prelude::*;
-    use soroban_sdk::{testutils::Address as _, Address, Env, String, Vec};
+    use soroban_sdk::{
+        testutils::{Address as _, EnvTestConfig},
+        Address, Env, String, Vec,
+    };
+
+    // Property cases run thousands of contract invocations; skip writing a
+    // ledger snapshot for every generated case.
+    fn test_env() -> Env {
+        Env::new_with_config(EnvTestConfig {
+            capture_snapshot_at_drop: false,
+        })
+    }
 
     // --- Strategy Helpers ---
     // Generates a random valid royalty fee (0 to 10,000)
+    #[allow(dead_code)]
     fn any_royalty_fee() -> impl Strategy<Value = u32> {
         0..=10000u32
     }
 
     // Generates a vector of strings (capabilities) with length limits
-    fn any_capabilities(
-        env: &Env,
-    ) -> impl Strategy<Value = core::primitive::vec::Vec<std::string::String>> {
+    #[allow(dead_code)]
+    fn any_capabilities() -> impl Strategy<Value = std::vec::Vec<std::string::String>> {
         prop::collection::vec(".*", 0..10)
     }
 
+    // Each case deploys a contract and may mint dozens of agents, so 1000
+    // cases take several minutes in a debug build. Run 64 by default and
+    // let PROPTEST_CASES raise it for a deeper pass.
+    fn proptest_cases() -> u32 {
+        std::env::var("PROPTEST_CASES")
+            .ok()
+            .and_then(|cases| cases.parse().ok())
+            .unwrap_or(64)
+    }
+
     proptest! {
-        #![proptest_config(ProptestConfig::with_cases(1000))]
+        #![proptest_config(ProptestConfig::with_cases(proptest_cases()))]
 
         #[test]
         fn prop_agent_counter_always_increases_correctly(num_mints in 1..50usize) {
-            let env = Env::default();
-            let contract_id = env.register_contract(None, AgentNFT);
+            let env = test_env();
+            let contract_id = env.register(AgentNFT, ());
             let client = AgentNFTClient::new(&env, &contract_id);
             let admin = Address::generate(&env);
 
             env.mock_all_auths();
             client.init_contract(&admin);
 
-            let mut expected_counter = 0;
-            for _ in 0..num_mints {
+            for expected_counter in 1..=num_mints as u64 {
                 let owner = Address::generate(&env);
+                client.add_approved_minter(&admin, &owner);
                 // Using legacy mint which utilizes the counter
                 client.mint_agent_legacy(
                     &owner,
@@ -43,7 +66,6 @@ mod prop_tests {
                     &None,
                     &None
                 );
-                expected_counter += 1;
 
                 // INVARIANT: Counter must match number of successful legacy mints
                 prop_assert_eq!(client.total_agents(), expected_counter);
@@ -52,8 +74,8 @@ mod prop_tests {
 
         #[test]
         fn prop_royalty_fee_invariant(fee in 10001..u32::MAX) {
-            let env = Env::default();
-            let contract_id = env.register_contract(None, AgentNFT);
+            let env = test_env();
+            let contract_id = env.register(AgentNFT, ());
             let client = AgentNFTClient::new(&env, &contract_id);
             let admin = Address::generate(&env);
 
@@ -62,6 +84,7 @@ mod prop_tests {
 
             let owner = Address::generate(&env);
             let recipient = Address::generate(&env);
+            client.add_approved_minter(&admin, &owner);
 
             // INVARIANT: Any fee > 10000 must return InvalidRoyaltyFee error
             let result = client.try_mint_agent(
@@ -82,15 +105,15 @@ mod prop_tests {
         #[test]
         fn prop_transfer_auth_invariant(
             id in 1..100u64,
-            random_user in prop::option::of(just(true)) // dummy for randomization
+            _random_user in prop::option::of(Just(true)) // dummy for randomization
         ) {
-            let env = Env::default();
+            let env = test_env();
             env.mock_all_auths();
             let (client, admin) = setup_contract(&env);
 
             let owner = Address::generate(&env);
             let stranger = Address::generate(&env);
-            let _ = client.add_approved_minter(&admin, &owner);
+            client.add_approved_minter(&admin, &owner);
 
             mint_test_agent(&env, &client, &owner, id as u128, "cid", 1);
 
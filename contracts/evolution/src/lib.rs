@@ -6,12 +6,34 @@ use evolution_history::{
     append_evolution, get_evolution_at_index, get_evolution_count, get_evolution_history,
     get_latest_evolution, EvolutionRecord,
 };
-use soroban_sdk::{contract, contractimpl, Address, Env, String, Symbol, Vec};
+use soroban_sdk::{
+    contract, contractimpl, token, Address, Bytes, BytesN, Env, IntoVal, String, Symbol, Val, Vec,
+};
 use stellai_lib::{
     audit::{create_audit_log, OperationType},
-    EvolutionRequest, EvolutionStatus, ADMIN_KEY, REQUEST_COUNTER_KEY,
+    EvolutionAttestation, EvolutionRequest, EvolutionStatus, ADMIN_KEY, REQUEST_COUNTER_KEY,
 };
 
+const MAX_EVOLUTION_LEVEL_KEY: &str = "max_evo_level";
+const UPGRADE_COOLDOWN_KEY: &str = "upgrade_cooldown";
+const STAKE_TOKEN_KEY: &str = "stake_token";
+const MIN_STAKE_KEY: &str = "min_stake";
+const STAKE_TOKEN_ALLOWLIST_KEY: &str = "stake_tokens";
+const SLASH_BPS_KEY: &str = "slash_bps";
+const ORACLE_CONTRACT_KEY: &str = "oracle_contract";
+const AGENT_NFT_KEY: &str = "agent_nft";
+const GOVERNANCE_CONTRACT_KEY: &str = "governance";
+const PAUSED_KEY: &str = "paused";
+const REQUEST_EXPIRY_KEY: &str = "request_expiry";
+const APPROVED_MODEL_HASHES_KEY: &str = "model_hashes";
+
+// No cap and no cooldown until the admin configures them
+const DEFAULT_MAX_EVOLUTION_LEVEL: u32 = u32::MAX;
+const DEFAULT_UPGRADE_COOLDOWN_SECONDS: u64 = 0;
+// Owners may reclaim a stake from an unprocessed request after a week
+const DEFAULT_REQUEST_EXPIRY_SECONDS: u64 = 7 * 24 * 60 * 60;
+const MAX_UPGRADE_BATCH_SIZE: u32 = 20;
+
 #[contract]
 pub struct Evolution;
 
@@ -36,8 +58,444 @@ impl Evolution {
             .set(&Symbol::new(&env, REQUEST_COUNTER_KEY), &0u64);
     }
 
+    /// Set the highest evolution level an agent may reach (admin only)
+    pub fn set_max_evolution_level(env: Env, admin: Address, max_level: u32) {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin);
+
+        if max_level == 0 {
+            panic!("Max evolution level must be positive");
+        }
+
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, MAX_EVOLUTION_LEVEL_KEY), &max_level);
+
+        env.events().publish(
+            (Symbol::new(&env, "max_level_updated"),),
+            (admin, max_level),
+        );
+    }
+
+    /// Get the configured max evolution level
+    pub fn get_max_evolution_level(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&Symbol::new(&env, MAX_EVOLUTION_LEVEL_KEY))
+            .unwrap_or(DEFAULT_MAX_EVOLUTION_LEVEL)
+    }
+
+    /// Set the minimum number of seconds between upgrades of the same agent (admin only)
+    pub fn set_min_seconds_between_upgrades(env: Env, admin: Address, seconds: u64) {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin);
+
+        if seconds > stellai_lib::MAX_AGE_SECONDS {
+            panic!("Cooldown exceeds maximum allowed duration");
+        }
+
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, UPGRADE_COOLDOWN_KEY), &seconds);
+
+        env.events().publish(
+            (Symbol::new(&env, "upgrade_cooldown_updated"),),
+            (admin, seconds),
+        );
+    }
+
+    /// Get the configured upgrade cooldown in seconds
+    pub fn get_min_seconds_between_upgrades(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&Symbol::new(&env, UPGRADE_COOLDOWN_KEY))
+            .unwrap_or(DEFAULT_UPGRADE_COOLDOWN_SECONDS)
+    }
+
+    /// Set how long a request must stay pending before its owner may cancel it (admin only)
+    pub fn set_request_expiry_seconds(env: Env, admin: Address, seconds: u64) {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin);
+
+        if seconds > stellai_lib::MAX_AGE_SECONDS {
+            panic!("Expiry exceeds maximum allowed duration");
+        }
+
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, REQUEST_EXPIRY_KEY), &seconds);
+
+        env.events().publish(
+            (Symbol::new(&env, "request_expiry_updated"),),
+            (admin, seconds),
+        );
+    }
+
+    /// Get the configured request expiry in seconds
+    pub fn get_request_expiry_seconds(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&Symbol::new(&env, REQUEST_EXPIRY_KEY))
+            .unwrap_or(DEFAULT_REQUEST_EXPIRY_SECONDS)
+    }
+
+    /// Set the token used to escrow evolution stakes (admin only)
+    ///
+    /// Requests created after this is set transfer their stake into the contract.
+    pub fn set_stake_token(env: Env, admin: Address, stake_token: Address) {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin);
+
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, STAKE_TOKEN_KEY), &stake_token);
+    }
+
+    /// Get the configured stake token, if any
+    pub fn get_stake_token(env: Env) -> Option<Address> {
+        env.storage()
+            .instance()
+            .get(&Symbol::new(&env, STAKE_TOKEN_KEY))
+    }
+
+    /// Set the minimum stake an evolution request must escrow (admin only)
+    pub fn set_min_stake(env: Env, admin: Address, amount: i128) {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin);
+
+        if amount < 0 {
+            panic!("Minimum stake cannot be negative");
+        }
+
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, MIN_STAKE_KEY), &amount);
+
+        env.events()
+            .publish((Symbol::new(&env, "min_stake_updated"),), (admin, amount));
+    }
+
+    /// Get the configured minimum stake (0 when unset)
+    pub fn get_min_stake(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&Symbol::new(&env, MIN_STAKE_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Add a token to the stake allow-list (admin only)
+    ///
+    /// Once the list is non-empty, requests are only accepted while the
+    /// configured stake token is on it.
+    pub fn add_stake_token(env: Env, admin: Address, token: Address) {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin);
+
+        let mut allowed = Self::get_allowed_stake_tokens(env.clone());
+        if allowed.contains(&token) {
+            panic!("Stake token already allowed");
+        }
+        allowed.push_back(token.clone());
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, STAKE_TOKEN_ALLOWLIST_KEY), &allowed);
+
+        env.events()
+            .publish((Symbol::new(&env, "stake_token_allowed"),), (admin, token));
+    }
+
+    /// Get the stake token allow-list
+    pub fn get_allowed_stake_tokens(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&Symbol::new(&env, STAKE_TOKEN_ALLOWLIST_KEY))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Approve a model hash agents may evolve into (admin only)
+    ///
+    /// While the list is empty any model hash is accepted.
+    pub fn add_approved_model_hash(env: Env, admin: Address, hash: String) {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin);
+
+        let mut approved = Self::get_approved_model_hashes(env.clone());
+        if approved.contains(&hash) {
+            panic!("Model hash already approved");
+        }
+        approved.push_back(hash.clone());
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, APPROVED_MODEL_HASHES_KEY), &approved);
+
+        env.events()
+            .publish((Symbol::new(&env, "model_hash_approved"),), (admin, hash));
+    }
+
+    /// Remove a model hash from the approved list (admin only)
+    pub fn remove_approved_model_hash(env: Env, admin: Address, hash: String) {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin);
+
+        let mut approved = Self::get_approved_model_hashes(env.clone());
+        let index = approved
+            .first_index_of(&hash)
+            .expect("Model hash not approved");
+        approved.remove(index);
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, APPROVED_MODEL_HASHES_KEY), &approved);
+
+        env.events()
+            .publish((Symbol::new(&env, "model_hash_removed"),), (admin, hash));
+    }
+
+    /// Get the approved model hash list
+    pub fn get_approved_model_hashes(env: Env) -> Vec<String> {
+        env.storage()
+            .instance()
+            .get(&Symbol::new(&env, APPROVED_MODEL_HASHES_KEY))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Set the share of stake (basis points) slashed when an upgrade fails (admin only)
+    pub fn set_slash_bps(env: Env, admin: Address, slash_bps: u32) {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin);
+
+        if slash_bps > 10000 {
+            panic!("Slash percentage exceeds 100%");
+        }
+
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, SLASH_BPS_KEY), &slash_bps);
+    }
+
+    /// Get the configured slash percentage in basis points
+    pub fn get_slash_bps(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&Symbol::new(&env, SLASH_BPS_KEY))
+            .unwrap_or(0)
+    }
+
+    /// Set the oracle contract whose registered providers may submit attestations (admin only)
+    pub fn set_oracle_contract(env: Env, admin: Address, oracle_contract: Address) {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin);
+
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, ORACLE_CONTRACT_KEY), &oracle_contract);
+    }
+
+    /// Get the configured oracle contract, if any
+    pub fn get_oracle_contract(env: Env) -> Option<Address> {
+        env.storage()
+            .instance()
+            .get(&Symbol::new(&env, ORACLE_CONTRACT_KEY))
+    }
+
+    /// Set the AgentNFT contract that completed upgrades are applied to (admin only)
+    pub fn set_agent_nft(env: Env, admin: Address, agent_nft: Address) {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin);
+
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, AGENT_NFT_KEY), &agent_nft);
+    }
+
+    /// Get the configured AgentNFT contract, if any
+    pub fn get_agent_nft(env: Env) -> Option<Address> {
+        env.storage()
+            .instance()
+            .get(&Symbol::new(&env, AGENT_NFT_KEY))
+    }
+
+    /// Set the governance contract allowed to pause evolution (admin only)
+    pub fn set_governance_contract(env: Env, admin: Address, governance: Address) {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin);
+
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, GOVERNANCE_CONTRACT_KEY), &governance);
+    }
+
+    /// Pause or unpause evolution. Callable by the admin or the governance
+    /// contract (e.g. via an EmergencyPause proposal).
+    pub fn set_paused(env: Env, caller: Address, paused: bool) {
+        caller.require_auth();
+        let governance: Option<Address> = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(&env, GOVERNANCE_CONTRACT_KEY));
+        if governance != Some(caller.clone()) {
+            Self::verify_admin(&env, &caller);
+        }
+
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, PAUSED_KEY), &paused);
+
+        env.events()
+            .publish((Symbol::new(&env, "paused_set"),), (caller, paused));
+    }
+
+    /// Whether evolution is paused
+    pub fn is_paused(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&Symbol::new(&env, PAUSED_KEY))
+            .unwrap_or(false)
+    }
+
+    /// Get the current evolution level of an agent
+    pub fn get_evolution_level(env: Env, agent_id: u64) -> u32 {
+        env.storage()
+            .instance()
+            .get(&(Symbol::new(&env, "level"), agent_id))
+            .unwrap_or(0)
+    }
+
+    /// Get the model hash applied by the agent's latest attested evolution
+    pub fn get_agent_model_hash(env: Env, agent_id: u64) -> Option<String> {
+        env.storage()
+            .instance()
+            .get(&(Symbol::new(&env, "model_hash"), agent_id))
+    }
+
+    /// Get the timestamp of an agent's last upgrade, if any
+    pub fn get_last_upgrade_at(env: Env, agent_id: u64) -> Option<u64> {
+        env.storage()
+            .instance()
+            .get(&Self::last_upgrade_key(&env, agent_id))
+    }
+
+    /// Panic unless `hash` is on the approved list (when the list is non-empty)
+    fn require_approved_model_hash(env: &Env, hash: &String) {
+        let approved = Self::get_approved_model_hashes(env.clone());
+        if !approved.is_empty() && !approved.contains(hash) {
+            panic!("Model hash not approved");
+        }
+    }
+
+    /// Verify caller is admin
+    fn verify_admin(env: &Env, caller: &Address) {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(env, ADMIN_KEY))
+            .expect("Admin not set");
+
+        if caller != &admin {
+            panic!("Unauthorized: caller is not admin");
+        }
+    }
+
+    /// Panic if evolution is paused
+    fn require_not_paused(env: &Env) {
+        if Self::is_paused(env.clone()) {
+            panic!("Evolution is paused");
+        }
+    }
+
+    /// Storage key for an agent's last upgrade timestamp
+    fn last_upgrade_key(env: &Env, agent_id: u64) -> (Symbol, u64) {
+        (Symbol::new(env, "last_upgrade"), agent_id)
+    }
+
+    /// Reject upgrades past the level cap or inside the cooldown window
+    fn enforce_upgrade_limits(env: &Env, agent_id: u64, new_level: u32) {
+        let max_level = Self::get_max_evolution_level(env.clone());
+        if new_level > max_level {
+            panic!("Evolution level exceeds maximum");
+        }
+
+        let cooldown = Self::get_min_seconds_between_upgrades(env.clone());
+        if let Some(last_upgrade) = Self::get_last_upgrade_at(env.clone(), agent_id) {
+            let elapsed = env.ledger().timestamp().saturating_sub(last_upgrade);
+            if elapsed < cooldown {
+                panic!("Upgrade cooldown has not elapsed");
+            }
+        }
+    }
+
+    /// Record the new level and upgrade time used for cooldown tracking
+    fn record_upgrade(env: &Env, agent_id: u64, new_level: u32) {
+        env.storage()
+            .instance()
+            .set(&(Symbol::new(env, "level"), agent_id), &new_level);
+        env.storage().instance().set(
+            &Self::last_upgrade_key(env, agent_id),
+            &env.ledger().timestamp(),
+        );
+    }
+
+    /// Push a completed upgrade to the configured AgentNFT contract so the
+    /// agent's level and model hash (when given) stay in sync
+    fn apply_to_agent_nft(env: &Env, agent_id: u64, new_level: u32, model_hash: Option<String>) {
+        if let Some(agent_nft) = Self::get_agent_nft(env.clone()) {
+            let args: Vec<Val> = Vec::from_array(
+                env,
+                [
+                    env.current_contract_address().into_val(env),
+                    agent_id.into_val(env),
+                    new_level.into_val(env),
+                    model_hash.into_val(env),
+                ],
+            );
+            env.invoke_contract::<()>(&agent_nft, &Symbol::new(env, "apply_evolution"), args);
+        }
+    }
+
+    /// Confirm with the oracle contract that a provider is registered
+    fn verify_oracle_provider(env: &Env, provider: &Address) {
+        let oracle_contract =
+            Self::get_oracle_contract(env.clone()).expect("Oracle contract not configured");
+
+        let args: Vec<Val> = Vec::from_array(env, [provider.into_val(env)]);
+        let authorized: bool =
+            env.invoke_contract(&oracle_contract, &Symbol::new(env, "is_provider"), args);
+
+        if !authorized {
+            panic!("Unauthorized: oracle provider not registered");
+        }
+    }
+
+    /// Verify the attestation signature against a registered oracle key
+    fn verify_attestation_signature(env: &Env, attestation: &EvolutionAttestation) {
+        let oracle_contract =
+            Self::get_oracle_contract(env.clone()).expect("Oracle contract not configured");
+
+        let args: Vec<Val> = Vec::from_array(env, [attestation.oracle_pubkey.into_val(env)]);
+        let registered: bool = env.invoke_contract(
+            &oracle_contract,
+            &Symbol::new(env, "is_registered_oracle_key"),
+            args,
+        );
+        if !registered {
+            panic!("Unauthorized: oracle key not registered");
+        }
+
+        let signature: BytesN<64> = attestation
+            .signature
+            .clone()
+            .try_into()
+            .expect("Invalid signature size");
+        let digest = Self::attestation_digest(env.clone(), attestation.clone());
+        env.crypto().ed25519_verify(
+            &attestation.oracle_pubkey,
+            &Bytes::from_array(env, &digest.to_array()),
+            &signature,
+        );
+    }
+
     /// Create an evolution request
     pub fn create_request(env: Env, agent_id: u64, owner: Address, stake_amount: i128) -> u64 {
+        Self::require_not_paused(&env);
         owner.require_auth();
 
         if agent_id == 0 {
@@ -46,6 +504,9 @@ impl Evolution {
         if stake_amount <= 0 {
             panic!("Stake amount must be positive");
         }
+        if stake_amount < Self::get_min_stake(env.clone()) {
+            panic!("Stake amount below minimum");
+        }
 
         let counter: u64 = env
             .storage()
@@ -54,11 +515,26 @@ impl Evolution {
             .unwrap_or(0);
         let request_id = counter + 1;
 
+        // Escrow the stake when a stake token is configured
+        let allowed = Self::get_allowed_stake_tokens(env.clone());
+        let stake_token = Self::get_stake_token(env.clone());
+        if !allowed.is_empty() {
+            match &stake_token {
+                Some(token) if allowed.contains(token) => {}
+                _ => panic!("Stake token not allowed"),
+            }
+        }
+        if let Some(stake_token) = &stake_token {
+            let token_client = token::Client::new(&env, stake_token);
+            token_client.transfer(&owner, &env.current_contract_address(), &stake_amount);
+        }
+
         let request = EvolutionRequest {
             request_id,
             agent_id,
             owner: owner.clone(),
             stake_amount,
+            stake_token,
             status: EvolutionStatus::Pending,
             created_at: env.ledger().timestamp(),
             completed_at: None,
@@ -105,9 +581,44 @@ impl Evolution {
         env.storage().instance().get(&request_key)
     }
 
+    /// List pending evolution requests, scanning ids from `start_id` upwards
+    ///
+    /// Returns at most `limit` requests (capped at `MAX_HISTORY_QUERY_LIMIT`).
+    pub fn get_pending_requests(env: Env, start_id: u64, limit: u32) -> Vec<EvolutionRequest> {
+        let mut pending = Vec::new(&env);
+        let limit = limit.min(stellai_lib::MAX_HISTORY_QUERY_LIMIT);
+        if limit == 0 {
+            return pending;
+        }
+
+        let counter: u64 = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(&env, REQUEST_COUNTER_KEY))
+            .unwrap_or(0);
+
+        let mut request_id = start_id.max(1);
+        while request_id <= counter && pending.len() < limit {
+            let request_key = (Symbol::new(&env, "request"), request_id);
+            if let Some(request) = env
+                .storage()
+                .instance()
+                .get::<_, EvolutionRequest>(&request_key)
+            {
+                if request.status == EvolutionStatus::Pending {
+                    pending.push_back(request);
+                }
+            }
+            request_id += 1;
+        }
+
+        pending
+    }
+
     /// Execute an evolution request (Admin only)
     /// This approves the request and records the history.
     pub fn execute_evolution(env: Env, request_id: u64, from_stage: u32, to_stage: u32) {
+        Self::require_not_paused(&env);
         // 1. Verify Admin Auth
         let admin: Address = env
             .storage()
@@ -129,12 +640,17 @@ impl Evolution {
             panic!("Request is not pending");
         }
 
-        // 4. Update status
+        // 4. Enforce level cap and cooldown
+        Self::enforce_upgrade_limits(&env, request.agent_id, to_stage);
+
+        // 5. Update status
         request.status = EvolutionStatus::Completed;
         request.completed_at = Some(env.ledger().timestamp());
         env.storage().instance().set(&request_key, &request);
+        Self::record_upgrade(&env, request.agent_id, to_stage);
+        Self::apply_to_agent_nft(&env, request.agent_id, to_stage, None);
 
-        // 5. Record evolution in immutable history (Step 3)
+        // 6. Record evolution in immutable history (Step 3)
         // Note: We use the owner's address to track the history
         append_evolution(
             &env,
@@ -144,13 +660,13 @@ impl Evolution {
             Symbol::new(&env, "admin_exe"),
         );
 
-        // 6. Emit event
+        // 7. Emit event
         env.events().publish(
             (Symbol::new(&env, "evolution_executed"),),
             (request_id, request.agent_id, to_stage),
         );
 
-        // 7. Audit log for evolution execution
+        // 8. Audit log for evolution execution
         let before_state = String::from_str(&env, "{\"status\":\"pending\"}");
         let after_state = String::from_str(&env, "{\"status\":\"completed\"}");
         let tx_hash = String::from_str(&env, "execute_evolution");
@@ -167,6 +683,397 @@ impl Evolution {
         );
     }
 
+    /// Compute the digest an oracle must sign for an attestation
+    ///
+    /// sha256(request_id || agent_id || new_model_hash || attestation_data || nonce),
+    /// with integers encoded big-endian.
+    pub fn attestation_digest(env: Env, attestation: EvolutionAttestation) -> BytesN<32> {
+        if attestation.new_model_hash.len() > stellai_lib::MAX_STRING_LENGTH {
+            panic!("Model hash exceeds maximum length");
+        }
+
+        let mut message = Bytes::new(&env);
+        message.extend_from_array(&attestation.request_id.to_be_bytes());
+        message.extend_from_array(&attestation.agent_id.to_be_bytes());
+
+        let mut hash_buf = [0u8; stellai_lib::MAX_STRING_LENGTH as usize];
+        let hash_len = attestation.new_model_hash.len() as usize;
+        attestation
+            .new_model_hash
+            .copy_into_slice(&mut hash_buf[..hash_len]);
+        message.extend_from_slice(&hash_buf[..hash_len]);
+
+        message.append(&attestation.attestation_data);
+        message.extend_from_array(&attestation.nonce.to_be_bytes());
+
+        env.crypto().sha256(&message).into()
+    }
+
+    /// Apply an oracle attestation that completes a pending evolution request
+    ///
+    /// The attesting provider must be registered with the configured oracle
+    /// contract and the signature must verify against a registered oracle key.
+    /// Each attestation advances the agent by one level.
+    pub fn apply_attestation(env: Env, attestation: EvolutionAttestation) {
+        Self::require_not_paused(&env);
+        if attestation.request_id == 0 {
+            panic!("Invalid request ID");
+        }
+        if attestation.agent_id == 0 {
+            panic!("Invalid agent ID");
+        }
+        if attestation.new_model_hash.len() > stellai_lib::MAX_STRING_LENGTH {
+            panic!("Model hash exceeds maximum length");
+        }
+        if attestation.signature.len() as usize != stellai_lib::ATTESTATION_SIGNATURE_SIZE {
+            panic!("Invalid signature size");
+        }
+        if attestation.attestation_data.len() as usize > stellai_lib::MAX_ATTESTATION_DATA_SIZE {
+            panic!("Attestation data exceeds maximum size");
+        }
+
+        // Replay protection: nonces must strictly increase per agent
+        let nonce_key = (Symbol::new(&env, "att_nonce"), attestation.agent_id);
+        let stored_nonce: Option<u64> = env.storage().instance().get(&nonce_key);
+        if let Some(prev_nonce) = stored_nonce {
+            if attestation.nonce <= prev_nonce {
+                panic!("Replay protection: invalid or reused nonce");
+            }
+        }
+
+        let request_key = (Symbol::new(&env, "request"), attestation.request_id);
+        let mut request: EvolutionRequest = env
+            .storage()
+            .instance()
+            .get(&request_key)
+            .expect("Request not found");
+
+        if request.status != EvolutionStatus::Pending {
+            panic!("Request is not pending");
+        }
+        if request.agent_id != attestation.agent_id {
+            panic!("Agent ID mismatch in attestation");
+        }
+
+        Self::require_approved_model_hash(&env, &attestation.new_model_hash);
+
+        // Verify the provider against the registered oracle contract
+        attestation.oracle_provider.require_auth();
+        Self::verify_oracle_provider(&env, &attestation.oracle_provider);
+        Self::verify_attestation_signature(&env, &attestation);
+
+        let from_level = Self::get_evolution_level(env.clone(), attestation.agent_id);
+        let to_level = from_level.checked_add(1).expect("Evolution level overflow");
+        Self::enforce_upgrade_limits(&env, attestation.agent_id, to_level);
+
+        request.status = EvolutionStatus::Completed;
+        request.completed_at = Some(env.ledger().timestamp());
+        env.storage().instance().set(&request_key, &request);
+        env.storage().instance().set(&nonce_key, &attestation.nonce);
+        env.storage().instance().set(
+            &(Symbol::new(&env, "model_hash"), attestation.agent_id),
+            &attestation.new_model_hash,
+        );
+        Self::record_upgrade(&env, attestation.agent_id, to_level);
+        Self::apply_to_agent_nft(
+            &env,
+            attestation.agent_id,
+            to_level,
+            Some(attestation.new_model_hash.clone()),
+        );
+
+        append_evolution(
+            &env,
+            &request.owner,
+            from_level,
+            to_level,
+            Symbol::new(&env, "oracle"),
+        );
+
+        env.events().publish(
+            (Symbol::new(&env, "attestation_applied"),),
+            (
+                attestation.request_id,
+                attestation.agent_id,
+                to_level,
+                attestation.oracle_provider,
+            ),
+        );
+    }
+
+    /// Complete several pending requests in one call (admin only)
+    ///
+    /// Each entry advances its agent by one level, records the new model hash
+    /// and returns the full stake to the owner. Entries whose request is
+    /// missing or no longer pending are skipped rather than aborting the
+    /// batch; level cap and cooldown violations still abort it. Returns the
+    /// number of requests completed.
+    pub fn complete_upgrades_batch(
+        env: Env,
+        admin: Address,
+        completions: Vec<(u64, String)>,
+    ) -> u32 {
+        Self::require_not_paused(&env);
+        admin.require_auth();
+        Self::verify_admin(&env, &admin);
+
+        if completions.len() > MAX_UPGRADE_BATCH_SIZE {
+            panic!("Batch size exceeds maximum");
+        }
+
+        let mut completed: u32 = 0;
+        let mut skipped: u32 = 0;
+
+        for (request_id, new_model_hash) in completions.iter() {
+            if new_model_hash.len() > stellai_lib::MAX_STRING_LENGTH {
+                panic!("Model hash exceeds maximum length");
+            }
+            Self::require_approved_model_hash(&env, &new_model_hash);
+
+            let request_key = (Symbol::new(&env, "request"), request_id);
+            let mut request: EvolutionRequest = match env.storage().instance().get(&request_key) {
+                Some(request) => request,
+                None => {
+                    skipped += 1;
+                    continue;
+                }
+            };
+            if request.status != EvolutionStatus::Pending {
+                skipped += 1;
+                continue;
+            }
+
+            let from_level = Self::get_evolution_level(env.clone(), request.agent_id);
+            let to_level = from_level.checked_add(1).expect("Evolution level overflow");
+            Self::enforce_upgrade_limits(&env, request.agent_id, to_level);
+
+            request.status = EvolutionStatus::Completed;
+            request.completed_at = Some(env.ledger().timestamp());
+            env.storage().instance().set(&request_key, &request);
+            env.storage().instance().set(
+                &(Symbol::new(&env, "model_hash"), request.agent_id),
+                &new_model_hash,
+            );
+            Self::record_upgrade(&env, request.agent_id, to_level);
+            Self::apply_to_agent_nft(
+                &env,
+                request.agent_id,
+                to_level,
+                Some(new_model_hash.clone()),
+            );
+
+            append_evolution(
+                &env,
+                &request.owner,
+                from_level,
+                to_level,
+                Symbol::new(&env, "admin_batch"),
+            );
+
+            // Return the stake directly so owners need not call claim_stake
+            env.storage()
+                .instance()
+                .set(&(Symbol::new(&env, "stake_claimed"), request_id), &true);
+            if let Some(stake_token) = &request.stake_token {
+                let token_client = token::Client::new(&env, stake_token);
+                token_client.transfer(
+                    &env.current_contract_address(),
+                    &request.owner,
+                    &request.stake_amount,
+                );
+            }
+
+            env.events().publish(
+                (Symbol::new(&env, "upgrade_completed"),),
+                (request_id, request.agent_id, to_level),
+            );
+            completed += 1;
+        }
+
+        env.events().publish(
+            (Symbol::new(&env, "upgrades_batch_completed"),),
+            (admin, completed, skipped),
+        );
+
+        completed
+    }
+
+    /// Mark a pending evolution request as failed (admin only)
+    ///
+    /// The configured slash percentage of the stake is sent to the admin; the
+    /// remainder is returned to the owner through `claim_stake`.
+    pub fn fail_upgrade(env: Env, admin: Address, request_id: u64, reason: String) {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin);
+
+        if reason.len() > stellai_lib::MAX_STRING_LENGTH {
+            panic!("Reason exceeds maximum length");
+        }
+
+        let request_key = (Symbol::new(&env, "request"), request_id);
+        let mut request: EvolutionRequest = env
+            .storage()
+            .instance()
+            .get(&request_key)
+            .expect("Request not found");
+
+        if request.status != EvolutionStatus::Pending
+            && request.status != EvolutionStatus::InProgress
+        {
+            panic!("Request is not pending");
+        }
+
+        request.status = EvolutionStatus::Failed;
+        request.completed_at = Some(env.ledger().timestamp());
+        env.storage().instance().set(&request_key, &request);
+        env.storage()
+            .instance()
+            .set(&(Symbol::new(&env, "fail_reason"), request_id), &reason);
+
+        // Slash part of the escrowed stake to the admin
+        let slashed = request
+            .stake_amount
+            .checked_mul(Self::get_slash_bps(env.clone()) as i128)
+            .expect("Slash overflow")
+            / 10000;
+        if slashed > 0 {
+            if let Some(stake_token) = &request.stake_token {
+                let token_client = token::Client::new(&env, stake_token);
+                token_client.transfer(&env.current_contract_address(), &admin, &slashed);
+                env.storage()
+                    .instance()
+                    .set(&(Symbol::new(&env, "slashed"), request_id), &slashed);
+            }
+        }
+
+        env.events().publish(
+            (Symbol::new(&env, "upgrade_failed"),),
+            (request_id, request.agent_id, reason),
+        );
+
+        let before_state = String::from_str(&env, "{\"status\":\"pending\"}");
+        let after_state = String::from_str(&env, "{\"status\":\"failed\"}");
+        let tx_hash = String::from_str(&env, "fail_upgrade");
+        let description = Some(String::from_str(&env, "Evolution request failed"));
+
+        let _ = create_audit_log(
+            &env,
+            admin,
+            OperationType::AdminSettingsChange,
+            before_state,
+            after_state,
+            tx_hash,
+            description,
+        );
+    }
+
+    /// Get the failure reason recorded for a request
+    pub fn get_failure_reason(env: Env, request_id: u64) -> Option<String> {
+        env.storage()
+            .instance()
+            .get(&(Symbol::new(&env, "fail_reason"), request_id))
+    }
+
+    /// Claim the escrowed stake after a request completes or fails
+    ///
+    /// Failed requests return the stake minus any slashed amount.
+    pub fn claim_stake(env: Env, owner: Address, request_id: u64) -> i128 {
+        Self::require_not_paused(&env);
+        owner.require_auth();
+
+        let request_key = (Symbol::new(&env, "request"), request_id);
+        let request: EvolutionRequest = env
+            .storage()
+            .instance()
+            .get(&request_key)
+            .expect("Request not found");
+
+        if request.owner != owner {
+            panic!("Unauthorized: only request owner can claim stake");
+        }
+
+        if request.status != EvolutionStatus::Completed && request.status != EvolutionStatus::Failed
+        {
+            panic!("Stake not yet available for claim");
+        }
+
+        let claimed_key = (Symbol::new(&env, "stake_claimed"), request_id);
+        if env.storage().instance().has(&claimed_key) {
+            panic!("Stake already claimed for this request");
+        }
+        env.storage().instance().set(&claimed_key, &true);
+
+        let slashed: i128 = env
+            .storage()
+            .instance()
+            .get(&(Symbol::new(&env, "slashed"), request_id))
+            .unwrap_or(0);
+        let refund = request.stake_amount - slashed;
+
+        // Refund in the token the stake was escrowed in, not the current one
+        if let Some(stake_token) = &request.stake_token {
+            let token_client = token::Client::new(&env, stake_token);
+            token_client.transfer(&env.current_contract_address(), &owner, &refund);
+        }
+
+        env.events().publish(
+            (Symbol::new(&env, "stake_claimed"),),
+            (request_id, request.agent_id, owner, refund),
+        );
+
+        refund
+    }
+
+    /// Cancel a request that has stayed pending past the expiry window
+    ///
+    /// The full stake is refunded to the owner and the request is marked
+    /// `Cancelled`, so it can no longer be executed or claimed.
+    pub fn cancel_upgrade_request(env: Env, request_id: u64, owner: Address) {
+        owner.require_auth();
+
+        let request_key = (Symbol::new(&env, "request"), request_id);
+        let mut request: EvolutionRequest = env
+            .storage()
+            .instance()
+            .get(&request_key)
+            .expect("Request not found");
+
+        if request.owner != owner {
+            panic!("Unauthorized: only request owner can cancel");
+        }
+        if request.status != EvolutionStatus::Pending {
+            panic!("Request is not pending");
+        }
+
+        let expires_at = request
+            .created_at
+            .saturating_add(Self::get_request_expiry_seconds(env.clone()));
+        if env.ledger().timestamp() < expires_at {
+            panic!("Request has not expired");
+        }
+
+        request.status = EvolutionStatus::Cancelled;
+        request.completed_at = Some(env.ledger().timestamp());
+        env.storage().instance().set(&request_key, &request);
+        env.storage()
+            .instance()
+            .set(&(Symbol::new(&env, "stake_claimed"), request_id), &true);
+
+        if let Some(stake_token) = &request.stake_token {
+            let token_client = token::Client::new(&env, stake_token);
+            token_client.transfer(
+                &env.current_contract_address(),
+                &owner,
+                &request.stake_amount,
+            );
+        }
+
+        env.events().publish(
+            (Symbol::new(&env, "request_cancelled"),),
+            (request_id, request.agent_id, owner, request.stake_amount),
+        );
+    }
+
     // Step 4: Add public getter functions
 
     /// Get full evolution history for an agent
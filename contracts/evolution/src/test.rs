@@ -1,7 +1,40 @@
 #![cfg(test)]
 
+extern crate std;
+
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Env, Symbol};
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{
+    contract, contractimpl,
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    Address, Bytes, BytesN, Env, String, Symbol,
+};
+
+#[contract]
+pub struct MockOracle;
+
+#[contractimpl]
+impl MockOracle {
+    pub fn set_provider(env: Env, provider: Address, authorized: bool) {
+        env.storage().instance().set(&provider, &authorized);
+    }
+
+    pub fn is_provider(env: Env, provider: Address) -> bool {
+        env.storage().instance().get(&provider).unwrap_or(false)
+    }
+
+    pub fn register_oracle_key(env: Env, oracle_pubkey: BytesN<32>) {
+        env.storage().instance().set(&oracle_pubkey, &true);
+    }
+
+    pub fn is_registered_oracle_key(env: Env, oracle_pubkey: BytesN<32>) -> bool {
+        env.storage()
+            .instance()
+            .get(&oracle_pubkey)
+            .unwrap_or(false)
+    }
+}
 
 #[test]
 fn test_evolution_history_recording() {
@@ -16,7 +49,7 @@ fn test_evolution_history_recording() {
     let owner = Address::generate(&env);
 
     // 3. Register Contract
-    let contract_id = env.register_contract(None, Evolution);
+    let contract_id = env.register(Evolution, ());
     let client = EvolutionClient::new(&env, &contract_id);
 
     // 4. Initialize (Now this will pass auth)
@@ -50,3 +83,635 @@ fn test_evolution_history_recording() {
     let latest = client.get_agent_latest_evolution(&owner).unwrap();
     assert_eq!(latest.to_stage, 2);
 }
+
+// Stands in for the Governance contract executing an EmergencyPause proposal
+#[contract]
+pub struct MockGovernance;
+
+#[contractimpl]
+impl MockGovernance {
+    pub fn emergency_pause(env: Env, evolution: Address, paused: bool) {
+        EvolutionClient::new(&env, &evolution).set_paused(&env.current_contract_address(), &paused);
+    }
+}
+
+fn setup_contract(env: &Env) -> (EvolutionClient<'_>, Address) {
+    env.mock_all_auths();
+
+    let admin = Address::generate(env);
+    let contract_id = env.register(Evolution, ());
+    let client = EvolutionClient::new(env, &contract_id);
+    client.init_contract(&admin);
+
+    (client, admin)
+}
+
+#[test]
+#[should_panic(expected = "Evolution level exceeds maximum")]
+fn test_upgrade_beyond_max_level_panics() {
+    let env = Env::default();
+    let (client, admin) = setup_contract(&env);
+    let owner = Address::generate(&env);
+
+    client.set_max_evolution_level(&admin, &3);
+
+    let request_id = client.create_request(&1, &owner, &1000);
+    client.execute_evolution(&request_id, &3, &4);
+}
+
+#[test]
+#[should_panic(expected = "Upgrade cooldown has not elapsed")]
+fn test_upgrade_within_cooldown_panics() {
+    let env = Env::default();
+    let (client, admin) = setup_contract(&env);
+    let owner = Address::generate(&env);
+
+    client.set_min_seconds_between_upgrades(&admin, &3600);
+
+    let first = client.create_request(&1, &owner, &1000);
+    client.execute_evolution(&first, &1, &2);
+
+    env.ledger().with_mut(|li| li.timestamp += 1800);
+    let second = client.create_request(&1, &owner, &1000);
+    client.execute_evolution(&second, &2, &3);
+}
+
+#[test]
+fn test_upgrade_after_cooldown_succeeds() {
+    let env = Env::default();
+    let (client, admin) = setup_contract(&env);
+    let owner = Address::generate(&env);
+
+    client.set_max_evolution_level(&admin, &5);
+    client.set_min_seconds_between_upgrades(&admin, &3600);
+
+    let first = client.create_request(&1, &owner, &1000);
+    client.execute_evolution(&first, &1, &2);
+
+    env.ledger().with_mut(|li| li.timestamp += 3600);
+    let second = client.create_request(&1, &owner, &1000);
+    client.execute_evolution(&second, &2, &3);
+
+    assert_eq!(client.get_agent_evolution_count(&owner), 2);
+    assert_eq!(
+        client.get_last_upgrade_at(&1),
+        Some(env.ledger().timestamp())
+    );
+}
+
+fn setup_stake_token(env: &Env, client: &EvolutionClient, admin: &Address) -> Address {
+    let token = env.register_stellar_asset_contract_v2(admin.clone());
+    client.set_stake_token(admin, &token.address());
+    token.address()
+}
+
+#[test]
+fn test_fail_pending_request() {
+    let env = Env::default();
+    let (client, admin) = setup_contract(&env);
+    let owner = Address::generate(&env);
+
+    let request_id = client.create_request(&1, &owner, &1000);
+    let reason = String::from_str(&env, "Training diverged");
+    client.fail_upgrade(&admin, &request_id, &reason);
+
+    let request = client.get_request(&request_id).unwrap();
+    assert!(request.status == EvolutionStatus::Failed);
+    assert_eq!(client.get_failure_reason(&request_id), Some(reason));
+}
+
+#[test]
+fn test_claim_reduced_stake_after_failure() {
+    let env = Env::default();
+    let (client, admin) = setup_contract(&env);
+    let owner = Address::generate(&env);
+
+    let token = setup_stake_token(&env, &client, &admin);
+    StellarAssetClient::new(&env, &token).mint(&owner, &1000);
+    client.set_slash_bps(&admin, &2500);
+
+    let request_id = client.create_request(&1, &owner, &1000);
+    let token_client = TokenClient::new(&env, &token);
+    assert_eq!(token_client.balance(&owner), 0);
+
+    client.fail_upgrade(&admin, &request_id, &String::from_str(&env, "Bad model"));
+    assert_eq!(token_client.balance(&admin), 250);
+
+    let refund = client.claim_stake(&owner, &request_id);
+    assert_eq!(refund, 750);
+    assert_eq!(token_client.balance(&owner), 750);
+}
+
+#[test]
+fn test_stake_refunded_in_escrowed_token_after_token_change() {
+    let env = Env::default();
+    let (client, admin) = setup_contract(&env);
+    let owner = Address::generate(&env);
+    let other = Address::generate(&env);
+
+    let first_token = setup_stake_token(&env, &client, &admin);
+    StellarAssetClient::new(&env, &first_token).mint(&owner, &1000);
+    let request_id = client.create_request(&1, &owner, &1000);
+    assert_eq!(
+        client.get_request(&request_id).unwrap().stake_token,
+        Some(first_token.clone())
+    );
+
+    // Another user's stake escrowed under a newer token must stay untouched
+    let second_token = setup_stake_token(&env, &client, &admin);
+    StellarAssetClient::new(&env, &second_token).mint(&other, &1000);
+    client.create_request(&2, &other, &1000);
+
+    client.fail_upgrade(&admin, &request_id, &String::from_str(&env, "Bad model"));
+    client.claim_stake(&owner, &request_id);
+
+    assert_eq!(TokenClient::new(&env, &first_token).balance(&owner), 1000);
+    assert_eq!(
+        TokenClient::new(&env, &second_token).balance(&client.address),
+        1000
+    );
+}
+
+#[test]
+fn test_unescrowed_request_claims_without_transfer() {
+    let env = Env::default();
+    let (client, admin) = setup_contract(&env);
+    let owner = Address::generate(&env);
+
+    let request_id = client.create_request(&1, &owner, &1000);
+    assert_eq!(client.get_request(&request_id).unwrap().stake_token, None);
+
+    // Configuring a token afterwards must not fund a refund of a stake never taken
+    let token = setup_stake_token(&env, &client, &admin);
+    StellarAssetClient::new(&env, &token).mint(&client.address, &1000);
+    client.fail_upgrade(&admin, &request_id, &String::from_str(&env, "Bad model"));
+    client.claim_stake(&owner, &request_id);
+
+    let token_client = TokenClient::new(&env, &token);
+    assert_eq!(token_client.balance(&owner), 0);
+    assert_eq!(token_client.balance(&admin), 0);
+    assert_eq!(token_client.balance(&client.address), 1000);
+}
+
+#[test]
+#[should_panic(expected = "Stake amount below minimum")]
+fn test_create_request_below_min_stake_panics() {
+    let env = Env::default();
+    let (client, admin) = setup_contract(&env);
+    let owner = Address::generate(&env);
+
+    client.set_min_stake(&admin, &500);
+    client.create_request(&1, &owner, &499);
+}
+
+#[test]
+#[should_panic(expected = "Stake token not allowed")]
+fn test_create_request_with_disallowed_token_panics() {
+    let env = Env::default();
+    let (client, admin) = setup_contract(&env);
+    let owner = Address::generate(&env);
+
+    let allowed = env.register_stellar_asset_contract_v2(admin.clone());
+    client.add_stake_token(&admin, &allowed.address());
+    let token = setup_stake_token(&env, &client, &admin);
+    StellarAssetClient::new(&env, &token).mint(&owner, &1000);
+
+    client.create_request(&1, &owner, &1000);
+}
+
+#[test]
+fn test_create_request_with_allowed_token_and_min_stake() {
+    let env = Env::default();
+    let (client, admin) = setup_contract(&env);
+    let owner = Address::generate(&env);
+
+    let token = setup_stake_token(&env, &client, &admin);
+    client.add_stake_token(&admin, &token);
+    client.set_min_stake(&admin, &500);
+    StellarAssetClient::new(&env, &token).mint(&owner, &1000);
+
+    let request_id = client.create_request(&1, &owner, &500);
+    assert_eq!(client.get_request(&request_id).unwrap().stake_amount, 500);
+    assert_eq!(TokenClient::new(&env, &token).balance(&owner), 500);
+}
+
+#[test]
+fn test_cancel_expired_request_refunds_stake() {
+    let env = Env::default();
+    let (client, admin) = setup_contract(&env);
+    let owner = Address::generate(&env);
+
+    let token = setup_stake_token(&env, &client, &admin);
+    StellarAssetClient::new(&env, &token).mint(&owner, &1000);
+    client.set_request_expiry_seconds(&admin, &3600);
+
+    let request_id = client.create_request(&1, &owner, &1000);
+    env.ledger().with_mut(|li| li.timestamp += 3600);
+    client.cancel_upgrade_request(&request_id, &owner);
+
+    let request = client.get_request(&request_id).unwrap();
+    assert!(request.status == EvolutionStatus::Cancelled);
+    assert_eq!(TokenClient::new(&env, &token).balance(&owner), 1000);
+}
+
+#[test]
+#[should_panic(expected = "Request has not expired")]
+fn test_cancel_request_before_expiry_panics() {
+    let env = Env::default();
+    let (client, admin) = setup_contract(&env);
+    let owner = Address::generate(&env);
+
+    client.set_request_expiry_seconds(&admin, &3600);
+    let request_id = client.create_request(&1, &owner, &1000);
+    env.ledger().with_mut(|li| li.timestamp += 3599);
+    client.cancel_upgrade_request(&request_id, &owner);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized: only request owner can cancel")]
+fn test_cancel_other_owners_request_panics() {
+    let env = Env::default();
+    let (client, _admin) = setup_contract(&env);
+    let owner = Address::generate(&env);
+    let other = Address::generate(&env);
+
+    let request_id = client.create_request(&1, &owner, &1000);
+    env.ledger()
+        .with_mut(|li| li.timestamp += DEFAULT_REQUEST_EXPIRY_SECONDS);
+    client.cancel_upgrade_request(&request_id, &other);
+}
+
+#[test]
+fn test_get_pending_requests_skips_completed() {
+    let env = Env::default();
+    let (client, _admin) = setup_contract(&env);
+    let owner = Address::generate(&env);
+
+    let first = client.create_request(&1, &owner, &1000);
+    let second = client.create_request(&2, &owner, &1000);
+    let third = client.create_request(&3, &owner, &1000);
+    client.execute_evolution(&second, &1, &2);
+
+    let pending = client.get_pending_requests(&1, &10);
+    assert_eq!(pending.len(), 2);
+    assert_eq!(pending.get(0).unwrap().request_id, first);
+    assert_eq!(pending.get(1).unwrap().request_id, third);
+
+    let page = client.get_pending_requests(&2, &1);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().request_id, third);
+}
+
+#[test]
+fn test_complete_upgrades_batch_completes_all() {
+    let env = Env::default();
+    let (client, admin) = setup_contract(&env);
+    let owner = Address::generate(&env);
+
+    let token = setup_stake_token(&env, &client, &admin);
+    StellarAssetClient::new(&env, &token).mint(&owner, &3000);
+
+    let mut completions = Vec::new(&env);
+    for agent_id in 1..=3u64 {
+        let request_id = client.create_request(&agent_id, &owner, &1000);
+        completions.push_back((request_id, String::from_str(&env, "model-v2")));
+    }
+
+    assert_eq!(client.complete_upgrades_batch(&admin, &completions), 3);
+    for agent_id in 1..=3u64 {
+        assert_eq!(client.get_evolution_level(&agent_id), 1);
+        assert_eq!(
+            client.get_agent_model_hash(&agent_id),
+            Some(String::from_str(&env, "model-v2"))
+        );
+    }
+    assert_eq!(TokenClient::new(&env, &token).balance(&owner), 3000);
+}
+
+#[test]
+fn test_complete_upgrades_batch_skips_non_pending() {
+    let env = Env::default();
+    let (client, admin) = setup_contract(&env);
+    let owner = Address::generate(&env);
+
+    let done = client.create_request(&1, &owner, &1000);
+    client.execute_evolution(&done, &0, &1);
+    let pending = client.create_request(&2, &owner, &1000);
+
+    let mut completions = Vec::new(&env);
+    completions.push_back((done, String::from_str(&env, "model-v2")));
+    completions.push_back((pending, String::from_str(&env, "model-v2")));
+
+    assert_eq!(client.complete_upgrades_batch(&admin, &completions), 1);
+    assert_eq!(client.get_evolution_level(&1), 1);
+    assert_eq!(client.get_evolution_level(&2), 1);
+    assert!(client.get_request(&pending).unwrap().status == EvolutionStatus::Completed);
+}
+
+#[test]
+fn test_complete_upgrade_to_approved_model_hash() {
+    let env = Env::default();
+    let (client, admin) = setup_contract(&env);
+    let owner = Address::generate(&env);
+    let approved = String::from_str(&env, "model-v2");
+    client.add_approved_model_hash(&admin, &approved);
+
+    let request_id = client.create_request(&1, &owner, &1000);
+    let mut completions = Vec::new(&env);
+    completions.push_back((request_id, approved.clone()));
+
+    assert_eq!(client.complete_upgrades_batch(&admin, &completions), 1);
+    assert_eq!(client.get_agent_model_hash(&1), Some(approved));
+}
+
+#[test]
+#[should_panic(expected = "Model hash not approved")]
+fn test_complete_upgrade_to_unapproved_model_hash_panics() {
+    let env = Env::default();
+    let (client, admin) = setup_contract(&env);
+    let owner = Address::generate(&env);
+    client.add_approved_model_hash(&admin, &String::from_str(&env, "model-v2"));
+
+    let request_id = client.create_request(&1, &owner, &1000);
+    let mut completions = Vec::new(&env);
+    completions.push_back((request_id, String::from_str(&env, "model-evil")));
+
+    client.complete_upgrades_batch(&admin, &completions);
+}
+
+#[test]
+fn test_empty_model_hash_list_accepts_any_hash() {
+    let env = Env::default();
+    let (client, admin) = setup_contract(&env);
+    let owner = Address::generate(&env);
+    let approved = String::from_str(&env, "model-v2");
+
+    client.add_approved_model_hash(&admin, &approved);
+    client.remove_approved_model_hash(&admin, &approved);
+    assert_eq!(client.get_approved_model_hashes().len(), 0);
+
+    let request_id = client.create_request(&1, &owner, &1000);
+    let mut completions = Vec::new(&env);
+    completions.push_back((request_id, String::from_str(&env, "anything")));
+
+    assert_eq!(client.complete_upgrades_batch(&admin, &completions), 1);
+}
+
+#[test]
+#[should_panic(expected = "Request is not pending")]
+fn test_fail_completed_request_panics() {
+    let env = Env::default();
+    let (client, admin) = setup_contract(&env);
+    let owner = Address::generate(&env);
+
+    let request_id = client.create_request(&1, &owner, &1000);
+    client.execute_evolution(&request_id, &1, &2);
+    client.fail_upgrade(&admin, &request_id, &String::from_str(&env, "Too late"));
+}
+
+fn oracle_signing_key() -> SigningKey {
+    SigningKey::from_bytes(&[7u8; 32])
+}
+
+fn oracle_pubkey(env: &Env) -> BytesN<32> {
+    BytesN::from_array(env, &oracle_signing_key().verifying_key().to_bytes())
+}
+
+fn setup_oracle(env: &Env, client: &EvolutionClient, admin: &Address) -> MockOracleClient<'static> {
+    let oracle_id = env.register(MockOracle, ());
+    client.set_oracle_contract(admin, &oracle_id);
+    let oracle = MockOracleClient::new(env, &oracle_id);
+    oracle.register_oracle_key(&oracle_pubkey(env));
+    oracle
+}
+
+fn sign_attestation(client: &EvolutionClient, attestation: &mut EvolutionAttestation) {
+    let env = &client.env;
+    let digest = client.attestation_digest(attestation);
+    let signature = oracle_signing_key().sign(&digest.to_array());
+    attestation.signature = Bytes::from_slice(env, &signature.to_bytes());
+}
+
+fn build_attestation(
+    client: &EvolutionClient,
+    request_id: u64,
+    agent_id: u64,
+    provider: &Address,
+    nonce: u64,
+) -> EvolutionAttestation {
+    let env = &client.env;
+    let mut attestation = EvolutionAttestation {
+        request_id,
+        agent_id,
+        oracle_provider: provider.clone(),
+        oracle_pubkey: oracle_pubkey(env),
+        new_model_hash: String::from_str(env, "QmEvolvedModel"),
+        attestation_data: Bytes::from_slice(env, b"training_complete"),
+        signature: Bytes::from_slice(env, &[0u8; 64]),
+        timestamp: env.ledger().timestamp(),
+        nonce,
+    };
+    sign_attestation(client, &mut attestation);
+    attestation
+}
+
+#[test]
+fn test_apply_attestation_authorized_provider() {
+    let env = Env::default();
+    let (client, admin) = setup_contract(&env);
+    let oracle = setup_oracle(&env, &client, &admin);
+    let owner = Address::generate(&env);
+    let provider = Address::generate(&env);
+    oracle.set_provider(&provider, &true);
+
+    let request_id = client.create_request(&1, &owner, &1000);
+    client.apply_attestation(&build_attestation(&client, request_id, 1, &provider, 1));
+
+    let request = client.get_request(&request_id).unwrap();
+    assert!(request.status == EvolutionStatus::Completed);
+    assert_eq!(client.get_evolution_level(&1), 1);
+    assert_eq!(
+        client.get_agent_model_hash(&1),
+        Some(String::from_str(&env, "QmEvolvedModel"))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized: oracle provider not registered")]
+fn test_apply_attestation_unauthorized_provider() {
+    let env = Env::default();
+    let (client, admin) = setup_contract(&env);
+    setup_oracle(&env, &client, &admin);
+    let owner = Address::generate(&env);
+    let provider = Address::generate(&env);
+
+    let request_id = client.create_request(&1, &owner, &1000);
+    client.apply_attestation(&build_attestation(&client, request_id, 1, &provider, 1));
+}
+
+#[test]
+fn test_apply_attestation_valid_signature() {
+    let env = Env::default();
+    let (client, admin) = setup_contract(&env);
+    let oracle = setup_oracle(&env, &client, &admin);
+    let owner = Address::generate(&env);
+    let provider = Address::generate(&env);
+    oracle.set_provider(&provider, &true);
+
+    let first = client.create_request(&1, &owner, &1000);
+    client.apply_attestation(&build_attestation(&client, first, 1, &provider, 1));
+
+    let second = client.create_request(&1, &owner, &1000);
+    client.apply_attestation(&build_attestation(&client, second, 1, &provider, 2));
+
+    assert_eq!(client.get_evolution_level(&1), 2);
+}
+
+#[test]
+#[should_panic]
+fn test_apply_attestation_tampered_fails_verification() {
+    let env = Env::default();
+    let (client, admin) = setup_contract(&env);
+    let oracle = setup_oracle(&env, &client, &admin);
+    let owner = Address::generate(&env);
+    let provider = Address::generate(&env);
+    oracle.set_provider(&provider, &true);
+
+    let request_id = client.create_request(&1, &owner, &1000);
+    let mut attestation = build_attestation(&client, request_id, 1, &provider, 1);
+    attestation.new_model_hash = String::from_str(&env, "QmTamperedModel");
+
+    client.apply_attestation(&attestation);
+}
+
+#[test]
+#[should_panic(expected = "Model hash not approved")]
+fn test_apply_attestation_unapproved_model_hash_panics() {
+    let env = Env::default();
+    let (client, admin) = setup_contract(&env);
+    let oracle = setup_oracle(&env, &client, &admin);
+    let owner = Address::generate(&env);
+    let provider = Address::generate(&env);
+    oracle.set_provider(&provider, &true);
+    client.add_approved_model_hash(&admin, &String::from_str(&env, "QmApprovedModel"));
+
+    // Validly signed, but for a model hash outside the allow-list
+    let request_id = client.create_request(&1, &owner, &1000);
+    client.apply_attestation(&build_attestation(&client, request_id, 1, &provider, 1));
+}
+
+#[test]
+fn test_governance_pause_blocks_create_request() {
+    let env = Env::default();
+    let (client, admin) = setup_contract(&env);
+    let owner = Address::generate(&env);
+    let governance = env.register(MockGovernance, ());
+    client.set_governance_contract(&admin, &governance);
+
+    MockGovernanceClient::new(&env, &governance).emergency_pause(&client.address, &true);
+    assert!(client.is_paused());
+    assert!(client.try_create_request(&1, &owner, &1000).is_err());
+
+    MockGovernanceClient::new(&env, &governance).emergency_pause(&client.address, &false);
+    assert!(!client.is_paused());
+    let request_id = client.create_request(&1, &owner, &1000);
+    assert!(client.get_request(&request_id).is_some());
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized: caller is not admin")]
+fn test_unauthorized_pause_rejected() {
+    let env = Env::default();
+    let (client, _admin) = setup_contract(&env);
+    let stranger = Address::generate(&env);
+
+    client.set_paused(&stranger, &true);
+}
+
+// Records the upgrades Evolution pushes to AgentNFT
+#[contract]
+pub struct MockAgentNFT;
+
+#[contractimpl]
+impl MockAgentNFT {
+    pub fn apply_evolution(
+        env: Env,
+        caller: Address,
+        agent_id: u64,
+        evolution_level: u32,
+        model_hash: Option<String>,
+    ) {
+        caller.require_auth();
+        env.storage()
+            .instance()
+            .set(&agent_id, &(evolution_level, model_hash));
+    }
+
+    pub fn applied(env: Env, agent_id: u64) -> Option<(u32, Option<String>)> {
+        env.storage().instance().get(&agent_id)
+    }
+}
+
+fn setup_agent_nft(
+    env: &Env,
+    client: &EvolutionClient,
+    admin: &Address,
+) -> MockAgentNFTClient<'static> {
+    let agent_nft_id = env.register(MockAgentNFT, ());
+    client.set_agent_nft(admin, &agent_nft_id);
+    MockAgentNFTClient::new(env, &agent_nft_id)
+}
+
+#[test]
+fn test_execute_evolution_applies_level_to_agent_nft() {
+    let env = Env::default();
+    let (client, admin) = setup_contract(&env);
+    let agent_nft = setup_agent_nft(&env, &client, &admin);
+    let owner = Address::generate(&env);
+
+    let request_id = client.create_request(&1, &owner, &1000);
+    client.execute_evolution(&request_id, &1, &2);
+
+    assert_eq!(agent_nft.applied(&1), Some((2, None)));
+}
+
+#[test]
+fn test_apply_attestation_applies_model_hash_to_agent_nft() {
+    let env = Env::default();
+    let (client, admin) = setup_contract(&env);
+    let oracle = setup_oracle(&env, &client, &admin);
+    let agent_nft = setup_agent_nft(&env, &client, &admin);
+    let owner = Address::generate(&env);
+    let provider = Address::generate(&env);
+    oracle.set_provider(&provider, &true);
+
+    let request_id = client.create_request(&1, &owner, &1000);
+    client.apply_attestation(&build_attestation(&client, request_id, 1, &provider, 1));
+
+    assert_eq!(
+        agent_nft.applied(&1),
+        Some((1, Some(String::from_str(&env, "QmEvolvedModel"))))
+    );
+}
+
+#[test]
+fn test_complete_upgrades_batch_applies_to_agent_nft() {
+    let env = Env::default();
+    let (client, admin) = setup_contract(&env);
+    let agent_nft = setup_agent_nft(&env, &client, &admin);
+    let owner = Address::generate(&env);
+
+    let mut completions = Vec::new(&env);
+    for agent_id in 1..=2u64 {
+        let request_id = client.create_request(&agent_id, &owner, &1000);
+        completions.push_back((request_id, String::from_str(&env, "model-v2")));
+    }
+    client.complete_upgrades_batch(&admin, &completions);
+
+    for agent_id in 1..=2u64 {
+        assert_eq!(
+            agent_nft.applied(&agent_id),
+            Some((1, Some(String::from_str(&env, "model-v2"))))
+        );
+    }
+}
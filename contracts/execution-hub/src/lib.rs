@@ -1,11 +1,15 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short, Address, Bytes, Env, String, Symbol, Vec,
+    contract, contractimpl, contracttype, symbol_short, xdr::ToXdr, Address, Bytes, BytesN, Env,
+    IntoVal, String, Symbol, Vec,
 };
 use stellai_lib::{
-    ADMIN_KEY, DEFAULT_RATE_LIMIT_OPERATIONS, DEFAULT_RATE_LIMIT_WINDOW_SECONDS, EXEC_CTR_KEY,
-    MAX_DATA_SIZE, MAX_HISTORY_QUERY_LIMIT, MAX_HISTORY_SIZE, MAX_STRING_LENGTH,
+    audit::{create_audit_log, OperationType},
+    errors::ContractError,
+    ADMIN_KEY, DEFAULT_MAX_OPERATOR_DURATION_SECONDS, DEFAULT_RATE_LIMIT_OPERATIONS,
+    DEFAULT_RATE_LIMIT_WINDOW_SECONDS, EXEC_CTR_KEY, MAX_DATA_SIZE, MAX_HISTORY_QUERY_LIMIT,
+    MAX_HISTORY_SIZE, MAX_STRING_LENGTH,
 };
 
 #[derive(Clone)]
@@ -28,7 +32,30 @@ const AGENT_NFT_KEY: &str = "agent_nft";
 const GLOBAL_RATE_LIMIT_KEY: Symbol = symbol_short!("rate_gl");
 const AGENT_RATE_LIMIT_PREFIX: Symbol = symbol_short!("rate_ag");
 const BYPASS_PREFIX: Symbol = symbol_short!("bypass");
+const MAX_OPERATOR_DURATION_KEY: Symbol = symbol_short!("op_maxdur");
 
+// Owner ed25519 keys for pre-signed executions, as (owner, pubkey) per agent
+const SIGNING_KEY_PREFIX: Symbol = symbol_short!("signkey");
+
+// Operators stored per agent; expired entries are pruned on authorization
+const OPERATORS_PREFIX: Symbol = symbol_short!("ops");
+const MAX_OPERATORS_PER_AGENT: u32 = 10;
+
+const MAX_RULES_BATCH_SIZE: u32 = 20;
+
+// Emergency pause council: (members, threshold) and the outstanding votes
+const COUNCIL_KEY: Symbol = symbol_short!("council");
+const PAUSE_VOTES_KEY: Symbol = symbol_short!("pausevote");
+const GLOBAL_PAUSED_KEY: Symbol = symbol_short!("gl_paused");
+// Votes older than this no longer count towards the threshold
+const EMERGENCY_VOTE_WINDOW_SECONDS: u64 = 3600;
+
+// Per-executor index of (agent_id, execution_id), capped at MAX_HISTORY_SIZE
+const EXECUTOR_INDEX_PREFIX: Symbol = symbol_short!("exec_by");
+
+// Deployment overrides for MAX_STRING_LENGTH and MAX_DATA_SIZE
+const MAX_STRING_LENGTH_KEY: Symbol = symbol_short!("max_str");
+const MAX_DATA_SIZE_KEY: Symbol = symbol_short!("max_data");
 
 #[derive(Clone)]
 #[contracttype]
@@ -57,6 +84,19 @@ pub struct ExecutionReceipt {
     pub created_at: u64,
 }
 
+/// Execution pre-authorized off-chain by the agent owner.
+/// The owner signs the XDR encoding of this struct.
+#[derive(Clone)]
+#[contracttype]
+pub struct SignedExecution {
+    pub hub: Address,
+    pub agent_id: u64,
+    pub owner_pubkey: BytesN<32>,
+    pub action: String,
+    pub parameters: Bytes,
+    pub nonce: u64,
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub struct RateLimitData {
@@ -102,9 +142,12 @@ impl ExecutionHub {
             operations: DEFAULT_RATE_LIMIT_OPERATIONS,
             window_seconds: DEFAULT_RATE_LIMIT_WINDOW_SECONDS,
         };
-        env.storage().instance().set(&GLOBAL_RATE_LIMIT_KEY, &global_rate_limit);
+        env.storage()
+            .instance()
+            .set(&GLOBAL_RATE_LIMIT_KEY, &global_rate_limit);
 
-        env.events().publish((symbol_short!("init"),), (admin, agent_nft));
+        env.events()
+            .publish((symbol_short!("init"),), (admin, agent_nft));
     }
 
     // Get current execution counter
@@ -134,8 +177,8 @@ impl ExecutionHub {
         owner.require_auth();
 
         Self::validate_agent_id(agent_id);
-        Self::validate_string_length(&rule_name, "Rule name");
-        Self::validate_data_size(&rule_data, "Rule data");
+        Self::validate_string_length(&env, &rule_name, "Rule name");
+        Self::validate_data_size(&env, &rule_data, "Rule data");
 
         let rule_key = RuleKey {
             agent_id,
@@ -150,6 +193,49 @@ impl ExecutionHub {
         );
     }
 
+    /// Register several rules for an agent in one call.
+    ///
+    /// The caller must own the agent in AgentNFT. The owner authorizes once
+    /// and every rule is validated before any is stored, so an invalid entry
+    /// reverts the whole batch.
+    pub fn register_rules_batch(
+        env: Env,
+        agent_id: u64,
+        owner: Address,
+        rules: Vec<(String, Bytes)>,
+    ) {
+        owner.require_auth();
+
+        Self::validate_agent_id(agent_id);
+        let actual_owner = Self::get_agent_owner(&env, agent_id);
+        if owner != actual_owner {
+            panic!("Unauthorized: caller is not agent owner");
+        }
+        if rules.is_empty() {
+            panic!("Batch cannot be empty");
+        }
+        if rules.len() > MAX_RULES_BATCH_SIZE {
+            panic!("Batch size exceeds maximum");
+        }
+        for (rule_name, rule_data) in rules.iter() {
+            Self::validate_string_length(&env, &rule_name, "Rule name");
+            Self::validate_data_size(&env, &rule_data, "Rule data");
+        }
+
+        for (rule_name, rule_data) in rules.iter() {
+            let rule_key = RuleKey {
+                agent_id,
+                rule_name,
+            };
+            env.storage().instance().set(&rule_key, &rule_data);
+        }
+
+        env.events().publish(
+            (Symbol::new(&env, "rules_registered"),),
+            (agent_id, owner, rules.len(), env.ledger().timestamp()),
+        );
+    }
+
     // Revoke existing rule
     pub fn revoke_rule(env: Env, agent_id: u64, owner: Address, rule_name: String) {
         owner.require_auth();
@@ -181,16 +267,38 @@ impl ExecutionHub {
         if owner != actual_owner {
             panic!("Unauthorized: caller is not agent owner");
         }
+        if duration_seconds > Self::get_max_operator_duration(env.clone()) {
+            panic!("Operator duration exceeds maximum");
+        }
+
+        let now = env.ledger().timestamp();
+        let expires_at = now + duration_seconds;
 
-        let expires_at = env.ledger().timestamp() + duration_seconds;
-        let operator_data = OperatorData {
+        // Re-authorizing an operator replaces its previous grant
+        let mut operators = Vec::new(&env);
+        for op_data in Self::load_operators(&env, agent_id).iter() {
+            if op_data.operator != operator && op_data.expires_at >= now {
+                operators.push_back(op_data);
+            }
+        }
+        if operators.len() >= MAX_OPERATORS_PER_AGENT {
+            panic!("Too many operators");
+        }
+        operators.push_back(OperatorData {
             operator: operator.clone(),
             expires_at,
-        };
+        });
+        Self::set_operators(&env, agent_id, &operators);
 
-        let op_key = symbol_short!("op");
-        let agent_op_key = (op_key, agent_id);
-        env.storage().instance().set(&agent_op_key, &operator_data);
+        Self::log_audit(
+            &env,
+            &owner,
+            OperationType::OperatorGranted,
+            "{\"operator\":false}",
+            "{\"operator\":true}",
+            "authorize_operator",
+            Some(String::from_str(&env, "Operator authorized for agent")),
+        );
 
         env.events().publish(
             (symbol_short!("auth_op"),),
@@ -199,7 +307,7 @@ impl ExecutionHub {
     }
 
     // Revoke an operator
-    pub fn revoke_operator(env: Env, agent_id: u64, owner: Address) {
+    pub fn revoke_operator(env: Env, agent_id: u64, owner: Address, operator: Address) {
         owner.require_auth();
         Self::validate_agent_id(agent_id);
 
@@ -209,12 +317,171 @@ impl ExecutionHub {
             panic!("Unauthorized: caller is not agent owner");
         }
 
-        let op_key = symbol_short!("op");
-        let agent_op_key = (op_key, agent_id);
-        env.storage().instance().remove(&agent_op_key);
+        let mut operators = Self::load_operators(&env, agent_id);
+        if let Some(index) = operators.iter().position(|op| op.operator == operator) {
+            operators.remove(index as u32);
+        }
+        Self::set_operators(&env, agent_id, &operators);
+
+        Self::log_audit(
+            &env,
+            &owner,
+            OperationType::OperatorRevoked,
+            "{\"operator\":true}",
+            "{\"operator\":false}",
+            "revoke_operator",
+            Some(String::from_str(&env, "Operator revoked for agent")),
+        );
+
+        env.events()
+            .publish((symbol_short!("rev_op"),), (agent_id, owner, operator));
+    }
+
+    /// Revoke every operator of an agent at once (owner only)
+    pub fn revoke_all_operators(env: Env, agent_id: u64, owner: Address) {
+        owner.require_auth();
+        Self::validate_agent_id(agent_id);
+
+        let actual_owner = Self::get_agent_owner(&env, agent_id);
+        if owner != actual_owner {
+            panic!("Unauthorized: caller is not agent owner");
+        }
+
+        let revoked = Self::load_operators(&env, agent_id).len();
+        env.storage()
+            .instance()
+            .remove(&(OPERATORS_PREFIX, agent_id));
+
+        // after_state records the count: {"revoked":N}
+        let mut after_state = [0u8; 22];
+        let prefix = b"{\"revoked\":";
+        after_state[..prefix.len()].copy_from_slice(prefix);
+        let mut digits = [0u8; 10];
+        let mut start = digits.len();
+        let mut n = revoked;
+        loop {
+            start -= 1;
+            digits[start] = b'0' + (n % 10) as u8;
+            n /= 10;
+            if n == 0 {
+                break;
+            }
+        }
+        let end = prefix.len() + digits.len() - start;
+        after_state[prefix.len()..end].copy_from_slice(&digits[start..]);
+        after_state[end] = b'}';
+
+        let _ = create_audit_log(
+            &env,
+            owner.clone(),
+            OperationType::OperatorRevoked,
+            String::from_str(&env, "{\"operator\":true}"),
+            String::from_bytes(&env, &after_state[..=end]),
+            String::from_str(&env, "revoke_all_operators"),
+            Some(String::from_str(&env, "All operators revoked for agent")),
+        );
+
+        env.events().publish(
+            (Symbol::new(&env, "all_operators_revoked"),),
+            (agent_id, owner, revoked),
+        );
+    }
+
+    /// Returns the operators currently stored for an agent, including expired ones
+    pub fn get_operators(env: Env, agent_id: u64) -> Vec<OperatorData> {
+        Self::load_operators(&env, agent_id)
+    }
+
+    /// Enable or disable strict nonce sequencing for an agent (owner only)
+    ///
+    /// In strict mode each execution must use exactly the stored nonce + 1,
+    /// so gaps from dropped executions are detected instead of skipped.
+    pub fn set_strict_nonce(env: Env, agent_id: u64, owner: Address, enabled: bool) {
+        owner.require_auth();
+        Self::validate_agent_id(agent_id);
+
+        let actual_owner = Self::get_agent_owner(&env, agent_id);
+        if owner != actual_owner {
+            panic!("Unauthorized: caller is not agent owner");
+        }
+
+        let strict_key = (symbol_short!("strict"), agent_id);
+        env.storage().instance().set(&strict_key, &enabled);
+
+        env.events()
+            .publish((symbol_short!("strict"),), (agent_id, enabled));
+    }
+
+    /// Returns whether strict nonce sequencing is enabled for an agent
+    pub fn is_strict_nonce(env: Env, agent_id: u64) -> bool {
+        let strict_key = (symbol_short!("strict"), agent_id);
+        env.storage().instance().get(&strict_key).unwrap_or(false)
+    }
+
+    /// Enable or disable strict execution hashes for an agent (owner only)
+    ///
+    /// In strict mode each execution's `execution_hash` must equal
+    /// `compute_execution_hash` over its fields, so a caller cannot record a
+    /// hash that does not match the parameters.
+    pub fn set_strict_hash(env: Env, agent_id: u64, owner: Address, enabled: bool) {
+        owner.require_auth();
+        Self::validate_agent_id(agent_id);
+
+        let actual_owner = Self::get_agent_owner(&env, agent_id);
+        if owner != actual_owner {
+            panic!("Unauthorized: caller is not agent owner");
+        }
+
+        let strict_key = (symbol_short!("strict_h"), agent_id);
+        env.storage().instance().set(&strict_key, &enabled);
+
+        env.events()
+            .publish((symbol_short!("strict_h"),), (agent_id, enabled));
+    }
+
+    /// Returns whether strict execution hashes are enabled for an agent
+    pub fn is_strict_hash(env: Env, agent_id: u64) -> bool {
+        let strict_key = (symbol_short!("strict_h"), agent_id);
+        env.storage().instance().get(&strict_key).unwrap_or(false)
+    }
+
+    /// Canonical execution hash: SHA-256 of the XDR encoding of
+    /// `(agent_id, action, parameters, nonce)`
+    pub fn compute_execution_hash(
+        env: Env,
+        agent_id: u64,
+        action: String,
+        parameters: Bytes,
+        nonce: u64,
+    ) -> Bytes {
+        let encoded = (agent_id, action, parameters, nonce).to_xdr(&env);
+        env.crypto().sha256(&encoded).into()
+    }
+
+    /// Pause or resume execution for a single agent (owner only)
+    ///
+    /// While paused, `execute_action` and `execute_actions_batch` reject every
+    /// executor, including authorized operators.
+    pub fn set_agent_paused(env: Env, agent_id: u64, owner: Address, paused: bool) {
+        owner.require_auth();
+        Self::validate_agent_id(agent_id);
+
+        let actual_owner = Self::get_agent_owner(&env, agent_id);
+        if owner != actual_owner {
+            panic!("Unauthorized: caller is not agent owner");
+        }
+
+        let paused_key = (symbol_short!("paused"), agent_id);
+        env.storage().instance().set(&paused_key, &paused);
 
         env.events()
-            .publish((symbol_short!("rev_op"),), (agent_id, owner));
+            .publish((symbol_short!("ag_pause"),), (agent_id, paused));
+    }
+
+    /// Returns whether execution is paused for an agent
+    pub fn is_agent_paused(env: Env, agent_id: u64) -> bool {
+        let paused_key = (symbol_short!("paused"), agent_id);
+        env.storage().instance().get(&paused_key).unwrap_or(false)
     }
 
     // Get rule data
@@ -238,7 +505,9 @@ impl ExecutionHub {
     /// * `execution_hash` - Cryptographic hash for off-chain verification
     ///
     /// # Returns
-    /// The execution ID for this action
+    /// The execution ID for this action, or 0 if the agent's rate limit is
+    /// exhausted. The rejection returns normally instead of panicking so it
+    /// stays counted in `get_rate_limit_violations`.
     pub fn execute_action(
         env: Env,
         agent_id: u64,
@@ -250,128 +519,335 @@ impl ExecutionHub {
     ) -> u64 {
         executor.require_auth();
 
-        Self::validate_agent_id(agent_id);
+        Self::execute_authorized(
+            &env,
+            agent_id,
+            &executor,
+            &action,
+            &parameters,
+            nonce,
+            &execution_hash,
+        )
+    }
 
-        // Permission Check: Owner or Authorized Operator
-        // 1. Check if executor is owner
-        let owner = Self::get_agent_owner(&env, agent_id);
-        let is_owner = executor == owner;
+    /// Register the ed25519 key the owner signs pre-authorized executions with.
+    /// The key stops being accepted once the agent changes owner.
+    pub fn set_owner_signing_key(env: Env, agent_id: u64, owner: Address, pubkey: BytesN<32>) {
+        owner.require_auth();
+        Self::validate_agent_id(agent_id);
 
-        // 2. If not owner, check if authorized operator
-        if !is_owner {
-            let op_key = symbol_short!("op");
-            let agent_op_key = (op_key, agent_id);
-            if let Some(op_data) = env
-                .storage()
-                .instance()
-                .get::<_, OperatorData>(&agent_op_key)
-            {
-                if op_data.operator != executor {
-                    panic!("Unauthorized: executor is not owner or operator");
-                }
-                if env.ledger().timestamp() > op_data.expires_at {
-                    panic!("Unauthorized: operator authorization expired");
-                }
-            } else {
-                panic!("Unauthorized: executor is not owner or operator");
-            }
+        if Self::get_agent_owner(&env, agent_id) != owner {
+            panic!("Unauthorized: only owner can set signing key");
         }
-        Self::validate_string_length(&action, "Action name");
-        Self::validate_data_size(&parameters, "Parameters");
-        Self::validate_data_size(&execution_hash, "Execution hash");
 
-        // Replay protection
-        let stored_nonce = Self::get_action_nonce(&env, agent_id);
-        if nonce <= stored_nonce {
-            panic!("Invalid nonce: replay protection triggered");
-        }
+        env.storage().instance().set(
+            &(SIGNING_KEY_PREFIX, agent_id),
+            &(owner.clone(), pubkey.clone()),
+        );
 
-        // Rate limiting (uses configurable global/per-agent config; bypass if admin set one)
-        Self::check_rate_limit(&env, agent_id);
+        Self::log_audit(
+            &env,
+            &owner,
+            OperationType::ExecutionAuthorized,
+            "{}",
+            "{\"signing_key\":true}",
+            "set_owner_signing_key",
+            Some(String::from_str(&env, "Signed executions authorized")),
+        );
 
-        let execution_id = Self::next_execution_id(&env);
-        let timestamp = env.ledger().timestamp();
+        env.events()
+            .publish((symbol_short!("sign_key"),), (agent_id, owner, pubkey));
+    }
 
-        Self::set_action_nonce(&env, agent_id, nonce);
-        Self::record_action_in_history(
-            &env,
+    /// Get the owner's registered signing key for an agent, if any
+    pub fn get_owner_signing_key(env: Env, agent_id: u64) -> Option<BytesN<32>> {
+        env.storage()
+            .instance()
+            .get::<_, (Address, BytesN<32>)>(&(SIGNING_KEY_PREFIX, agent_id))
+            .map(|(_, pubkey)| pubkey)
+    }
+
+    /// Execute an action the owner pre-authorized off-chain, so a relayer can
+    /// submit it without the owner's live auth.
+    ///
+    /// `signature` must be the owner's ed25519 signature over the XDR encoding
+    /// of the matching `SignedExecution`, and `owner_pubkey` must be the key
+    /// the current owner registered with `set_owner_signing_key`. The action
+    /// then runs through the normal execution checks with the owner as
+    /// executor, recording the canonical `compute_execution_hash`.
+    pub fn execute_action_signed(
+        env: Env,
+        agent_id: u64,
+        owner_pubkey: BytesN<32>,
+        action: String,
+        parameters: Bytes,
+        nonce: u64,
+        signature: BytesN<64>,
+    ) -> u64 {
+        Self::validate_agent_id(agent_id);
+
+        let owner = Self::get_agent_owner(&env, agent_id);
+        let registered: Option<(Address, BytesN<32>)> = env
+            .storage()
+            .instance()
+            .get(&(SIGNING_KEY_PREFIX, agent_id));
+        match registered {
+            Some((key_owner, pubkey)) if key_owner == owner && pubkey == owner_pubkey => {}
+            _ => panic!("Unauthorized: signing key does not belong to owner"),
+        }
+
+        let message = SignedExecution {
+            hub: env.current_contract_address(),
             agent_id,
-            execution_id,
-            &action,
-            &executor,
+            owner_pubkey: owner_pubkey.clone(),
+            action: action.clone(),
+            parameters: parameters.clone(),
+            nonce,
+        }
+        .to_xdr(&env);
+        env.crypto()
+            .ed25519_verify(&owner_pubkey, &message, &signature);
+
+        let execution_hash = Self::compute_execution_hash(
+            env.clone(),
+            agent_id,
+            action.clone(),
+            parameters.clone(),
             nonce,
-            &execution_hash,
         );
-        Self::store_execution_receipt(
+        Self::execute_authorized(
             &env,
-            execution_id,
             agent_id,
+            &owner,
             &action,
-            &executor,
-            timestamp,
+            &parameters,
+            nonce,
             &execution_hash,
-        );
-
-        env.events().publish(
-            (symbol_short!("act_exec"),),
-            (
-                execution_id,
-                agent_id,
-                action.clone(),
-                executor.clone(),
-                timestamp,
-                nonce,
-                execution_hash.clone(),
-            ),
-        );
-
-        execution_id
+        )
     }
 
-    // Get execution history
-    pub fn get_history(env: Env, agent_id: u64, limit: u32) -> Vec<ActionRecord> {
+    // Helper: run an execution for an executor whose authorization is established
+    fn execute_authorized(
+        env: &Env,
+        agent_id: u64,
+        executor: &Address,
+        action: &String,
+        parameters: &Bytes,
+        nonce: u64,
+        execution_hash: &Bytes,
+    ) -> u64 {
         Self::validate_agent_id(agent_id);
-
-        if limit > MAX_HISTORY_QUERY_LIMIT {
-            panic!("Limit exceeds maximum allowed (500)");
+        if Self::is_paused(env.clone()) {
+            panic!("Contract paused");
+        }
+        if Self::is_agent_paused(env.clone(), agent_id) {
+            panic!("Agent paused");
         }
 
-        let history_key = symbol_short!("hist");
-        let agent_key = (history_key, agent_id);
-        let history: Vec<ActionRecord> = env
-            .storage()
-            .instance()
-            .get(&agent_key)
-            .unwrap_or_else(|| Vec::new(&env));
+        Self::verify_executor(env, agent_id, executor);
+        Self::validate_string_length(env, action, "Action name");
+        Self::validate_data_size(env, parameters, "Parameters");
+        Self::validate_data_size(env, execution_hash, "Execution hash");
+        Self::verify_execution_hash(env, agent_id, action, parameters, nonce, execution_hash);
 
-        let mut result = Vec::new(&env);
-        let start_idx = if history.len() > limit {
-            history.len() - limit
-        } else {
-            0
-        };
+        // Replay protection
+        let stored_nonce = Self::get_action_nonce(env, agent_id);
+        if nonce <= stored_nonce {
+            panic!("Invalid nonce: replay protection triggered");
+        }
+        if Self::is_strict_nonce(env.clone(), agent_id) && nonce != stored_nonce + 1 {
+            panic!("Nonce must be exactly sequential");
+        }
 
-        for i in start_idx..history.len() {
-            if let Some(item) = history.get(i) {
-                result.push_back(item);
-            }
+        // Rate limiting (uses configurable global/per-agent config; bypass if admin set one)
+        if !Self::check_rate_limit(env, agent_id, executor, 1) {
+            return 0;
         }
 
-        result
+        Self::record_execution(env, agent_id, executor, action, nonce, execution_hash)
     }
 
-    // Get total action count
-    pub fn get_action_count(env: Env, agent_id: u64) -> u32 {
-        Self::validate_agent_id(agent_id);
-        let history_key = symbol_short!("hist");
-        let agent_key = (history_key, agent_id);
-        let history: Vec<ActionRecord> = env
-            .storage()
-            .instance()
-            .get(&agent_key)
-            .unwrap_or_else(|| Vec::new(&env));
-        history.len()
-    }
+    /// Dry-run the checks `execute_action` performs (pause, permissions, nonce
+    /// and rate limit) without consuming the nonce or recording anything.
+    pub fn can_execute_action(
+        env: Env,
+        agent_id: u64,
+        executor: Address,
+        nonce: u64,
+    ) -> Result<(), ContractError> {
+        if agent_id == 0 {
+            return Err(ContractError::InvalidAgentId);
+        }
+        if Self::is_paused(env.clone()) {
+            return Err(ContractError::ContractPaused);
+        }
+        if Self::is_agent_paused(env.clone(), agent_id) {
+            return Err(ContractError::AgentPaused);
+        }
+        Self::check_executor(&env, agent_id, &executor)?;
+
+        let stored_nonce = Self::get_action_nonce(&env, agent_id);
+        if nonce <= stored_nonce {
+            return Err(ContractError::StaleNonce);
+        }
+        if Self::is_strict_nonce(env.clone(), agent_id) && nonce != stored_nonce + 1 {
+            return Err(ContractError::NonSequentialNonce);
+        }
+
+        if !Self::has_active_bypass(&env, agent_id)
+            && Self::next_rate_limit_state(&env, agent_id, 1).is_none()
+        {
+            return Err(ContractError::RateLimitExceeded);
+        }
+
+        Ok(())
+    }
+
+    /// Execute several actions for one agent in a single call.
+    ///
+    /// The executor authorizes once and permissions and rate limits are
+    /// checked once, with the whole batch counted against the window. Nonces
+    /// must be strictly increasing within the batch and above the stored
+    /// nonce. Any invalid entry reverts the entire batch.
+    ///
+    /// # Returns
+    /// The execution IDs, in the order the actions were supplied. A batch over
+    /// the rate limit executes nothing and returns an empty list.
+    pub fn execute_actions_batch(
+        env: Env,
+        agent_id: u64,
+        executor: Address,
+        actions: Vec<(String, Bytes, u64, Bytes)>,
+    ) -> Vec<u64> {
+        executor.require_auth();
+
+        Self::validate_agent_id(agent_id);
+        if actions.is_empty() {
+            panic!("Batch cannot be empty");
+        }
+        if Self::is_paused(env.clone()) {
+            panic!("Contract paused");
+        }
+        if Self::is_agent_paused(env.clone(), agent_id) {
+            panic!("Agent paused");
+        }
+
+        Self::verify_executor(&env, agent_id, &executor);
+
+        let strict = Self::is_strict_nonce(env.clone(), agent_id);
+        let mut last_nonce = Self::get_action_nonce(&env, agent_id);
+        for (action, parameters, nonce, execution_hash) in actions.iter() {
+            Self::validate_string_length(&env, &action, "Action name");
+            Self::validate_data_size(&env, &parameters, "Parameters");
+            Self::validate_data_size(&env, &execution_hash, "Execution hash");
+            Self::verify_execution_hash(
+                &env,
+                agent_id,
+                &action,
+                &parameters,
+                nonce,
+                &execution_hash,
+            );
+
+            if nonce <= last_nonce {
+                panic!("Invalid nonce: replay protection triggered");
+            }
+            if strict && nonce != last_nonce + 1 {
+                panic!("Nonce must be exactly sequential");
+            }
+            last_nonce = nonce;
+        }
+
+        let mut execution_ids = Vec::new(&env);
+        if !Self::check_rate_limit(&env, agent_id, &executor, actions.len()) {
+            return execution_ids;
+        }
+
+        for (action, _parameters, nonce, execution_hash) in actions.iter() {
+            let execution_id =
+                Self::record_execution(&env, agent_id, &executor, &action, nonce, &execution_hash);
+            execution_ids.push_back(execution_id);
+        }
+
+        execution_ids
+    }
+
+    /// Record a failed off-chain execution for an agent. Lowers the agent's
+    /// reputation in AgentNFT and emits an `act_fail` event.
+    pub fn report_execution_failure(
+        env: Env,
+        agent_id: u64,
+        executor: Address,
+        execution_hash: Bytes,
+    ) {
+        executor.require_auth();
+
+        Self::validate_agent_id(agent_id);
+        Self::verify_executor(&env, agent_id, &executor);
+        Self::validate_data_size(&env, &execution_hash, "Execution hash");
+
+        Self::report_reputation(&env, agent_id, false);
+
+        env.events().publish(
+            (symbol_short!("act_fail"),),
+            (agent_id, executor, env.ledger().timestamp(), execution_hash),
+        );
+    }
+
+    // Get execution history
+    pub fn get_history(env: Env, agent_id: u64, limit: u32) -> Vec<ActionRecord> {
+        Self::validate_agent_id(agent_id);
+
+        if limit > MAX_HISTORY_QUERY_LIMIT {
+            panic!("Limit exceeds maximum allowed (500)");
+        }
+
+        let history_key = symbol_short!("hist");
+        let agent_key = (history_key, agent_id);
+        let history: Vec<ActionRecord> = env
+            .storage()
+            .instance()
+            .get(&agent_key)
+            .unwrap_or_else(|| Vec::new(&env));
+
+        let mut result = Vec::new(&env);
+        let start_idx = if history.len() > limit {
+            history.len() - limit
+        } else {
+            0
+        };
+
+        for i in start_idx..history.len() {
+            if let Some(item) = history.get(i) {
+                result.push_back(item);
+            }
+        }
+
+        result
+    }
+
+    // Get action count (records currently retained in history)
+    pub fn get_action_count(env: Env, agent_id: u64) -> u32 {
+        Self::validate_agent_id(agent_id);
+        let history_key = symbol_short!("hist");
+        let agent_key = (history_key, agent_id);
+        let history: Vec<ActionRecord> = env
+            .storage()
+            .instance()
+            .get(&agent_key)
+            .unwrap_or_else(|| Vec::new(&env));
+        history.len()
+    }
+
+    /// Aggregate activity for an agent: (total executions, last execution timestamp).
+    /// Unlike history this is never truncated; `(0, 0)` if it has never executed.
+    pub fn get_agent_activity(env: Env, agent_id: u64) -> (u64, u64) {
+        Self::validate_agent_id(agent_id);
+        env.storage()
+            .instance()
+            .get(&(symbol_short!("activity"), agent_id))
+            .unwrap_or((0, 0))
+    }
 
     /// Get execution receipt by execution ID (Issue #10)
     /// Read-only getter for immutable execution proofs
@@ -428,6 +904,78 @@ impl ExecutionHub {
         receipts
     }
 
+    /// Page through every execution an address has run, across agents, as
+    /// `(agent_id, execution_id)` pairs, oldest first. `start` indexes the
+    /// retained list, which keeps the most recent MAX_HISTORY_SIZE entries.
+    pub fn get_executions_by_executor(
+        env: Env,
+        executor: Address,
+        start: u32,
+        limit: u32,
+    ) -> Vec<(u64, u64)> {
+        if limit > MAX_HISTORY_QUERY_LIMIT {
+            panic!("Limit exceeds maximum allowed (500)");
+        }
+
+        let index: Vec<(u64, u64)> = env
+            .storage()
+            .instance()
+            .get(&(EXECUTOR_INDEX_PREFIX, executor))
+            .unwrap_or_else(|| Vec::new(&env));
+
+        let mut result = Vec::new(&env);
+        let end = start.saturating_add(limit).min(index.len());
+        for i in start..end {
+            if let Some(entry) = index.get(i) {
+                result.push_back(entry);
+            }
+        }
+        result
+    }
+
+    /// Get an agent's execution receipts with `from_ts <= timestamp <= to_ts`,
+    /// oldest first, e.g. to audit activity around a sale. Only executions
+    /// still retained in the agent's history are scanned.
+    pub fn get_executions_in_range(
+        env: Env,
+        agent_id: u64,
+        from_ts: u64,
+        to_ts: u64,
+        limit: u32,
+    ) -> Vec<ExecutionReceipt> {
+        Self::validate_agent_id(agent_id);
+
+        if limit > MAX_HISTORY_QUERY_LIMIT {
+            panic!("Limit exceeds maximum allowed (500)");
+        }
+        if from_ts > to_ts {
+            panic!("Invalid time range");
+        }
+
+        let history_key = symbol_short!("hist");
+        let agent_key = (history_key, agent_id);
+        let history: Vec<ActionRecord> = env
+            .storage()
+            .instance()
+            .get(&agent_key)
+            .unwrap_or_else(|| Vec::new(&env));
+
+        let mut receipts = Vec::new(&env);
+        for record in history.iter() {
+            if receipts.len() >= limit || record.timestamp > to_ts {
+                break;
+            }
+            if record.timestamp < from_ts {
+                continue;
+            }
+            if let Some(receipt) = Self::get_execution_receipt(env.clone(), record.execution_id) {
+                receipts.push_back(receipt);
+            }
+        }
+
+        receipts
+    }
+
     // Get admin address
     pub fn get_admin(env: Env) -> Address {
         env.storage()
@@ -441,100 +989,370 @@ impl ExecutionHub {
         Self::get_effective_rate_limit(&env, agent_id)
     }
 
-    /// Admin: set global rate limit (applies to all agents without an override).
-    pub fn set_global_rate_limit(env: Env, admin: Address, ops: u32, window_secs: u64) {
+    /// Returns `(remaining, resets_at)` for the agent's current rate-limit window.
+    /// A fresh or expired window reports the full allowance. With an active bypass
+    /// the agent is unlimited, reported as `(u32::MAX, u64::MAX)`.
+    pub fn get_rate_limit_status(env: Env, agent_id: u64) -> (u32, u64) {
+        if Self::has_active_bypass(&env, agent_id) {
+            return (u32::MAX, u64::MAX);
+        }
+        let config = Self::get_effective_rate_limit(&env, agent_id);
+        let now = env.ledger().timestamp();
+
+        let agent_limit_key = (symbol_short!("ratelim"), agent_id);
+        match env
+            .storage()
+            .instance()
+            .get::<_, RateLimitData>(&agent_limit_key)
+        {
+            Some(data) if now.saturating_sub(data.last_reset) <= config.window_seconds => (
+                config.operations.saturating_sub(data.count),
+                data.last_reset.saturating_add(config.window_seconds),
+            ),
+            _ => (config.operations, now.saturating_add(config.window_seconds)),
+        }
+    }
+
+    /// Number of recorded rate-limit violations for an agent.
+    pub fn get_rate_limit_violations(env: Env, agent_id: u64) -> u32 {
+        env.storage()
+            .instance()
+            .get(&(symbol_short!("rl_viol"), agent_id))
+            .unwrap_or(0)
+    }
+
+    /// Admin: set the emergency council whose `threshold` distinct members
+    /// must vote to pause all executions. Outstanding votes are cleared.
+    pub fn set_council(env: Env, admin: Address, members: Vec<Address>, threshold: u32) {
         admin.require_auth();
         Self::verify_admin(&env, &admin);
-        Self::validate_rate_limit_config(ops, window_secs);
 
-        let config = RateLimitConfig {
-            operations: ops,
-            window_seconds: window_secs,
-        };
-        env.storage().instance().set(&GLOBAL_RATE_LIMIT_KEY, &config);
-        // agent_id 0 denotes global in events
+        if threshold == 0 || threshold > members.len() {
+            panic!("Invalid council threshold");
+        }
+        for (i, member) in members.iter().enumerate() {
+            if members.first_index_of(&member) != Some(i as u32) {
+                panic!("Duplicate council member");
+            }
+        }
+
+        env.storage()
+            .instance()
+            .set(&COUNCIL_KEY, &(members.clone(), threshold));
+        env.storage().instance().remove(&PAUSE_VOTES_KEY);
+        env.events()
+            .publish((symbol_short!("council"),), (members, threshold));
+    }
+
+    /// Returns the emergency council members and vote threshold
+    pub fn get_council(env: Env) -> (Vec<Address>, u32) {
+        env.storage()
+            .instance()
+            .get(&COUNCIL_KEY)
+            .unwrap_or_else(|| (Vec::new(&env), 0))
+    }
+
+    /// Council member: vote to pause every execution. The pause takes effect
+    /// once `threshold` distinct members have voted within the vote window.
+    ///
+    /// # Returns
+    /// Whether the contract is paused after this vote
+    pub fn propose_emergency_pause(env: Env, admin: Address) -> bool {
+        admin.require_auth();
+
+        let (members, threshold) = Self::get_council(env.clone());
+        if !members.contains(&admin) {
+            panic!("Unauthorized: caller is not a council member");
+        }
+        if Self::is_paused(env.clone()) {
+            panic!("Contract already paused");
+        }
+
+        let now = env.ledger().timestamp();
+        let mut votes = Vec::new(&env);
+        let stored: Vec<(Address, u64)> = env
+            .storage()
+            .instance()
+            .get(&PAUSE_VOTES_KEY)
+            .unwrap_or_else(|| Vec::new(&env));
+        for (voter, voted_at) in stored.iter() {
+            if now.saturating_sub(voted_at) > EMERGENCY_VOTE_WINDOW_SECONDS {
+                continue;
+            }
+            if voter == admin {
+                panic!("Council member already voted");
+            }
+            votes.push_back((voter, voted_at));
+        }
+        votes.push_back((admin.clone(), now));
+
         env.events().publish(
-            (symbol_short!("rate_cfg"),),
-            (0u64, ops, window_secs),
+            (symbol_short!("pause_vot"),),
+            (admin, votes.len(), threshold),
         );
+
+        if votes.len() >= threshold {
+            env.storage().instance().set(&GLOBAL_PAUSED_KEY, &true);
+            env.storage().instance().remove(&PAUSE_VOTES_KEY);
+            env.events()
+                .publish((symbol_short!("em_pause"),), (now, votes.len()));
+            return true;
+        }
+
+        env.storage().instance().set(&PAUSE_VOTES_KEY, &votes);
+        false
     }
 
-    /// Admin: set per-agent rate limit override (e.g. for trusted oracles or high-frequency agents).
-    pub fn set_agent_rate_limit(env: Env, admin: Address, agent_id: u64, ops: u32, window_secs: u64) {
+    /// Admin: lift an emergency pause and clear any outstanding votes
+    pub fn unpause(env: Env, admin: Address) {
         admin.require_auth();
         Self::verify_admin(&env, &admin);
-        Self::validate_agent_id(agent_id);
-        Self::validate_rate_limit_config(ops, window_secs);
 
-        let config = RateLimitConfig {
-            operations: ops,
-            window_seconds: window_secs,
-        };
-        let agent_key = (AGENT_RATE_LIMIT_PREFIX, agent_id);
-        env.storage().instance().set(&agent_key, &config);
+        env.storage().instance().set(&GLOBAL_PAUSED_KEY, &false);
+        env.storage().instance().remove(&PAUSE_VOTES_KEY);
         env.events().publish(
-            (symbol_short!("rate_cfg"),),
-            (agent_id, ops, window_secs),
+            (symbol_short!("unpause"),),
+            (admin, env.ledger().timestamp()),
         );
     }
 
-    /// Admin: remove per-agent override; agent falls back to global config.
-    pub fn reset_agent_rate_limit(env: Env, admin: Address, agent_id: u64) {
+    /// Returns whether all executions are paused by the emergency council
+    pub fn is_paused(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&GLOBAL_PAUSED_KEY)
+            .unwrap_or(false)
+    }
+
+    /// Admin: override the maximum length of action and rule names
+    pub fn set_max_string_length(env: Env, admin: Address, max_length: u32) {
         admin.require_auth();
         Self::verify_admin(&env, &admin);
-        Self::validate_agent_id(agent_id);
+        if max_length == 0 {
+            panic!("Limit must be greater than 0");
+        }
+        env.storage()
+            .instance()
+            .set(&MAX_STRING_LENGTH_KEY, &max_length);
+    }
 
-        let agent_key = (AGENT_RATE_LIMIT_PREFIX, agent_id);
-        env.storage().instance().remove(&agent_key);
-        env.events().publish((symbol_short!("rate_rst"),), (agent_id,));
+    /// Returns the string length limit (defaults to MAX_STRING_LENGTH)
+    pub fn get_max_string_length(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&MAX_STRING_LENGTH_KEY)
+            .unwrap_or(MAX_STRING_LENGTH)
     }
 
-    /// Admin: emergency rate limit bypass for a specific agent (with audit log).
-    pub fn set_rate_limit_bypass(
-        env: Env,
-        admin: Address,
-        agent_id: u64,
-        reason: String,
-        valid_until: u64,
-    ) {
+    /// Admin: override the maximum size of parameters, rule data and hashes
+    pub fn set_max_data_size(env: Env, admin: Address, max_size: u32) {
         admin.require_auth();
         Self::verify_admin(&env, &admin);
-        Self::validate_agent_id(agent_id);
-        let now = env.ledger().timestamp();
-        if valid_until <= now {
-            panic!("valid_until must be in the future");
+        if max_size == 0 {
+            panic!("Limit must be greater than 0");
         }
+        env.storage().instance().set(&MAX_DATA_SIZE_KEY, &max_size);
+    }
 
-        let record = BypassRecord {
-            valid_until,
-            reason: reason.clone(),
-        };
-        let bypass_key = (BYPASS_PREFIX, agent_id);
-        env.storage().instance().set(&bypass_key, &record);
-        env.events().publish((symbol_short!("bypass_on"),), (agent_id, reason));
+    /// Returns the data size limit (defaults to MAX_DATA_SIZE)
+    pub fn get_max_data_size(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&MAX_DATA_SIZE_KEY)
+            .unwrap_or(MAX_DATA_SIZE)
     }
 
-    /// Admin: clear emergency bypass for an agent.
-    pub fn clear_rate_limit_bypass(env: Env, admin: Address, agent_id: u64) {
+    /// Admin: set a per-agent history cap (1..=MAX_HISTORY_SIZE).
+    /// Once reached, the oldest history records are evicted.
+    pub fn set_history_cap(env: Env, admin: Address, agent_id: u64, cap: u32) {
         admin.require_auth();
         Self::verify_admin(&env, &admin);
         Self::validate_agent_id(agent_id);
+        if cap == 0 || cap > MAX_HISTORY_SIZE {
+            panic!("History cap must be between 1 and MAX_HISTORY_SIZE");
+        }
 
-        let bypass_key = (BYPASS_PREFIX, agent_id);
-        env.storage().instance().remove(&bypass_key);
-        env.events().publish((symbol_short!("bypass_off"),), (agent_id,));
+        env.storage()
+            .instance()
+            .set(&(symbol_short!("hist_cap"), agent_id), &cap);
+        env.events()
+            .publish((symbol_short!("hist_cap"),), (agent_id, cap));
     }
 
-    // Transfer admin rights
+    /// Returns the history cap for an agent (defaults to MAX_HISTORY_SIZE).
+    pub fn get_history_cap(env: Env, agent_id: u64) -> u32 {
+        env.storage()
+            .instance()
+            .get(&(symbol_short!("hist_cap"), agent_id))
+            .unwrap_or(MAX_HISTORY_SIZE)
+    }
+
+    /// Admin: set the longest duration an operator can be authorized for.
+    pub fn set_max_operator_duration(env: Env, admin: Address, seconds: u64) {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin);
+        if seconds == 0 {
+            panic!("Max operator duration must be positive");
+        }
+
+        env.storage()
+            .instance()
+            .set(&MAX_OPERATOR_DURATION_KEY, &seconds);
+        env.events().publish((symbol_short!("op_maxdur"),), seconds);
+    }
+
+    /// Returns the operator authorization cap (defaults to 90 days).
+    pub fn get_max_operator_duration(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&MAX_OPERATOR_DURATION_KEY)
+            .unwrap_or(DEFAULT_MAX_OPERATOR_DURATION_SECONDS)
+    }
+
+    /// Admin: set global rate limit (applies to all agents without an override).
+    pub fn set_global_rate_limit(env: Env, admin: Address, ops: u32, window_secs: u64) {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin);
+        Self::validate_rate_limit_config(ops, window_secs);
+
+        let config = RateLimitConfig {
+            operations: ops,
+            window_seconds: window_secs,
+        };
+        env.storage()
+            .instance()
+            .set(&GLOBAL_RATE_LIMIT_KEY, &config);
+        // agent_id 0 denotes global in events
+        env.events()
+            .publish((symbol_short!("rate_cfg"),), (0u64, ops, window_secs));
+    }
+
+    /// Admin: set per-agent rate limit override (e.g. for trusted oracles or high-frequency agents).
+    pub fn set_agent_rate_limit(
+        env: Env,
+        admin: Address,
+        agent_id: u64,
+        ops: u32,
+        window_secs: u64,
+    ) {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin);
+        Self::validate_agent_id(agent_id);
+        Self::validate_rate_limit_config(ops, window_secs);
+
+        let config = RateLimitConfig {
+            operations: ops,
+            window_seconds: window_secs,
+        };
+        let agent_key = (AGENT_RATE_LIMIT_PREFIX, agent_id);
+        env.storage().instance().set(&agent_key, &config);
+        env.events()
+            .publish((symbol_short!("rate_cfg"),), (agent_id, ops, window_secs));
+    }
+
+    /// Admin: remove per-agent override; agent falls back to global config.
+    pub fn reset_agent_rate_limit(env: Env, admin: Address, agent_id: u64) {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin);
+        Self::validate_agent_id(agent_id);
+
+        let agent_key = (AGENT_RATE_LIMIT_PREFIX, agent_id);
+        env.storage().instance().remove(&agent_key);
+        env.events()
+            .publish((symbol_short!("rate_rst"),), (agent_id,));
+    }
+
+    /// Admin: emergency rate limit bypass for a specific agent (with audit log).
+    pub fn set_rate_limit_bypass(
+        env: Env,
+        admin: Address,
+        agent_id: u64,
+        reason: String,
+        valid_until: u64,
+    ) {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin);
+        Self::validate_agent_id(agent_id);
+        let now = env.ledger().timestamp();
+        if valid_until <= now {
+            panic!("valid_until must be in the future");
+        }
+
+        let record = BypassRecord {
+            valid_until,
+            reason: reason.clone(),
+        };
+        let bypass_key = (BYPASS_PREFIX, agent_id);
+        env.storage().instance().set(&bypass_key, &record);
+
+        Self::log_audit(
+            &env,
+            &admin,
+            OperationType::RateLimitBypass,
+            "{\"bypass\":false}",
+            "{\"bypass\":true}",
+            "set_rate_limit_bypass",
+            Some(reason.clone()),
+        );
+
+        env.events()
+            .publish((symbol_short!("bypass_on"),), (agent_id, reason));
+    }
+
+    /// Admin: clear emergency bypass for an agent.
+    pub fn clear_rate_limit_bypass(env: Env, admin: Address, agent_id: u64) {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin);
+        Self::validate_agent_id(agent_id);
+
+        let bypass_key = (BYPASS_PREFIX, agent_id);
+        env.storage().instance().remove(&bypass_key);
+        env.events()
+            .publish((Symbol::new(&env, "bypass_off"),), (agent_id,));
+    }
+
+    // Transfer admin rights
     pub fn transfer_admin(env: Env, current_admin: Address, new_admin: Address) {
         current_admin.require_auth();
         Self::verify_admin(&env, &current_admin);
 
         env.storage().instance().set(&ADMIN_KEY, &new_admin);
+
+        Self::log_audit(
+            &env,
+            &current_admin,
+            OperationType::AdminTransfer,
+            "{\"admin\":\"current\"}",
+            "{\"admin\":\"new\"}",
+            "transfer_admin",
+            Some(String::from_str(&env, "ExecutionHub admin transferred")),
+        );
+
         env.events()
             .publish((symbol_short!("adm_xfer"),), (current_admin, new_admin));
     }
 
+    // Helper: record a security-sensitive operation in the shared audit log
+    fn log_audit(
+        env: &Env,
+        operator: &Address,
+        operation_type: OperationType,
+        before_state: &str,
+        after_state: &str,
+        tx_hash: &str,
+        description: Option<String>,
+    ) {
+        let _ = create_audit_log(
+            env,
+            operator.clone(),
+            operation_type,
+            String::from_str(env, before_state),
+            String::from_str(env, after_state),
+            String::from_str(env, tx_hash),
+            description,
+        );
+    }
+
     // Helper: verify admin
     fn verify_admin(env: &Env, caller: &Address) {
         let admin: Address = env
@@ -560,7 +1378,11 @@ impl ExecutionHub {
     // Helper: get effective rate limit for agent (override or global)
     fn get_effective_rate_limit(env: &Env, agent_id: u64) -> RateLimitConfig {
         let agent_key = (AGENT_RATE_LIMIT_PREFIX, agent_id);
-        if let Some(config) = env.storage().instance().get::<_, RateLimitConfig>(&agent_key) {
+        if let Some(config) = env
+            .storage()
+            .instance()
+            .get::<_, RateLimitConfig>(&agent_key)
+        {
             return config;
         }
         env.storage()
@@ -587,19 +1409,43 @@ impl ExecutionHub {
     }
 
     // Helper: validate string length
-    fn validate_string_length(s: &String, _field_name: &str) {
-        if s.len() > MAX_STRING_LENGTH {
+    fn validate_string_length(env: &Env, s: &String, _field_name: &str) {
+        if s.len() > Self::get_max_string_length(env.clone()) {
             panic!("String exceeds maximum length");
         }
     }
 
     // Helper: validate data size
-    fn validate_data_size(data: &Bytes, _field_name: &str) {
-        if data.len() > MAX_DATA_SIZE {
+    fn validate_data_size(env: &Env, data: &Bytes, _field_name: &str) {
+        if data.len() > Self::get_max_data_size(env.clone()) {
             panic!("Data exceeds maximum size");
         }
     }
 
+    // Helper: in strict hash mode, reject a hash that is not the canonical one
+    fn verify_execution_hash(
+        env: &Env,
+        agent_id: u64,
+        action: &String,
+        parameters: &Bytes,
+        nonce: u64,
+        execution_hash: &Bytes,
+    ) {
+        if !Self::is_strict_hash(env.clone(), agent_id) {
+            return;
+        }
+        let expected = Self::compute_execution_hash(
+            env.clone(),
+            agent_id,
+            action.clone(),
+            parameters.clone(),
+            nonce,
+        );
+        if *execution_hash != expected {
+            panic!("Execution hash mismatch");
+        }
+    }
+
     // Helper: get nonce
     fn get_action_nonce(env: &Env, agent_id: u64) -> u64 {
         let nonce_key = symbol_short!("nonce");
@@ -633,8 +1479,11 @@ impl ExecutionHub {
             .get(&agent_key)
             .unwrap_or_else(|| Vec::new(env));
 
-        if history.len() >= MAX_HISTORY_SIZE {
-            panic!("Action history limit exceeded");
+        // Ring buffer: evict the oldest records once the cap is reached.
+        // Receipts are stored separately and stay retrievable by ID.
+        let cap = Self::get_history_cap(env.clone(), agent_id);
+        while history.len() >= cap {
+            history.pop_front();
         }
 
         let timestamp = env.ledger().timestamp();
@@ -686,11 +1535,176 @@ impl ExecutionHub {
         env.storage().instance().set(&exec_to_agent_key, &agent_id);
     }
 
-    // Helper: check rate limit (uses effective config; skips if bypass active)
-    fn check_rate_limit(env: &Env, agent_id: u64) {
+    // Helper: assign an execution ID, then record nonce, history, receipt and event
+    fn record_execution(
+        env: &Env,
+        agent_id: u64,
+        executor: &Address,
+        action: &String,
+        nonce: u64,
+        execution_hash: &Bytes,
+    ) -> u64 {
+        let execution_id = Self::next_execution_id(env);
+        let timestamp = env.ledger().timestamp();
+
+        Self::set_action_nonce(env, agent_id, nonce);
+        Self::record_activity(env, agent_id, timestamp);
+        Self::index_executor(env, executor, agent_id, execution_id);
+        Self::record_action_in_history(
+            env,
+            agent_id,
+            execution_id,
+            action,
+            executor,
+            nonce,
+            execution_hash,
+        );
+        Self::store_execution_receipt(
+            env,
+            execution_id,
+            agent_id,
+            action,
+            executor,
+            timestamp,
+            execution_hash,
+        );
+
+        Self::report_reputation(env, agent_id, true);
+
+        env.events().publish(
+            (symbol_short!("act_exec"),),
+            (
+                execution_id,
+                agent_id,
+                action.clone(),
+                executor.clone(),
+                timestamp,
+                nonce,
+                execution_hash.clone(),
+            ),
+        );
+
+        execution_id
+    }
+
+    // Helper: append to the executor's (agent_id, execution_id) index,
+    // evicting the oldest entry once MAX_HISTORY_SIZE is reached
+    fn index_executor(env: &Env, executor: &Address, agent_id: u64, execution_id: u64) {
+        let key = (EXECUTOR_INDEX_PREFIX, executor.clone());
+        let mut index: Vec<(u64, u64)> = env
+            .storage()
+            .instance()
+            .get(&key)
+            .unwrap_or_else(|| Vec::new(env));
+        while index.len() >= MAX_HISTORY_SIZE {
+            index.pop_front();
+        }
+        index.push_back((agent_id, execution_id));
+        env.storage().instance().set(&key, &index);
+    }
+
+    // Helper: bump an agent's lifetime execution counter and last-executed time
+    fn record_activity(env: &Env, agent_id: u64, timestamp: u64) {
+        let activity_key = (symbol_short!("activity"), agent_id);
+        let (total, _): (u64, u64) = env
+            .storage()
+            .instance()
+            .get(&activity_key)
+            .unwrap_or((0, 0));
+        env.storage()
+            .instance()
+            .set(&activity_key, &(total + 1, timestamp));
+    }
+
+    // Helper: Permission Check: Owner or Authorized Operator
+    fn verify_executor(env: &Env, agent_id: u64, executor: &Address) {
+        match Self::check_executor(env, agent_id, executor) {
+            Ok(()) => {}
+            Err(ContractError::OperatorExpired) => {
+                panic!("Unauthorized: operator authorization expired")
+            }
+            Err(_) => panic!("Unauthorized: executor is not owner or operator"),
+        }
+    }
+
+    // Helper: non-panicking owner/operator check shared with the dry run
+    fn check_executor(env: &Env, agent_id: u64, executor: &Address) -> Result<(), ContractError> {
+        // 1. Check if executor is owner
+        let owner = Self::get_agent_owner(env, agent_id);
+        if *executor == owner {
+            return Ok(());
+        }
+
+        // 2. If not owner, check if authorized operator
+        let operators = Self::load_operators(env, agent_id);
+        match operators.iter().find(|op| op.operator == *executor) {
+            Some(op_data) if env.ledger().timestamp() > op_data.expires_at => {
+                Err(ContractError::OperatorExpired)
+            }
+            Some(_) => Ok(()),
+            None => Err(ContractError::Unauthorized),
+        }
+    }
+
+    // Helper: load an agent's operator list
+    fn load_operators(env: &Env, agent_id: u64) -> Vec<OperatorData> {
+        env.storage()
+            .instance()
+            .get(&(OPERATORS_PREFIX, agent_id))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    // Helper: persist an agent's operator list, dropping the key when empty
+    fn set_operators(env: &Env, agent_id: u64, operators: &Vec<OperatorData>) {
+        let key = (OPERATORS_PREFIX, agent_id);
+        if operators.is_empty() {
+            env.storage().instance().remove(&key);
+        } else {
+            env.storage().instance().set(&key, operators);
+        }
+    }
+
+    // Helper: check rate limit (uses effective config; skips if bypass active).
+    // `operations` is the number of actions counted against the window.
+    // Over the limit, counts a violation and publishes `rate_limit_exceeded`,
+    // then returns false; callers return without panicking so both persist.
+    fn check_rate_limit(env: &Env, agent_id: u64, executor: &Address, operations: u32) -> bool {
         if Self::has_active_bypass(env, agent_id) {
-            return;
+            return true;
         }
+
+        let Some(new_rate_data) = Self::next_rate_limit_state(env, agent_id, operations) else {
+            let violations_key = (symbol_short!("rl_viol"), agent_id);
+            let violations: u32 = env
+                .storage()
+                .instance()
+                .get::<_, u32>(&violations_key)
+                .unwrap_or(0)
+                .saturating_add(1);
+            env.storage().instance().set(&violations_key, &violations);
+
+            let config = Self::get_effective_rate_limit(env, agent_id);
+            env.events().publish(
+                (Symbol::new(env, "rate_limit_exceeded"),),
+                (
+                    agent_id,
+                    executor.clone(),
+                    config.operations,
+                    env.ledger().timestamp(),
+                ),
+            );
+            return false;
+        };
+
+        env.storage()
+            .instance()
+            .set(&(symbol_short!("ratelim"), agent_id), &new_rate_data);
+        true
+    }
+
+    // Helper: window state after counting `operations`, or None if over the limit.
+    // Read-only so the dry run can share it.
+    fn next_rate_limit_state(env: &Env, agent_id: u64, operations: u32) -> Option<RateLimitData> {
         let config = Self::get_effective_rate_limit(env, agent_id);
         let max_operations = config.operations;
         let window_seconds = config.window_seconds;
@@ -707,22 +1721,42 @@ impl ExecutionHub {
 
         let elapsed = now.saturating_sub(last_reset);
 
-        let (new_reset, new_count) = if elapsed > window_seconds {
-            (now, 1)
-        } else if count < max_operations {
-            (last_reset, count + 1)
+        let (new_reset, new_count) = if elapsed > window_seconds && operations <= max_operations {
+            (now, operations)
+        } else if elapsed <= window_seconds && count.saturating_add(operations) <= max_operations {
+            (last_reset, count + operations)
         } else {
-            panic!("Rate limit exceeded");
+            return None;
         };
 
-        let new_rate_data = RateLimitData {
+        Some(RateLimitData {
             last_reset: new_reset,
             count: new_count,
-        };
+        })
+    }
 
-        env.storage()
+    // Helper: report an execution outcome to AgentNFT's reputation score.
+    // Best-effort: if AgentNFT has not registered this hub the call fails and
+    // is ignored, so executions are never blocked by reputation tracking.
+    fn report_reputation(env: &Env, agent_id: u64, success: bool) {
+        let agent_nft_addr: Address = env
+            .storage()
             .instance()
-            .set(&agent_limit_key, &new_rate_data);
+            .get(&Symbol::new(env, AGENT_NFT_KEY))
+            .expect("AgentNFT contract not set");
+
+        let _ = env.try_invoke_contract::<u64, soroban_sdk::Error>(
+            &agent_nft_addr,
+            &Symbol::new(env, "update_reputation"),
+            Vec::from_array(
+                env,
+                [
+                    env.current_contract_address().into_val(env),
+                    agent_id.into_val(env),
+                    success.into_val(env),
+                ],
+            ),
+        );
     }
 
     // Helper: Get agent owner from AgentNFT contract
@@ -764,6 +1798,26 @@ mod test {
         pub fn set_owner(env: Env, agent_id: u64, owner: Address) {
             env.storage().instance().set(&agent_id, &owner);
         }
+
+        pub fn update_reputation(env: Env, caller: Address, agent_id: u64, success: bool) -> u64 {
+            caller.require_auth();
+            let key = (symbol_short!("rep"), agent_id);
+            let reputation: u64 = env.storage().instance().get(&key).unwrap_or(0);
+            let reputation = if success {
+                reputation + 1
+            } else {
+                reputation.saturating_sub(1)
+            };
+            env.storage().instance().set(&key, &reputation);
+            reputation
+        }
+
+        pub fn get_agent_reputation(env: Env, agent_id: u64) -> u64 {
+            env.storage()
+                .instance()
+                .get(&(symbol_short!("rep"), agent_id))
+                .unwrap_or(0)
+        }
     }
 
     fn setup_test() -> (
@@ -776,10 +1830,10 @@ mod test {
         let env = Env::default();
         env.mock_all_auths();
 
-        let contract_id = env.register_contract(None, ExecutionHub);
+        let contract_id = env.register(ExecutionHub, ());
         let client = ExecutionHubClient::new(&env, &contract_id);
 
-        let agent_nft_id = env.register_contract(None, MockAgentNFT);
+        let agent_nft_id = env.register(MockAgentNFT, ());
         let agent_nft_client = MockAgentNFTClient::new(&env, &agent_nft_id);
 
         let admin = Address::generate(&env);
@@ -792,7 +1846,7 @@ mod test {
 
     #[test]
     fn test_initialization() {
-        let (env, client, admin, _, agent_nft_id) = setup_test();
+        let (_env, client, admin, _, _agent_nft_id) = setup_test();
 
         assert_eq!(client.get_admin(), admin);
         assert_eq!(client.get_execution_counter(), 0);
@@ -803,7 +1857,7 @@ mod test {
     #[test]
     #[should_panic(expected = "Contract already initialized")]
     fn test_double_initialization() {
-        let (env, client, admin, _, agent_nft_id) = setup_test();
+        let (_env, client, admin, _, agent_nft_id) = setup_test();
         client.initialize(&admin, &agent_nft_id);
     }
 
@@ -833,7 +1887,7 @@ mod test {
     fn test_permission_checks() {
         let (env, client, _admin, agent_nft, _) = setup_test();
         let owner = Address::generate(&env);
-        let other = Address::generate(&env);
+        let _other = Address::generate(&env);
 
         // Set owner for agent 1
         agent_nft.set_owner(&1, &owner);
@@ -887,7 +1941,7 @@ mod test {
         client.execute_action(&1, &operator, &action, &params, &1, &exec_hash);
 
         // Revoke
-        client.revoke_operator(&1, &owner);
+        client.revoke_operator(&1, &owner, &operator);
 
         // Should fail now (need separate test for panic)
     }
@@ -901,7 +1955,7 @@ mod test {
 
         agent_nft.set_owner(&1, &owner);
         client.authorize_operator(&1, &owner, &operator, &100);
-        client.revoke_operator(&1, &owner);
+        client.revoke_operator(&1, &owner, &operator);
 
         let action = String::from_str(&env, "test");
         let params = Bytes::from_array(&env, &[1]);
@@ -1165,7 +2219,6 @@ mod test {
     }
 
     #[test]
-    #[should_panic(expected = "Rate limit exceeded")]
     fn test_rate_limit_integration_with_execution() {
         let (env, client, admin, agent_nft, _) = setup_test();
         let executor = Address::generate(&env);
@@ -1180,20 +2233,25 @@ mod test {
             client.execute_action(&1, &executor, &action, &params, &i, &h);
         }
         let h4 = Bytes::from_array(&env, &[4, 8]);
-        client.execute_action(&1, &executor, &action, &params, &4, &h4);
+        assert_eq!(
+            client.execute_action(&1, &executor, &action, &params, &4, &h4),
+            0
+        );
+        assert_eq!(client.get_action_count(&1), 3);
+        assert_eq!(client.get_rate_limit_violations(&1), 1);
     }
 
     #[test]
     #[should_panic(expected = "operations must be greater than 0")]
     fn test_rate_limit_zero_ops_panics() {
-        let (env, client, admin, _, _) = setup_test();
+        let (_env, client, admin, _, _) = setup_test();
         client.set_global_rate_limit(&admin, &0, &60);
     }
 
     #[test]
     #[should_panic(expected = "window_seconds must be greater than 0")]
     fn test_rate_limit_zero_window_panics() {
-        let (env, client, admin, _, _) = setup_test();
+        let (_env, client, admin, _, _) = setup_test();
         client.set_global_rate_limit(&admin, &100, &0);
     }
 
@@ -1229,7 +2287,6 @@ mod test {
     }
 
     #[test]
-    #[should_panic(expected = "Rate limit exceeded")]
     fn test_bypass_cleared_then_limit_applies() {
         let (env, client, admin, agent_nft, _) = setup_test();
         let executor = Address::generate(&env);
@@ -1248,7 +2305,11 @@ mod test {
         let h2 = Bytes::from_array(&env, &[2]);
         client.execute_action(&1, &executor, &action, &params, &2, &h2);
         let h3 = Bytes::from_array(&env, &[3]);
-        client.execute_action(&1, &executor, &action, &params, &3, &h3);
+        assert_eq!(
+            client.execute_action(&1, &executor, &action, &params, &3, &h3),
+            0
+        );
+        assert_eq!(client.get_rate_limit_violations(&1), 1);
     }
 
     #[test]
@@ -1267,4 +2328,1027 @@ mod test {
         let other = Address::generate(&env);
         client.set_global_rate_limit(&other, &50, &60);
     }
+
+    #[test]
+    fn test_strict_nonce_accepts_sequential() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+
+        client.set_strict_nonce(&1, &owner, &true);
+        assert!(client.is_strict_nonce(&1));
+
+        let action = String::from_str(&env, "test");
+        let params = Bytes::from_array(&env, &[1]);
+        let exec_hash = Bytes::from_array(&env, &[0xaa]);
+
+        client.execute_action(&1, &owner, &action, &params, &1, &exec_hash);
+        client.execute_action(&1, &owner, &action, &params, &2, &exec_hash);
+        assert_eq!(client.get_action_count(&1), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Nonce must be exactly sequential")]
+    fn test_strict_nonce_rejects_gap() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+
+        client.set_strict_nonce(&1, &owner, &true);
+
+        let action = String::from_str(&env, "test");
+        let params = Bytes::from_array(&env, &[1]);
+        let exec_hash = Bytes::from_array(&env, &[0xaa]);
+
+        client.execute_action(&1, &owner, &action, &params, &1, &exec_hash);
+        client.execute_action(&1, &owner, &action, &params, &3, &exec_hash);
+    }
+
+    #[test]
+    fn test_lenient_nonce_accepts_gap() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+
+        let action = String::from_str(&env, "test");
+        let params = Bytes::from_array(&env, &[1]);
+        let exec_hash = Bytes::from_array(&env, &[0xaa]);
+
+        client.execute_action(&1, &owner, &action, &params, &1, &exec_hash);
+        client.execute_action(&1, &owner, &action, &params, &5, &exec_hash);
+        assert!(!client.is_strict_nonce(&1));
+        assert_eq!(client.get_action_count(&1), 2);
+    }
+    fn batch_entry(env: &Env, nonce: u64) -> (String, Bytes, u64, Bytes) {
+        (
+            String::from_str(env, "test"),
+            Bytes::from_array(env, &[1]),
+            nonce,
+            Bytes::from_array(env, &[0xaa]),
+        )
+    }
+
+    #[test]
+    fn test_execute_actions_batch() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+
+        let actions = Vec::from_array(
+            &env,
+            [
+                batch_entry(&env, 1),
+                batch_entry(&env, 2),
+                batch_entry(&env, 5),
+            ],
+        );
+        let ids = client.execute_actions_batch(&1, &owner, &actions);
+
+        assert_eq!(ids.len(), 3);
+        assert_eq!(client.get_action_count(&1), 3);
+        assert_eq!(client.get_history(&1, &10).get(2).unwrap().nonce, 5);
+        for id in ids.iter() {
+            assert!(client.get_execution_receipt(&id).is_some());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid nonce: replay protection triggered")]
+    fn test_execute_actions_batch_rejects_non_increasing_nonce() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+
+        let actions = Vec::from_array(
+            &env,
+            [
+                batch_entry(&env, 1),
+                batch_entry(&env, 3),
+                batch_entry(&env, 3),
+            ],
+        );
+        client.execute_actions_batch(&1, &owner, &actions);
+    }
+
+    #[test]
+    fn test_execute_actions_batch_exceeds_rate_limit() {
+        let (env, client, admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+
+        client.set_agent_rate_limit(&admin, &1, &2, &60);
+
+        let actions = Vec::from_array(
+            &env,
+            [
+                batch_entry(&env, 1),
+                batch_entry(&env, 2),
+                batch_entry(&env, 3),
+            ],
+        );
+        assert_eq!(client.execute_actions_batch(&1, &owner, &actions).len(), 0);
+        assert_eq!(client.get_action_count(&1), 0);
+        assert_eq!(client.get_rate_limit_violations(&1), 1);
+    }
+    #[test]
+    fn test_rate_limit_status_decreases_with_executions() {
+        let (env, client, admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+        client.set_agent_rate_limit(&admin, &1, &5, &60);
+
+        let start = env.ledger().timestamp();
+        let action = String::from_str(&env, "test");
+        let params = Bytes::from_array(&env, &[1]);
+        let exec_hash = Bytes::from_array(&env, &[0xaa]);
+
+        client.execute_action(&1, &owner, &action, &params, &1, &exec_hash);
+        assert_eq!(client.get_rate_limit_status(&1), (4, start + 60));
+
+        env.ledger().set_timestamp(start + 10);
+        client.execute_action(&1, &owner, &action, &params, &2, &exec_hash);
+        client.execute_action(&1, &owner, &action, &params, &3, &exec_hash);
+        assert_eq!(client.get_rate_limit_status(&1), (2, start + 60));
+    }
+
+    #[test]
+    fn test_rate_limit_status_fresh_window() {
+        let (env, client, admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+        client.set_agent_rate_limit(&admin, &1, &5, &60);
+
+        let start = env.ledger().timestamp();
+        assert_eq!(client.get_rate_limit_status(&1), (5, start + 60));
+
+        let action = String::from_str(&env, "test");
+        let params = Bytes::from_array(&env, &[1]);
+        let exec_hash = Bytes::from_array(&env, &[0xaa]);
+        client.execute_action(&1, &owner, &action, &params, &1, &exec_hash);
+
+        // Once the window has elapsed the full allowance is available again
+        env.ledger().set_timestamp(start + 61);
+        assert_eq!(client.get_rate_limit_status(&1), (5, start + 121));
+    }
+
+    #[test]
+    fn test_rate_limit_status_with_bypass() {
+        let (env, client, admin, _agent_nft, _) = setup_test();
+        let valid_until = env.ledger().timestamp() + 100;
+        client.set_rate_limit_bypass(&admin, &1, &String::from_str(&env, "oracle"), &valid_until);
+
+        assert_eq!(client.get_rate_limit_status(&1), (u32::MAX, u64::MAX));
+    }
+    #[test]
+    fn test_repeated_over_limit_executions_are_counted() {
+        let (env, client, admin, agent_nft, _) = setup_test();
+        let executor = Address::generate(&env);
+        agent_nft.set_owner(&1, &executor);
+        client.set_agent_rate_limit(&admin, &1, &2, &60);
+
+        let action = String::from_str(&env, "test");
+        let params = Bytes::from_array(&env, &[1]);
+        let exec_hash = Bytes::from_array(&env, &[0xaa]);
+        client.execute_action(&1, &executor, &action, &params, &1, &exec_hash);
+        client.execute_action(&1, &executor, &action, &params, &2, &exec_hash);
+        assert_eq!(client.get_rate_limit_violations(&1), 0);
+
+        // Every rejected attempt counts, with no report from the executor
+        for nonce in 3..=5u64 {
+            let id = client.execute_action(&1, &executor, &action, &params, &nonce, &exec_hash);
+            assert_eq!(id, 0);
+        }
+        assert_eq!(client.get_rate_limit_violations(&1), 3);
+        assert_eq!(client.get_action_count(&1), 2);
+    }
+    #[test]
+    fn test_history_evicts_oldest_beyond_cap() {
+        let (env, client, admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+        client.set_history_cap(&admin, &1, &3);
+        assert_eq!(client.get_history_cap(&1), 3);
+
+        let action = String::from_str(&env, "test");
+        let params = Bytes::from_array(&env, &[1]);
+        let exec_hash = Bytes::from_array(&env, &[0xaa]);
+
+        let mut ids = Vec::new(&env);
+        for nonce in 1..=5u64 {
+            ids.push_back(client.execute_action(&1, &owner, &action, &params, &nonce, &exec_hash));
+        }
+
+        assert_eq!(client.get_action_count(&1), 3);
+        let history = client.get_history(&1, &10);
+        assert_eq!(history.get(0).unwrap().nonce, 3);
+        assert_eq!(history.get(2).unwrap().nonce, 5);
+
+        // Evicted actions still have their receipts
+        for id in ids.iter() {
+            assert!(client.get_execution_receipt(&id).is_some());
+        }
+    }
+
+    #[test]
+    fn test_history_cap_defaults_to_max() {
+        let (_env, client, _admin, _, _) = setup_test();
+        assert_eq!(client.get_history_cap(&1), MAX_HISTORY_SIZE);
+    }
+
+    #[test]
+    #[should_panic(expected = "History cap must be between 1 and MAX_HISTORY_SIZE")]
+    fn test_history_cap_zero_panics() {
+        let (_env, client, admin, _, _) = setup_test();
+        client.set_history_cap(&admin, &1, &0);
+    }
+
+    #[test]
+    fn test_execution_updates_agent_reputation() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+
+        let action = String::from_str(&env, "test");
+        let params = Bytes::from_array(&env, &[1]);
+        let exec_hash = Bytes::from_array(&env, &[0xaa]);
+
+        client.execute_action(&1, &owner, &action, &params, &1, &exec_hash);
+        client.execute_action(&1, &owner, &action, &params, &2, &exec_hash);
+        assert_eq!(agent_nft.get_agent_reputation(&1), 2);
+
+        client.report_execution_failure(&1, &owner, &exec_hash);
+        assert_eq!(agent_nft.get_agent_reputation(&1), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: executor is not owner or operator")]
+    fn test_report_execution_failure_requires_executor() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        let stranger = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+
+        let exec_hash = Bytes::from_array(&env, &[0xaa]);
+        client.report_execution_failure(&1, &stranger, &exec_hash);
+    }
+
+    #[test]
+    fn test_owner_can_pause_and_resume_agent() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+
+        assert!(!client.is_agent_paused(&1));
+        client.set_agent_paused(&1, &owner, &true);
+        assert!(client.is_agent_paused(&1));
+
+        client.set_agent_paused(&1, &owner, &false);
+        assert!(!client.is_agent_paused(&1));
+
+        let action = String::from_str(&env, "test");
+        let params = Bytes::from_array(&env, &[1]);
+        let exec_hash = Bytes::from_array(&env, &[0xaa]);
+        client.execute_action(&1, &owner, &action, &params, &1, &exec_hash);
+    }
+
+    #[test]
+    #[should_panic(expected = "Agent paused")]
+    fn test_paused_agent_blocks_operator_execution() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        let operator = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+        client.authorize_operator(&1, &owner, &operator, &100);
+        client.set_agent_paused(&1, &owner, &true);
+
+        let action = String::from_str(&env, "test");
+        let params = Bytes::from_array(&env, &[1]);
+        let exec_hash = Bytes::from_array(&env, &[0xaa]);
+        client.execute_action(&1, &operator, &action, &params, &1, &exec_hash);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: caller is not agent owner")]
+    fn test_non_owner_cannot_pause_agent() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        let stranger = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+
+        client.set_agent_paused(&1, &stranger, &true);
+    }
+
+    #[test]
+    fn test_authorize_operator_within_duration_cap() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        let operator = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+
+        assert_eq!(
+            client.get_max_operator_duration(),
+            DEFAULT_MAX_OPERATOR_DURATION_SECONDS
+        );
+        client.authorize_operator(
+            &1,
+            &owner,
+            &operator,
+            &DEFAULT_MAX_OPERATOR_DURATION_SECONDS,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Operator duration exceeds maximum")]
+    fn test_authorize_operator_beyond_duration_cap_panics() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        let operator = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+
+        client.authorize_operator(
+            &1,
+            &owner,
+            &operator,
+            &(DEFAULT_MAX_OPERATOR_DURATION_SECONDS + 1),
+        );
+    }
+
+    #[test]
+    fn test_admin_raises_operator_duration_cap() {
+        let (env, client, admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        let operator = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+
+        let long_duration = DEFAULT_MAX_OPERATOR_DURATION_SECONDS * 2;
+        assert!(client
+            .try_authorize_operator(&1, &owner, &operator, &long_duration)
+            .is_err());
+
+        client.set_max_operator_duration(&admin, &long_duration);
+        client.authorize_operator(&1, &owner, &operator, &long_duration);
+
+        let action = String::from_str(&env, "test");
+        let params = Bytes::from_array(&env, &[1]);
+        let exec_hash = Bytes::from_array(&env, &[0xaa]);
+        client.execute_action(&1, &operator, &action, &params, &1, &exec_hash);
+    }
+
+    #[test]
+    fn test_agent_activity_advances_with_executions() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+
+        let action = String::from_str(&env, "test");
+        let params = Bytes::from_array(&env, &[1]);
+        let exec_hash = Bytes::from_array(&env, &[0xaa]);
+
+        env.ledger().set_timestamp(1_000);
+        client.execute_action(&1, &owner, &action, &params, &1, &exec_hash);
+        assert_eq!(client.get_agent_activity(&1), (1, 1_000));
+
+        env.ledger().set_timestamp(1_500);
+        client.execute_action(&1, &owner, &action, &params, &2, &exec_hash);
+        client.execute_action(&1, &owner, &action, &params, &3, &exec_hash);
+        assert_eq!(client.get_agent_activity(&1), (3, 1_500));
+    }
+
+    #[test]
+    fn test_agent_activity_defaults_to_zero() {
+        let (_env, client, _admin, _, _) = setup_test();
+        assert_eq!(client.get_agent_activity(&7), (0, 0));
+    }
+
+    #[test]
+    fn test_revoke_all_operators_blocks_every_operator() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        let first = Address::generate(&env);
+        let second = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+
+        client.authorize_operator(&1, &owner, &first, &100);
+        client.authorize_operator(&1, &owner, &second, &100);
+        assert_eq!(client.get_operators(&1).len(), 2);
+
+        let action = String::from_str(&env, "test");
+        let params = Bytes::from_array(&env, &[1]);
+        let exec_hash = Bytes::from_array(&env, &[0xaa]);
+        client.execute_action(&1, &first, &action, &params, &1, &exec_hash);
+        client.execute_action(&1, &second, &action, &params, &2, &exec_hash);
+
+        client.revoke_all_operators(&1, &owner);
+        assert_eq!(client.get_operators(&1).len(), 0);
+
+        assert!(client
+            .try_execute_action(&1, &first, &action, &params, &3, &exec_hash)
+            .is_err());
+        assert!(client
+            .try_execute_action(&1, &second, &action, &params, &3, &exec_hash)
+            .is_err());
+        // The owner keeps executing
+        client.execute_action(&1, &owner, &action, &params, &3, &exec_hash);
+    }
+
+    #[test]
+    fn test_revoke_operator_leaves_others_authorized() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        let first = Address::generate(&env);
+        let second = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+
+        client.authorize_operator(&1, &owner, &first, &100);
+        client.authorize_operator(&1, &owner, &second, &100);
+        client.revoke_operator(&1, &owner, &first);
+
+        let action = String::from_str(&env, "test");
+        let params = Bytes::from_array(&env, &[1]);
+        let exec_hash = Bytes::from_array(&env, &[0xaa]);
+        assert!(client
+            .try_execute_action(&1, &first, &action, &params, &1, &exec_hash)
+            .is_err());
+        client.execute_action(&1, &second, &action, &params, &1, &exec_hash);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: caller is not agent owner")]
+    fn test_non_owner_cannot_revoke_all_operators() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        let operator = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+        client.authorize_operator(&1, &owner, &operator, &100);
+
+        client.revoke_all_operators(&1, &operator);
+    }
+
+    #[test]
+    fn test_can_execute_action_succeeds_without_side_effects() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+
+        assert_eq!(client.try_can_execute_action(&1, &owner, &1), Ok(Ok(())));
+        client.can_execute_action(&1, &owner, &1);
+
+        // Nothing consumed: the same nonce still executes and counts once
+        assert_eq!(client.get_rate_limit_status(&1).0, 100);
+        assert_eq!(client.get_action_count(&1), 0);
+        let action = String::from_str(&env, "test");
+        let params = Bytes::from_array(&env, &[1]);
+        let exec_hash = Bytes::from_array(&env, &[0xaa]);
+        client.execute_action(&1, &owner, &action, &params, &1, &exec_hash);
+        assert_eq!(client.get_action_count(&1), 1);
+    }
+
+    #[test]
+    fn test_can_execute_action_reports_failure_reasons() {
+        let (env, client, admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        let stranger = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+
+        assert_eq!(
+            client.try_can_execute_action(&1, &stranger, &1),
+            Err(Ok(ContractError::Unauthorized))
+        );
+
+        let action = String::from_str(&env, "test");
+        let params = Bytes::from_array(&env, &[1]);
+        let exec_hash = Bytes::from_array(&env, &[0xaa]);
+        client.execute_action(&1, &owner, &action, &params, &5, &exec_hash);
+        assert_eq!(
+            client.try_can_execute_action(&1, &owner, &5),
+            Err(Ok(ContractError::StaleNonce))
+        );
+
+        client.set_agent_rate_limit(&admin, &1, &1, &60);
+        assert_eq!(
+            client.try_can_execute_action(&1, &owner, &6),
+            Err(Ok(ContractError::RateLimitExceeded))
+        );
+
+        client.set_agent_paused(&1, &owner, &true);
+        assert_eq!(
+            client.try_can_execute_action(&1, &owner, &6),
+            Err(Ok(ContractError::AgentPaused))
+        );
+
+        assert_eq!(client.get_action_count(&1), 1);
+    }
+
+    #[test]
+    fn test_get_executions_in_range_filters_by_timestamp() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+
+        let action = String::from_str(&env, "test");
+        let params = Bytes::from_array(&env, &[1]);
+        let exec_hash = Bytes::from_array(&env, &[0xaa]);
+        let mut ids = Vec::new(&env);
+        for (nonce, timestamp) in [(1u64, 100u64), (2, 200), (3, 300), (4, 400)] {
+            env.ledger().set_timestamp(timestamp);
+            ids.push_back(client.execute_action(&1, &owner, &action, &params, &nonce, &exec_hash));
+        }
+
+        let window = client.get_executions_in_range(&1, &200, &300, &10);
+        assert_eq!(window.len(), 2);
+        assert_eq!(window.get(0).unwrap().execution_id, ids.get(1).unwrap());
+        assert_eq!(window.get(1).unwrap().execution_id, ids.get(2).unwrap());
+
+        let limited = client.get_executions_in_range(&1, &0, &1000, &3);
+        assert_eq!(limited.len(), 3);
+        assert_eq!(limited.get(0).unwrap().timestamp, 100);
+
+        assert_eq!(
+            client.get_executions_in_range(&1, &401, &1000, &10).len(),
+            0
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid time range")]
+    fn test_get_executions_in_range_rejects_inverted_window() {
+        let (_env, client, _admin, _agent_nft, _) = setup_test();
+        client.get_executions_in_range(&1, &300, &200, &10);
+    }
+
+    fn sign_execution(
+        env: &Env,
+        client: &ExecutionHubClient,
+        signing_key: &ed25519_dalek::SigningKey,
+        agent_id: u64,
+        action: &String,
+        parameters: &Bytes,
+        nonce: u64,
+    ) -> BytesN<64> {
+        use ed25519_dalek::Signer;
+        extern crate std;
+
+        let message = SignedExecution {
+            hub: client.address.clone(),
+            agent_id,
+            owner_pubkey: BytesN::from_array(env, &signing_key.verifying_key().to_bytes()),
+            action: action.clone(),
+            parameters: parameters.clone(),
+            nonce,
+        }
+        .to_xdr(env);
+        let buf: std::vec::Vec<u8> = message.iter().collect();
+        BytesN::from_array(env, &signing_key.sign(&buf).to_bytes())
+    }
+
+    fn setup_signed_owner(
+        env: &Env,
+        client: &ExecutionHubClient,
+        agent_nft: &MockAgentNFTClient,
+    ) -> (ed25519_dalek::SigningKey, BytesN<32>) {
+        let owner = Address::generate(env);
+        agent_nft.set_owner(&1, &owner);
+
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&[7u8; 32]);
+        let pubkey = BytesN::from_array(env, &signing_key.verifying_key().to_bytes());
+        client.set_owner_signing_key(&1, &owner, &pubkey);
+        (signing_key, pubkey)
+    }
+
+    #[test]
+    fn test_execute_action_signed() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let (signing_key, pubkey) = setup_signed_owner(&env, &client, &agent_nft);
+
+        let action = String::from_str(&env, "trade");
+        let params = Bytes::from_array(&env, &[1, 2, 3]);
+        let signature = sign_execution(&env, &client, &signing_key, 1, &action, &params, 1);
+
+        // No live auth from the owner is needed
+        env.set_auths(&[]);
+        let execution_id =
+            client.execute_action_signed(&1, &pubkey, &action, &params, &1, &signature);
+
+        let receipt = client.get_execution_receipt(&execution_id).unwrap();
+        assert_eq!(receipt.action, action);
+        assert_eq!(receipt.executor, agent_nft.get_agent_owner(&1));
+        assert_eq!(receipt.execution_hash.len(), 32);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_execute_action_signed_rejects_tampered_parameters() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let (signing_key, pubkey) = setup_signed_owner(&env, &client, &agent_nft);
+
+        let action = String::from_str(&env, "trade");
+        let params = Bytes::from_array(&env, &[1, 2, 3]);
+        let signature = sign_execution(&env, &client, &signing_key, 1, &action, &params, 1);
+
+        let tampered = Bytes::from_array(&env, &[9, 9, 9]);
+        client.execute_action_signed(&1, &pubkey, &action, &tampered, &1, &signature);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid nonce: replay protection triggered")]
+    fn test_execute_action_signed_rejects_replayed_nonce() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let (signing_key, pubkey) = setup_signed_owner(&env, &client, &agent_nft);
+
+        let action = String::from_str(&env, "trade");
+        let params = Bytes::from_array(&env, &[1, 2, 3]);
+        let signature = sign_execution(&env, &client, &signing_key, 1, &action, &params, 1);
+
+        client.execute_action_signed(&1, &pubkey, &action, &params, &1, &signature);
+        client.execute_action_signed(&1, &pubkey, &action, &params, &1, &signature);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: signing key does not belong to owner")]
+    fn test_execute_action_signed_rejects_key_after_owner_change() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let (signing_key, pubkey) = setup_signed_owner(&env, &client, &agent_nft);
+        agent_nft.set_owner(&1, &Address::generate(&env));
+
+        let action = String::from_str(&env, "trade");
+        let params = Bytes::from_array(&env, &[1, 2, 3]);
+        let signature = sign_execution(&env, &client, &signing_key, 1, &action, &params, 1);
+
+        client.execute_action_signed(&1, &pubkey, &action, &params, &1, &signature);
+    }
+
+    #[test]
+    fn test_register_rules_batch() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+
+        let mut rules = Vec::new(&env);
+        for (name, data) in [("max_trade", 1u8), ("allowed_pair", 2), ("stop_loss", 3)] {
+            rules.push_back((
+                String::from_str(&env, name),
+                Bytes::from_array(&env, &[data]),
+            ));
+        }
+        client.register_rules_batch(&1, &owner, &rules);
+
+        for (name, data) in rules.iter() {
+            assert_eq!(client.get_rule(&1, &name), Some(data));
+        }
+    }
+
+    #[test]
+    fn test_register_rules_batch_reverts_on_long_rule_name() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+
+        let long_name = String::from_bytes(&env, &[b'a'; 300]);
+        let mut rules = Vec::new(&env);
+        rules.push_back((
+            String::from_str(&env, "valid_rule"),
+            Bytes::from_array(&env, &[1]),
+        ));
+        rules.push_back((long_name, Bytes::from_array(&env, &[2])));
+
+        assert!(client.try_register_rules_batch(&1, &owner, &rules).is_err());
+        assert_eq!(
+            client.get_rule(&1, &String::from_str(&env, "valid_rule")),
+            None
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Batch size exceeds maximum")]
+    fn test_register_rules_batch_rejects_oversized_batch() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+
+        let mut rules = Vec::new(&env);
+        for i in 0..21u8 {
+            rules.push_back((
+                String::from_bytes(&env, &[b'r', b'a' + i]),
+                Bytes::from_array(&env, &[i]),
+            ));
+        }
+        client.register_rules_batch(&1, &owner, &rules);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: caller is not agent owner")]
+    fn test_register_rules_batch_rejects_non_owner() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        agent_nft.set_owner(&1, &Address::generate(&env));
+        let intruder = Address::generate(&env);
+
+        let mut rules = Vec::new(&env);
+        rules.push_back((
+            String::from_str(&env, "max_trade"),
+            Bytes::from_array(&env, &[1]),
+        ));
+        client.register_rules_batch(&1, &intruder, &rules);
+    }
+
+    fn setup_council(env: &Env, client: &ExecutionHubClient, admin: &Address) -> Vec<Address> {
+        let members = Vec::from_array(
+            env,
+            [
+                Address::generate(env),
+                Address::generate(env),
+                Address::generate(env),
+            ],
+        );
+        client.set_council(admin, &members, &2);
+        members
+    }
+
+    #[test]
+    fn test_single_council_vote_does_not_pause() {
+        let (env, client, admin, agent_nft, _) = setup_test();
+        let members = setup_council(&env, &client, &admin);
+        let owner = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+
+        assert!(!client.propose_emergency_pause(&members.get(0).unwrap()));
+        assert!(!client.is_paused());
+
+        let action = String::from_str(&env, "test");
+        let params = Bytes::from_array(&env, &[1]);
+        let exec_hash = Bytes::from_array(&env, &[0xaa]);
+        client.execute_action(&1, &owner, &action, &params, &1, &exec_hash);
+    }
+
+    #[test]
+    fn test_threshold_votes_pause_and_unpause_clears_votes() {
+        let (env, client, admin, agent_nft, _) = setup_test();
+        let members = setup_council(&env, &client, &admin);
+        let owner = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+
+        client.propose_emergency_pause(&members.get(0).unwrap());
+        assert!(client.propose_emergency_pause(&members.get(1).unwrap()));
+        assert!(client.is_paused());
+        assert_eq!(
+            client.try_can_execute_action(&1, &owner, &1),
+            Err(Ok(ContractError::ContractPaused))
+        );
+
+        client.unpause(&admin);
+        assert!(!client.is_paused());
+
+        // Earlier votes were cleared, so one new vote is not enough
+        assert!(!client.propose_emergency_pause(&members.get(2).unwrap()));
+        assert!(client.try_can_execute_action(&1, &owner, &1).is_ok());
+    }
+
+    #[test]
+    fn test_expired_council_votes_do_not_count() {
+        let (env, client, admin, _agent_nft, _) = setup_test();
+        let members = setup_council(&env, &client, &admin);
+
+        client.propose_emergency_pause(&members.get(0).unwrap());
+        env.ledger()
+            .set_timestamp(env.ledger().timestamp() + EMERGENCY_VOTE_WINDOW_SECONDS + 1);
+        assert!(!client.propose_emergency_pause(&members.get(1).unwrap()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Contract paused")]
+    fn test_emergency_pause_blocks_execution() {
+        let (env, client, admin, agent_nft, _) = setup_test();
+        let members = setup_council(&env, &client, &admin);
+        let owner = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+
+        client.propose_emergency_pause(&members.get(0).unwrap());
+        client.propose_emergency_pause(&members.get(1).unwrap());
+
+        let action = String::from_str(&env, "test");
+        let params = Bytes::from_array(&env, &[1]);
+        let exec_hash = Bytes::from_array(&env, &[0xaa]);
+        client.execute_action(&1, &owner, &action, &params, &1, &exec_hash);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: caller is not a council member")]
+    fn test_non_council_vote_rejected() {
+        let (env, client, admin, _agent_nft, _) = setup_test();
+        setup_council(&env, &client, &admin);
+
+        client.propose_emergency_pause(&Address::generate(&env));
+    }
+
+    #[test]
+    fn test_size_limits_default_to_constants() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        assert_eq!(client.get_max_string_length(), MAX_STRING_LENGTH);
+        assert_eq!(client.get_max_data_size(), MAX_DATA_SIZE);
+
+        let owner = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+        let action = String::from_bytes(&env, &[b'a'; MAX_STRING_LENGTH as usize]);
+        let params = Bytes::from_array(&env, &[1; 64]);
+        let exec_hash = Bytes::from_array(&env, &[0xaa]);
+        client.execute_action(&1, &owner, &action, &params, &1, &exec_hash);
+    }
+
+    #[test]
+    #[should_panic(expected = "Data exceeds maximum size")]
+    fn test_lowered_data_size_rejects_previously_valid_data() {
+        let (env, client, admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+
+        let action = String::from_str(&env, "test");
+        let params = Bytes::from_array(&env, &[1; 64]);
+        let exec_hash = Bytes::from_array(&env, &[0xaa]);
+        client.execute_action(&1, &owner, &action, &params, &1, &exec_hash);
+
+        client.set_max_data_size(&admin, &32);
+        client.execute_action(&1, &owner, &action, &params, &2, &exec_hash);
+    }
+
+    #[test]
+    #[should_panic(expected = "String exceeds maximum length")]
+    fn test_lowered_string_length_rejects_rule_name() {
+        let (env, client, admin, _agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        let rule_name = String::from_str(&env, "max_trade_size");
+        let rule_data = Bytes::from_array(&env, &[1]);
+        client.register_rule(&1, &owner, &rule_name, &rule_data);
+
+        client.set_max_string_length(&admin, &8);
+        client.register_rule(&1, &owner, &rule_name, &rule_data);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: caller is not admin")]
+    fn test_non_admin_cannot_set_size_limits() {
+        let (env, client, _admin, _agent_nft, _) = setup_test();
+        client.set_max_data_size(&Address::generate(&env), &32);
+    }
+
+    #[test]
+    fn test_executions_indexed_by_executor_across_agents() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        let operator = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+        agent_nft.set_owner(&2, &owner);
+        client.authorize_operator(&1, &owner, &operator, &100);
+        client.authorize_operator(&2, &owner, &operator, &100);
+
+        let action = String::from_str(&env, "test");
+        let params = Bytes::from_array(&env, &[1]);
+        let exec_hash = Bytes::from_array(&env, &[0xaa]);
+        let first = client.execute_action(&1, &operator, &action, &params, &1, &exec_hash);
+        let second = client.execute_action(&2, &operator, &action, &params, &1, &exec_hash);
+        client.execute_action(&1, &owner, &action, &params, &2, &exec_hash);
+        let third = client.execute_action(&1, &operator, &action, &params, &3, &exec_hash);
+
+        let all = client.get_executions_by_executor(&operator, &0, &10);
+        assert_eq!(all.len(), 3);
+        assert_eq!(all.get(0).unwrap(), (1, first));
+        assert_eq!(all.get(1).unwrap(), (2, second));
+        assert_eq!(all.get(2).unwrap(), (1, third));
+
+        let page = client.get_executions_by_executor(&operator, &1, &1);
+        assert_eq!(page.len(), 1);
+        assert_eq!(page.get(0).unwrap(), (2, second));
+
+        assert_eq!(
+            client.get_executions_by_executor(&operator, &3, &10).len(),
+            0
+        );
+        assert_eq!(client.get_executions_by_executor(&owner, &0, &10).len(), 1);
+    }
+
+    #[test]
+    fn test_compute_execution_hash_is_deterministic() {
+        let (env, client, _admin, _agent_nft, _) = setup_test();
+        let action = String::from_str(&env, "trade");
+        let params = Bytes::from_array(&env, &[1, 2, 3]);
+
+        let hash = client.compute_execution_hash(&1, &action, &params, &1);
+        assert_eq!(hash.len(), 32);
+        assert_eq!(
+            hash,
+            client.compute_execution_hash(&1, &action, &params, &1)
+        );
+
+        let other_params = Bytes::from_array(&env, &[1, 2, 4]);
+        assert_ne!(
+            hash,
+            client.compute_execution_hash(&1, &action, &other_params, &1)
+        );
+        assert_ne!(
+            hash,
+            client.compute_execution_hash(&1, &action, &params, &2)
+        );
+        assert_ne!(
+            hash,
+            client.compute_execution_hash(&2, &action, &params, &1)
+        );
+    }
+
+    #[test]
+    fn test_strict_hash_accepts_canonical_hash() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+        client.set_strict_hash(&1, &owner, &true);
+
+        let action = String::from_str(&env, "trade");
+        let params = Bytes::from_array(&env, &[1, 2, 3]);
+        let exec_hash = client.compute_execution_hash(&1, &action, &params, &1);
+        let execution_id = client.execute_action(&1, &owner, &action, &params, &1, &exec_hash);
+
+        let receipt = client.get_execution_receipt(&execution_id).unwrap();
+        assert_eq!(receipt.execution_hash, exec_hash);
+    }
+
+    #[test]
+    #[should_panic(expected = "Execution hash mismatch")]
+    fn test_strict_hash_rejects_forged_hash() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+        client.set_strict_hash(&1, &owner, &true);
+
+        let action = String::from_str(&env, "trade");
+        let params = Bytes::from_array(&env, &[1, 2, 3]);
+        let forged = Bytes::from_array(&env, &[0xaa]);
+        client.execute_action(&1, &owner, &action, &params, &1, &forged);
+    }
+
+    fn latest_audit_log(env: &Env, client: &ExecutionHubClient) -> stellai_lib::audit::AuditLog {
+        env.as_contract(&client.address, || {
+            let latest = stellai_lib::audit::get_log_id_counter(env);
+            stellai_lib::audit::get_audit_log(env, latest).unwrap()
+        })
+    }
+
+    #[test]
+    fn test_operator_changes_are_audited() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        let operator = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+
+        client.authorize_operator(&1, &owner, &operator, &100);
+        let granted = latest_audit_log(&env, &client);
+        assert_eq!(granted.operator, owner);
+        assert_eq!(granted.operation_type, OperationType::OperatorGranted);
+
+        client.revoke_operator(&1, &owner, &operator);
+        let revoked = latest_audit_log(&env, &client);
+        assert_eq!(revoked.id, granted.id + 1);
+        assert_eq!(revoked.operator, owner);
+        assert_eq!(revoked.operation_type, OperationType::OperatorRevoked);
+    }
+
+    #[test]
+    fn test_revoke_all_operators_is_audited() {
+        let (env, client, _admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+        client.authorize_operator(&1, &owner, &Address::generate(&env), &100);
+        client.authorize_operator(&1, &owner, &Address::generate(&env), &100);
+
+        client.revoke_all_operators(&1, &owner);
+        let revoked = latest_audit_log(&env, &client);
+        assert_eq!(revoked.operator, owner);
+        assert_eq!(revoked.operation_type, OperationType::OperatorRevoked);
+        assert_eq!(
+            revoked.after_state,
+            String::from_str(&env, "{\"revoked\":2}")
+        );
+    }
+
+    #[test]
+    fn test_admin_operations_are_audited() {
+        let (env, client, admin, agent_nft, _) = setup_test();
+        let owner = Address::generate(&env);
+        agent_nft.set_owner(&1, &owner);
+
+        let reason = String::from_str(&env, "incident response");
+        let now = env.ledger().timestamp();
+        client.set_rate_limit_bypass(&admin, &1, &reason, &(now + 3600));
+        let bypass = latest_audit_log(&env, &client);
+        assert_eq!(bypass.operator, admin);
+        assert_eq!(bypass.operation_type, OperationType::RateLimitBypass);
+        assert_eq!(bypass.description, Some(reason));
+
+        let new_admin = Address::generate(&env);
+        client.transfer_admin(&admin, &new_admin);
+        let transfer = latest_audit_log(&env, &client);
+        assert_eq!(transfer.operator, admin);
+        assert_eq!(transfer.operation_type, OperationType::AdminTransfer);
+
+        client.set_owner_signing_key(&1, &owner, &BytesN::from_array(&env, &[3u8; 32]));
+        let signing = latest_audit_log(&env, &client);
+        assert_eq!(signing.operator, owner);
+        assert_eq!(signing.operation_type, OperationType::ExecutionAuthorized);
+    }
 }
@@ -1,11 +1,10 @@
 #![no_std]
+#![allow(clippy::too_many_arguments)]
 
 extern crate alloc;
 
-use alloc::vec::Vec as StdVec;
-use soroban_sdk::{
-    contract, contractimpl, contracttype, token, Address, Env, String, Symbol, Val, Vec,
-};
+use soroban_sdk::{contract, contractimpl, token, Address, Env, String, Symbol, Val, Vec};
+use stellai_lib::audit::{create_audit_log, OperationType};
 
 mod storage;
 mod types;
@@ -16,6 +15,26 @@ mod test;
 use storage::*;
 use types::*;
 
+/// Maximum number of proposal IDs returned per page
+const MAX_PROPOSALS_PAGE_SIZE: u32 = 100;
+
+/// Upper bound on any escrow multiplier (10x)
+const MAX_ESCROW_MULTIPLIER: u32 = 100000;
+
+/// Integer square root (floor) via Newton's method
+fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    let mut x = n;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x
+}
+
 #[contract]
 pub struct Governance;
 
@@ -31,6 +50,7 @@ impl Governance {
         min_voting_period: Option<u64>,
         max_voting_period: Option<u64>,
         min_proposal_deposit: Option<u128>,
+        circulating_supply: u128,
     ) {
         if env.storage().instance().has(&DataKey::Admin) {
             panic!("Contract already initialized");
@@ -46,6 +66,7 @@ impl Governance {
         set_min_voting_period(&env, min_voting_period.unwrap_or(7 * 24 * 60 * 60));
         set_max_voting_period(&env, max_voting_period.unwrap_or(14 * 24 * 60 * 60));
         set_min_proposal_deposit(&env, min_proposal_deposit.unwrap_or(1000u128));
+        storage::set_circulating_voting_power(&env, circulating_supply);
         env.storage()
             .instance()
             .set(&DataKey::ProposalCounter, &0u64);
@@ -63,6 +84,7 @@ impl Governance {
         target_contract: Option<Address>,
         target_function: Option<Symbol>,
         target_args: Option<Vec<Val>>,
+        voting_mode: VotingMode,
     ) -> u64 {
         proposer.require_auth();
 
@@ -112,12 +134,25 @@ impl Governance {
             votes_against: 0,
             votes_abstain: 0,
             status: ProposalStatus::Active,
+            voting_mode,
+            executable_after: 0,
             target_contract,
             target_function,
             target_args,
         };
 
         set_proposal(&env, &proposal);
+        set_active_proposal_count(&env, get_active_proposal_count(&env) + 1);
+
+        Self::log_proposal_audit(
+            &env,
+            &proposer,
+            OperationType::ProposalCreated,
+            "{}",
+            "{\"status\":\"Active\"}",
+            "create_proposal",
+            "Governance proposal created",
+        );
 
         env.events().publish(
             (Symbol::new(&env, "ProposalCreated"),),
@@ -138,37 +173,30 @@ impl Governance {
         let token_client = token::Client::new(&env, &governance_token);
         let base_balance = token_client.balance(&address) as u128;
 
-        let escrow_power = if let Some(escrow) = get_vote_escrow(&env, &address) {
-            let current_time = env.ledger().timestamp();
-            if escrow.lock_end > current_time {
-                (escrow.amount as u128 * escrow.multiplier as u128) / 10000u128
-            } else {
-                0
-            }
-        } else {
-            0
-        };
+        let escrow_power = Self::escrow_power(&env, &address);
 
         let delegated_power = Self::calculate_delegated_power_to(&env, &address);
 
-        let own_delegated_away = if let Some(delegation) = get_delegation(&env, &address) {
+        let own_delegated_away = if let Some(delegation) = Self::active_delegation(&env, &address) {
             delegation.amount
         } else {
             0
         };
 
         let own_power = base_balance + escrow_power;
-        let available_own_power = if own_delegated_away > own_power {
-            0
-        } else {
-            own_power - own_delegated_away
-        };
+        let available_own_power = own_power.saturating_sub(own_delegated_away);
 
         available_own_power + delegated_power
     }
 
-    /// Delegate voting power to another address
-    pub fn delegate_voting_power(env: Env, delegator: Address, delegatee: Address, amount: u128) {
+    /// Delegate voting power to another address, optionally until `expires_at`
+    pub fn delegate_voting_power(
+        env: Env,
+        delegator: Address,
+        delegatee: Address,
+        amount: u128,
+        expires_at: Option<u64>,
+    ) {
         delegator.require_auth();
 
         if delegator == delegatee {
@@ -179,23 +207,19 @@ impl Governance {
             panic!("Amount must be greater than 0");
         }
 
+        if let Some(expires_at) = expires_at {
+            if expires_at <= env.ledger().timestamp() {
+                panic!("Delegation expiry must be in the future");
+            }
+        }
+
         let governance_token = get_governance_token(&env);
         let token_client = token::Client::new(&env, &governance_token);
         let base_balance = token_client.balance(&delegator) as u128;
 
-        let escrow_power = if let Some(escrow) = get_vote_escrow(&env, &delegator) {
-            let current_time = env.ledger().timestamp();
-            if escrow.lock_end > current_time {
-                (escrow.amount as u128 * escrow.multiplier as u128) / 10000u128
-            } else {
-                0
-            }
-        } else {
-            0
-        };
+        let escrow_power = Self::escrow_power(&env, &delegator);
 
         let available_power = base_balance + escrow_power;
-        let existing_delegation = get_delegation(&env, &delegator);
         let new_total = amount;
 
         if new_total > available_power {
@@ -205,12 +229,13 @@ impl Governance {
         let delegation = Delegation {
             delegatee: delegatee.clone(),
             amount: new_total,
+            expires_at,
         };
         set_delegation(&env, &delegator, &delegation);
 
         env.events().publish(
             (Symbol::new(&env, "VotingPowerDelegated"),),
-            (delegator, delegatee, new_total),
+            (delegator, delegatee, new_total, expires_at),
         );
     }
 
@@ -242,7 +267,7 @@ impl Governance {
             panic!("Amount must be greater than 0");
         }
 
-        if lock_duration_weeks < 4 || lock_duration_weeks > 52 {
+        if !(4..=52).contains(&lock_duration_weeks) {
             panic!("Lock duration must be between 4 and 52 weeks");
         }
 
@@ -254,7 +279,7 @@ impl Governance {
             panic!("Insufficient balance");
         }
 
-        let multiplier = 20000u32 + ((lock_duration_weeks - 4) * 20000u32) / 48;
+        let multiplier = Self::escrow_multiplier(&get_escrow_tiers(&env), lock_duration_weeks);
 
         let contract_address = env.current_contract_address();
         token_client.transfer(&locker, &contract_address, &(amount as i128));
@@ -262,59 +287,121 @@ impl Governance {
         let current_time = env.ledger().timestamp();
         let lock_end = current_time + (lock_duration_weeks as u64 * 7 * 24 * 60 * 60);
 
-        let existing_escrow = get_vote_escrow(&env, &locker);
-        let (new_amount, new_lock_end) = if let Some(escrow) = &existing_escrow {
-            let amount_to_add = if escrow.lock_end <= current_time {
-                amount
-            } else {
-                escrow.amount + amount
-            };
-            let lock_end_to_use = if escrow.lock_end > lock_end {
-                escrow.lock_end
-            } else {
-                lock_end
-            };
-            (amount_to_add, lock_end_to_use)
-        } else {
-            (amount, lock_end)
-        };
-
-        let escrow = VoteEscrow {
-            amount: new_amount,
-            lock_end: new_lock_end,
+        // Each lock is its own tranche so tranches can mature independently
+        let tranche = VoteEscrow {
+            amount,
+            lock_end,
             multiplier,
         };
-        set_vote_escrow(&env, &locker, &escrow);
+        let mut tranches = get_vote_escrow_tranches(&env, &locker);
+        tranches.push_back(tranche.clone());
+        set_vote_escrow_tranches(&env, &locker, &tranches);
+
+        // Locked tokens stay in circulation; only the multiplier bonus is new voting power
+        let circulating = Self::get_circulating_voting_power(env.clone());
+        storage::set_circulating_voting_power(&env, circulating + Self::escrow_bonus(&tranche));
 
         env.events().publish(
             (Symbol::new(&env, "VoteEscrowLocked"),),
-            (locker, new_amount, new_lock_end, multiplier),
+            (locker, amount, lock_end, multiplier),
         );
     }
 
-    /// Unlock escrowed tokens
+    /// Unlock matured escrow tranches; tranches still locked are left in place
     pub fn unlock_escrow(env: Env, locker: Address) {
         locker.require_auth();
 
-        let escrow = get_vote_escrow(&env, &locker).expect("No escrow found");
+        let tranches = get_vote_escrow_tranches(&env, &locker);
+        if tranches.is_empty() {
+            panic!("No escrow found");
+        }
 
         let current_time = env.ledger().timestamp();
-        if escrow.lock_end > current_time {
+        let mut remaining = Vec::new(&env);
+        let mut unlocked_amount = 0u128;
+        let mut unlocked_bonus = 0u128;
+        for tranche in tranches.iter() {
+            if tranche.lock_end > current_time {
+                remaining.push_back(tranche);
+            } else {
+                unlocked_amount += tranche.amount;
+                unlocked_bonus += Self::escrow_bonus(&tranche);
+            }
+        }
+
+        if unlocked_amount == 0 {
             panic!("Escrow is still locked");
         }
 
         let governance_token = get_governance_token(&env);
         let token_client = token::Client::new(&env, &governance_token);
         let contract_address = env.current_contract_address();
-        token_client.transfer(&contract_address, &locker, &(escrow.amount as i128));
+        token_client.transfer(&contract_address, &locker, &(unlocked_amount as i128));
+
+        if remaining.is_empty() {
+            env.storage()
+                .instance()
+                .remove(&DataKey::VoteEscrow(locker.clone()));
+        } else {
+            set_vote_escrow_tranches(&env, &locker, &remaining);
+        }
+
+        let circulating = Self::get_circulating_voting_power(env.clone());
+        storage::set_circulating_voting_power(&env, circulating.saturating_sub(unlocked_bonus));
+
+        env.events().publish(
+            (Symbol::new(&env, "VoteEscrowUnlocked"),),
+            (locker, unlocked_amount),
+        );
+    }
+
+    /// Withdraw all escrow tranches immediately, forfeiting their multiplier.
+    ///
+    /// Tranches still locked lose `early_unlock_penalty_bps` of their amount,
+    /// which stays in the governance contract; matured tranches are returned
+    /// in full.
+    pub fn emergency_unlock_escrow(env: Env, locker: Address) {
+        locker.require_auth();
+
+        let tranches = get_vote_escrow_tranches(&env, &locker);
+        if tranches.is_empty() {
+            panic!("No escrow found");
+        }
+
+        let current_time = env.ledger().timestamp();
+        let penalty_bps = get_early_unlock_penalty_bps(&env) as u128;
+        let mut returned_amount = 0u128;
+        let mut penalty = 0u128;
+        let mut forfeited_bonus = 0u128;
+        for tranche in tranches.iter() {
+            if tranche.lock_end > current_time {
+                let tranche_penalty = (tranche.amount * penalty_bps) / 10000u128;
+                penalty += tranche_penalty;
+                returned_amount += tranche.amount - tranche_penalty;
+            } else {
+                returned_amount += tranche.amount;
+            }
+            // Every tranche leaves the escrow, so its bonus leaves circulation too
+            forfeited_bonus += Self::escrow_bonus(&tranche);
+        }
 
         env.storage()
             .instance()
             .remove(&DataKey::VoteEscrow(locker.clone()));
 
+        let circulating = Self::get_circulating_voting_power(env.clone());
+        storage::set_circulating_voting_power(&env, circulating.saturating_sub(forfeited_bonus));
+
+        if returned_amount > 0 {
+            let governance_token = get_governance_token(&env);
+            let token_client = token::Client::new(&env, &governance_token);
+            let contract_address = env.current_contract_address();
+            token_client.transfer(&contract_address, &locker, &(returned_amount as i128));
+        }
+
         env.events().publish(
-            (Symbol::new(&env, "VoteEscrowUnlocked"),),
-            (locker, escrow.amount),
+            (Symbol::new(&env, "VoteEscrowEmergencyUnlocked"),),
+            (locker, returned_amount, penalty),
         );
     }
 
@@ -350,15 +437,33 @@ impl Governance {
             panic!("No voting power");
         }
 
+        // Cap a single voter's weight at a share of circulating power (0 = uncapped)
+        let raw_weight = voting_power;
+        let max_weight_bps = get_max_vote_weight_bps(&env);
+        let voting_power = if max_weight_bps > 0 {
+            let cap = (Self::get_circulating_voting_power(env.clone()) * max_weight_bps as u128)
+                / 10000u128;
+            raw_weight.min(cap)
+        } else {
+            raw_weight
+        };
+        let voting_power = match proposal.voting_mode {
+            VotingMode::Linear => voting_power,
+            VotingMode::Quadratic => isqrt(voting_power),
+        };
+
         // Record vote
         let vote = Vote {
             proposal_id,
             voter: voter.clone(),
             vote_type: vote_type.clone(),
             weight: voting_power,
+            raw_weight,
             timestamp: current_time,
         };
         set_vote(&env, proposal_id, &voter, &vote);
+        // Duplicate votes are rejected above, so each voter is listed once
+        add_voter(&env, proposal_id, &voter);
 
         // Update proposal vote counts
         match vote_type {
@@ -368,6 +473,16 @@ impl Governance {
         }
         set_proposal(&env, &proposal);
 
+        Self::log_proposal_audit(
+            &env,
+            &voter,
+            OperationType::ProposalVoteCast,
+            "{\"status\":\"Active\"}",
+            "{\"status\":\"Active\"}",
+            "cast_vote",
+            "Vote cast on governance proposal",
+        );
+
         env.events().publish(
             (Symbol::new(&env, "VoteCast"),),
             (proposal_id, voter, vote_type, voting_power),
@@ -382,18 +497,7 @@ impl Governance {
         let base_balance = token_client.balance(address) as u128;
 
         // Add vote escrow power
-        let escrow_power = if let Some(escrow) = get_vote_escrow(env, address) {
-            let current_time = env.ledger().timestamp();
-            if escrow.lock_end > current_time {
-                // Escrow is still locked, apply multiplier
-                (escrow.amount as u128 * escrow.multiplier as u128) / 10000u128
-            } else {
-                // Escrow expired, no multiplier
-                0
-            }
-        } else {
-            0
-        };
+        let escrow_power = Self::escrow_power(env, address);
 
         // Calculate delegated power TO this address using reverse index
         let delegated_power = Self::calculate_delegated_power_to(env, address);
@@ -407,23 +511,14 @@ impl Governance {
 
         for i in 0..delegators.len() {
             let delegator = delegators.get(i).unwrap();
-            if let Some(delegation) = storage::get_delegation(env, &delegator) {
+            if let Some(delegation) = Self::active_delegation(env, &delegator) {
                 // Get delegator's base voting power (not including their own delegations)
                 let governance_token = get_governance_token(env);
                 let token_client = token::Client::new(env, &governance_token);
                 let base_balance = token_client.balance(&delegator) as u128;
 
                 // Add escrow power if exists
-                let escrow_power = if let Some(escrow) = storage::get_vote_escrow(env, &delegator) {
-                    let current_time = env.ledger().timestamp();
-                    if escrow.lock_end > current_time {
-                        (escrow.amount as u128 * escrow.multiplier as u128) / 10000u128
-                    } else {
-                        0
-                    }
-                } else {
-                    0
-                };
+                let escrow_power = Self::escrow_power(env, &delegator);
 
                 // The delegated amount is what was actually delegated
                 // We cap it at the delegator's own power (base + escrow) at the time of delegation
@@ -443,38 +538,63 @@ impl Governance {
         total_delegated
     }
 
+    /// A delegator's delegation, unless it has expired
+    fn active_delegation(env: &Env, delegator: &Address) -> Option<Delegation> {
+        get_delegation(env, delegator).filter(|delegation| match delegation.expires_at {
+            Some(expires_at) => env.ledger().timestamp() < expires_at,
+            None => true,
+        })
+    }
+
     /// Execute a passed proposal
     pub fn execute_proposal(env: Env, executor: Address, proposal_id: u64) {
         executor.require_auth();
+        Self::execute_passed_proposal(&env, &executor, proposal_id);
+    }
 
-        let mut proposal = get_proposal(&env, proposal_id).expect("Proposal not found");
+    /// Keeper entry point: anyone may execute a passed proposal once its
+    /// timelock has elapsed. A proposal whose voting period has ended is
+    /// settled first, so no separate `update_proposal_status` call is needed.
+    pub fn execute_if_ready(env: Env, proposal_id: u64) {
+        let proposal = get_proposal(&env, proposal_id).expect("Proposal not found");
+        if proposal.status == ProposalStatus::Active {
+            Self::update_proposal_status(env.clone(), proposal_id);
+        }
+
+        let executor = env.current_contract_address();
+        Self::execute_passed_proposal(&env, &executor, proposal_id);
+    }
+
+    fn execute_passed_proposal(env: &Env, executor: &Address, proposal_id: u64) {
+        let mut proposal = get_proposal(env, proposal_id).expect("Proposal not found");
+
+        if proposal.status == ProposalStatus::Vetoed {
+            panic!("Proposal was vetoed");
+        }
 
-        // Check if proposal has passed
+        // Guard: only a Passed proposal can execute, and only once
         if proposal.status != ProposalStatus::Passed {
             panic!("Proposal has not passed");
         }
 
-        // Check thresholds
-        let total_votes = proposal.votes_for + proposal.votes_against + proposal.votes_abstain;
-        let circulating_power = Self::get_circulating_voting_power(env.clone());
-
-        let quorum_threshold = get_quorum_threshold(&env);
-        let approval_threshold = get_approval_threshold(&env);
+        if env.ledger().timestamp() < proposal.executable_after {
+            panic!("Proposal timelock has not elapsed");
+        }
 
-        // Check quorum (30% of circulating voting power)
-        let quorum_required = (circulating_power * quorum_threshold as u128) / 10000u128;
-        if total_votes < quorum_required {
+        // Check thresholds
+        let (quorum_met, approval_met, total_votes, _, _) = Self::tally(env, &proposal);
+        if !quorum_met {
             panic!("Quorum not met");
         }
-
-        // Check approval (66% of votes cast must be For)
-        if total_votes > 0 {
-            let approval_required = (total_votes * approval_threshold as u128) / 10000u128;
-            if proposal.votes_for < approval_required {
-                panic!("Approval threshold not met");
-            }
+        if total_votes > 0 && !approval_met {
+            panic!("Approval threshold not met");
         }
 
+        // Mark as executed before any external call (checks-effects-interactions),
+        // so a second or re-entrant call fails the status check above
+        proposal.status = ProposalStatus::Executed;
+        set_proposal(env, &proposal);
+
         // Execute proposal based on type
         match &proposal.proposal_type {
             ProposalType::ParameterChange => {
@@ -487,7 +607,7 @@ impl Governance {
                     }
                     let params = &proposal.parameters;
                     // Build arguments: parameter name and value as strings
-                    let mut args = Vec::new(&env);
+                    let mut args = Vec::new(env);
                     args.push_back(params.name.clone().into());
                     args.push_back(params.value.clone().into());
 
@@ -500,7 +620,7 @@ impl Governance {
 
                     // Invoke target contract function
                     // Invoke target contract function
-                    let _result: Val = env.invoke_contract(&target, function, args);
+                    let _result: Val = env.invoke_contract(target, function, args);
                 } else {
                     panic!(
                         "ParameterChange proposal missing target contract, function, or parameters"
@@ -513,7 +633,7 @@ impl Governance {
                     (&proposal.target_contract, &proposal.target_function)
                 {
                     // Build arguments: new contract address from parameters or target args
-                    let mut args = Vec::new(&env);
+                    let mut args = Vec::new(env);
 
                     if let Some(target_args) = &proposal.target_args {
                         // Use provided target args (should contain new contract address)
@@ -528,7 +648,7 @@ impl Governance {
                     }
 
                     // Invoke upgrade function
-                    let _result: Val = env.invoke_contract(&target, function, args);
+                    let _result: Val = env.invoke_contract(target, function, args);
                 } else {
                     panic!("ContractUpgrade proposal missing target contract or function");
                 }
@@ -539,7 +659,7 @@ impl Governance {
                     (&proposal.target_contract, &proposal.target_function)
                 {
                     // Build arguments: pause state (true/false)
-                    let mut args = Vec::new(&env);
+                    let mut args = Vec::new(env);
 
                     if let Some(target_args) = &proposal.target_args {
                         // Use provided target args (should contain pause boolean)
@@ -551,40 +671,101 @@ impl Governance {
                         // Value should be "true" or "false" as string
                         // Compare String directly (Soroban String doesn't have to_string())
                         let params = &proposal.parameters;
-                        let pause_bool = params.value == String::from_str(&env, "true")
-                            || params.value == String::from_str(&env, "1");
+                        let pause_bool = params.value == String::from_str(env, "true")
+                            || params.value == String::from_str(env, "1");
                         args.push_back(pause_bool.into());
                     } else {
                         panic!("EmergencyPause proposal missing pause state");
                     }
 
                     // Invoke pause/unpause function
-                    let _result: Val = env.invoke_contract(&target, function, args);
+                    let _result: Val = env.invoke_contract(target, function, args);
                 } else {
                     panic!("EmergencyPause proposal missing target contract or function");
                 }
             }
         }
 
-        // Mark as executed
-        proposal.status = ProposalStatus::Executed;
+        // Return proposal deposit to proposer only after execution succeeded
+        let min_deposit = get_min_proposal_deposit(env);
+        let governance_token = get_governance_token(env);
+        let token_client = token::Client::new(env, &governance_token);
+        let contract_address = env.current_contract_address();
+        token_client.transfer(
+            &contract_address,
+            &proposal.proposer,
+            &(min_deposit as i128),
+        );
+
+        Self::log_proposal_audit(
+            env,
+            executor,
+            OperationType::ProposalExecuted,
+            "{\"status\":\"Passed\"}",
+            "{\"status\":\"Executed\"}",
+            "execute_proposal",
+            "Governance proposal executed",
+        );
+
+        // Emit event
+        env.events().publish(
+            (Symbol::new(env, "ProposalExecuted"),),
+            (proposal_id, executor.clone(), proposal.proposal_type),
+        );
+    }
+
+    /// Record a proposal lifecycle step in the shared audit log
+    fn log_proposal_audit(
+        env: &Env,
+        operator: &Address,
+        operation_type: OperationType,
+        before_state: &str,
+        after_state: &str,
+        tx_hash: &str,
+        description: &str,
+    ) {
+        let _ = create_audit_log(
+            env,
+            operator.clone(),
+            operation_type,
+            String::from_str(env, before_state),
+            String::from_str(env, after_state),
+            String::from_str(env, tx_hash),
+            Some(String::from_str(env, description)),
+        );
+    }
+
+    /// Veto a passed proposal that is still inside its timelock (guardian only).
+    /// The proposer's deposit is refunded.
+    pub fn veto_proposal(env: Env, guardian: Address, proposal_id: u64) {
+        guardian.require_auth();
+        if !get_guardians(&env).contains(&guardian) {
+            panic!("Unauthorized: caller is not a guardian");
+        }
+
+        let mut proposal = get_proposal(&env, proposal_id).expect("Proposal not found");
+        if proposal.status != ProposalStatus::Passed {
+            panic!("Proposal has not passed");
+        }
+        if env.ledger().timestamp() >= proposal.executable_after {
+            panic!("Proposal timelock has elapsed");
+        }
+
+        proposal.status = ProposalStatus::Vetoed;
         set_proposal(&env, &proposal);
 
-        // Return proposal deposit to proposer (if proposal passed and executed)
         let min_deposit = get_min_proposal_deposit(&env);
         let governance_token = get_governance_token(&env);
         let token_client = token::Client::new(&env, &governance_token);
-        let contract_address = env.current_contract_address();
         token_client.transfer(
-            &contract_address,
+            &env.current_contract_address(),
             &proposal.proposer,
             &(min_deposit as i128),
         );
 
-        // Emit event
         env.events().publish(
-            (Symbol::new(&env, "ProposalExecuted"),),
-            (proposal_id, executor, proposal.proposal_type),
+            (Symbol::new(&env, "ProposalVetoed"),),
+            (proposal_id, guardian),
         );
     }
 
@@ -602,26 +783,11 @@ impl Governance {
         }
 
         // Voting period ended, check if proposal passed
-        let total_votes = proposal.votes_for + proposal.votes_against + proposal.votes_abstain;
-        let circulating_power = Self::get_circulating_voting_power(env.clone());
-
-        let quorum_threshold = get_quorum_threshold(&env);
-        let approval_threshold = get_approval_threshold(&env);
-
-        // Check quorum
-        let quorum_required = (circulating_power * quorum_threshold as u128) / 10000u128;
-        let quorum_met = total_votes >= quorum_required;
-
-        // Check approval
-        let approval_met = if total_votes > 0 {
-            let approval_required = (total_votes * approval_threshold as u128) / 10000u128;
-            proposal.votes_for >= approval_required
-        } else {
-            false
-        };
+        let (quorum_met, approval_met, _, _, _) = Self::tally(&env, &proposal);
 
         if quorum_met && approval_met {
             proposal.status = ProposalStatus::Passed;
+            proposal.executable_after = current_time + get_execution_timelock(&env);
             env.events().publish(
                 (Symbol::new(&env, "ProposalPassed"),),
                 (
@@ -636,35 +802,189 @@ impl Governance {
         }
 
         set_proposal(&env, &proposal);
+        set_active_proposal_count(&env, get_active_proposal_count(&env).saturating_sub(1));
+    }
+
+    /// Preview whether a proposal currently meets quorum and approval, without
+    /// changing its status.
+    ///
+    /// # Returns
+    /// `(quorum_met, approval_met, total_votes, quorum_required, approval_required)`
+    pub fn get_proposal_result_preview(
+        env: Env,
+        proposal_id: u64,
+    ) -> (bool, bool, u128, u128, u128) {
+        let proposal = get_proposal(&env, proposal_id).expect("Proposal not found");
+        Self::tally(&env, &proposal)
+    }
+
+    // Quorum and approval math shared by status updates, execution and previews
+    fn tally(env: &Env, proposal: &Proposal) -> (bool, bool, u128, u128, u128) {
+        let total_votes = proposal.votes_for + proposal.votes_against + proposal.votes_abstain;
+        let circulating_power = Self::get_circulating_voting_power(env.clone());
+
+        // Type-specific thresholds take precedence over the global ones
+        let (quorum_threshold, approval_threshold) =
+            get_type_thresholds(env, &proposal.proposal_type)
+                .unwrap_or((get_quorum_threshold(env), get_approval_threshold(env)));
+
+        // Quorum: share of circulating voting power that must vote
+        let quorum_required = (circulating_power * quorum_threshold as u128) / 10000u128;
+        let quorum_met = total_votes >= quorum_required;
+
+        // Approval: share of votes cast that must be For
+        let approval_required = (total_votes * approval_threshold as u128) / 10000u128;
+        let approval_met = total_votes > 0 && proposal.votes_for >= approval_required;
+
+        (
+            quorum_met,
+            approval_met,
+            total_votes,
+            quorum_required,
+            approval_required,
+        )
+    }
+
+    /// Get circulating voting power used for quorum calculations.
+    /// Set at init, adjusted by escrow locks/unlocks, overridable by the admin.
+    pub fn get_circulating_voting_power(env: Env) -> u128 {
+        storage::get_circulating_voting_power(&env).unwrap_or(0)
     }
 
-    fn get_circulating_voting_power(env: Env) -> u128 {
-        // Try to get cached value
-        if let Some(cached) = storage::get_circulating_voting_power(&env) {
-            return cached;
+    /// Voting power from an address's escrow tranches that are still locked
+    fn escrow_power(env: &Env, address: &Address) -> u128 {
+        let current_time = env.ledger().timestamp();
+        let mut power = 0u128;
+        for tranche in get_vote_escrow_tranches(env, address).iter() {
+            if tranche.lock_end > current_time {
+                power += (tranche.amount * tranche.multiplier as u128) / 10000u128;
+            }
         }
+        power
+    }
 
-        // Calculate from governance token
-        // For Stellar asset contracts, we need to track this manually
-        // The admin should call update_circulating_voting_power when supply changes
-        // For now, we calculate a reasonable estimate
+    /// Extra voting power an escrow adds on top of its locked amount
+    fn escrow_bonus(escrow: &VoteEscrow) -> u128 {
+        let power = (escrow.amount * escrow.multiplier as u128) / 10000u128;
+        power.saturating_sub(escrow.amount)
+    }
 
-        let governance_token = get_governance_token(&env);
-        let token_client = token::Client::new(&env, &governance_token);
+    /// Multiplier for a lock of `weeks` (4-52) under the given tiers
+    fn escrow_multiplier(tiers: &EscrowTierConfig, weeks: u32) -> u32 {
+        let linear = tiers.base_multiplier
+            + ((weeks - 4) * (tiers.max_multiplier - tiers.base_multiplier)) / 48;
+        let bonus = if weeks >= tiers.top_tier_weeks {
+            tiers.top_tier_bonus
+        } else if weeks >= tiers.mid_tier_weeks {
+            tiers.mid_tier_bonus
+        } else {
+            0
+        };
+        linear + bonus
+    }
+
+    /// Configure the escrow multiplier tiers used by future locks (admin only)
+    pub fn set_escrow_tiers(env: Env, admin: Address, tiers: EscrowTierConfig) {
+        admin.require_auth();
+        storage::require_admin(&env, &admin);
+
+        if tiers.base_multiplier < 10000 || tiers.max_multiplier < tiers.base_multiplier {
+            panic!("Invalid escrow multiplier range");
+        }
+        if tiers.mid_tier_weeks < 4
+            || tiers.top_tier_weeks > 52
+            || tiers.mid_tier_weeks > tiers.top_tier_weeks
+        {
+            panic!("Invalid escrow tier weeks");
+        }
+        let peak =
+            tiers.max_multiplier as u64 + tiers.mid_tier_bonus.max(tiers.top_tier_bonus) as u64;
+        if peak > MAX_ESCROW_MULTIPLIER as u64 {
+            panic!("Escrow multiplier exceeds maximum");
+        }
 
-        // Get contract's token balance (tokens held by governance contract)
-        // This includes escrowed tokens and proposal deposits
-        let contract_balance = token_client.balance(&env.current_contract_address()) as u128;
+        set_escrow_tiers(&env, &tiers);
 
-        // Estimate circulating supply
-        // In a real implementation, this would be tracked as a running total
-        // For now, we use a conservative estimate: assume most tokens are in circulation
-        // The admin should update this value when token supply changes significantly
-        let circulating = contract_balance * 10; // Conservative multiplier estimate
+        env.events()
+            .publish((Symbol::new(&env, "EscrowTiersUpdated"),), (admin, tiers));
+    }
 
-        // Cache the value
-        storage::set_circulating_voting_power(&env, circulating);
-        circulating
+    /// Get the escrow multiplier tiers
+    pub fn get_escrow_tiers(env: Env) -> EscrowTierConfig {
+        get_escrow_tiers(&env)
+    }
+
+    /// Set the penalty for emergency escrow unlocks in basis points (admin only)
+    pub fn set_early_unlock_penalty_bps(env: Env, admin: Address, penalty_bps: u32) {
+        admin.require_auth();
+        storage::require_admin(&env, &admin);
+        if penalty_bps > 10000 {
+            panic!("Penalty cannot exceed 10000 basis points");
+        }
+        set_early_unlock_penalty_bps(&env, penalty_bps);
+    }
+
+    /// Get the emergency unlock penalty in basis points
+    pub fn get_early_unlock_penalty_bps(env: Env) -> u32 {
+        get_early_unlock_penalty_bps(&env)
+    }
+
+    /// Set quorum and approval thresholds (basis points) for one proposal type (admin only)
+    pub fn set_type_thresholds(
+        env: Env,
+        admin: Address,
+        proposal_type: ProposalType,
+        quorum_bps: u32,
+        approval_bps: u32,
+    ) {
+        admin.require_auth();
+        storage::require_admin(&env, &admin);
+        if quorum_bps > 10000 || approval_bps > 10000 {
+            panic!("Thresholds cannot exceed 10000 basis points");
+        }
+        set_type_thresholds(&env, &proposal_type, (quorum_bps, approval_bps));
+
+        env.events().publish(
+            (Symbol::new(&env, "TypeThresholdsUpdated"),),
+            (proposal_type, quorum_bps, approval_bps),
+        );
+    }
+
+    /// Get `(quorum_bps, approval_bps)` for a proposal type, falling back to the global thresholds
+    pub fn get_type_thresholds(env: Env, proposal_type: ProposalType) -> (u32, u32) {
+        get_type_thresholds(&env, &proposal_type)
+            .unwrap_or((get_quorum_threshold(&env), get_approval_threshold(&env)))
+    }
+
+    /// Add a guardian who may veto passed proposals (admin only)
+    pub fn add_guardian(env: Env, admin: Address, guardian: Address) {
+        admin.require_auth();
+        storage::require_admin(&env, &admin);
+
+        let mut guardians = get_guardians(&env);
+        if guardians.contains(&guardian) {
+            panic!("Guardian already added");
+        }
+        guardians.push_back(guardian);
+        set_guardians(&env, &guardians);
+    }
+
+    /// Remove a guardian (admin only)
+    pub fn remove_guardian(env: Env, admin: Address, guardian: Address) {
+        admin.require_auth();
+        storage::require_admin(&env, &admin);
+
+        let mut guardians = get_guardians(&env);
+        let index = guardians
+            .first_index_of(&guardian)
+            .expect("Guardian not found");
+        guardians.remove(index);
+        set_guardians(&env, &guardians);
+    }
+
+    /// Get the guardian set
+    pub fn get_guardians(env: Env) -> Vec<Address> {
+        get_guardians(&env)
     }
 
     /// Update circulating voting power (admin only)
@@ -674,6 +994,34 @@ impl Governance {
         storage::set_circulating_voting_power(&env, new_value);
     }
 
+    /// Set the delay between a proposal passing and becoming executable (admin only)
+    pub fn set_execution_timelock(env: Env, admin: Address, timelock_seconds: u64) {
+        admin.require_auth();
+        storage::require_admin(&env, &admin);
+        set_execution_timelock(&env, timelock_seconds);
+    }
+
+    /// Get the execution timelock in seconds
+    pub fn get_execution_timelock(env: Env) -> u64 {
+        get_execution_timelock(&env)
+    }
+
+    /// Set the maximum weight of a single vote as basis points of circulating
+    /// voting power (admin only). Zero disables the cap.
+    pub fn set_max_vote_weight_bps(env: Env, admin: Address, max_vote_weight_bps: u32) {
+        admin.require_auth();
+        storage::require_admin(&env, &admin);
+        if max_vote_weight_bps > 10000 {
+            panic!("Max vote weight cannot exceed 10000 basis points");
+        }
+        set_max_vote_weight_bps(&env, max_vote_weight_bps);
+    }
+
+    /// Get the per-voter weight cap in basis points (0 = uncapped)
+    pub fn get_max_vote_weight_bps(env: Env) -> u32 {
+        get_max_vote_weight_bps(&env)
+    }
+
     /* ---------------- QUERY FUNCTIONS ---------------- */
 
     /// Get a proposal by ID
@@ -697,14 +1045,87 @@ impl Governance {
         active
     }
 
-    /// Get delegation for an address
+    /// Page through active proposal IDs starting at `start_id`.
+    ///
+    /// Returns up to `limit` IDs (capped at `MAX_PROPOSALS_PAGE_SIZE`) and
+    /// whether proposal IDs remain beyond the page; resume from the last
+    /// returned ID + 1.
+    pub fn get_active_proposals_page(env: Env, start_id: u64, limit: u32) -> (Vec<u64>, bool) {
+        let counter = get_proposal_counter(&env);
+        let limit = limit.min(MAX_PROPOSALS_PAGE_SIZE);
+        let mut active = Vec::new(&env);
+
+        let mut id = start_id.max(1);
+        while id <= counter && active.len() < limit {
+            if let Some(proposal) = get_proposal(&env, id) {
+                if proposal.status == ProposalStatus::Active {
+                    active.push_back(id);
+                }
+            }
+            id += 1;
+        }
+
+        (active, id <= counter)
+    }
+
+    /// Number of proposals currently active
+    pub fn get_active_count(env: Env) -> u64 {
+        get_active_proposal_count(&env)
+    }
+
+    /// Page through the voters on a proposal, in voting order
+    pub fn get_voters(env: Env, proposal_id: u64, start: u32, limit: u32) -> Vec<Address> {
+        let voters = storage::get_voters(&env, proposal_id);
+        let end = start.saturating_add(limit).min(voters.len());
+        if start >= end {
+            return Vec::new(&env);
+        }
+        voters.slice(start..end)
+    }
+
+    /// Turnout for a proposal as `(total_weight_voted, circulating_voting_power)`
+    pub fn get_turnout(env: Env, proposal_id: u64) -> (u128, u128) {
+        let proposal = get_proposal(&env, proposal_id).expect("Proposal not found");
+        let total_weight_voted =
+            proposal.votes_for + proposal.votes_against + proposal.votes_abstain;
+        (
+            total_weight_voted,
+            Self::get_circulating_voting_power(env.clone()),
+        )
+    }
+
+    /// Get delegation for an address, including its expiry (expired delegations are still returned)
     pub fn get_delegation(env: Env, delegator: Address) -> Option<Delegation> {
         get_delegation(&env, &delegator)
     }
 
-    /// Get vote escrow for an address
+    /// Get a summary of all escrow tranches for an address: total amount,
+    /// latest lock end and amount-weighted multiplier
     pub fn get_vote_escrow(env: Env, address: Address) -> Option<VoteEscrow> {
-        get_vote_escrow(&env, &address)
+        let tranches = get_vote_escrow_tranches(&env, &address);
+        if tranches.is_empty() {
+            return None;
+        }
+
+        let mut amount = 0u128;
+        let mut weighted = 0u128;
+        let mut lock_end = 0u64;
+        for tranche in tranches.iter() {
+            amount += tranche.amount;
+            weighted += tranche.amount * tranche.multiplier as u128;
+            lock_end = lock_end.max(tranche.lock_end);
+        }
+
+        Some(VoteEscrow {
+            amount,
+            lock_end,
+            multiplier: (weighted / amount) as u32,
+        })
+    }
+
+    /// Get the individual escrow tranches for an address
+    pub fn get_vote_escrow_tranches(env: Env, address: Address) -> Vec<VoteEscrow> {
+        get_vote_escrow_tranches(&env, &address)
     }
 
     /// Get vote record for a voter on a proposal
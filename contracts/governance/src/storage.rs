@@ -1,7 +1,6 @@
-#![no_std]
 use soroban_sdk::{contracttype, Address, Env, Vec};
 
-use crate::types::{Delegation, Proposal, Vote, VoteEscrow};
+use crate::types::{Delegation, EscrowTierConfig, Proposal, ProposalType, Vote, VoteEscrow};
 
 #[contracttype]
 #[derive(Clone)]
@@ -14,7 +13,7 @@ pub enum DataKey {
     ProposalCounter,
     /// Proposal by ID
     Proposal(u64),
-    /// Vote escrow for an address
+    /// Vote escrow tranches for an address
     VoteEscrow(Address),
     /// Delegation from an address
     Delegation(Address),
@@ -22,6 +21,8 @@ pub enum DataKey {
     DelegatorsTo(Address),
     /// Vote record: (proposal_id, voter)
     Vote(u64, Address),
+    /// Voters on a proposal, in voting order
+    Voters(u64),
     /// Quorum threshold (basis points, default 3000 = 30%)
     QuorumThreshold,
     /// Approval threshold (basis points, default 6600 = 66%)
@@ -32,8 +33,22 @@ pub enum DataKey {
     MaxVotingPeriod,
     /// Minimum proposal deposit
     MinProposalDeposit,
-    /// Circulating voting power (cached for efficiency)
+    /// Circulating voting power (running total)
     CirculatingVotingPower,
+    /// Max weight of a single vote (basis points of circulating power, 0 = uncapped)
+    MaxVoteWeightBps,
+    /// Delay in seconds between a proposal passing and becoming executable
+    ExecutionTimelock,
+    /// Number of proposals currently in the Active state
+    ActiveProposalCount,
+    /// Escrow multiplier tiers
+    EscrowTiers,
+    /// Share of still-locked escrow kept on emergency unlock (basis points)
+    EarlyUnlockPenaltyBps,
+    /// Guardians allowed to veto passed proposals
+    Guardians,
+    /// (quorum_bps, approval_bps) overriding the global thresholds for a proposal type
+    TypeThresholds(ProposalType),
 }
 
 /* ---------------- ADMIN ---------------- */
@@ -56,6 +71,19 @@ pub fn require_admin(env: &Env, caller: &Address) {
     }
 }
 
+/* ---------------- GUARDIANS ---------------- */
+
+pub fn get_guardians(env: &Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Guardians)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+pub fn set_guardians(env: &Env, guardians: &Vec<Address>) {
+    env.storage().instance().set(&DataKey::Guardians, guardians);
+}
+
 /* ---------------- GOVERNANCE TOKEN ---------------- */
 
 pub fn set_governance_token(env: &Env, token: &Address) {
@@ -90,6 +118,19 @@ pub fn increment_proposal_counter(env: &Env) -> u64 {
 
 /* ---------------- PROPOSALS ---------------- */
 
+pub fn get_active_proposal_count(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::ActiveProposalCount)
+        .unwrap_or(0)
+}
+
+pub fn set_active_proposal_count(env: &Env, count: u64) {
+    env.storage()
+        .instance()
+        .set(&DataKey::ActiveProposalCount, &count);
+}
+
 pub fn set_proposal(env: &Env, proposal: &Proposal) {
     env.storage()
         .instance()
@@ -104,16 +145,17 @@ pub fn get_proposal(env: &Env, proposal_id: u64) -> Option<Proposal> {
 
 /* ---------------- VOTE ESCROW ---------------- */
 
-pub fn set_vote_escrow(env: &Env, address: &Address, escrow: &VoteEscrow) {
+pub fn set_vote_escrow_tranches(env: &Env, address: &Address, tranches: &Vec<VoteEscrow>) {
     env.storage()
         .instance()
-        .set(&DataKey::VoteEscrow(address.clone()), escrow);
+        .set(&DataKey::VoteEscrow(address.clone()), tranches);
 }
 
-pub fn get_vote_escrow(env: &Env, address: &Address) -> Option<VoteEscrow> {
+pub fn get_vote_escrow_tranches(env: &Env, address: &Address) -> Vec<VoteEscrow> {
     env.storage()
         .instance()
         .get(&DataKey::VoteEscrow(address.clone()))
+        .unwrap_or_else(|| Vec::new(env))
 }
 
 /* ---------------- DELEGATIONS ---------------- */
@@ -166,7 +208,7 @@ pub(crate) fn add_delegator_to_list(env: &Env, delegatee: &Address, delegator: &
 }
 
 pub(crate) fn remove_delegator_from_list(env: &Env, delegatee: &Address, delegator: &Address) {
-    let mut delegators = get_delegators_to(env, delegatee);
+    let delegators = get_delegators_to(env, delegatee);
     let mut new_delegators = Vec::new(env);
     for i in 0..delegators.len() {
         let addr = delegators.get(i).unwrap();
@@ -174,7 +216,7 @@ pub(crate) fn remove_delegator_from_list(env: &Env, delegatee: &Address, delegat
             new_delegators.push_back(addr);
         }
     }
-    if new_delegators.len() > 0 {
+    if !new_delegators.is_empty() {
         env.storage()
             .instance()
             .set(&DataKey::DelegatorsTo(delegatee.clone()), &new_delegators);
@@ -199,6 +241,21 @@ pub fn get_vote(env: &Env, proposal_id: u64, voter: &Address) -> Option<Vote> {
         .get(&DataKey::Vote(proposal_id, voter.clone()))
 }
 
+pub fn add_voter(env: &Env, proposal_id: u64, voter: &Address) {
+    let mut voters = get_voters(env, proposal_id);
+    voters.push_back(voter.clone());
+    env.storage()
+        .instance()
+        .set(&DataKey::Voters(proposal_id), &voters);
+}
+
+pub fn get_voters(env: &Env, proposal_id: u64) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Voters(proposal_id))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
 /* ---------------- CONFIGURATION ---------------- */
 
 pub fn set_quorum_threshold(env: &Env, threshold: u32) {
@@ -227,6 +284,18 @@ pub fn get_approval_threshold(env: &Env) -> u32 {
         .unwrap_or(6600) // Default 66%
 }
 
+pub fn set_type_thresholds(env: &Env, proposal_type: &ProposalType, thresholds: (u32, u32)) {
+    env.storage()
+        .instance()
+        .set(&DataKey::TypeThresholds(proposal_type.clone()), &thresholds);
+}
+
+pub fn get_type_thresholds(env: &Env, proposal_type: &ProposalType) -> Option<(u32, u32)> {
+    env.storage()
+        .instance()
+        .get(&DataKey::TypeThresholds(proposal_type.clone()))
+}
+
 pub fn set_min_voting_period(env: &Env, period: u64) {
     env.storage()
         .instance()
@@ -266,7 +335,67 @@ pub fn get_min_proposal_deposit(env: &Env) -> u128 {
         .unwrap_or(1000u128)
 }
 
-/* ---------------- CIRCULATING VOTING POWER (CACHED) ---------------- */
+pub fn set_max_vote_weight_bps(env: &Env, bps: u32) {
+    env.storage()
+        .instance()
+        .set(&DataKey::MaxVoteWeightBps, &bps);
+}
+
+pub fn get_max_vote_weight_bps(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::MaxVoteWeightBps)
+        .unwrap_or(0) // Default uncapped
+}
+
+pub fn set_execution_timelock(env: &Env, timelock: u64) {
+    env.storage()
+        .instance()
+        .set(&DataKey::ExecutionTimelock, &timelock);
+}
+
+pub fn get_execution_timelock(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::ExecutionTimelock)
+        .unwrap_or(0) // Default: executable as soon as it passes
+}
+
+/* ---------------- ESCROW TIERS ---------------- */
+
+pub fn set_escrow_tiers(env: &Env, tiers: &EscrowTierConfig) {
+    env.storage().instance().set(&DataKey::EscrowTiers, tiers);
+}
+
+pub fn get_escrow_tiers(env: &Env) -> EscrowTierConfig {
+    env.storage()
+        .instance()
+        .get(&DataKey::EscrowTiers)
+        // Default: the original 2x-4x linear curve with no tier bonuses
+        .unwrap_or(EscrowTierConfig {
+            base_multiplier: 20000,
+            max_multiplier: 40000,
+            mid_tier_weeks: 26,
+            mid_tier_bonus: 0,
+            top_tier_weeks: 52,
+            top_tier_bonus: 0,
+        })
+}
+
+pub fn set_early_unlock_penalty_bps(env: &Env, bps: u32) {
+    env.storage()
+        .instance()
+        .set(&DataKey::EarlyUnlockPenaltyBps, &bps);
+}
+
+pub fn get_early_unlock_penalty_bps(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::EarlyUnlockPenaltyBps)
+        .unwrap_or(2500) // Default 25%
+}
+
+/* ---------------- CIRCULATING VOTING POWER ---------------- */
 
 pub fn set_circulating_voting_power(env: &Env, power: u128) {
     env.storage()
@@ -5,7 +5,7 @@ use super::*;
 use soroban_sdk::{
     contract, contractimpl, contracttype,
     testutils::{Address as _, Ledger, LedgerInfo},
-    token, Address, Env, String, Symbol, Val, Vec,
+    Address, Env, String, Symbol, Val, Vec,
 };
 
 // Mock token contract for testing with mint functionality
@@ -64,17 +64,17 @@ impl MockToken {
     }
 
     pub fn approve(
-        env: Env,
+        _env: Env,
         from: Address,
-        spender: Address,
-        amount: i128,
-        expiration_ledger: u32,
+        _spender: Address,
+        _amount: i128,
+        _expiration_ledger: u32,
     ) {
         from.require_auth();
         // Simplified approval for testing
     }
 
-    pub fn allowance(env: Env, from: Address, spender: Address) -> i128 {
+    pub fn allowance(_env: Env, _from: Address, _spender: Address) -> i128 {
         0 // Simplified for testing
     }
 }
@@ -96,15 +96,15 @@ impl MockTargetContract {
 // MockTargetContractClient is automatically generated by #[contract] macro
 
 fn create_token_contract(e: &Env, admin: &Address) -> Address {
-    let contract_id = e.register_contract(None, MockToken);
+    let contract_id = e.register(MockToken, ());
     let mock_token = MockTokenClient::new(e, &contract_id);
     e.mock_all_auths();
-    mock_token.initialize(&admin);
+    mock_token.initialize(admin);
     contract_id
 }
 
 fn create_governance_contract(e: &Env) -> Address {
-    e.register_contract(None, Governance)
+    e.register(Governance, ())
 }
 
 fn setup_governance(
@@ -131,6 +131,7 @@ fn setup_governance(
         &None::<u64>,
         &None::<u64>,
         &None::<u128>,
+        &100000u128,
     );
 
     (gov_client, admin, governance_token, mock_token_client)
@@ -139,13 +140,27 @@ fn setup_governance(
 // Helper to simulate minting tokens in test environment
 // Note: StellarAsset contracts don't have a mint method in test environment
 // This is a workaround - in production, tokens would be minted by the issuer
-fn simulate_mint(e: &Env, token: &Address, admin: &Address, to: &Address, amount: i128) {
+#[allow(dead_code)]
+fn simulate_mint(_e: &Env, _token: &Address, _admin: &Address, _to: &Address, _amount: i128) {
     // In test environment, we can't actually mint StellarAsset tokens
     // For testing purposes, we'll skip this and assume tokens exist
     // In a real test setup, you would use a mock token contract or proper minting
     // For now, tests that require tokens will need to be adjusted
 }
 
+fn set_timestamp(e: &Env, timestamp: u64, sequence_number: u32) {
+    e.ledger().set(LedgerInfo {
+        timestamp,
+        protocol_version: 22,
+        sequence_number,
+        network_id: Default::default(),
+        base_reserve: 10,
+        max_entry_ttl: 31536000,
+        min_persistent_entry_ttl: 2592000,
+        min_temp_entry_ttl: 16,
+    });
+}
+
 // ============================================================================
 // 1. Unit tests for voting power calculations
 // ============================================================================
@@ -154,7 +169,7 @@ fn simulate_mint(e: &Env, token: &Address, admin: &Address, to: &Address, amount
 fn test_voting_power_base_tokens_only() {
     let e = Env::default();
     e.mock_all_auths();
-    let (gov_client, admin, governance_token, token_client) = setup_governance(&e);
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
 
     let user = Address::generate(&e);
     token_client.mint(&user, &10000);
@@ -169,7 +184,7 @@ fn test_voting_power_with_escrow() {
     e.mock_all_auths();
     e.ledger().set(LedgerInfo {
         timestamp: 1000,
-        protocol_version: 20,
+        protocol_version: 22,
         sequence_number: 10,
         network_id: Default::default(),
         base_reserve: 10,
@@ -178,7 +193,7 @@ fn test_voting_power_with_escrow() {
         min_temp_entry_ttl: 16,
     });
 
-    let (gov_client, admin, governance_token, token_client) = setup_governance(&e);
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
     let user = Address::generate(&e);
 
     token_client.mint(&user, &20000);
@@ -193,14 +208,14 @@ fn test_voting_power_with_escrow() {
 fn test_voting_power_with_delegation() {
     let e = Env::default();
     e.mock_all_auths();
-    let (gov_client, admin, governance_token, token_client) = setup_governance(&e);
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
 
     let delegator = Address::generate(&e);
     let delegatee = Address::generate(&e);
 
     e.mock_all_auths();
     token_client.mint(&delegator, &10000);
-    gov_client.delegate_voting_power(&delegator, &delegatee, &5000);
+    gov_client.delegate_voting_power(&delegator, &delegatee, &5000, &None);
 
     let delegator_power = gov_client.get_vote_power(&delegator);
     let delegatee_power = gov_client.get_vote_power(&delegatee);
@@ -215,7 +230,7 @@ fn test_voting_power_combined() {
     e.mock_all_auths();
     e.ledger().set(LedgerInfo {
         timestamp: 1000,
-        protocol_version: 20,
+        protocol_version: 22,
         sequence_number: 10,
         network_id: Default::default(),
         base_reserve: 10,
@@ -224,7 +239,7 @@ fn test_voting_power_combined() {
         min_temp_entry_ttl: 16,
     });
 
-    let (gov_client, admin, governance_token, token_client) = setup_governance(&e);
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
 
     let user1 = Address::generate(&e);
     let user2 = Address::generate(&e);
@@ -234,7 +249,7 @@ fn test_voting_power_combined() {
     token_client.mint(&user2, &10000);
 
     gov_client.lock_for_escrow(&user1, &5000, &26); // 26 weeks = 3x
-    gov_client.delegate_voting_power(&user2, &user1, &5000);
+    gov_client.delegate_voting_power(&user2, &user1, &5000, &None);
 
     let power = gov_client.get_vote_power(&user1);
     // Base: 15000 (20000 - 5000 locked)
@@ -252,7 +267,7 @@ fn test_voting_power_escrow_expired() {
     e.mock_all_auths();
     e.ledger().set(LedgerInfo {
         timestamp: 1000,
-        protocol_version: 20,
+        protocol_version: 22,
         sequence_number: 10,
         network_id: Default::default(),
         base_reserve: 10,
@@ -261,7 +276,7 @@ fn test_voting_power_escrow_expired() {
         min_temp_entry_ttl: 16,
     });
 
-    let (gov_client, admin, governance_token, token_client) = setup_governance(&e);
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
     let user = Address::generate(&e);
 
     token_client.mint(&user, &20000);
@@ -269,7 +284,7 @@ fn test_voting_power_escrow_expired() {
 
     e.ledger().set(LedgerInfo {
         timestamp: 1000 + (4 * 7 * 24 * 60 * 60) + 1,
-        protocol_version: 20,
+        protocol_version: 22,
         sequence_number: 20,
         network_id: Default::default(),
         base_reserve: 10,
@@ -290,14 +305,14 @@ fn test_voting_power_escrow_expired() {
 fn test_simple_delegation() {
     let e = Env::default();
     e.mock_all_auths();
-    let (gov_client, admin, governance_token, token_client) = setup_governance(&e);
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
 
     let delegator = Address::generate(&e);
     let delegatee = Address::generate(&e);
 
     e.mock_all_auths();
     token_client.mint(&delegator, &10000);
-    gov_client.delegate_voting_power(&delegator, &delegatee, &5000);
+    gov_client.delegate_voting_power(&delegator, &delegatee, &5000, &None);
 
     let delegation = gov_client.get_delegation(&delegator);
     assert!(delegation.is_some());
@@ -310,7 +325,7 @@ fn test_simple_delegation() {
 fn test_re_delegation() {
     let e = Env::default();
     e.mock_all_auths();
-    let (gov_client, admin, governance_token, token_client) = setup_governance(&e);
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
 
     let alice = Address::generate(&e);
     let bob = Address::generate(&e);
@@ -320,8 +335,8 @@ fn test_re_delegation() {
     token_client.mint(&alice, &10000);
     token_client.mint(&bob, &5000);
 
-    gov_client.delegate_voting_power(&alice, &bob, &5000);
-    gov_client.delegate_voting_power(&bob, &charlie, &3000);
+    gov_client.delegate_voting_power(&alice, &bob, &5000, &None);
+    gov_client.delegate_voting_power(&bob, &charlie, &3000, &None);
 
     let bob_power = gov_client.get_vote_power(&bob);
     let charlie_power = gov_client.get_vote_power(&charlie);
@@ -334,7 +349,7 @@ fn test_re_delegation() {
 fn test_change_delegation() {
     let e = Env::default();
     e.mock_all_auths();
-    let (gov_client, admin, governance_token, token_client) = setup_governance(&e);
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
 
     let delegator = Address::generate(&e);
     let delegatee1 = Address::generate(&e);
@@ -342,8 +357,8 @@ fn test_change_delegation() {
 
     e.mock_all_auths();
     token_client.mint(&delegator, &10000);
-    gov_client.delegate_voting_power(&delegator, &delegatee1, &5000);
-    gov_client.delegate_voting_power(&delegator, &delegatee2, &5000);
+    gov_client.delegate_voting_power(&delegator, &delegatee1, &5000, &None);
+    gov_client.delegate_voting_power(&delegator, &delegatee2, &5000, &None);
 
     let delegation = gov_client.get_delegation(&delegator);
     assert_eq!(delegation.unwrap().delegatee, delegatee2);
@@ -353,14 +368,14 @@ fn test_change_delegation() {
 fn test_undelegate() {
     let e = Env::default();
     e.mock_all_auths();
-    let (gov_client, admin, governance_token, token_client) = setup_governance(&e);
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
 
     let delegator = Address::generate(&e);
     let delegatee = Address::generate(&e);
 
     e.mock_all_auths();
     token_client.mint(&delegator, &10000);
-    gov_client.delegate_voting_power(&delegator, &delegatee, &5000);
+    gov_client.delegate_voting_power(&delegator, &delegatee, &5000, &None);
     gov_client.undelegate_voting_power(&delegator);
 
     let delegation = gov_client.get_delegation(&delegator);
@@ -372,11 +387,11 @@ fn test_undelegate() {
 fn test_delegate_to_self() {
     let e = Env::default();
     e.mock_all_auths();
-    let (gov_client, admin, governance_token, token_client) = setup_governance(&e);
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
 
     let user = Address::generate(&e);
     token_client.mint(&user, &10000);
-    gov_client.delegate_voting_power(&user, &user, &5000);
+    gov_client.delegate_voting_power(&user, &user, &5000, &None);
 }
 
 // ============================================================================
@@ -389,7 +404,7 @@ fn test_escrow_multipliers() {
     e.mock_all_auths();
     e.ledger().set(LedgerInfo {
         timestamp: 1000,
-        protocol_version: 20,
+        protocol_version: 22,
         sequence_number: 10,
         network_id: Default::default(),
         base_reserve: 10,
@@ -398,7 +413,7 @@ fn test_escrow_multipliers() {
         min_temp_entry_ttl: 16,
     });
 
-    let (gov_client, admin, governance_token, token_client) = setup_governance(&e);
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
     let user = Address::generate(&e);
     e.mock_all_auths();
     token_client.mint(&user, &50000);
@@ -428,7 +443,7 @@ fn test_escrow_add_to_existing() {
     e.mock_all_auths();
     e.ledger().set(LedgerInfo {
         timestamp: 1000,
-        protocol_version: 20,
+        protocol_version: 22,
         sequence_number: 10,
         network_id: Default::default(),
         base_reserve: 10,
@@ -437,7 +452,7 @@ fn test_escrow_add_to_existing() {
         min_temp_entry_ttl: 16,
     });
 
-    let (gov_client, admin, governance_token, token_client) = setup_governance(&e);
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
     let user = Address::generate(&e);
     e.mock_all_auths();
     token_client.mint(&user, &50000);
@@ -455,7 +470,7 @@ fn test_unlock_escrow() {
     e.mock_all_auths();
     e.ledger().set(LedgerInfo {
         timestamp: 1000,
-        protocol_version: 20,
+        protocol_version: 22,
         sequence_number: 10,
         network_id: Default::default(),
         base_reserve: 10,
@@ -464,7 +479,7 @@ fn test_unlock_escrow() {
         min_temp_entry_ttl: 16,
     });
 
-    let (gov_client, _, governance_token, token_client) = setup_governance(&e);
+    let (gov_client, _, _governance_token, token_client) = setup_governance(&e);
     let user = Address::generate(&e);
     token_client.mint(&user, &20000);
 
@@ -474,7 +489,7 @@ fn test_unlock_escrow() {
     let lock_duration = 4 * 7 * 24 * 60 * 60;
     e.ledger().set(LedgerInfo {
         timestamp: 1000 + lock_duration + 1,
-        protocol_version: 20,
+        protocol_version: 22,
         sequence_number: 20,
         network_id: Default::default(),
         base_reserve: 10,
@@ -488,6 +503,33 @@ fn test_unlock_escrow() {
     assert_eq!(final_balance, initial_balance);
 }
 
+#[test]
+fn test_partial_escrow_unlock() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
+    let user = Address::generate(&e);
+    token_client.mint(&user, &30000);
+
+    gov_client.lock_for_escrow(&user, &10000, &4); // 2x, matures first
+    gov_client.lock_for_escrow(&user, &5000, &52); // 4x
+    assert_eq!(gov_client.get_vote_escrow_tranches(&user).len(), 2);
+
+    set_timestamp(&e, 1000 + 4 * 7 * 24 * 60 * 60 + 1, 20);
+    gov_client.unlock_escrow(&user);
+
+    // Only the matured 10000 tranche is withdrawn
+    assert_eq!(token_client.balance(&user), 25000);
+    let tranches = gov_client.get_vote_escrow_tranches(&user);
+    assert_eq!(tranches.len(), 1);
+    assert_eq!(tranches.get(0).unwrap().amount, 5000);
+
+    // Base 25000 + still-locked tranche 5000 * 4x
+    assert_eq!(gov_client.get_vote_power(&user), 45000);
+}
+
 #[test]
 #[should_panic(expected = "Escrow is still locked")]
 fn test_unlock_before_expiry() {
@@ -495,7 +537,7 @@ fn test_unlock_before_expiry() {
     e.mock_all_auths();
     e.ledger().set(LedgerInfo {
         timestamp: 1000,
-        protocol_version: 20,
+        protocol_version: 22,
         sequence_number: 10,
         network_id: Default::default(),
         base_reserve: 10,
@@ -504,7 +546,7 @@ fn test_unlock_before_expiry() {
         min_temp_entry_ttl: 16,
     });
 
-    let (gov_client, admin, governance_token, token_client) = setup_governance(&e);
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
     let user = Address::generate(&e);
     token_client.mint(&user, &20000);
 
@@ -522,7 +564,7 @@ fn test_full_proposal_lifecycle() {
     e.mock_all_auths();
     e.ledger().set(LedgerInfo {
         timestamp: 1000,
-        protocol_version: 20,
+        protocol_version: 22,
         sequence_number: 10,
         network_id: Default::default(),
         base_reserve: 10,
@@ -531,7 +573,7 @@ fn test_full_proposal_lifecycle() {
         min_temp_entry_ttl: 16,
     });
 
-    let (gov_client, admin, governance_token, token_client) = setup_governance(&e);
+    let (gov_client, admin, _governance_token, token_client) = setup_governance(&e);
 
     let proposer = Address::generate(&e);
     let voter1 = Address::generate(&e);
@@ -544,7 +586,7 @@ fn test_full_proposal_lifecycle() {
 
     gov_client.update_circulating_voting_power(&admin, &100000u128);
 
-    let target_contract = e.register_contract(None, MockTargetContract);
+    let target_contract = e.register(MockTargetContract, ());
     let params = ProposalParameters {
         name: String::from_str(&e, "test_param"),
         value: String::from_str(&e, "test_value"),
@@ -563,6 +605,7 @@ fn test_full_proposal_lifecycle() {
         &Some(target_contract),
         &Some(Symbol::new(&e, "update_parameter")),
         &None::<Vec<Val>>,
+        &VotingMode::Linear,
     );
 
     assert_eq!(proposal_id, 1);
@@ -575,7 +618,7 @@ fn test_full_proposal_lifecycle() {
 
     e.ledger().set(LedgerInfo {
         timestamp: 1000 + voting_period + 1,
-        protocol_version: 20,
+        protocol_version: 22,
         sequence_number: 20,
         network_id: Default::default(),
         base_reserve: 10,
@@ -607,7 +650,7 @@ fn test_no_double_voting() {
     e.mock_all_auths();
     e.ledger().set(LedgerInfo {
         timestamp: 1000,
-        protocol_version: 20,
+        protocol_version: 22,
         sequence_number: 10,
         network_id: Default::default(),
         base_reserve: 10,
@@ -616,7 +659,7 @@ fn test_no_double_voting() {
         min_temp_entry_ttl: 16,
     });
 
-    let (gov_client, admin, governance_token, token_client) = setup_governance(&e);
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
     let proposer = Address::generate(&e);
     let voter = Address::generate(&e);
 
@@ -634,6 +677,7 @@ fn test_no_double_voting() {
         &None::<Address>,
         &None::<Symbol>,
         &None::<Vec<Val>>,
+        &VotingMode::Linear,
     );
 
     gov_client.cast_vote(&voter, &proposal_id, &VoteType::For);
@@ -647,7 +691,7 @@ fn test_vote_before_start() {
     e.mock_all_auths();
     e.ledger().set(LedgerInfo {
         timestamp: 1000,
-        protocol_version: 20,
+        protocol_version: 22,
         sequence_number: 10,
         network_id: Default::default(),
         base_reserve: 10,
@@ -656,7 +700,7 @@ fn test_vote_before_start() {
         min_temp_entry_ttl: 16,
     });
 
-    let (gov_client, admin, governance_token, token_client) = setup_governance(&e);
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
     let proposer = Address::generate(&e);
     let voter = Address::generate(&e);
 
@@ -674,11 +718,12 @@ fn test_vote_before_start() {
         &None::<Address>,
         &None::<Symbol>,
         &None::<Vec<Val>>,
+        &VotingMode::Linear,
     );
 
     e.ledger().set(LedgerInfo {
         timestamp: 999,
-        protocol_version: 20,
+        protocol_version: 22,
         sequence_number: 9,
         network_id: Default::default(),
         base_reserve: 10,
@@ -697,7 +742,7 @@ fn test_vote_after_end() {
     e.mock_all_auths();
     e.ledger().set(LedgerInfo {
         timestamp: 1000,
-        protocol_version: 20,
+        protocol_version: 22,
         sequence_number: 10,
         network_id: Default::default(),
         base_reserve: 10,
@@ -706,7 +751,7 @@ fn test_vote_after_end() {
         min_temp_entry_ttl: 16,
     });
 
-    let (gov_client, admin, governance_token, token_client) = setup_governance(&e);
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
     let proposer = Address::generate(&e);
     let voter = Address::generate(&e);
 
@@ -725,11 +770,12 @@ fn test_vote_after_end() {
         &None::<Address>,
         &None::<Symbol>,
         &None::<Vec<Val>>,
+        &VotingMode::Linear,
     );
 
     e.ledger().set(LedgerInfo {
         timestamp: 1000 + voting_period + 1,
-        protocol_version: 20,
+        protocol_version: 22,
         sequence_number: 20,
         network_id: Default::default(),
         base_reserve: 10,
@@ -748,7 +794,7 @@ fn test_vote_without_power() {
     e.mock_all_auths();
     e.ledger().set(LedgerInfo {
         timestamp: 1000,
-        protocol_version: 20,
+        protocol_version: 22,
         sequence_number: 10,
         network_id: Default::default(),
         base_reserve: 10,
@@ -757,7 +803,7 @@ fn test_vote_without_power() {
         min_temp_entry_ttl: 16,
     });
 
-    let (gov_client, admin, governance_token, token_client) = setup_governance(&e);
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
     let proposer = Address::generate(&e);
     let voter = Address::generate(&e);
 
@@ -774,6 +820,7 @@ fn test_vote_without_power() {
         &None::<Address>,
         &None::<Symbol>,
         &None::<Vec<Val>>,
+        &VotingMode::Linear,
     );
 
     gov_client.cast_vote(&voter, &proposal_id, &VoteType::For);
@@ -789,7 +836,7 @@ fn test_execute_parameter_change_proposal() {
     e.mock_all_auths();
     e.ledger().set(LedgerInfo {
         timestamp: 1000,
-        protocol_version: 20,
+        protocol_version: 22,
         sequence_number: 10,
         network_id: Default::default(),
         base_reserve: 10,
@@ -798,7 +845,7 @@ fn test_execute_parameter_change_proposal() {
         min_temp_entry_ttl: 16,
     });
 
-    let (gov_client, admin, governance_token, token_client) = setup_governance(&e);
+    let (gov_client, admin, _governance_token, token_client) = setup_governance(&e);
     let proposer = Address::generate(&e);
     let voter = Address::generate(&e);
 
@@ -808,7 +855,7 @@ fn test_execute_parameter_change_proposal() {
 
     gov_client.update_circulating_voting_power(&admin, &100000u128);
 
-    let target_contract = e.register_contract(None, MockTargetContract);
+    let target_contract = e.register(MockTargetContract, ());
     let params = ProposalParameters {
         name: String::from_str(&e, "fee_rate"),
         value: String::from_str(&e, "500"),
@@ -825,13 +872,14 @@ fn test_execute_parameter_change_proposal() {
         &Some(target_contract),
         &Some(Symbol::new(&e, "update_parameter")),
         &None::<Vec<Val>>,
+        &VotingMode::Linear,
     );
 
     gov_client.cast_vote(&voter, &proposal_id, &VoteType::For);
 
     e.ledger().set(LedgerInfo {
         timestamp: 1000 + voting_period + 1,
-        protocol_version: 20,
+        protocol_version: 22,
         sequence_number: 20,
         network_id: Default::default(),
         base_reserve: 10,
@@ -856,7 +904,7 @@ fn test_execute_before_passing() {
     e.mock_all_auths();
     e.ledger().set(LedgerInfo {
         timestamp: 1000,
-        protocol_version: 20,
+        protocol_version: 22,
         sequence_number: 10,
         network_id: Default::default(),
         base_reserve: 10,
@@ -865,7 +913,7 @@ fn test_execute_before_passing() {
         min_temp_entry_ttl: 16,
     });
 
-    let (gov_client, admin, governance_token, token_client) = setup_governance(&e);
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
     let proposer = Address::generate(&e);
 
     e.mock_all_auths();
@@ -881,10 +929,83 @@ fn test_execute_before_passing() {
         &None::<Address>,
         &None::<Symbol>,
         &None::<Vec<Val>>,
+        &VotingMode::Linear,
+    );
+
+    let executor = Address::generate(&e);
+    gov_client.execute_proposal(&executor, &proposal_id);
+}
+
+// Creates a ParameterChange proposal, votes it through and moves it to Passed.
+// The proposer starts with 10000 tokens and no deposit has been refunded yet.
+fn create_passed_proposal(
+    e: &Env,
+    gov_client: &GovernanceClient,
+    token_client: &MockTokenClient,
+) -> (u64, Address) {
+    let proposer = Address::generate(e);
+    let voter = Address::generate(e);
+    token_client.mint(&proposer, &10000);
+    token_client.mint(&voter, &50000);
+
+    let start = e.ledger().timestamp();
+    let target_contract = e.register(MockTargetContract, ());
+    let voting_period = 7 * 24 * 60 * 60;
+    let proposal_id = gov_client.create_proposal(
+        &proposer,
+        &String::from_str(e, "Change Fee"),
+        &String::from_str(e, "Update fee rate"),
+        &voting_period,
+        &ProposalType::ParameterChange,
+        &Some(ProposalParameters {
+            name: String::from_str(e, "fee_rate"),
+            value: String::from_str(e, "500"),
+        }),
+        &Some(target_contract),
+        &Some(Symbol::new(e, "update_parameter")),
+        &None::<Vec<Val>>,
+        &VotingMode::Linear,
     );
+    gov_client.cast_vote(&voter, &proposal_id, &VoteType::For);
+
+    set_timestamp(e, start + voting_period + 1, e.ledger().sequence() + 10);
+    gov_client.update_proposal_status(&proposal_id);
+    (proposal_id, proposer)
+}
+
+#[test]
+#[should_panic(expected = "Proposal has not passed")]
+fn test_execute_proposal_twice() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
+    let (proposal_id, _proposer) = create_passed_proposal(&e, &gov_client, &token_client);
+
+    let executor = Address::generate(&e);
+    gov_client.execute_proposal(&executor, &proposal_id);
+    gov_client.execute_proposal(&executor, &proposal_id);
+}
+
+#[test]
+fn test_execute_proposal_refunds_deposit_once() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
+    let (proposal_id, proposer) = create_passed_proposal(&e, &gov_client, &token_client);
+    assert_eq!(token_client.balance(&proposer), 9000);
 
     let executor = Address::generate(&e);
     gov_client.execute_proposal(&executor, &proposal_id);
+    assert_eq!(token_client.balance(&proposer), 10000);
+
+    assert!(gov_client
+        .try_execute_proposal(&executor, &proposal_id)
+        .is_err());
+    assert_eq!(token_client.balance(&proposer), 10000);
 }
 
 // ============================================================================
@@ -897,7 +1018,7 @@ fn test_many_proposals() {
     e.mock_all_auths();
     e.ledger().set(LedgerInfo {
         timestamp: 1000,
-        protocol_version: 20,
+        protocol_version: 22,
         sequence_number: 10,
         network_id: Default::default(),
         base_reserve: 10,
@@ -906,7 +1027,7 @@ fn test_many_proposals() {
         min_temp_entry_ttl: 16,
     });
 
-    let (gov_client, admin, governance_token, token_client) = setup_governance(&e);
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
     let proposer = Address::generate(&e);
     e.mock_all_auths();
     token_client.mint(&proposer, &1000000);
@@ -924,6 +1045,7 @@ fn test_many_proposals() {
             &None::<Address>,
             &None::<Symbol>,
             &None::<Vec<Val>>,
+            &VotingMode::Linear,
         );
         assert_eq!(proposal_id, i);
     }
@@ -938,7 +1060,7 @@ fn test_many_voters() {
     e.mock_all_auths();
     e.ledger().set(LedgerInfo {
         timestamp: 1000,
-        protocol_version: 20,
+        protocol_version: 22,
         sequence_number: 10,
         network_id: Default::default(),
         base_reserve: 10,
@@ -947,7 +1069,7 @@ fn test_many_voters() {
         min_temp_entry_ttl: 16,
     });
 
-    let (gov_client, admin, governance_token, token_client) = setup_governance(&e);
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
     let proposer = Address::generate(&e);
     e.mock_all_auths();
     token_client.mint(&proposer, &10000);
@@ -962,6 +1084,7 @@ fn test_many_voters() {
         &None::<Address>,
         &None::<Symbol>,
         &None::<Vec<Val>>,
+        &VotingMode::Linear,
     );
 
     let mut total_votes = 0u128;
@@ -987,7 +1110,7 @@ fn test_quorum_edge_case() {
     e.mock_all_auths();
     e.ledger().set(LedgerInfo {
         timestamp: 1000,
-        protocol_version: 20,
+        protocol_version: 22,
         sequence_number: 10,
         network_id: Default::default(),
         base_reserve: 10,
@@ -996,7 +1119,7 @@ fn test_quorum_edge_case() {
         min_temp_entry_ttl: 16,
     });
 
-    let (gov_client, admin, governance_token, token_client) = setup_governance(&e);
+    let (gov_client, admin, _governance_token, token_client) = setup_governance(&e);
     let proposer = Address::generate(&e);
     let voter = Address::generate(&e);
 
@@ -1017,13 +1140,14 @@ fn test_quorum_edge_case() {
         &None::<Address>,
         &None::<Symbol>,
         &None::<Vec<Val>>,
+        &VotingMode::Linear,
     );
 
     gov_client.cast_vote(&voter, &proposal_id, &VoteType::For);
 
     e.ledger().set(LedgerInfo {
         timestamp: 1000 + voting_period + 1,
-        protocol_version: 20,
+        protocol_version: 22,
         sequence_number: 20,
         network_id: Default::default(),
         base_reserve: 10,
@@ -1044,7 +1168,7 @@ fn test_approval_threshold_edge_case() {
     e.mock_all_auths();
     e.ledger().set(LedgerInfo {
         timestamp: 1000,
-        protocol_version: 20,
+        protocol_version: 22,
         sequence_number: 10,
         network_id: Default::default(),
         base_reserve: 10,
@@ -1053,7 +1177,7 @@ fn test_approval_threshold_edge_case() {
         min_temp_entry_ttl: 16,
     });
 
-    let (gov_client, admin, governance_token, token_client) = setup_governance(&e);
+    let (gov_client, admin, _governance_token, token_client) = setup_governance(&e);
     let proposer = Address::generate(&e);
     let voter_for = Address::generate(&e);
     let voter_against = Address::generate(&e);
@@ -1076,6 +1200,7 @@ fn test_approval_threshold_edge_case() {
         &None::<Address>,
         &None::<Symbol>,
         &None::<Vec<Val>>,
+        &VotingMode::Linear,
     );
 
     gov_client.cast_vote(&voter_for, &proposal_id, &VoteType::For);
@@ -1083,7 +1208,7 @@ fn test_approval_threshold_edge_case() {
 
     e.ledger().set(LedgerInfo {
         timestamp: 1000 + voting_period + 1,
-        protocol_version: 20,
+        protocol_version: 22,
         sequence_number: 20,
         network_id: Default::default(),
         base_reserve: 10,
@@ -1114,7 +1239,7 @@ fn test_vote_weight_calculation_edge_cases() {
     e.mock_all_auths();
     e.ledger().set(LedgerInfo {
         timestamp: 1000,
-        protocol_version: 20,
+        protocol_version: 22,
         sequence_number: 10,
         network_id: Default::default(),
         base_reserve: 10,
@@ -1123,7 +1248,7 @@ fn test_vote_weight_calculation_edge_cases() {
         min_temp_entry_ttl: 16,
     });
 
-    let (gov_client, admin, governance_token, token_client) = setup_governance(&e);
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
     let user = Address::generate(&e);
 
     e.mock_all_auths();
@@ -1136,3 +1261,1037 @@ fn test_vote_weight_calculation_edge_cases() {
     // Base: 0 (1 token locked), Escrow: 1 * 40000 / 10000 = 4
     assert_eq!(power, 4);
 }
+
+// ============================================================================
+// 9. Circulating supply tracking
+// ============================================================================
+
+#[test]
+fn test_circulating_supply_set_at_init() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (gov_client, _admin, _governance_token, _token_client) = setup_governance(&e);
+
+    assert_eq!(gov_client.get_circulating_voting_power(), 100000);
+}
+
+#[test]
+fn test_quorum_uses_tracked_circulating_supply() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
+
+    let proposer = Address::generate(&e);
+    let voter = Address::generate(&e);
+    token_client.mint(&proposer, &10000);
+    // 29999 of 100000 circulating is just below the 30% quorum
+    token_client.mint(&voter, &29999);
+
+    let voting_period = 7 * 24 * 60 * 60;
+    let proposal_id = gov_client.create_proposal(
+        &proposer,
+        &String::from_str(&e, "Quorum"),
+        &String::from_str(&e, "Tracked supply quorum"),
+        &voting_period,
+        &ProposalType::ParameterChange,
+        &None::<ProposalParameters>,
+        &None::<Address>,
+        &None::<Symbol>,
+        &None::<Vec<Val>>,
+        &VotingMode::Linear,
+    );
+    gov_client.cast_vote(&voter, &proposal_id, &VoteType::For);
+
+    set_timestamp(&e, 1000 + voting_period + 1, 20);
+    gov_client.update_proposal_status(&proposal_id);
+
+    let proposal = gov_client.get_proposal(&proposal_id).unwrap();
+    assert_eq!(proposal.status, ProposalStatus::Failed);
+}
+
+#[test]
+fn test_escrow_lock_only_adds_multiplier_bonus() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
+    let user = Address::generate(&e);
+    token_client.mint(&user, &20000);
+
+    gov_client.lock_for_escrow(&user, &10000, &4); // 4 weeks = 2x
+                                                   // Locked tokens remain circulating; the 2x multiplier adds 10000
+    assert_eq!(gov_client.get_circulating_voting_power(), 110000);
+
+    set_timestamp(&e, 1000 + 4 * 7 * 24 * 60 * 60 + 1, 20);
+    gov_client.unlock_escrow(&user);
+    assert_eq!(gov_client.get_circulating_voting_power(), 100000);
+}
+
+// ============================================================================
+// 10. Proposal result preview
+// ============================================================================
+
+fn create_active_proposal(
+    e: &Env,
+    gov_client: &GovernanceClient,
+    token_client: &MockTokenClient,
+) -> u64 {
+    create_active_proposal_with_mode(e, gov_client, token_client, VotingMode::Linear)
+}
+
+fn create_active_proposal_with_mode(
+    e: &Env,
+    gov_client: &GovernanceClient,
+    token_client: &MockTokenClient,
+    voting_mode: VotingMode,
+) -> u64 {
+    let proposer = Address::generate(e);
+    token_client.mint(&proposer, &10000);
+    gov_client.create_proposal(
+        &proposer,
+        &String::from_str(e, "Preview"),
+        &String::from_str(e, "Preview test"),
+        &(7 * 24 * 60 * 60),
+        &ProposalType::ParameterChange,
+        &None::<ProposalParameters>,
+        &None::<Address>,
+        &None::<Symbol>,
+        &None::<Vec<Val>>,
+        &voting_mode,
+    )
+}
+
+fn vote_with_balance(
+    e: &Env,
+    gov_client: &GovernanceClient,
+    token_client: &MockTokenClient,
+    proposal_id: u64,
+    balance: i128,
+    vote_type: VoteType,
+) {
+    let voter = Address::generate(e);
+    token_client.mint(&voter, &balance);
+    gov_client.cast_vote(&voter, &proposal_id, &vote_type);
+}
+
+#[test]
+fn test_preview_below_quorum() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
+    let proposal_id = create_active_proposal(&e, &gov_client, &token_client);
+    vote_with_balance(
+        &e,
+        &gov_client,
+        &token_client,
+        proposal_id,
+        20000,
+        VoteType::For,
+    );
+
+    let (quorum_met, approval_met, total_votes, quorum_required, approval_required) =
+        gov_client.get_proposal_result_preview(&proposal_id);
+    assert!(!quorum_met);
+    assert!(approval_met);
+    assert_eq!(total_votes, 20000);
+    assert_eq!(quorum_required, 30000);
+    assert_eq!(approval_required, 13200);
+
+    // Preview does not change the proposal
+    let proposal = gov_client.get_proposal(&proposal_id).unwrap();
+    assert_eq!(proposal.status, ProposalStatus::Active);
+}
+
+#[test]
+fn test_preview_quorum_met_approval_not_met() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
+    let proposal_id = create_active_proposal(&e, &gov_client, &token_client);
+    vote_with_balance(
+        &e,
+        &gov_client,
+        &token_client,
+        proposal_id,
+        20000,
+        VoteType::For,
+    );
+    vote_with_balance(
+        &e,
+        &gov_client,
+        &token_client,
+        proposal_id,
+        20000,
+        VoteType::Against,
+    );
+
+    let (quorum_met, approval_met, total_votes, _, approval_required) =
+        gov_client.get_proposal_result_preview(&proposal_id);
+    assert!(quorum_met);
+    assert!(!approval_met);
+    assert_eq!(total_votes, 40000);
+    assert_eq!(approval_required, 26400);
+}
+
+#[test]
+fn test_preview_quorum_and_approval_met() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
+    let proposal_id = create_active_proposal(&e, &gov_client, &token_client);
+    vote_with_balance(
+        &e,
+        &gov_client,
+        &token_client,
+        proposal_id,
+        40000,
+        VoteType::For,
+    );
+    vote_with_balance(
+        &e,
+        &gov_client,
+        &token_client,
+        proposal_id,
+        10000,
+        VoteType::Against,
+    );
+
+    let (quorum_met, approval_met, total_votes, _, _) =
+        gov_client.get_proposal_result_preview(&proposal_id);
+    assert!(quorum_met);
+    assert!(approval_met);
+    assert_eq!(total_votes, 50000);
+}
+
+// ============================================================================
+// 11. Vote weight capping
+// ============================================================================
+
+#[test]
+fn test_vote_weight_cap_limits_whale() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, admin, _governance_token, token_client) = setup_governance(&e);
+    // Cap each voter at 10% of the 100000 circulating power
+    gov_client.set_max_vote_weight_bps(&admin, &1000);
+
+    let proposal_id = create_active_proposal(&e, &gov_client, &token_client);
+    let whale = Address::generate(&e);
+    let small = Address::generate(&e);
+    token_client.mint(&whale, &50000);
+    token_client.mint(&small, &5000);
+
+    gov_client.cast_vote(&whale, &proposal_id, &VoteType::For);
+    gov_client.cast_vote(&small, &proposal_id, &VoteType::Against);
+
+    let whale_vote = gov_client.get_vote(&proposal_id, &whale).unwrap();
+    assert_eq!(whale_vote.raw_weight, 50000);
+    assert_eq!(whale_vote.weight, 10000);
+
+    let small_vote = gov_client.get_vote(&proposal_id, &small).unwrap();
+    assert_eq!(small_vote.raw_weight, 5000);
+    assert_eq!(small_vote.weight, 5000);
+
+    let proposal = gov_client.get_proposal(&proposal_id).unwrap();
+    assert_eq!(proposal.votes_for, 10000);
+    assert_eq!(proposal.votes_against, 5000);
+}
+
+#[test]
+fn test_vote_weight_cap_disabled() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, admin, _governance_token, token_client) = setup_governance(&e);
+    gov_client.set_max_vote_weight_bps(&admin, &1000);
+    gov_client.set_max_vote_weight_bps(&admin, &0);
+    assert_eq!(gov_client.get_max_vote_weight_bps(), 0);
+
+    let proposal_id = create_active_proposal(&e, &gov_client, &token_client);
+    let whale = Address::generate(&e);
+    token_client.mint(&whale, &50000);
+    gov_client.cast_vote(&whale, &proposal_id, &VoteType::For);
+
+    let whale_vote = gov_client.get_vote(&proposal_id, &whale).unwrap();
+    assert_eq!(whale_vote.weight, 50000);
+    assert_eq!(whale_vote.raw_weight, 50000);
+}
+
+// ============================================================================
+// 12. Quadratic voting
+// ============================================================================
+
+// One whale (40000) votes For, three small holders (10000 each) vote Against
+fn whale_vs_small_holders(
+    e: &Env,
+    gov_client: &GovernanceClient,
+    token_client: &MockTokenClient,
+    voting_mode: VotingMode,
+) -> Proposal {
+    let proposal_id = create_active_proposal_with_mode(e, gov_client, token_client, voting_mode);
+    vote_with_balance(
+        e,
+        gov_client,
+        token_client,
+        proposal_id,
+        40000,
+        VoteType::For,
+    );
+    for _ in 0..3 {
+        vote_with_balance(
+            e,
+            gov_client,
+            token_client,
+            proposal_id,
+            10000,
+            VoteType::Against,
+        );
+    }
+    gov_client.get_proposal(&proposal_id).unwrap()
+}
+
+#[test]
+fn test_linear_vs_quadratic_tally() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
+
+    let linear = whale_vs_small_holders(&e, &gov_client, &token_client, VotingMode::Linear);
+    assert_eq!(linear.votes_for, 40000);
+    assert_eq!(linear.votes_against, 30000);
+    assert!(linear.votes_for > linear.votes_against);
+
+    let quadratic = whale_vs_small_holders(&e, &gov_client, &token_client, VotingMode::Quadratic);
+    assert_eq!(quadratic.voting_mode, VotingMode::Quadratic);
+    assert_eq!(quadratic.votes_for, 200);
+    assert_eq!(quadratic.votes_against, 300);
+    // The whale outvotes the small holders linearly but not quadratically
+    assert!(quadratic.votes_for < quadratic.votes_against);
+}
+
+#[test]
+fn test_quadratic_weight_rounds_down() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
+    let proposal_id =
+        create_active_proposal_with_mode(&e, &gov_client, &token_client, VotingMode::Quadratic);
+
+    let voter = Address::generate(&e);
+    token_client.mint(&voter, &99);
+    gov_client.cast_vote(&voter, &proposal_id, &VoteType::For);
+
+    let vote = gov_client.get_vote(&proposal_id, &voter).unwrap();
+    assert_eq!(vote.raw_weight, 99);
+    assert_eq!(vote.weight, 9);
+}
+
+// ============================================================================
+// 13. Keeper execution with timelock
+// ============================================================================
+
+#[test]
+fn test_execute_if_ready_by_third_party() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, admin, _governance_token, token_client) = setup_governance(&e);
+    gov_client.set_execution_timelock(&admin, &(24 * 60 * 60));
+
+    let (proposal_id, proposer) = create_passed_proposal(&e, &gov_client, &token_client);
+    let proposal = gov_client.get_proposal(&proposal_id).unwrap();
+    assert_eq!(
+        proposal.executable_after,
+        e.ledger().timestamp() + 24 * 60 * 60
+    );
+
+    set_timestamp(&e, proposal.executable_after, 100);
+    gov_client.execute_if_ready(&proposal_id);
+
+    let proposal = gov_client.get_proposal(&proposal_id).unwrap();
+    assert_eq!(proposal.status, ProposalStatus::Executed);
+    assert_eq!(token_client.balance(&proposer), 10000);
+}
+
+#[test]
+#[should_panic(expected = "Proposal timelock has not elapsed")]
+fn test_execute_if_ready_before_timelock() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, admin, _governance_token, token_client) = setup_governance(&e);
+    gov_client.set_execution_timelock(&admin, &(24 * 60 * 60));
+
+    let (proposal_id, _proposer) = create_passed_proposal(&e, &gov_client, &token_client);
+    gov_client.execute_if_ready(&proposal_id);
+}
+
+#[test]
+fn test_execute_if_ready_settles_ended_proposal() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
+    let proposer = Address::generate(&e);
+    token_client.mint(&proposer, &10000);
+    let target_contract = e.register(MockTargetContract, ());
+    let proposal_id = gov_client.create_proposal(
+        &proposer,
+        &String::from_str(&e, "Change Fee"),
+        &String::from_str(&e, "Update fee rate"),
+        &(7 * 24 * 60 * 60),
+        &ProposalType::ParameterChange,
+        &Some(ProposalParameters {
+            name: String::from_str(&e, "fee_rate"),
+            value: String::from_str(&e, "500"),
+        }),
+        &Some(target_contract),
+        &Some(Symbol::new(&e, "update_parameter")),
+        &None::<Vec<Val>>,
+        &VotingMode::Linear,
+    );
+    vote_with_balance(
+        &e,
+        &gov_client,
+        &token_client,
+        proposal_id,
+        50000,
+        VoteType::For,
+    );
+
+    // Nobody calls update_proposal_status; the keeper settles and executes
+    set_timestamp(&e, 1000 + 7 * 24 * 60 * 60 + 1, 20);
+    gov_client.execute_if_ready(&proposal_id);
+
+    let proposal = gov_client.get_proposal(&proposal_id).unwrap();
+    assert_eq!(proposal.status, ProposalStatus::Executed);
+}
+
+// ============================================================================
+// 14. Active proposal paging and count
+// ============================================================================
+
+#[test]
+fn test_active_proposals_page_and_count() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
+    for _ in 0..5 {
+        create_active_proposal(&e, &gov_client, &token_client);
+    }
+    assert_eq!(gov_client.get_active_count(), 5);
+
+    let (page, has_more) = gov_client.get_active_proposals_page(&1, &2);
+    assert_eq!(page, Vec::from_array(&e, [1u64, 2]));
+    assert!(has_more);
+
+    let (page, has_more) = gov_client.get_active_proposals_page(&3, &10);
+    assert_eq!(page, Vec::from_array(&e, [3u64, 4, 5]));
+    assert!(!has_more);
+}
+
+#[test]
+fn test_active_count_drops_when_proposals_settle() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
+    let (passed_id, _proposer) = create_passed_proposal(&e, &gov_client, &token_client);
+    let failing_id = create_active_proposal(&e, &gov_client, &token_client);
+    let remaining_id = create_active_proposal(&e, &gov_client, &token_client);
+    assert_eq!(gov_client.get_active_count(), 2);
+
+    // Let the second proposal's voting window lapse without votes
+    let proposal = gov_client.get_proposal(&failing_id).unwrap();
+    set_timestamp(&e, proposal.voting_ends + 1, 200);
+    gov_client.update_proposal_status(&failing_id);
+    assert_eq!(
+        gov_client.get_proposal(&failing_id).unwrap().status,
+        ProposalStatus::Failed
+    );
+
+    gov_client.execute_proposal(&Address::generate(&e), &passed_id);
+    assert_eq!(gov_client.get_active_count(), 1);
+
+    let (page, has_more) = gov_client.get_active_proposals_page(&1, &10);
+    assert_eq!(page.len(), 1);
+    assert!(!has_more);
+    // The remaining proposal ended too, but stays Active until settled
+    assert_eq!(page.get(0).unwrap(), remaining_id);
+}
+
+// ============================================================================
+// 15. Voter list and turnout
+// ============================================================================
+
+#[test]
+fn test_voter_list_and_turnout() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
+    let proposal_id = create_active_proposal(&e, &gov_client, &token_client);
+
+    let mut voters = Vec::new(&e);
+    for (balance, vote_type) in [
+        (10000, VoteType::For),
+        (5000, VoteType::Against),
+        (2000, VoteType::Abstain),
+    ] {
+        let voter = Address::generate(&e);
+        token_client.mint(&voter, &balance);
+        gov_client.cast_vote(&voter, &proposal_id, &vote_type);
+        voters.push_back(voter);
+    }
+
+    assert_eq!(gov_client.get_voters(&proposal_id, &0, &10), voters);
+    assert_eq!(
+        gov_client.get_voters(&proposal_id, &1, &1),
+        Vec::from_array(&e, [voters.get(1).unwrap()])
+    );
+    assert_eq!(gov_client.get_voters(&proposal_id, &3, &10).len(), 0);
+
+    assert_eq!(gov_client.get_turnout(&proposal_id), (17000, 100000));
+}
+
+#[test]
+fn test_rejected_duplicate_vote_not_listed_twice() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
+    let proposal_id = create_active_proposal(&e, &gov_client, &token_client);
+
+    let voter = Address::generate(&e);
+    token_client.mint(&voter, &10000);
+    gov_client.cast_vote(&voter, &proposal_id, &VoteType::For);
+    assert!(gov_client
+        .try_cast_vote(&voter, &proposal_id, &VoteType::Against)
+        .is_err());
+
+    assert_eq!(gov_client.get_voters(&proposal_id, &0, &10).len(), 1);
+}
+
+// ============================================================================
+// 16. Escrow multiplier tiers
+// ============================================================================
+
+fn escrow_multiplier_for(
+    e: &Env,
+    gov_client: &GovernanceClient,
+    token_client: &MockTokenClient,
+    weeks: u32,
+) -> u32 {
+    let user = Address::generate(e);
+    token_client.mint(&user, &10000);
+    gov_client.lock_for_escrow(&user, &10000, &weeks);
+    gov_client.get_vote_escrow(&user).unwrap().multiplier
+}
+
+#[test]
+fn test_escrow_tiers_apply_bonus_at_boundaries() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, admin, _governance_token, token_client) = setup_governance(&e);
+
+    // Defaults keep the original linear curve
+    assert_eq!(
+        escrow_multiplier_for(&e, &gov_client, &token_client, 25),
+        28750
+    );
+    assert_eq!(
+        escrow_multiplier_for(&e, &gov_client, &token_client, 26),
+        29166
+    );
+
+    gov_client.set_escrow_tiers(
+        &admin,
+        &EscrowTierConfig {
+            base_multiplier: 20000,
+            max_multiplier: 40000,
+            mid_tier_weeks: 26,
+            mid_tier_bonus: 2500,
+            top_tier_weeks: 52,
+            top_tier_bonus: 5000,
+        },
+    );
+
+    assert_eq!(
+        escrow_multiplier_for(&e, &gov_client, &token_client, 4),
+        20000
+    );
+    assert_eq!(
+        escrow_multiplier_for(&e, &gov_client, &token_client, 25),
+        28750
+    );
+    assert_eq!(
+        escrow_multiplier_for(&e, &gov_client, &token_client, 26),
+        31666
+    );
+    assert_eq!(
+        escrow_multiplier_for(&e, &gov_client, &token_client, 51),
+        42083
+    );
+    assert_eq!(
+        escrow_multiplier_for(&e, &gov_client, &token_client, 52),
+        45000
+    );
+}
+
+#[test]
+fn test_escrow_tier_change_only_affects_new_locks() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, admin, _governance_token, token_client) = setup_governance(&e);
+    let early = Address::generate(&e);
+    token_client.mint(&early, &10000);
+    gov_client.lock_for_escrow(&early, &10000, &4);
+
+    let mut tiers = gov_client.get_escrow_tiers();
+    tiers.base_multiplier = 15000;
+    gov_client.set_escrow_tiers(&admin, &tiers);
+
+    assert_eq!(
+        gov_client.get_vote_escrow(&early).unwrap().multiplier,
+        20000
+    );
+    assert_eq!(
+        escrow_multiplier_for(&e, &gov_client, &token_client, 4),
+        15000
+    );
+}
+
+#[test]
+#[should_panic(expected = "Invalid escrow multiplier range")]
+fn test_escrow_tiers_reject_inverted_range() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (gov_client, admin, _governance_token, _token_client) = setup_governance(&e);
+    let mut tiers = gov_client.get_escrow_tiers();
+    tiers.max_multiplier = 15000;
+    gov_client.set_escrow_tiers(&admin, &tiers);
+}
+
+// ============================================================================
+// 17. Emergency escrow unlock
+// ============================================================================
+
+#[test]
+fn test_emergency_unlock_returns_amount_minus_penalty() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, admin, _governance_token, token_client) = setup_governance(&e);
+    gov_client.set_early_unlock_penalty_bps(&admin, &1000);
+    let user = Address::generate(&e);
+    token_client.mint(&user, &10000);
+    gov_client.lock_for_escrow(&user, &10000, &52);
+
+    gov_client.emergency_unlock_escrow(&user);
+
+    assert_eq!(token_client.balance(&user), 9000);
+    // The penalty stays with the governance contract
+    assert_eq!(token_client.balance(&gov_client.address), 1000);
+    assert!(gov_client.get_vote_escrow(&user).is_none());
+}
+
+#[test]
+fn test_emergency_unlock_forfeits_escrow_boost() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
+    let user = Address::generate(&e);
+    token_client.mint(&user, &10000);
+    gov_client.lock_for_escrow(&user, &10000, &4); // 2x
+    assert_eq!(gov_client.get_vote_power(&user), 20000);
+    assert_eq!(gov_client.get_circulating_voting_power(), 110000);
+
+    gov_client.emergency_unlock_escrow(&user);
+
+    // Default 25% penalty; only the returned tokens count afterwards
+    assert_eq!(gov_client.get_vote_power(&user), 7500);
+    assert_eq!(gov_client.get_circulating_voting_power(), 100000);
+}
+
+#[test]
+fn test_emergency_unlock_removes_matured_tranche_bonus() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
+    let user = Address::generate(&e);
+    token_client.mint(&user, &10000);
+    gov_client.lock_for_escrow(&user, &10000, &4); // 2x
+    assert_eq!(gov_client.get_circulating_voting_power(), 110000);
+
+    // Let the lock mature, then withdraw through the emergency path
+    set_timestamp(&e, 1000 + 5 * 7 * 24 * 60 * 60, 20);
+    gov_client.emergency_unlock_escrow(&user);
+
+    assert_eq!(token_client.balance(&user), 10000);
+    assert_eq!(gov_client.get_circulating_voting_power(), 100000);
+}
+
+// ============================================================================
+// 18. Delegation expiry
+// ============================================================================
+
+#[test]
+fn test_delegation_expires() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
+    let delegator = Address::generate(&e);
+    let delegatee = Address::generate(&e);
+    token_client.mint(&delegator, &10000);
+
+    gov_client.delegate_voting_power(&delegator, &delegatee, &5000, &Some(2000));
+    assert_eq!(
+        gov_client.get_delegation(&delegator).unwrap().expires_at,
+        Some(2000)
+    );
+    assert_eq!(gov_client.get_vote_power(&delegatee), 5000);
+    assert_eq!(gov_client.get_vote_power(&delegator), 5000);
+
+    set_timestamp(&e, 2000, 20);
+    assert_eq!(gov_client.get_vote_power(&delegatee), 0);
+    assert_eq!(gov_client.get_vote_power(&delegator), 10000);
+}
+
+#[test]
+fn test_expired_delegation_can_be_recreated() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
+    let delegator = Address::generate(&e);
+    let delegatee = Address::generate(&e);
+    token_client.mint(&delegator, &10000);
+
+    gov_client.delegate_voting_power(&delegator, &delegatee, &5000, &Some(2000));
+    set_timestamp(&e, 3000, 20);
+    assert_eq!(gov_client.get_vote_power(&delegatee), 0);
+
+    gov_client.delegate_voting_power(&delegator, &delegatee, &4000, &Some(5000));
+    assert_eq!(gov_client.get_vote_power(&delegatee), 4000);
+    assert_eq!(
+        gov_client.get_delegation(&delegator).unwrap().expires_at,
+        Some(5000)
+    );
+}
+
+#[test]
+#[should_panic(expected = "Delegation expiry must be in the future")]
+fn test_delegation_expiry_in_past_rejected() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
+    let delegator = Address::generate(&e);
+    let delegatee = Address::generate(&e);
+    token_client.mint(&delegator, &10000);
+
+    gov_client.delegate_voting_power(&delegator, &delegatee, &5000, &Some(1000));
+}
+
+// ============================================================================
+// 19. Guardian veto
+// ============================================================================
+
+fn setup_vetoable_proposal(
+    e: &Env,
+) -> (
+    GovernanceClient<'static>,
+    MockTokenClient<'static>,
+    Address,
+    u64,
+    Address,
+) {
+    let (gov_client, admin, _governance_token, token_client) = setup_governance(e);
+    gov_client.set_execution_timelock(&admin, &(24 * 60 * 60));
+    let guardian = Address::generate(e);
+    gov_client.add_guardian(&admin, &guardian);
+
+    let (proposal_id, proposer) = create_passed_proposal(e, &gov_client, &token_client);
+    (gov_client, token_client, guardian, proposal_id, proposer)
+}
+
+#[test]
+fn test_guardian_vetoes_passed_proposal() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, token_client, guardian, proposal_id, proposer) = setup_vetoable_proposal(&e);
+    assert_eq!(token_client.balance(&proposer), 9000);
+
+    gov_client.veto_proposal(&guardian, &proposal_id);
+
+    let proposal = gov_client.get_proposal(&proposal_id).unwrap();
+    assert_eq!(proposal.status, ProposalStatus::Vetoed);
+    assert_eq!(token_client.balance(&proposer), 10000);
+}
+
+#[test]
+#[should_panic(expected = "Proposal was vetoed")]
+fn test_vetoed_proposal_cannot_execute() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, _token_client, guardian, proposal_id, _proposer) = setup_vetoable_proposal(&e);
+    gov_client.veto_proposal(&guardian, &proposal_id);
+
+    let proposal = gov_client.get_proposal(&proposal_id).unwrap();
+    set_timestamp(&e, proposal.executable_after, 100);
+    gov_client.execute_proposal(&Address::generate(&e), &proposal_id);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized: caller is not a guardian")]
+fn test_non_guardian_cannot_veto() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, _token_client, _guardian, proposal_id, _proposer) =
+        setup_vetoable_proposal(&e);
+    gov_client.veto_proposal(&Address::generate(&e), &proposal_id);
+}
+
+// ============================================================================
+// 20. Per-type thresholds
+// ============================================================================
+
+fn create_typed_proposal(
+    e: &Env,
+    gov_client: &GovernanceClient,
+    token_client: &MockTokenClient,
+    proposal_type: ProposalType,
+) -> u64 {
+    let proposer = Address::generate(e);
+    token_client.mint(&proposer, &10000);
+    gov_client.create_proposal(
+        &proposer,
+        &String::from_str(e, "Typed"),
+        &String::from_str(e, "Type threshold test"),
+        &(7 * 24 * 60 * 60),
+        &proposal_type,
+        &None::<ProposalParameters>,
+        &None::<Address>,
+        &None::<Symbol>,
+        &None::<Vec<Val>>,
+        &VotingMode::Linear,
+    )
+}
+
+#[test]
+fn test_contract_upgrade_needs_higher_quorum() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, admin, _governance_token, token_client) = setup_governance(&e);
+    gov_client.set_type_thresholds(&admin, &ProposalType::ContractUpgrade, &5000, &6600);
+    assert_eq!(
+        gov_client.get_type_thresholds(&ProposalType::ContractUpgrade),
+        (5000, 6600)
+    );
+    assert_eq!(
+        gov_client.get_type_thresholds(&ProposalType::ParameterChange),
+        (3000, 6600)
+    );
+
+    let param_id = create_typed_proposal(
+        &e,
+        &gov_client,
+        &token_client,
+        ProposalType::ParameterChange,
+    );
+    let upgrade_id = create_typed_proposal(
+        &e,
+        &gov_client,
+        &token_client,
+        ProposalType::ContractUpgrade,
+    );
+
+    // 40% turnout clears the global 30% quorum but not the upgrade's 50%
+    vote_with_balance(
+        &e,
+        &gov_client,
+        &token_client,
+        param_id,
+        40000,
+        VoteType::For,
+    );
+    vote_with_balance(
+        &e,
+        &gov_client,
+        &token_client,
+        upgrade_id,
+        40000,
+        VoteType::For,
+    );
+
+    set_timestamp(&e, 1000 + 7 * 24 * 60 * 60 + 1, 20);
+    gov_client.update_proposal_status(&param_id);
+    gov_client.update_proposal_status(&upgrade_id);
+
+    assert_eq!(
+        gov_client.get_proposal(&param_id).unwrap().status,
+        ProposalStatus::Passed
+    );
+    assert_eq!(
+        gov_client.get_proposal(&upgrade_id).unwrap().status,
+        ProposalStatus::Failed
+    );
+}
+
+#[test]
+fn test_contract_upgrade_passes_with_type_quorum_met() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, admin, _governance_token, token_client) = setup_governance(&e);
+    gov_client.set_type_thresholds(&admin, &ProposalType::ContractUpgrade, &5000, &6600);
+
+    let upgrade_id = create_typed_proposal(
+        &e,
+        &gov_client,
+        &token_client,
+        ProposalType::ContractUpgrade,
+    );
+    vote_with_balance(
+        &e,
+        &gov_client,
+        &token_client,
+        upgrade_id,
+        60000,
+        VoteType::For,
+    );
+
+    let (quorum_met, approval_met, _, quorum_required, _) =
+        gov_client.get_proposal_result_preview(&upgrade_id);
+    assert!(quorum_met && approval_met);
+    assert_eq!(quorum_required, 50000);
+
+    set_timestamp(&e, 1000 + 7 * 24 * 60 * 60 + 1, 20);
+    gov_client.update_proposal_status(&upgrade_id);
+    assert_eq!(
+        gov_client.get_proposal(&upgrade_id).unwrap().status,
+        ProposalStatus::Passed
+    );
+}
+
+// ============================================================================
+// Audit logging
+// ============================================================================
+
+fn latest_audit_log(e: &Env, gov_client: &GovernanceClient) -> stellai_lib::audit::AuditLog {
+    e.as_contract(&gov_client.address, || {
+        let latest = stellai_lib::audit::get_log_id_counter(e);
+        stellai_lib::audit::get_audit_log(e, latest).unwrap()
+    })
+}
+
+#[test]
+fn test_proposal_lifecycle_writes_audit_logs() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
+    let (proposal_id, proposer) = create_passed_proposal(&e, &gov_client, &token_client);
+
+    let (created, voted) = e.as_contract(&gov_client.address, || {
+        (
+            stellai_lib::audit::get_audit_log(&e, 1).unwrap(),
+            stellai_lib::audit::get_audit_log(&e, 2).unwrap(),
+        )
+    });
+    assert_eq!(created.operator, proposer);
+    assert_eq!(
+        created.operation_type,
+        stellai_lib::audit::OperationType::ProposalCreated
+    );
+    assert_eq!(
+        voted.operation_type,
+        stellai_lib::audit::OperationType::ProposalVoteCast
+    );
+
+    let executor = Address::generate(&e);
+    gov_client.execute_proposal(&executor, &proposal_id);
+
+    let executed = latest_audit_log(&e, &gov_client);
+    assert_eq!(executed.id, 3);
+    assert_eq!(executed.operator, executor);
+    assert_eq!(
+        executed.operation_type,
+        stellai_lib::audit::OperationType::ProposalExecuted
+    );
+    assert_eq!(
+        executed.before_state,
+        String::from_str(&e, "{\"status\":\"Passed\"}")
+    );
+    assert_eq!(
+        executed.after_state,
+        String::from_str(&e, "{\"status\":\"Executed\"}")
+    );
+}
+
+#[test]
+fn test_keeper_execution_audits_contract_as_operator() {
+    let e = Env::default();
+    e.mock_all_auths();
+    set_timestamp(&e, 1000, 10);
+
+    let (gov_client, _admin, _governance_token, token_client) = setup_governance(&e);
+    let (proposal_id, _proposer) = create_passed_proposal(&e, &gov_client, &token_client);
+
+    gov_client.execute_if_ready(&proposal_id);
+
+    let executed = latest_audit_log(&e, &gov_client);
+    assert_eq!(executed.operator, gov_client.address);
+    assert_eq!(
+        executed.operation_type,
+        stellai_lib::audit::OperationType::ProposalExecuted
+    );
+}
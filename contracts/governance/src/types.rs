@@ -1,4 +1,3 @@
-#![no_std]
 use soroban_sdk::{contracttype, Address, String, Symbol, Val, Vec};
 
 /// Vote types for proposals
@@ -22,6 +21,16 @@ pub enum ProposalType {
     EmergencyPause,
 }
 
+/// How vote weight is derived from voting power
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VotingMode {
+    /// One token, one vote
+    Linear,
+    /// Weight is the integer square root of voting power
+    Quadratic,
+}
+
 /// Status of a proposal
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -38,6 +47,8 @@ pub enum ProposalStatus {
     Executed,
     /// Proposal cancelled
     Cancelled,
+    /// Passed proposal vetoed by a guardian during its timelock
+    Vetoed,
 }
 
 /// Proposal structure
@@ -61,6 +72,10 @@ pub struct Proposal {
     pub votes_against: u128,
     pub votes_abstain: u128,
     pub status: ProposalStatus,
+    /// Voting mode fixed at creation
+    pub voting_mode: VotingMode,
+    /// Earliest execution time, set when the proposal passes (0 until then)
+    pub executable_after: u64,
     /// Target contract address for execution (if applicable)
     pub target_contract: Option<Address>,
     /// Function to call on target contract (if applicable)
@@ -79,7 +94,7 @@ pub struct ProposalParameters {
     pub value: String,
 }
 
-/// Vote escrow structure
+/// Vote escrow tranche (an address may hold several with different lock ends)
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct VoteEscrow {
@@ -91,6 +106,23 @@ pub struct VoteEscrow {
     pub multiplier: u32,
 }
 
+/// Escrow multiplier curve: linear from `base_multiplier` at 4 weeks to
+/// `max_multiplier` at 52 weeks, plus a bonus once a lock reaches a tier
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EscrowTierConfig {
+    /// Multiplier for a 4-week lock (basis points, 10000 = 1x)
+    pub base_multiplier: u32,
+    /// Multiplier for a 52-week lock before tier bonuses
+    pub max_multiplier: u32,
+    /// Locks of at least this many weeks earn `mid_tier_bonus`
+    pub mid_tier_weeks: u32,
+    pub mid_tier_bonus: u32,
+    /// Locks of at least this many weeks earn `top_tier_bonus` instead
+    pub top_tier_weeks: u32,
+    pub top_tier_bonus: u32,
+}
+
 /// Delegation information
 #[contracttype]
 #[derive(Clone, Debug)]
@@ -99,6 +131,8 @@ pub struct Delegation {
     pub delegatee: Address,
     /// Amount of voting power delegated
     pub amount: u128,
+    /// Timestamp after which the delegation no longer counts (None = never)
+    pub expires_at: Option<u64>,
 }
 
 /// Vote record for a user on a proposal
@@ -108,6 +142,9 @@ pub struct Vote {
     pub proposal_id: u64,
     pub voter: Address,
     pub vote_type: VoteType,
+    /// Weight counted in the tally (after any cap)
     pub weight: u128,
+    /// Voting power before the per-voter cap was applied
+    pub raw_weight: u128,
     pub timestamp: u64,
 }
@@ -1,6 +1,4 @@
-#![no_std]
-
-use soroban_sdk::{contracttype, Address, Env, String, Symbol, Val, Vec};
+use soroban_sdk::{contracttype, Env, String, Symbol, Val, Vec};
 
 use stellai_lib::atomic::AtomicTransactionSupport;
 
@@ -21,7 +19,7 @@ impl AtomicTransactionSupport for MarketplaceAtomicSupport {
         env: &Env,
         transaction_id: u64,
         step_id: u32,
-        function: &Symbol,
+        _function: &Symbol,
         _args: &Vec<Val>,
     ) -> bool {
         // Simplified implementation - just mark as prepared
@@ -42,7 +40,7 @@ impl AtomicTransactionSupport for MarketplaceAtomicSupport {
         env: &Env,
         transaction_id: u64,
         step_id: u32,
-        function: &Symbol,
+        _function: &Symbol,
         _args: &Vec<Val>,
     ) -> Val {
         // Simplified implementation - just mark as executed and return success
@@ -0,0 +1,57 @@
+use soroban_sdk::contracterror;
+
+/// Errors returned by the core listing and sale entrypoints
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum MarketplaceError {
+    InvalidListingId = 1,
+    ListingNotFound = 2,
+    ListingInactive = 3,
+    Unauthorized = 4,
+    PriceNotPositive = 5,
+    ApprovalRequired = 6,
+    InvalidAgentId = 7,
+    InvalidListingType = 8,
+    BuyerNotAllowed = 9,
+    AgentEscrowLocked = 10,
+    Paused = 11,
+    ApprovalNotRequired = 12,
+    InsufficientApprovers = 13,
+    TooManyApprovers = 14,
+    InvalidDisputeWindow = 15,
+    NoHeldFunds = 16,
+    DisputeWindowOpen = 17,
+    SaleDisputed = 18,
+    DisputeWindowClosed = 19,
+    SaleNotDisputed = 20,
+    InvalidShareAmount = 21,
+    InsufficientShares = 22,
+    NoProceeds = 23,
+    InvalidLeaseBounds = 24,
+    ListingCapReached = 25,
+}
+
+/// Errors returned by the lease lifecycle entrypoints
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum LeaseError {
+    Paused = 1,
+    ListingNotFound = 2,
+    ListingInactive = 3,
+    NotLeaseListing = 4,
+    SelfLease = 5,
+    InvalidDuration = 6,
+    LeaseNotFound = 7,
+    Unauthorized = 8,
+    LeaseNotActive = 9,
+    LeaseEnded = 10,
+    ExtensionNotPending = 11,
+    ExtensionNotFound = 12,
+    ExtensionExpired = 13,
+    AlreadyTerminated = 14,
+    NotYetExpired = 15,
+    PriceOverflow = 16,
+    StreamingExtensionUnsupported = 17,
+}
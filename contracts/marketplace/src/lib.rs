@@ -1,19 +1,50 @@
 #![no_std]
+#![allow(clippy::too_many_arguments)]
 
 mod atomic;
+mod errors;
 mod storage;
 
-use soroban_sdk::{contract, contractimpl, token, Address, Env, String, Symbol, Val, Vec};
+use soroban_sdk::{
+    contract, contractimpl, panic_with_error, token, Address, Env, IntoVal, String, Symbol, Val,
+    Vec,
+};
 use stellai_lib::{
     atomic::AtomicTransactionSupport,
     audit::{create_audit_log, OperationType},
-    Approval, ApprovalConfig, ApprovalHistory, ApprovalStatus, Auction, AuctionStatus, AuctionType,
-    Listing, ListingType, RoyaltyInfo, LISTING_COUNTER_KEY,
+    Agent, Approval, ApprovalConfig, ApprovalHistory, ApprovalStatus, Auction, AuctionStatus,
+    AuctionType, LeaseConfig, LeaseData, LeaseExtensionRequest, LeaseHistoryEntry, LeaseState,
+    Listing, ListingType, OracleData, RoyaltyInfo, LEASE_EXTENSION_GRACE_PERIOD_SECONDS,
+    LEASE_EXTENSION_REQUEST_TTL_SECONDS, LISTING_COUNTER_KEY, MAX_DURATION_DAYS,
 };
 
 use atomic::MarketplaceAtomicSupport;
+pub use errors::{LeaseError, MarketplaceError};
 use storage::*;
 
+// Maximum listing IDs scanned per capability search (bounds cross-contract calls)
+const MAX_CAPABILITY_SCAN: u64 = 100;
+// Upper bound on the platform fee taken from seller proceeds (10%)
+const MAX_PLATFORM_FEE_BPS: u32 = 1000;
+// Oracle prices older than this are rejected (5 minutes)
+const ORACLE_PRICE_MAX_AGE_SECONDS: u64 = 300;
+// Fixed-point scale of oracle exchange rates (7 decimals, 10_000_000 = 1.0)
+const ORACLE_RATE_SCALE: i128 = 10_000_000;
+// Maximum listing IDs visited per migrate_listings call
+const MAX_LISTING_MIGRATION_BATCH: u32 = 100;
+// Maximum lease IDs visited per cleanup_expired_extensions call
+const MAX_EXTENSION_CLEANUP_BATCH: u32 = 100;
+// Anti-sniping: a bid this close to the end pushes it back by the same amount
+const AUCTION_EXTENSION_SECONDS: u64 = 300;
+// Extensions allowed per auction before late bids stop moving the end time
+const MAX_AUCTION_EXTENSIONS: u32 = 10;
+// Longest dispute window a seller may put on an escrow-hold listing (30 days)
+const MAX_DISPUTE_WINDOW_SECONDS: u64 = 30 * 24 * 60 * 60;
+// Consecutive missing approval IDs after which repair_approval_counter stops scanning
+const APPROVAL_REPAIR_GAP: u64 = 100;
+// Approval IDs inspected by a single repair_approval_counter call
+const MAX_APPROVAL_REPAIR_SCAN: u64 = 500;
+
 #[contract]
 pub struct Marketplace;
 
@@ -57,6 +88,140 @@ impl Marketplace {
         set_payment_token(&env, token);
     }
 
+    /// Get the token payments are made in, if one has been configured
+    pub fn get_payment_token_address(env: Env) -> Option<Address> {
+        try_get_payment_token(&env)
+    }
+
+    /// Get the effective approval and lease configs, falling back to the
+    /// built-in defaults for any not explicitly set
+    pub fn get_defaults(env: Env) -> (ApprovalConfig, LeaseConfig) {
+        (get_approval_config(&env), get_lease_config(&env))
+    }
+
+    /// Set the AgentNFT contract used to look up listed agents (admin only)
+    pub fn set_agent_nft(env: Env, admin: Address, agent_nft: Address) {
+        admin.require_auth();
+        let current_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .expect("Contract not initialized");
+        assert!(admin == current_admin, "Unauthorized");
+
+        set_agent_nft(&env, &agent_nft);
+    }
+
+    /// Set the Oracle contract used for oracle-priced auctions (admin only)
+    pub fn set_price_oracle(env: Env, admin: Address, oracle: Address) {
+        admin.require_auth();
+        let current_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .expect("Contract not initialized");
+        assert!(admin == current_admin, "Unauthorized");
+
+        set_price_oracle(&env, &oracle);
+    }
+
+    /// Set the platform fee taken from seller proceeds and its recipient (admin only)
+    pub fn set_platform_fee(env: Env, admin: Address, fee_bps: u32, fee_recipient: Address) {
+        admin.require_auth();
+        let current_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .expect("Contract not initialized");
+        assert!(admin == current_admin, "Unauthorized");
+        assert!(
+            fee_bps <= MAX_PLATFORM_FEE_BPS,
+            "Platform fee exceeds maximum"
+        );
+
+        set_platform_fee(&env, fee_bps, &fee_recipient);
+
+        env.events().publish(
+            (Symbol::new(&env, "platform_fee_set"),),
+            (fee_bps, fee_recipient),
+        );
+    }
+
+    /// Get the platform fee in basis points and its recipient, if configured
+    pub fn get_platform_fee(env: Env) -> (u32, Option<Address>) {
+        (get_platform_fee_bps(&env), get_fee_recipient(&env))
+    }
+
+    /// Set the governance contract allowed to pause the marketplace (admin only)
+    pub fn set_governance_contract(env: Env, admin: Address, governance: Address) {
+        admin.require_auth();
+        let current_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .expect("Contract not initialized");
+        assert!(admin == current_admin, "Unauthorized");
+
+        set_governance_contract(&env, &governance);
+    }
+
+    /// Pause or unpause the marketplace. Callable by the admin or the
+    /// governance contract (e.g. via an EmergencyPause proposal).
+    pub fn set_paused(env: Env, caller: Address, paused: bool) {
+        caller.require_auth();
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .expect("Contract not initialized");
+        let is_governance = get_governance_contract(&env) == Some(caller.clone());
+        assert!(caller == admin || is_governance, "Unauthorized");
+
+        set_paused(&env, paused);
+
+        env.events()
+            .publish((Symbol::new(&env, "paused_set"),), (caller, paused));
+    }
+
+    /// Whether the marketplace is paused
+    pub fn is_paused(env: Env) -> bool {
+        is_paused(&env)
+    }
+
+    fn require_not_paused(env: &Env) {
+        if is_paused(env) {
+            panic!("Marketplace is paused");
+        }
+    }
+
+    /// Reject agents already locked in escrow by a pending sale, so the same
+    /// agent cannot be sold twice. Skipped until an AgentNFT is configured.
+    fn require_agent_not_escrowed(env: &Env, agent_id: u64) -> Result<(), MarketplaceError> {
+        if let Some(agent_nft) = try_get_agent_nft(env) {
+            let agent: Agent = env.invoke_contract(
+                &agent_nft,
+                &Symbol::new(env, "get_agent"),
+                Vec::from_array(env, [agent_id.into_val(env)]),
+            );
+            if agent.escrow_locked {
+                return Err(MarketplaceError::AgentEscrowLocked);
+            }
+        }
+        Ok(())
+    }
+
+    /// Royalty cap shared with the AgentNFT contract, or the default if none is configured
+    fn max_royalty_bps(env: &Env) -> u32 {
+        match try_get_agent_nft(env) {
+            Some(agent_nft) => env.invoke_contract(
+                &agent_nft,
+                &Symbol::new(env, "get_max_royalty"),
+                Vec::new(env),
+            ),
+            None => stellai_lib::DEFAULT_MAX_ROYALTY_BPS,
+        }
+    }
+
     /// Create a new listing
     pub fn create_listing(
         env: Env,
@@ -64,17 +229,27 @@ impl Marketplace {
         seller: Address,
         listing_type: u32,
         price: i128,
-    ) -> u64 {
+    ) -> Result<u64, MarketplaceError> {
+        if is_paused(&env) {
+            return Err(MarketplaceError::Paused);
+        }
         seller.require_auth();
 
         if agent_id == 0 {
-            panic!("Invalid agent ID");
+            return Err(MarketplaceError::InvalidAgentId);
         }
         if listing_type > 2 {
-            panic!("Invalid listing type");
+            return Err(MarketplaceError::InvalidListingType);
         }
         if price <= 0 {
-            panic!("Price must be positive");
+            return Err(MarketplaceError::PriceNotPositive);
+        }
+        Self::require_agent_not_escrowed(&env, agent_id)?;
+
+        let active_listings = get_seller_listing_count(&env, &seller);
+        let max_active = get_max_active_listings_per_seller(&env);
+        if max_active > 0 && active_listings >= max_active {
+            return Err(MarketplaceError::ListingCapReached);
         }
 
         // Generate listing ID
@@ -94,15 +269,14 @@ impl Marketplace {
                 0 => ListingType::Sale,
                 1 => ListingType::Lease,
                 2 => ListingType::Auction,
-                _ => panic!("Invalid listing type"),
+                _ => return Err(MarketplaceError::InvalidListingType),
             },
             active: true,
             created_at: env.ledger().timestamp(),
         };
 
-        // Store listing using tuple key
-        let listing_key = (Symbol::new(&env, "listing"), listing_id);
-        env.storage().instance().set(&listing_key, &listing);
+        set_listing(&env, &listing);
+        set_seller_listing_count(&env, &seller, active_listings + 1);
 
         // Update counter
         env.storage()
@@ -130,32 +304,165 @@ impl Marketplace {
             description,
         );
 
-        listing_id
+        Ok(listing_id)
     }
 
-    /// Purchase an agent
-    pub fn buy_agent(env: Env, listing_id: u64, buyer: Address) {
-        buyer.require_auth();
+    /// Create a listing that only the given buyers may purchase (OTC / private
+    /// sale). An empty `allowed_buyers` list makes the listing public.
+    pub fn create_private_listing(
+        env: Env,
+        seller: Address,
+        agent_id: u64,
+        listing_type: u32,
+        price: i128,
+        allowed_buyers: Vec<Address>,
+    ) -> Result<u64, MarketplaceError> {
+        let listing_id = Self::create_listing(env.clone(), agent_id, seller, listing_type, price)?;
+        set_allowed_buyers(&env, listing_id, &allowed_buyers);
+        Ok(listing_id)
+    }
+
+    /// Create a listing whose sale proceeds are held for `dispute_window_seconds`
+    /// after purchase, giving the buyer time to raise a dispute before the
+    /// seller is paid.
+    pub fn create_escrow_listing(
+        env: Env,
+        seller: Address,
+        agent_id: u64,
+        listing_type: u32,
+        price: i128,
+        dispute_window_seconds: u64,
+    ) -> Result<u64, MarketplaceError> {
+        if dispute_window_seconds == 0 || dispute_window_seconds > MAX_DISPUTE_WINDOW_SECONDS {
+            return Err(MarketplaceError::InvalidDisputeWindow);
+        }
+        let listing_id = Self::create_listing(env.clone(), agent_id, seller, listing_type, price)?;
+        set_dispute_window(&env, listing_id, dispute_window_seconds);
+        Ok(listing_id)
+    }
+
+    /// Create a Lease listing priced per second of lease time.
+    ///
+    /// `initiate_lease` charges `price_per_second * duration_seconds` for these
+    /// listings; plain Lease listings keep charging their flat price.
+    pub fn create_prorated_lease_listing(
+        env: Env,
+        seller: Address,
+        agent_id: u64,
+        price_per_second: i128,
+    ) -> Result<u64, MarketplaceError> {
+        let listing_id = Self::create_listing(env.clone(), agent_id, seller, 1, price_per_second)?;
+        set_lease_price_per_second(&env, listing_id, price_per_second);
+        Ok(listing_id)
+    }
+
+    /// Create a Lease listing that only accepts lease durations within
+    /// `min_lease_seconds..=max_lease_seconds`. Either bound may be omitted.
+    pub fn create_lease_listing(
+        env: Env,
+        seller: Address,
+        agent_id: u64,
+        price: i128,
+        min_lease_seconds: Option<u64>,
+        max_lease_seconds: Option<u64>,
+    ) -> Result<u64, MarketplaceError> {
+        if min_lease_seconds == Some(0) || max_lease_seconds == Some(0) {
+            return Err(MarketplaceError::InvalidLeaseBounds);
+        }
+        if let (Some(min), Some(max)) = (min_lease_seconds, max_lease_seconds) {
+            if min > max {
+                return Err(MarketplaceError::InvalidLeaseBounds);
+            }
+        }
+        let listing_id = Self::create_listing(env.clone(), agent_id, seller, 1, price)?;
+        if min_lease_seconds.is_some() || max_lease_seconds.is_some() {
+            set_lease_duration_bounds(&env, listing_id, min_lease_seconds, max_lease_seconds);
+        }
+        Ok(listing_id)
+    }
+
+    /// Get the (min, max) lease duration in seconds accepted by a listing
+    pub fn get_lease_duration_bounds(env: Env, listing_id: u64) -> (Option<u64>, Option<u64>) {
+        get_lease_duration_bounds(&env, listing_id)
+    }
+
+    /// Get the per-second rate of a duration-priced lease listing
+    pub fn get_lease_price_per_second(env: Env, listing_id: u64) -> Option<i128> {
+        get_lease_price_per_second(&env, listing_id)
+    }
+
+    /// Replace the allow-list of a listing (seller only); empty makes it public
+    pub fn update_allowed_buyers(
+        env: Env,
+        listing_id: u64,
+        seller: Address,
+        allowed_buyers: Vec<Address>,
+    ) {
+        seller.require_auth();
+
+        let listing = get_listing(&env, listing_id).expect("Listing not found");
+        assert!(listing.seller == seller, "Unauthorized");
+
+        set_allowed_buyers(&env, listing_id, &allowed_buyers);
+
+        env.events().publish(
+            (Symbol::new(&env, "allowed_buyers_updated"),),
+            (listing_id, allowed_buyers.len()),
+        );
+    }
 
+    /// Get the allow-list of a listing (empty for public listings)
+    pub fn get_allowed_buyers(env: Env, listing_id: u64) -> Vec<Address> {
+        get_allowed_buyers(&env, listing_id)
+    }
+
+    fn require_allowed_buyer(
+        env: &Env,
+        listing_id: u64,
+        buyer: &Address,
+    ) -> Result<(), MarketplaceError> {
+        let allowed_buyers = get_allowed_buyers(env, listing_id);
+        if !allowed_buyers.is_empty() && !allowed_buyers.contains(buyer) {
+            return Err(MarketplaceError::BuyerNotAllowed);
+        }
+        Ok(())
+    }
+
+    /// Load an active listing for a purchase or sale proposal by `buyer`
+    fn load_purchasable_listing(
+        env: &Env,
+        listing_id: u64,
+        buyer: &Address,
+    ) -> Result<Listing, MarketplaceError> {
         if listing_id == 0 {
-            panic!("Invalid listing ID");
+            return Err(MarketplaceError::InvalidListingId);
         }
 
-        let listing_key = (Symbol::new(&env, "listing"), listing_id);
-        let mut listing: Listing = env
-            .storage()
-            .instance()
-            .get(&listing_key)
-            .expect("Listing not found");
+        let listing = get_listing(env, listing_id).ok_or(MarketplaceError::ListingNotFound)?;
 
         if !listing.active {
-            panic!("Listing is not active");
+            return Err(MarketplaceError::ListingInactive);
         }
+        Self::require_allowed_buyer(env, listing_id, buyer)?;
+
+        Ok(listing)
+    }
+
+    /// Purchase an agent
+    ///
+    /// High-value sales fail with `ApprovalRequired`; use `propose_sale` for those.
+    pub fn buy_agent(env: Env, listing_id: u64, buyer: Address) -> Result<(), MarketplaceError> {
+        if is_paused(&env) {
+            return Err(MarketplaceError::Paused);
+        }
+        buyer.require_auth();
+
+        let mut listing = Self::load_purchasable_listing(&env, listing_id, &buyer)?;
 
         // Check if multi-signature approval is required
         let config = get_approval_config(&env);
-        if listing.price >= config.threshold {
-            panic!("High-value sale requires multi-signature approval. Use propose_sale() first.");
+        if Self::requires_approval(&env, listing.price, &config) {
+            return Err(MarketplaceError::ApprovalRequired);
         }
 
         // Process fee transition if active
@@ -169,124 +476,546 @@ impl Marketplace {
         // Transfer payment
         let token_client = token::Client::new(&env, &get_payment_token(&env));
 
+        // Escrow-hold listings keep the full price until the dispute window passes
+        if let Some(window) = get_dispute_window(&env, listing_id) {
+            token_client.transfer(&buyer, &env.current_contract_address(), &listing.price);
+            let release_after = env.ledger().timestamp() + window;
+            set_sale_hold(
+                &env,
+                listing_id,
+                &SaleHold {
+                    buyer: buyer.clone(),
+                    seller: listing.seller.clone(),
+                    agent_id: listing.agent_id,
+                    amount: listing.price,
+                    marketplace_fee,
+                    release_after,
+                    disputed: false,
+                },
+            );
+
+            listing.active = false;
+            set_listing(&env, &listing);
+            Self::release_seller_listing_slot(&env, &listing.seller);
+
+            env.events().publish(
+                (Symbol::new(&env, "sale_held"),),
+                (listing_id, listing.agent_id, buyer, release_after),
+            );
+            return Ok(());
+        }
+
         // Transfer marketplace fee to contract
         if marketplace_fee > 0 {
             token_client.transfer(&buyer, &env.current_contract_address(), &marketplace_fee);
         }
 
+        // Deduct platform fee from the seller's proceeds
+        let platform_fee = Self::collect_platform_fee(
+            &env,
+            &token_client,
+            &buyer,
+            listing.agent_id,
+            seller_amount,
+        );
+        let seller_amount = seller_amount - platform_fee;
+
         // Transfer remaining amount to seller
         token_client.transfer(&buyer, &listing.seller, &seller_amount);
 
         // Mark listing as inactive
         listing.active = false;
-        env.storage().instance().set(&listing_key, &listing);
+        set_listing(&env, &listing);
+        Self::release_seller_listing_slot(&env, &listing.seller);
 
         env.events().publish(
             (Symbol::new(&env, "agent_sold"),),
             (listing_id, listing.agent_id, buyer, marketplace_fee_bps),
         );
+
+        Ok(())
     }
 
-    /// Cancel a listing
-    pub fn cancel_listing(env: Env, listing_id: u64, seller: Address) {
+    /// List shares of an agent's future revenue for sale at a fixed price per share
+    pub fn create_fractional_listing(
+        env: Env,
+        seller: Address,
+        agent_id: u64,
+        total_shares: u64,
+        price_per_share: i128,
+    ) -> Result<u64, MarketplaceError> {
+        if is_paused(&env) {
+            return Err(MarketplaceError::Paused);
+        }
         seller.require_auth();
 
-        if listing_id == 0 {
-            panic!("Invalid listing ID");
+        if agent_id == 0 {
+            return Err(MarketplaceError::InvalidAgentId);
         }
-
-        let listing_key = (Symbol::new(&env, "listing"), listing_id);
-        let mut listing: Listing = env
-            .storage()
-            .instance()
-            .get(&listing_key)
-            .expect("Listing not found");
-
-        if listing.seller != seller {
-            panic!("Unauthorized: only seller can cancel listing");
+        if total_shares == 0 {
+            return Err(MarketplaceError::InvalidShareAmount);
+        }
+        if price_per_share <= 0 {
+            return Err(MarketplaceError::PriceNotPositive);
         }
+        Self::require_agent_not_escrowed(&env, agent_id)?;
 
-        listing.active = false;
-        env.storage().instance().set(&listing_key, &listing);
+        let listing_id = increment_fractional_listing_counter(&env);
+        set_fractional_listing(
+            &env,
+            &FractionalListing {
+                listing_id,
+                agent_id,
+                seller: seller.clone(),
+                total_shares,
+                available_shares: total_shares,
+                price_per_share,
+                proceeds: 0,
+                created_at: env.ledger().timestamp(),
+            },
+        );
 
         env.events().publish(
-            (Symbol::new(&env, "listing_cancelled"),),
-            (listing_id, listing.agent_id, seller),
+            (Symbol::new(&env, "fractional_listing_created"),),
+            (listing_id, agent_id, seller, total_shares, price_per_share),
         );
+
+        Ok(listing_id)
     }
 
-    /// Get a specific listing
-    pub fn get_listing(env: Env, listing_id: u64) -> Option<Listing> {
-        if listing_id == 0 {
-            panic!("Invalid listing ID");
+    /// Buy shares from a fractional listing; payment is escrowed for the seller
+    pub fn buy_shares(
+        env: Env,
+        listing_id: u64,
+        buyer: Address,
+        shares: u64,
+    ) -> Result<(), MarketplaceError> {
+        if is_paused(&env) {
+            return Err(MarketplaceError::Paused);
+        }
+        buyer.require_auth();
+
+        let mut listing =
+            get_fractional_listing(&env, listing_id).ok_or(MarketplaceError::ListingNotFound)?;
+        if listing.available_shares == 0 {
+            return Err(MarketplaceError::ListingInactive);
+        }
+        if shares == 0 {
+            return Err(MarketplaceError::InvalidShareAmount);
         }
+        if shares > listing.available_shares {
+            return Err(MarketplaceError::InsufficientShares);
+        }
+
+        let cost = listing.price_per_share * shares as i128;
+        let token_client = token::Client::new(&env, &get_payment_token(&env));
+        token_client.transfer(&buyer, &env.current_contract_address(), &cost);
+
+        listing.available_shares -= shares;
+        listing.proceeds += cost;
+        set_fractional_listing(&env, &listing);
+
+        let held = get_shares(&env, listing_id, &buyer);
+        set_shares(&env, listing_id, &buyer, held + shares);
+
+        env.events().publish(
+            (Symbol::new(&env, "shares_purchased"),),
+            (listing_id, buyer, shares, listing.available_shares),
+        );
 
-        let listing_key = (Symbol::new(&env, "listing"), listing_id);
-        env.storage().instance().get(&listing_key)
+        Ok(())
     }
 
-    /// Set royalty info for an agent
-    pub fn set_royalty(env: Env, agent_id: u64, creator: Address, recipient: Address, fee: u32) {
-        creator.require_auth();
+    /// Pay out escrowed share sales to the seller
+    pub fn claim_share_proceeds(
+        env: Env,
+        listing_id: u64,
+        seller: Address,
+    ) -> Result<i128, MarketplaceError> {
+        seller.require_auth();
 
-        if agent_id == 0 {
-            panic!("Invalid agent ID");
+        let mut listing =
+            get_fractional_listing(&env, listing_id).ok_or(MarketplaceError::ListingNotFound)?;
+        if listing.seller != seller {
+            return Err(MarketplaceError::Unauthorized);
         }
-        if fee > 10000 {
-            // 100% in basis points
-            panic!("Royalty fee exceeds maximum (100%)");
+        if listing.proceeds == 0 {
+            return Err(MarketplaceError::NoProceeds);
         }
 
-        let royalty_info = RoyaltyInfo { recipient, fee };
+        let amount = listing.proceeds;
+        listing.proceeds = 0;
+        set_fractional_listing(&env, &listing);
 
-        let royalty_key = (Symbol::new(&env, "royalty"), agent_id);
-        env.storage().instance().set(&royalty_key, &royalty_info);
+        let token_client = token::Client::new(&env, &get_payment_token(&env));
+        token_client.transfer(&env.current_contract_address(), &seller, &amount);
 
-        env.events()
-            .publish((Symbol::new(&env, "royalty_set"),), (agent_id, fee));
+        env.events().publish(
+            (Symbol::new(&env, "share_proceeds_claimed"),),
+            (listing_id, seller, amount),
+        );
+
+        Ok(amount)
     }
 
-    /// Get royalty info for an agent
-    pub fn get_royalty(env: Env, agent_id: u64) -> Option<RoyaltyInfo> {
-        if agent_id == 0 {
-            panic!("Invalid agent ID");
+    /// Get a fractional listing by ID
+    pub fn get_fractional_listing(env: Env, listing_id: u64) -> Option<FractionalListing> {
+        get_fractional_listing(&env, listing_id)
+    }
+
+    /// Get the shares a buyer holds in a fractional listing
+    pub fn get_shares(env: Env, listing_id: u64, buyer: Address) -> u64 {
+        get_shares(&env, listing_id, &buyer)
+    }
+
+    /// Pay the seller once an undisputed hold's dispute window has passed (anyone may call)
+    pub fn release_funds(env: Env, listing_id: u64) -> Result<(), MarketplaceError> {
+        let hold = get_sale_hold(&env, listing_id).ok_or(MarketplaceError::NoHeldFunds)?;
+        if hold.disputed {
+            return Err(MarketplaceError::SaleDisputed);
+        }
+        if env.ledger().timestamp() < hold.release_after {
+            return Err(MarketplaceError::DisputeWindowOpen);
         }
 
-        let royalty_key = (Symbol::new(&env, "royalty"), agent_id);
-        env.storage().instance().get(&royalty_key)
+        Self::pay_out_hold(&env, listing_id, &hold);
+        Ok(())
     }
 
-    // ---------------- MULTI-SIGNATURE APPROVAL ----------------
+    /// Freeze the release of held funds pending admin resolution (buyer only)
+    pub fn raise_dispute(
+        env: Env,
+        listing_id: u64,
+        buyer: Address,
+    ) -> Result<(), MarketplaceError> {
+        buyer.require_auth();
 
-    /// Configure approval settings (admin only)
-    pub fn set_approval_config(
+        let mut hold = get_sale_hold(&env, listing_id).ok_or(MarketplaceError::NoHeldFunds)?;
+        if hold.buyer != buyer {
+            return Err(MarketplaceError::Unauthorized);
+        }
+        if hold.disputed {
+            return Err(MarketplaceError::SaleDisputed);
+        }
+        if env.ledger().timestamp() >= hold.release_after {
+            return Err(MarketplaceError::DisputeWindowClosed);
+        }
+
+        hold.disputed = true;
+        set_sale_hold(&env, listing_id, &hold);
+
+        env.events().publish(
+            (Symbol::new(&env, "sale_disputed"),),
+            (listing_id, hold.agent_id, buyer),
+        );
+        Ok(())
+    }
+
+    /// Settle a disputed sale (admin only): refund the buyer in full and
+    /// relist the agent, or pay the seller as a normal release. A refunded
+    /// listing stays closed if the seller has reached the listing cap since.
+    pub fn resolve_dispute(
         env: Env,
         admin: Address,
-        threshold: i128,
-        approvers_required: u32,
-        total_approvers: u32,
-        ttl_seconds: u64,
-    ) {
+        listing_id: u64,
+        refund_buyer: bool,
+    ) -> Result<(), MarketplaceError> {
+        admin.require_auth();
         let current_admin: Address = env
             .storage()
             .instance()
             .get(&DataKey::Admin)
             .expect("Contract not initialized");
-        assert!(admin == current_admin, "Unauthorized");
+        if admin != current_admin {
+            return Err(MarketplaceError::Unauthorized);
+        }
 
-        assert!(threshold > 0, "Threshold must be positive");
-        assert!(
-            approvers_required > 0,
-            "Approvers required must be positive"
-        );
-        assert!(
-            total_approvers >= approvers_required,
-            "Total approvers must be >= required"
-        );
-        assert!(ttl_seconds > 0, "TTL must be positive");
+        let hold = get_sale_hold(&env, listing_id).ok_or(MarketplaceError::NoHeldFunds)?;
+        if !hold.disputed {
+            return Err(MarketplaceError::SaleNotDisputed);
+        }
 
-        let config = ApprovalConfig {
-            threshold,
+        if refund_buyer {
+            let token_client = token::Client::new(&env, &get_payment_token(&env));
+            token_client.transfer(&env.current_contract_address(), &hold.buyer, &hold.amount);
+            remove_sale_hold(&env, listing_id);
+            Self::relist_refunded_sale(&env, listing_id);
+        } else {
+            Self::pay_out_hold(&env, listing_id, &hold);
+        }
+
+        env.events().publish(
+            (Symbol::new(&env, "dispute_resolved"),),
+            (listing_id, hold.agent_id, refund_buyer),
+        );
+        Ok(())
+    }
+
+    /// Get the funds held for an escrow-hold sale, if any
+    pub fn get_sale_hold(env: Env, listing_id: u64) -> Option<SaleHold> {
+        get_sale_hold(&env, listing_id)
+    }
+
+    /// Pay a hold out to the seller, keeping the marketplace and platform fees
+    fn pay_out_hold(env: &Env, listing_id: u64, hold: &SaleHold) {
+        remove_sale_hold(env, listing_id);
+
+        let token_client = token::Client::new(env, &get_payment_token(env));
+        let contract = env.current_contract_address();
+        let seller_amount = hold.amount - hold.marketplace_fee;
+        let platform_fee =
+            Self::collect_platform_fee(env, &token_client, &contract, hold.agent_id, seller_amount);
+        token_client.transfer(&contract, &hold.seller, &(seller_amount - platform_fee));
+
+        env.events().publish(
+            (Symbol::new(env, "funds_released"),),
+            (listing_id, hold.agent_id, hold.seller.clone()),
+        );
+    }
+
+    /// Cancel a listing
+    pub fn cancel_listing(
+        env: Env,
+        listing_id: u64,
+        seller: Address,
+    ) -> Result<(), MarketplaceError> {
+        if is_paused(&env) {
+            return Err(MarketplaceError::Paused);
+        }
+        seller.require_auth();
+
+        if listing_id == 0 {
+            return Err(MarketplaceError::InvalidListingId);
+        }
+
+        let mut listing = get_listing(&env, listing_id).ok_or(MarketplaceError::ListingNotFound)?;
+
+        if listing.seller != seller {
+            return Err(MarketplaceError::Unauthorized);
+        }
+
+        if listing.active {
+            Self::release_seller_listing_slot(&env, &seller);
+        }
+        listing.active = false;
+        set_listing(&env, &listing);
+        // Keep a lease or refunded sale from reopening the listing later
+        set_listing_cancelled(&env, listing_id);
+
+        env.events().publish(
+            (Symbol::new(&env, "listing_cancelled"),),
+            (listing_id, listing.agent_id, seller),
+        );
+
+        Ok(())
+    }
+
+    /// Cap how many active listings a seller may hold at once (admin only).
+    /// A cap of 0 removes the limit.
+    pub fn set_max_listings_per_seller(env: Env, admin: Address, max: u32) {
+        admin.require_auth();
+        let current_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .expect("Contract not initialized");
+        assert!(admin == current_admin, "Unauthorized");
+
+        set_max_active_listings_per_seller(&env, max);
+
+        env.events()
+            .publish((Symbol::new(&env, "max_listings_set"),), max);
+    }
+
+    pub fn get_max_listings_per_seller(env: Env) -> u32 {
+        get_max_active_listings_per_seller(&env)
+    }
+
+    /// Number of active listings the seller currently holds
+    pub fn get_seller_listing_count(env: Env, seller: Address) -> u32 {
+        get_seller_listing_count(&env, &seller)
+    }
+
+    fn release_seller_listing_slot(env: &Env, seller: &Address) {
+        let count = get_seller_listing_count(env, seller);
+        set_seller_listing_count(env, seller, count.saturating_sub(1));
+    }
+
+    /// Reopen a listing whose held sale was refunded, taking back the seller
+    /// slot it released; the listing stays closed when no slot is free
+    fn relist_refunded_sale(env: &Env, listing_id: u64) {
+        if is_listing_cancelled(env, listing_id) {
+            return;
+        }
+        let Some(mut listing) = get_listing(env, listing_id) else {
+            return;
+        };
+
+        let active_listings = get_seller_listing_count(env, &listing.seller);
+        let max_active = get_max_active_listings_per_seller(env);
+        if max_active > 0 && active_listings >= max_active {
+            return;
+        }
+
+        listing.active = true;
+        set_listing(env, &listing);
+        set_seller_listing_count(env, &listing.seller, active_listings + 1);
+    }
+
+    /// Get a specific listing
+    pub fn get_listing(env: Env, listing_id: u64) -> Option<Listing> {
+        if listing_id == 0 {
+            panic!("Invalid listing ID");
+        }
+
+        get_listing(&env, listing_id)
+    }
+
+    /// Move legacy listings from instance to persistent storage (admin only).
+    ///
+    /// Visits IDs `start_id..start_id + limit` (at most
+    /// `MAX_LISTING_MIGRATION_BATCH` per call) and returns how many were moved.
+    /// Reads fall back to instance storage until every ID is migrated.
+    pub fn migrate_listings(env: Env, admin: Address, start_id: u64, limit: u32) -> u32 {
+        admin.require_auth();
+        let current_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .expect("Contract not initialized");
+        assert!(admin == current_admin, "Unauthorized");
+
+        let start_id = start_id.max(1);
+        let limit = limit.min(MAX_LISTING_MIGRATION_BATCH) as u64;
+        let mut migrated = 0u32;
+        for listing_id in start_id..start_id.saturating_add(limit) {
+            if migrate_listing(&env, listing_id) {
+                migrated += 1;
+            }
+        }
+
+        env.events().publish(
+            (Symbol::new(&env, "listings_migrated"),),
+            (start_id, migrated),
+        );
+
+        migrated
+    }
+
+    /// Find active listings whose agent has the given capability.
+    ///
+    /// Scans listing IDs from `start_id` (at most `MAX_CAPABILITY_SCAN` IDs per
+    /// call) and returns up to `limit` matches. Callers page by passing the
+    /// next ID after the last scanned one.
+    pub fn get_listings_by_capability(
+        env: Env,
+        capability: String,
+        start_id: u64,
+        limit: u32,
+    ) -> Vec<Listing> {
+        let mut result = Vec::new(&env);
+        if limit == 0 {
+            return result;
+        }
+
+        let counter: u64 = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(&env, LISTING_COUNTER_KEY))
+            .unwrap_or(0);
+        let agent_nft = get_agent_nft(&env);
+        let start_id = start_id.max(1);
+        let end_id = counter.min(start_id.saturating_add(MAX_CAPABILITY_SCAN - 1));
+
+        for listing_id in start_id..=end_id {
+            let listing = match get_listing(&env, listing_id) {
+                Some(listing) => listing,
+                None => continue,
+            };
+            if !listing.active {
+                continue;
+            }
+
+            let agent = env.try_invoke_contract::<Agent, soroban_sdk::Error>(
+                &agent_nft,
+                &Symbol::new(&env, "get_agent"),
+                Vec::from_array(&env, [listing.agent_id.into_val(&env)]),
+            );
+            if let Ok(Ok(agent)) = agent {
+                if agent.capabilities.contains(&capability) {
+                    result.push_back(listing);
+                    if result.len() >= limit {
+                        break;
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Set royalty info for an agent
+    pub fn set_royalty(env: Env, agent_id: u64, creator: Address, recipient: Address, fee: u32) {
+        Self::require_not_paused(&env);
+        creator.require_auth();
+
+        if agent_id == 0 {
+            panic!("Invalid agent ID");
+        }
+        if fee > Self::max_royalty_bps(&env) {
+            panic!("Royalty fee exceeds maximum");
+        }
+
+        let royalty_info = RoyaltyInfo { recipient, fee };
+
+        let royalty_key = (Symbol::new(&env, "royalty"), agent_id);
+        env.storage().instance().set(&royalty_key, &royalty_info);
+
+        env.events()
+            .publish((Symbol::new(&env, "royalty_set"),), (agent_id, fee));
+    }
+
+    /// Get royalty info for an agent
+    pub fn get_royalty(env: Env, agent_id: u64) -> Option<RoyaltyInfo> {
+        if agent_id == 0 {
+            panic!("Invalid agent ID");
+        }
+
+        let royalty_key = (Symbol::new(&env, "royalty"), agent_id);
+        env.storage().instance().get(&royalty_key)
+    }
+
+    // ---------------- MULTI-SIGNATURE APPROVAL ----------------
+
+    /// Configure approval settings (admin only)
+    pub fn set_approval_config(
+        env: Env,
+        admin: Address,
+        threshold: i128,
+        approvers_required: u32,
+        total_approvers: u32,
+        ttl_seconds: u64,
+    ) {
+        let current_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .expect("Contract not initialized");
+        assert!(admin == current_admin, "Unauthorized");
+
+        assert!(threshold > 0, "Threshold must be positive");
+        assert!(
+            approvers_required > 0,
+            "Approvers required must be positive"
+        );
+        assert!(
+            total_approvers >= approvers_required,
+            "Total approvers must be >= required"
+        );
+        assert!(ttl_seconds > 0, "TTL must be positive");
+
+        let config = ApprovalConfig {
+            threshold,
             approvers_required,
             total_approvers,
             ttl_seconds,
@@ -305,44 +1034,62 @@ impl Marketplace {
         get_approval_config(&env)
     }
 
-    /// Propose a sale for multi-signature approval (fixed-price listing)
-    pub fn propose_sale(env: Env, listing_id: u64, buyer: Address, approvers: Vec<Address>) -> u64 {
-        buyer.require_auth();
-
-        if listing_id == 0 {
-            panic!("Invalid listing ID");
-        }
-
-        let listing_key = (Symbol::new(&env, "listing"), listing_id);
-        let listing: Listing = env
+    /// Denominate the approval threshold in a reference currency (admin only).
+    /// Prices are converted with the `oracle_key` feed of the price oracle;
+    /// `None` reverts to comparing raw token amounts.
+    pub fn set_approval_threshold_oracle(env: Env, admin: Address, oracle_key: Option<Symbol>) {
+        admin.require_auth();
+        let current_admin: Address = env
             .storage()
             .instance()
-            .get(&listing_key)
-            .expect("Listing not found");
+            .get(&DataKey::Admin)
+            .expect("Contract not initialized");
+        assert!(admin == current_admin, "Unauthorized");
 
-        if !listing.active {
-            panic!("Listing is not active");
+        set_approval_threshold_oracle_key(&env, &oracle_key);
+
+        env.events().publish(
+            (Symbol::new(&env, "ApprovalThresholdOracleSet"),),
+            (oracle_key,),
+        );
+    }
+
+    /// Propose a sale for multi-signature approval (fixed-price listing)
+    pub fn propose_sale(
+        env: Env,
+        listing_id: u64,
+        buyer: Address,
+        approvers: Vec<Address>,
+    ) -> Result<u64, MarketplaceError> {
+        if is_paused(&env) {
+            return Err(MarketplaceError::Paused);
         }
+        buyer.require_auth();
+
+        let listing = Self::load_purchasable_listing(&env, listing_id, &buyer)?;
 
         let config = get_approval_config(&env);
 
         // Check if approval is required
-        if listing.price < config.threshold {
-            panic!("Price below approval threshold");
+        if !Self::requires_approval(&env, listing.price, &config) {
+            return Err(MarketplaceError::ApprovalNotRequired);
         }
 
-        assert!(
-            approvers.len() as u32 >= config.approvers_required,
-            "Insufficient approvers"
-        );
-        assert!(
-            approvers.len() as u32 <= config.total_approvers,
-            "Too many approvers"
-        );
+        if approvers.len() < config.approvers_required {
+            return Err(MarketplaceError::InsufficientApprovers);
+        }
+        if approvers.len() > config.total_approvers {
+            return Err(MarketplaceError::TooManyApprovers);
+        }
 
         let approval_id = increment_approval_counter(&env);
         let now = env.ledger().timestamp();
 
+        // Escrow the buyer's funds until the sale is executed, rejected or expires
+        let token_client = token::Client::new(&env, &get_payment_token(&env));
+        token_client.transfer(&buyer, &env.current_contract_address(), &listing.price);
+        set_approval_escrow(&env, approval_id, listing.price);
+
         let approval = Approval {
             approval_id,
             listing_id: Some(listing_id),
@@ -376,11 +1123,12 @@ impl Marketplace {
             (approval_id, listing_id, buyer, listing.price),
         );
 
-        approval_id
+        Ok(approval_id)
     }
 
     /// Propose an auction win for multi-signature approval
     pub fn propose_auction_sale(env: Env, auction_id: u64, approvers: Vec<Address>) -> u64 {
+        Self::require_not_paused(&env);
         let auction = get_auction(&env, auction_id).expect("Auction not found");
         assert!(
             auction.status == AuctionStatus::Active,
@@ -391,16 +1139,16 @@ impl Marketplace {
         let config = get_approval_config(&env);
 
         // Check if approval is required
-        if auction.highest_bid < config.threshold {
+        if !Self::requires_approval(&env, auction.highest_bid, &config) {
             panic!("Price below approval threshold");
         }
 
         assert!(
-            approvers.len() as u32 >= config.approvers_required,
+            approvers.len() >= config.approvers_required,
             "Insufficient approvers"
         );
         assert!(
-            approvers.len() as u32 <= config.total_approvers,
+            approvers.len() <= config.total_approvers,
             "Too many approvers"
         );
 
@@ -446,6 +1194,7 @@ impl Marketplace {
 
     /// Approve a proposed sale
     pub fn approve_sale(env: Env, approval_id: u64, approver: Address) {
+        Self::require_not_paused(&env);
         approver.require_auth();
 
         if approval_id == 0 {
@@ -492,7 +1241,7 @@ impl Marketplace {
         add_approval_history(&env, approval_id, &history);
 
         // Check if we have enough approvals
-        if approval.approvals_received.len() as u32 >= approval.required_approvals {
+        if approval.approvals_received.len() >= approval.required_approvals {
             approval.status = ApprovalStatus::Approved;
 
             // Add final approval to history
@@ -521,6 +1270,7 @@ impl Marketplace {
 
     /// Reject a proposed sale
     pub fn reject_sale(env: Env, approval_id: u64, approver: Address, reason: String) {
+        Self::require_not_paused(&env);
         approver.require_auth();
 
         if approval_id == 0 {
@@ -574,10 +1324,57 @@ impl Marketplace {
         );
 
         set_approval(&env, &approval);
+
+        Self::refund_approval_escrow(&env, &approval);
+    }
+
+    /// Withdraw a pending or approved (not yet executed) sale proposal,
+    /// returning the buyer's escrowed funds
+    pub fn withdraw_approval(env: Env, approval_id: u64, buyer: Address) {
+        buyer.require_auth();
+
+        let mut approval = get_approval(&env, approval_id).expect("Approval not found");
+        assert!(approval.buyer == buyer, "Unauthorized");
+        assert!(
+            approval.status == ApprovalStatus::Pending
+                || approval.status == ApprovalStatus::Approved,
+            "Approval cannot be withdrawn"
+        );
+
+        approval.status = ApprovalStatus::Withdrawn;
+        set_approval(&env, &approval);
+
+        let history = ApprovalHistory {
+            approval_id,
+            action: String::from_str(&env, "withdrawn"),
+            actor: buyer.clone(),
+            timestamp: env.ledger().timestamp(),
+            reason: None,
+        };
+        add_approval_history(&env, approval_id, &history);
+
+        env.events()
+            .publish((Symbol::new(&env, "SaleWithdrawn"),), (approval_id, buyer));
+
+        Self::refund_approval_escrow(&env, &approval);
+    }
+
+    /// Return any funds escrowed for an approval to its buyer
+    fn refund_approval_escrow(env: &Env, approval: &Approval) {
+        if let Some(amount) = take_approval_escrow(env, approval.approval_id) {
+            let token_client = token::Client::new(env, &get_payment_token(env));
+            token_client.transfer(&env.current_contract_address(), &approval.buyer, &amount);
+
+            env.events().publish(
+                (Symbol::new(env, "EscrowRefunded"),),
+                (approval.approval_id, approval.buyer.clone(), amount),
+            );
+        }
     }
 
     /// Execute an approved sale
     pub fn execute_approved_sale(env: Env, approval_id: u64) {
+        Self::require_not_paused(&env);
         if approval_id == 0 {
             panic!("Invalid approval ID");
         }
@@ -607,41 +1404,69 @@ impl Marketplace {
 
     /// Execute approved fixed-price sale (internal function)
     fn execute_approved_listing_sale(env: Env, approval_id: u64, listing_id: u64) {
-        let listing_key = (Symbol::new(&env, "listing"), listing_id);
-        let mut listing: Listing = env
-            .storage()
-            .instance()
-            .get(&listing_key)
-            .expect("Listing not found");
+        let mut listing = get_listing(&env, listing_id).expect("Listing not found");
 
         let approval = get_approval(&env, approval_id).expect("Approval not found");
 
+        // The listing was sold or cancelled since the proposal; return the
+        // buyer's escrow instead of paying the seller a second time
+        if !listing.active {
+            let mut rejected = approval.clone();
+            rejected.status = ApprovalStatus::Rejected;
+            set_approval(&env, &rejected);
+
+            let history = ApprovalHistory {
+                approval_id,
+                action: String::from_str(&env, "rejected"),
+                actor: env.current_contract_address(),
+                timestamp: env.ledger().timestamp(),
+                reason: Some(String::from_str(&env, "Listing no longer active")),
+            };
+            add_approval_history(&env, approval_id, &history);
+
+            env.events().publish(
+                (Symbol::new(&env, "SaleRejected"),),
+                (approval_id, env.current_contract_address()),
+            );
+
+            Self::refund_approval_escrow(&env, &rejected);
+            return;
+        }
+
         // Process fee transition if active
         Self::process_fee_transition(env.clone());
 
+        // Pay out of the buyer's escrow; the marketplace fee stays in the contract
+        let escrowed = take_approval_escrow(&env, approval_id).expect("Escrow not found");
+
         // Calculate dynamic marketplace fee
         let marketplace_fee_bps = Self::get_current_marketplace_fee(env.clone());
-        let marketplace_fee = (listing.price * marketplace_fee_bps as i128) / 10000;
-        let seller_amount = listing.price - marketplace_fee;
+        let marketplace_fee = (escrowed * marketplace_fee_bps as i128) / 10000;
+        let seller_amount = escrowed - marketplace_fee;
 
-        // Transfer payment
         let token_client = token::Client::new(&env, &get_payment_token(&env));
 
-        // Transfer marketplace fee to contract
-        if marketplace_fee > 0 {
-            token_client.transfer(
-                &approval.buyer,
-                &env.current_contract_address(),
-                &marketplace_fee,
-            );
-        }
+        // Deduct platform fee from the seller's proceeds
+        let platform_fee = Self::collect_platform_fee(
+            &env,
+            &token_client,
+            &env.current_contract_address(),
+            listing.agent_id,
+            seller_amount,
+        );
+        let seller_amount = seller_amount - platform_fee;
 
         // Transfer remaining amount to seller
-        token_client.transfer(&approval.buyer, &listing.seller, &seller_amount);
+        token_client.transfer(
+            &env.current_contract_address(),
+            &listing.seller,
+            &seller_amount,
+        );
 
         // Mark listing as inactive
         listing.active = false;
-        env.storage().instance().set(&listing_key, &listing);
+        set_listing(&env, &listing);
+        Self::release_seller_listing_slot(&env, &listing.seller);
 
         // Update approval status
         let mut updated_approval = approval.clone();
@@ -669,47 +1494,10 @@ impl Marketplace {
         let mut auction = get_auction(&env, auction_id).expect("Auction not found");
         let approval = get_approval(&env, approval_id).expect("Approval not found");
 
-        // Process fee transition if active
-        Self::process_fee_transition(env.clone());
-
         // Process the auction resolution
         if let Some(winner) = auction.highest_bidder.clone() {
             if auction.highest_bid >= auction.reserve_price {
-                // Calculate dynamic marketplace fee
-                let marketplace_fee_bps = Self::get_current_marketplace_fee(env.clone());
-                let marketplace_fee = (auction.highest_bid * marketplace_fee_bps as i128) / 10000;
-
-                let royalty_info = Marketplace::get_royalty(env.clone(), auction.agent_id)
-                    .expect("Royalty info not found");
-
-                let royalty =
-                    (((auction.highest_bid as u128) * (royalty_info.fee as u128)) / 10000) as i128;
-                let seller_amount = auction.highest_bid - royalty - marketplace_fee;
-
-                let token_client = token::Client::new(&env, &get_payment_token(&env));
-
-                // Transfer marketplace fee to contract
-                if marketplace_fee > 0 {
-                    token_client.transfer(
-                        &env.current_contract_address(),
-                        &env.current_contract_address(),
-                        &marketplace_fee,
-                    );
-                }
-
-                // Transfer royalty
-                token_client.transfer(
-                    &env.current_contract_address(),
-                    &royalty_info.recipient,
-                    &royalty,
-                );
-
-                // Transfer seller payout
-                token_client.transfer(
-                    &env.current_contract_address(),
-                    &auction.seller,
-                    &seller_amount,
-                );
+                let marketplace_fee_bps = Self::pay_out_winning_bid(&env, &auction);
 
                 // NOTE: NFT transfer logic should be added here
 
@@ -733,6 +1521,7 @@ impl Marketplace {
             auction.status = AuctionStatus::Ended;
         }
 
+        Self::pay_out_bid_fees(&env, &mut auction);
         set_auction(&env, &auction);
 
         // Update approval status
@@ -782,6 +1571,51 @@ impl Marketplace {
         history
     }
 
+    /// Reset the approval counter to the highest approval ID actually stored
+    /// (admin only), so new proposals cannot collide with existing records.
+    ///
+    /// IDs are scanned upward from `start_id` until `APPROVAL_REPAIR_GAP`
+    /// consecutive IDs are missing, inspecting at most
+    /// `MAX_APPROVAL_REPAIR_SCAN` IDs per call. A repair starts at 1; a call
+    /// resuming at a later ID keeps the highest ID found by earlier calls.
+    /// Returns the repaired counter and the ID to resume from, or `None` once
+    /// the scan is complete.
+    pub fn repair_approval_counter(env: Env, admin: Address, start_id: u64) -> (u64, Option<u64>) {
+        admin.require_auth();
+        let current_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .expect("Contract not initialized");
+        assert!(admin == current_admin, "Unauthorized");
+
+        let previous = get_approval_counter(&env);
+        let start_id = start_id.max(1);
+        let mut max_id = if start_id > 1 { previous } else { 0 };
+        let scan_end = start_id.saturating_add(MAX_APPROVAL_REPAIR_SCAN);
+        let mut approval_id = start_id;
+        while approval_id <= max_id + APPROVAL_REPAIR_GAP && approval_id < scan_end {
+            if get_approval(&env, approval_id).is_some() {
+                max_id = approval_id;
+            }
+            approval_id += 1;
+        }
+        let resume_from = if approval_id <= max_id + APPROVAL_REPAIR_GAP {
+            Some(approval_id)
+        } else {
+            None
+        };
+
+        set_approval_counter(&env, max_id);
+
+        env.events().publish(
+            (Symbol::new(&env, "ApprovalCounterRepaired"),),
+            (previous, max_id),
+        );
+
+        (max_id, resume_from)
+    }
+
     /// Clean up expired approvals (can be called by anyone)
     pub fn cleanup_expired_approvals(env: Env) {
         let counter = get_approval_counter(&env);
@@ -796,6 +1630,7 @@ impl Marketplace {
                     let mut expired_approval = approval;
                     expired_approval.status = ApprovalStatus::Expired;
                     set_approval(&env, &expired_approval);
+                    Self::refund_approval_escrow(&env, &expired_approval);
 
                     // Add to history
                     let history = ApprovalHistory {
@@ -823,6 +1658,9 @@ impl Marketplace {
     // ---------------- AUCTIONS ----------------
 
     /// Dutch params: (start_price, end_price, duration_seconds, price_decay). Use (None,None,None,None) for non-Dutch.
+    /// `oracle_key` prices a Dutch auction from the configured Oracle feed instead of the curve.
+    /// `bid_fee` is a non-refundable fee charged on each English auction bid and paid
+    /// to the seller on resolution; `None` charges nothing.
     pub fn create_auction(
         env: Env,
         agent_id: u64,
@@ -832,10 +1670,21 @@ impl Marketplace {
         reserve_price: i128,
         duration: u64,
         min_bid_increment_bps: u32,
+        min_increment_absolute: Option<i128>,
+        oracle_key: Option<Symbol>,
+        bid_fee: Option<i128>,
     ) -> u64 {
+        Self::require_not_paused(&env);
         seller.require_auth();
         assert!(start_price > 0, "Invalid start price");
         assert!(duration > 0, "Invalid duration");
+        let min_increment_absolute = min_increment_absolute.unwrap_or(1);
+        assert!(min_increment_absolute > 0, "Invalid minimum increment");
+        let bid_fee = bid_fee.unwrap_or(0);
+        assert!(bid_fee >= 0, "Invalid bid fee");
+        if let Err(error) = Self::require_agent_not_escrowed(&env, agent_id) {
+            panic_with_error!(&env, error);
+        }
 
         let auction_id = increment_auction_counter(&env);
         let start_time = env.ledger().timestamp();
@@ -853,7 +1702,12 @@ impl Marketplace {
             start_time,
             end_time,
             min_bid_increment_bps,
+            min_increment_absolute,
+            oracle_key,
             status: AuctionStatus::Active,
+            extension_count: 0,
+            bid_fee,
+            accumulated_bid_fees: 0,
             // dutch_config, // Temporarily commented out
         };
 
@@ -867,6 +1721,11 @@ impl Marketplace {
         auction_id
     }
 
+    /// Get an auction by ID
+    pub fn get_auction(env: Env, auction_id: u64) -> Option<Auction> {
+        get_auction(&env, auction_id)
+    }
+
     pub fn calculate_dutch_price(env: Env, auction_id: u64) -> i128 {
         let auction = get_auction(&env, auction_id).expect("Auction not found");
         assert!(
@@ -874,6 +1733,11 @@ impl Marketplace {
             "Not a Dutch auction"
         );
 
+        // Oracle-priced auctions follow the feed, never dropping below reserve
+        if let Some(oracle_key) = &auction.oracle_key {
+            return Self::read_oracle_price(&env, oracle_key).max(auction.reserve_price);
+        }
+
         // Simplified calculation without dutch_config
         let now = env.ledger().timestamp();
         if now <= auction.start_time {
@@ -891,6 +1755,7 @@ impl Marketplace {
     }
 
     pub fn place_bid(env: Env, auction_id: u64, bidder: Address, amount: i128) {
+        Self::require_not_paused(&env);
         bidder.require_auth();
         let mut auction = get_auction(&env, auction_id).expect("Auction not found");
         assert!(
@@ -907,12 +1772,7 @@ impl Marketplace {
         );
 
         let min_increment = (auction.highest_bid * (auction.min_bid_increment_bps as i128)) / 10000;
-        let min_bid = auction.highest_bid
-            + (if min_increment > 1000 {
-                min_increment
-            } else {
-                1000
-            });
+        let min_bid = auction.highest_bid + min_increment.max(auction.min_increment_absolute);
         assert!(amount >= min_bid, "Bid too low");
 
         let token_client = token::Client::new(&env, &get_payment_token(&env));
@@ -929,17 +1789,34 @@ impl Marketplace {
         // Lock new bid in contract
         token_client.transfer(&bidder, &env.current_contract_address(), &amount);
 
+        // Collect the non-refundable bid fee, held for the seller until resolution
+        if auction.bid_fee > 0 {
+            token_client.transfer(&bidder, &env.current_contract_address(), &auction.bid_fee);
+            auction.accumulated_bid_fees += auction.bid_fee;
+        }
+
         auction.highest_bidder = Some(bidder.clone());
         auction.highest_bid = amount;
 
-        // Extend auction by 5 minutes if bid in final 5 minutes
+        // Extend auction by 5 minutes if bid in final 5 minutes, up to the
+        // extension cap; once reached the bid still stands but the end holds
         let time_left = auction.end_time - env.ledger().timestamp();
-        if time_left < 300 {
-            auction.end_time += 300;
+        let extended = time_left < AUCTION_EXTENSION_SECONDS
+            && auction.extension_count < MAX_AUCTION_EXTENSIONS;
+        if extended {
+            auction.end_time += AUCTION_EXTENSION_SECONDS;
+            auction.extension_count += 1;
         }
 
         set_auction(&env, &auction);
 
+        if extended {
+            env.events().publish(
+                (Symbol::new(&env, "AuctionExtended"),),
+                (auction_id, auction.end_time, auction.extension_count),
+            );
+        }
+
         env.events().publish(
             (Symbol::new(&env, "BidPlaced"),),
             (auction_id, bidder.clone(), amount, auction.end_time),
@@ -963,6 +1840,7 @@ impl Marketplace {
     }
 
     pub fn accept_dutch_price(env: Env, auction_id: u64, buyer: Address) {
+        Self::require_not_paused(&env);
         buyer.require_auth();
         let mut auction = get_auction(&env, auction_id).expect("Auction not found");
         assert!(
@@ -973,6 +1851,10 @@ impl Marketplace {
             auction.auction_type == AuctionType::Dutch,
             "Not a Dutch auction"
         );
+        assert!(
+            env.ledger().timestamp() < auction.end_time,
+            "Auction expired"
+        );
 
         let current_price = Marketplace::calculate_dutch_price(env.clone(), auction_id);
 
@@ -987,7 +1869,74 @@ impl Marketplace {
         Marketplace::resolve_auction(env, auction_id);
     }
 
+    /// Refund the highest bidder of an auction that is past its end time but
+    /// could not be resolved (admin only). The auction is marked Ended.
+    pub fn emergency_refund_bid(env: Env, auction_id: u64, admin: Address) {
+        admin.require_auth();
+        let current_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .expect("Contract not initialized");
+        assert!(admin == current_admin, "Unauthorized");
+
+        let mut auction = get_auction(&env, auction_id).expect("Auction not found");
+        assert!(
+            auction.status == AuctionStatus::Active,
+            "Auction not active"
+        );
+        assert!(
+            env.ledger().timestamp() >= auction.end_time,
+            "Auction not yet ended"
+        );
+        let bidder = auction.highest_bidder.clone().expect("No bid to refund");
+
+        let token_client = token::Client::new(&env, &get_payment_token(&env));
+        token_client.transfer(
+            &env.current_contract_address(),
+            &bidder,
+            &auction.highest_bid,
+        );
+
+        auction.status = AuctionStatus::Ended;
+        Self::pay_out_bid_fees(&env, &mut auction);
+        set_auction(&env, &auction);
+
+        env.events().publish(
+            (Symbol::new(&env, "EmergencyBidRefunded"),),
+            (auction_id, bidder, auction.highest_bid),
+        );
+    }
+
+    /// End a Dutch auction that reached its end time without a buyer.
+    /// Callable by anyone.
+    pub fn expire_dutch_auction(env: Env, auction_id: u64) {
+        let mut auction = get_auction(&env, auction_id).expect("Auction not found");
+        assert!(
+            auction.status == AuctionStatus::Active,
+            "Auction not active"
+        );
+        assert!(
+            auction.auction_type == AuctionType::Dutch,
+            "Not a Dutch auction"
+        );
+        assert!(
+            env.ledger().timestamp() >= auction.end_time,
+            "Auction not yet ended"
+        );
+        assert!(auction.highest_bidder.is_none(), "Auction has a buyer");
+
+        auction.status = AuctionStatus::Ended;
+        set_auction(&env, &auction);
+
+        env.events().publish(
+            (Symbol::new(&env, "AuctionEnded"),),
+            (auction_id, auction.status),
+        );
+    }
+
     pub fn resolve_auction(env: Env, auction_id: u64) {
+        Self::require_not_paused(&env);
         let mut auction = get_auction(&env, auction_id).expect("Auction not found");
         assert!(
             auction.status == AuctionStatus::Active,
@@ -1007,50 +1956,13 @@ impl Marketplace {
             if auction.highest_bid >= auction.reserve_price {
                 // Check if multi-signature approval is required
                 let config = get_approval_config(&env);
-                if auction.highest_bid >= config.threshold {
+                if Self::requires_approval(&env, auction.highest_bid, &config) {
                     panic!(
                         "High-value auction requires multi-signature approval. Use propose_auction_sale() first."
                     );
                 }
 
-                // Process fee transition if active
-                Self::process_fee_transition(env.clone());
-
-                // Calculate dynamic marketplace fee
-                let marketplace_fee_bps = Self::get_current_marketplace_fee(env.clone());
-                let marketplace_fee = (auction.highest_bid * marketplace_fee_bps as i128) / 10000;
-
-                let royalty_info = Marketplace::get_royalty(env.clone(), auction.agent_id)
-                    .expect("Royalty info not found");
-
-                let royalty =
-                    (((auction.highest_bid as u128) * (royalty_info.fee as u128)) / 10000) as i128;
-                let seller_amount = auction.highest_bid - royalty - marketplace_fee;
-
-                let token_client = token::Client::new(&env, &get_payment_token(&env));
-
-                // Transfer marketplace fee to contract
-                if marketplace_fee > 0 {
-                    token_client.transfer(
-                        &env.current_contract_address(),
-                        &env.current_contract_address(),
-                        &marketplace_fee,
-                    );
-                }
-
-                // Transfer royalty
-                token_client.transfer(
-                    &env.current_contract_address(),
-                    &royalty_info.recipient,
-                    &royalty,
-                );
-
-                // Transfer seller payout
-                token_client.transfer(
-                    &env.current_contract_address(),
-                    &auction.seller,
-                    &seller_amount,
-                );
+                let marketplace_fee_bps = Self::pay_out_winning_bid(&env, &auction);
 
                 // NOTE: NFT transfer logic should be added here
 
@@ -1076,15 +1988,218 @@ impl Marketplace {
             auction.status = AuctionStatus::Ended;
         }
 
+        Self::pay_out_bid_fees(&env, &mut auction);
+        set_auction(&env, &auction);
+
+        env.events().publish(
+            (Symbol::new(&env, "AuctionEnded"),),
+            (auction_id, auction.status),
+        );
+    }
+
+    /// Resolve an auction and hand the agent to the winner as one unit.
+    ///
+    /// The AgentNFT transfer runs before any payout; if it fails the winning
+    /// bid is refunded and the auction ends unsold, so the bidder is never
+    /// charged for an agent they did not receive. Auctions without a winning
+    /// bid resolve exactly as `resolve_auction`. Returns the final status.
+    ///
+    /// The seller authorizes the call so the nested AgentNFT transfer from
+    /// their account is covered by the same signature.
+    pub fn resolve_auction_atomic(env: Env, auction_id: u64) -> AuctionStatus {
+        Self::require_not_paused(&env);
+        let mut auction = get_auction(&env, auction_id).expect("Auction not found");
+        auction.seller.require_auth();
+
+        let has_winning_bid =
+            auction.highest_bidder.is_some() && auction.highest_bid >= auction.reserve_price;
+        if auction.status != AuctionStatus::Active || !has_winning_bid {
+            Self::resolve_auction(env.clone(), auction_id);
+            return get_auction(&env, auction_id).unwrap().status;
+        }
+
+        let is_english = auction.auction_type == AuctionType::English;
+        assert!(
+            !is_english || env.ledger().timestamp() >= auction.end_time,
+            "Auction not yet ended"
+        );
+        let config = get_approval_config(&env);
+        if Self::requires_approval(&env, auction.highest_bid, &config) {
+            panic!(
+                "High-value auction requires multi-signature approval. Use propose_auction_sale() first."
+            );
+        }
+
+        let winner = auction.highest_bidder.clone().unwrap();
+        let transfer = env.try_invoke_contract::<(), soroban_sdk::Error>(
+            &get_agent_nft(&env),
+            &Symbol::new(&env, "transfer_agent"),
+            Vec::from_array(
+                &env,
+                [
+                    auction.agent_id.into_val(&env),
+                    auction.seller.into_val(&env),
+                    winner.into_val(&env),
+                ],
+            ),
+        );
+
+        if let Ok(Ok(())) = transfer {
+            let marketplace_fee_bps = Self::pay_out_winning_bid(&env, &auction);
+            auction.status = AuctionStatus::Won;
+
+            env.events().publish(
+                (Symbol::new(&env, "AuctionWon"),),
+                (auction_id, winner, auction.highest_bid, marketplace_fee_bps),
+            );
+        } else {
+            let token_client = token::Client::new(&env, &get_payment_token(&env));
+            token_client.transfer(
+                &env.current_contract_address(),
+                &winner,
+                &auction.highest_bid,
+            );
+            auction.status = AuctionStatus::Ended;
+
+            env.events().publish(
+                (Symbol::new(&env, "AuctionTransferFailed"),),
+                (auction_id, winner, auction.highest_bid),
+            );
+        }
+
+        Self::pay_out_bid_fees(&env, &mut auction);
         set_auction(&env, &auction);
 
         env.events().publish(
             (Symbol::new(&env, "AuctionEnded"),),
             (auction_id, auction.status),
         );
+
+        auction.status
+    }
+
+    /// Split an escrowed winning bid into marketplace fee, royalty, platform
+    /// fee and seller proceeds. Returns the marketplace fee in basis points.
+    fn pay_out_winning_bid(env: &Env, auction: &Auction) -> u32 {
+        // Process fee transition if active
+        Self::process_fee_transition(env.clone());
+
+        // Calculate dynamic marketplace fee
+        let marketplace_fee_bps = Self::get_current_marketplace_fee(env.clone());
+        let marketplace_fee = (auction.highest_bid * marketplace_fee_bps as i128) / 10000;
+
+        let royalty_info = Marketplace::get_royalty(env.clone(), auction.agent_id)
+            .expect("Royalty info not found");
+
+        let royalty =
+            (((auction.highest_bid as u128) * (royalty_info.fee as u128)) / 10000) as i128;
+        let seller_amount = auction.highest_bid - royalty - marketplace_fee;
+
+        let token_client = token::Client::new(env, &get_payment_token(env));
+
+        // Transfer marketplace fee to contract
+        if marketplace_fee > 0 {
+            token_client.transfer(
+                &env.current_contract_address(),
+                &env.current_contract_address(),
+                &marketplace_fee,
+            );
+        }
+
+        // Transfer royalty
+        token_client.transfer(
+            &env.current_contract_address(),
+            &royalty_info.recipient,
+            &royalty,
+        );
+
+        // Deduct platform fee from the seller's proceeds (after royalty)
+        let platform_fee = Self::collect_platform_fee(
+            env,
+            &token_client,
+            &env.current_contract_address(),
+            auction.agent_id,
+            seller_amount,
+        );
+        let seller_amount = seller_amount - platform_fee;
+
+        // Transfer seller payout
+        token_client.transfer(
+            &env.current_contract_address(),
+            &auction.seller,
+            &seller_amount,
+        );
+
+        marketplace_fee_bps
+    }
+
+    /// Transfer the bid fees collected by an auction to its seller
+    fn pay_out_bid_fees(env: &Env, auction: &mut Auction) {
+        if auction.accumulated_bid_fees == 0 {
+            return;
+        }
+        let token_client = token::Client::new(env, &get_payment_token(env));
+        token_client.transfer(
+            &env.current_contract_address(),
+            &auction.seller,
+            &auction.accumulated_bid_fees,
+        );
+        env.events().publish(
+            (Symbol::new(env, "BidFeesPaid"),),
+            (auction.auction_id, auction.accumulated_bid_fees),
+        );
+        auction.accumulated_bid_fees = 0;
+    }
+
+    /// Start a fresh English auction for the agent of an auction that ended
+    /// without meeting its reserve, keeping the original pricing rules.
+    /// Each failed auction can be relisted once.
+    pub fn relist_auction(
+        env: Env,
+        auction_id: u64,
+        seller: Address,
+        new_duration: u64,
+        new_reserve: i128,
+    ) -> u64 {
+        // Seller auth is required by create_auction below
+        let auction = get_auction(&env, auction_id).expect("Auction not found");
+        assert!(auction.seller == seller, "Unauthorized");
+        assert!(
+            auction.auction_type == AuctionType::English
+                && auction.status == AuctionStatus::Ended
+                && auction.highest_bid < auction.reserve_price,
+            "Auction did not fail its reserve"
+        );
+        assert!(
+            get_auction_relisted_as(&env, auction_id).is_none(),
+            "Auction already relisted"
+        );
+
+        let new_auction_id = Self::create_auction(
+            env.clone(),
+            auction.agent_id,
+            seller,
+            AuctionType::English,
+            auction.start_price,
+            new_reserve,
+            new_duration,
+            auction.min_bid_increment_bps,
+            Some(auction.min_increment_absolute),
+            None,
+            Some(auction.bid_fee),
+        );
+        set_auction_relisted_as(&env, auction_id, new_auction_id);
+
+        env.events().publish(
+            (Symbol::new(&env, "AuctionRelisted"),),
+            (auction_id, new_auction_id, new_reserve),
+        );
+
+        new_auction_id
     }
 
     pub fn cancel_auction(env: Env, auction_id: u64) {
+        Self::require_not_paused(&env);
         let mut auction = get_auction(&env, auction_id).expect("Auction not found");
         auction.seller.require_auth();
         assert!(
@@ -1271,7 +2386,7 @@ impl Marketplace {
             let fee_change_ratio = (new_fee_structure.marketplace_fee_bps as i128 * 1000)
                 / (current.marketplace_fee_bps as i128);
 
-            if fee_change_ratio > 1200 || fee_change_ratio < 800 {
+            if !(800..=1200).contains(&fee_change_ratio) {
                 // Start gradual transition
                 Self::start_fee_transition(
                     &env,
@@ -1288,6 +2403,32 @@ impl Marketplace {
         }
     }
 
+    /// Transfer the platform fee on `proceeds` from `from` to the fee
+    /// recipient and return the amount collected (0 if not configured).
+    fn collect_platform_fee(
+        env: &Env,
+        token_client: &token::Client,
+        from: &Address,
+        agent_id: u64,
+        proceeds: i128,
+    ) -> i128 {
+        let fee_bps = get_platform_fee_bps(env);
+        let recipient = match get_fee_recipient(env) {
+            Some(recipient) if fee_bps > 0 => recipient,
+            _ => return 0,
+        };
+
+        let fee = (proceeds * fee_bps as i128) / 10000;
+        if fee > 0 {
+            token_client.transfer(from, &recipient, &fee);
+            env.events().publish(
+                (Symbol::new(env, "fee_collected"),),
+                (agent_id, recipient, fee),
+            );
+        }
+        fee
+    }
+
     /// Get current effective marketplace fee
     pub fn get_current_marketplace_fee(env: Env) -> u32 {
         // Check if in transition
@@ -1343,6 +2484,36 @@ impl Marketplace {
 
     // ---------------- INTERNAL FEE CALCULATION HELPERS ----------------
 
+    /// Whether a sale at `price` crosses the approval threshold, converting the
+    /// price to the reference currency when a threshold oracle is configured
+    fn requires_approval(env: &Env, price: i128, config: &ApprovalConfig) -> bool {
+        let value = match (
+            get_price_oracle(env),
+            get_approval_threshold_oracle_key(env),
+        ) {
+            (Some(_), Some(key)) => price * Self::read_oracle_price(env, &key) / ORACLE_RATE_SCALE,
+            _ => price,
+        };
+        value >= config.threshold
+    }
+
+    /// Read a price from the configured Oracle, rejecting missing or stale data
+    fn read_oracle_price(env: &Env, key: &Symbol) -> i128 {
+        let oracle = get_price_oracle(env).expect("Price oracle not set");
+        let data: Option<OracleData> = env.invoke_contract(
+            &oracle,
+            &Symbol::new(env, "get_data"),
+            Vec::from_array(env, [key.into_val(env)]),
+        );
+        let data = data.expect("Oracle price unavailable");
+
+        let age = env.ledger().timestamp().saturating_sub(data.timestamp);
+        assert!(age <= ORACLE_PRICE_MAX_AGE_SECONDS, "Stale oracle price");
+        assert!(data.value > 0, "Invalid oracle price");
+
+        data.value
+    }
+
     fn get_oracle_value_by_key(
         _env: &Env,
         _oracle_id: &Address,
@@ -1354,26 +2525,21 @@ impl Marketplace {
         fallback
     }
 
-    fn get_oracle_value(env: &Env, oracle_id: &Address, fallback: i128) -> i128 {
-        // Legacy function - use the key-based version
-        Self::get_oracle_value_by_key(env, oracle_id, "default", fallback)
-    }
-
     fn calculate_congestion_factor(congestion: i128) -> i128 {
         // Network congestion: 0.5x - 2.0x (500 - 2000 basis points)
-        let clamped = congestion.max(0).min(100);
+        let clamped = congestion.clamp(0, 100);
         500 + (clamped * 1500) / 100
     }
 
     fn calculate_utilization_factor(utilization: i128) -> i128 {
         // Platform utilization: 0.7x - 1.5x (700 - 1500 basis points)
-        let clamped = utilization.max(0).min(100);
+        let clamped = utilization.clamp(0, 100);
         700 + (clamped * 800) / 100
     }
 
     fn calculate_volatility_factor(volatility: i128) -> i128 {
         // Market volatility: 0.9x - 1.3x (900 - 1300 basis points)
-        let clamped = volatility.max(0).min(100);
+        let clamped = volatility.clamp(0, 100);
         900 + (clamped * 400) / 100
     }
 
@@ -1440,7 +2606,7 @@ impl Marketplace {
             congestion_value: new_fee_structure.congestion_factor,
             utilization_value: new_fee_structure.utilization_factor,
             volatility_value: new_fee_structure.volatility_factor,
-            adjustment_reason: String::from_str(&env, "oracle_update"),
+            adjustment_reason: String::from_str(env, "oracle_update"),
         };
 
         storage::add_fee_adjustment_history(env, &history);
@@ -1455,24 +2621,769 @@ impl Marketplace {
         );
     }
 
-    // ============ ATOMIC TRANSACTION SUPPORT ============
+    // ============ LEASE LIFECYCLE ============
 
-    /// Prepare atomic transaction step
-    pub fn prepare_atomic_step(
+    /// Set the lease deposit and early-termination penalty (admin only)
+    pub fn set_lease_config(
         env: Env,
-        transaction_id: u64,
-        step_id: u32,
-        function: Symbol,
-        args: Vec<Val>,
-    ) -> bool {
-        MarketplaceAtomicSupport::prepare_step(&env, transaction_id, step_id, &function, &args)
-    }
+        admin: Address,
+        deposit_bps: u32,
+        early_termination_penalty_bps: u32,
+    ) {
+        admin.require_auth();
+        let current_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .expect("Contract not initialized");
+        assert!(admin == current_admin, "Unauthorized");
+        assert!(
+            deposit_bps <= 10000 && early_termination_penalty_bps <= 10000,
+            "Invalid basis points"
+        );
 
-    /// Commit atomic transaction step
-    pub fn commit_atomic_step(
-        env: Env,
-        transaction_id: u64,
-        step_id: u32,
+        set_lease_config(
+            &env,
+            &LeaseConfig {
+                deposit_bps,
+                early_termination_penalty_bps,
+            },
+        );
+
+        env.events().publish(
+            (Symbol::new(&env, "lease_config_set"),),
+            (deposit_bps, early_termination_penalty_bps),
+        );
+    }
+
+    /// Set the largest share of a lease deposit, in bps, that a lessor may
+    /// keep for damage when settling an expired lease (admin only)
+    pub fn set_max_deduction_bps(env: Env, admin: Address, max_deduction_bps: u32) {
+        admin.require_auth();
+        let current_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .expect("Contract not initialized");
+        assert!(admin == current_admin, "Unauthorized");
+        assert!(max_deduction_bps <= 10000, "Invalid basis points");
+
+        set_max_lease_deduction_bps(&env, max_deduction_bps);
+
+        env.events()
+            .publish((Symbol::new(&env, "max_deduction_set"),), max_deduction_bps);
+    }
+
+    /// Get the cap on lease deposit deductions, in bps of the deposit
+    pub fn get_max_deduction_bps(env: Env) -> u32 {
+        get_max_lease_deduction_bps(&env)
+    }
+
+    /// Lease an agent from an active Lease listing.
+    ///
+    /// The lessee pays the lease value plus a refundable deposit. The value is
+    /// `listing.price`, or the per-second rate times `duration_seconds` for
+    /// listings made with `create_prorated_lease_listing`. In streaming
+    /// mode the contract holds the lease value and the lessor claims it as it
+    /// vests; otherwise the full value is paid to the lessor upfront.
+    pub fn initiate_lease(
+        env: Env,
+        listing_id: u64,
+        lessee: Address,
+        duration_seconds: u64,
+        auto_renew: bool,
+        streaming: bool,
+    ) -> Result<u64, LeaseError> {
+        if is_paused(&env) {
+            return Err(LeaseError::Paused);
+        }
+        lessee.require_auth();
+
+        let mut listing = get_listing(&env, listing_id).ok_or(LeaseError::ListingNotFound)?;
+
+        if !listing.active {
+            return Err(LeaseError::ListingInactive);
+        }
+        if listing.listing_type != ListingType::Lease {
+            return Err(LeaseError::NotLeaseListing);
+        }
+        if listing.seller == lessee {
+            return Err(LeaseError::SelfLease);
+        }
+        if duration_seconds == 0 || duration_seconds > MAX_DURATION_DAYS * 86400 {
+            return Err(LeaseError::InvalidDuration);
+        }
+        let (min_seconds, max_seconds) = get_lease_duration_bounds(&env, listing_id);
+        if min_seconds.is_some_and(|min| duration_seconds < min)
+            || max_seconds.is_some_and(|max| duration_seconds > max)
+        {
+            return Err(LeaseError::InvalidDuration);
+        }
+
+        let config = get_lease_config(&env);
+        let total_value = match get_lease_price_per_second(&env, listing_id) {
+            Some(rate) => rate
+                .checked_mul(duration_seconds as i128)
+                .ok_or(LeaseError::PriceOverflow)?,
+            None => listing.price,
+        };
+        let deposit_amount = total_value
+            .checked_mul(config.deposit_bps as i128)
+            .ok_or(LeaseError::PriceOverflow)?
+            / 10000;
+
+        let token_client = token::Client::new(&env, &get_payment_token(&env));
+        if deposit_amount > 0 {
+            token_client.transfer(&lessee, &env.current_contract_address(), &deposit_amount);
+        }
+        if streaming {
+            token_client.transfer(&lessee, &env.current_contract_address(), &total_value);
+        } else {
+            Self::pay_lessor(
+                &env,
+                &token_client,
+                &lessee,
+                listing.agent_id,
+                &listing.seller,
+                total_value,
+            );
+        }
+
+        let lease_id = increment_lease_counter(&env);
+        let now = env.ledger().timestamp();
+        let lease = LeaseData {
+            lease_id,
+            agent_id: listing.agent_id,
+            listing_id,
+            lessor: listing.seller.clone(),
+            lessee: lessee.clone(),
+            start_time: now,
+            end_time: now + duration_seconds,
+            duration_seconds,
+            deposit_amount,
+            total_value,
+            auto_renew,
+            lessee_consent_for_renewal: false,
+            status: LeaseState::Active,
+            pending_extension_id: None,
+            streaming,
+            claimed_amount: 0,
+        };
+        set_lease(&env, &lease);
+        lessee_leases_append(&env, &lessee, lease_id);
+        lessor_leases_append(&env, &listing.seller, lease_id);
+        Self::record_lease_history(&env, lease_id, "initiated", &lessee);
+
+        // The agent is unavailable until the lease ends
+        listing.active = false;
+        set_listing(&env, &listing);
+
+        env.events().publish(
+            (Symbol::new(&env, "lease_started"),),
+            (lease_id, listing_id, lessee, lease.end_time),
+        );
+
+        Ok(lease_id)
+    }
+
+    /// Request to extend an active lease; the lessor must approve
+    pub fn request_lease_extension(
+        env: Env,
+        lease_id: u64,
+        lessee: Address,
+        additional_duration_seconds: u64,
+    ) -> Result<u64, LeaseError> {
+        lessee.require_auth();
+
+        let mut lease = get_lease(&env, lease_id).ok_or(LeaseError::LeaseNotFound)?;
+        if lease.lessee != lessee {
+            return Err(LeaseError::Unauthorized);
+        }
+        if lease.status != LeaseState::Active && lease.status != LeaseState::Renewed {
+            return Err(LeaseError::LeaseNotActive);
+        }
+        if env.ledger().timestamp() >= lease.end_time {
+            return Err(LeaseError::LeaseEnded);
+        }
+        if additional_duration_seconds == 0 {
+            return Err(LeaseError::InvalidDuration);
+        }
+        // Streamed value vests over the original duration; stretching it
+        // would shrink the vested amount below what was already claimed
+        if lease.streaming {
+            return Err(LeaseError::StreamingExtensionUnsupported);
+        }
+
+        let extension_id = increment_lease_extension_counter(&env);
+        set_lease_extension(
+            &env,
+            &LeaseExtensionRequest {
+                extension_id,
+                lease_id,
+                additional_duration_seconds,
+                requested_at: env.ledger().timestamp(),
+                approved: false,
+            },
+        );
+
+        lease.status = LeaseState::ExtensionRequested;
+        lease.pending_extension_id = Some(extension_id);
+        set_lease(&env, &lease);
+        Self::record_lease_history(&env, lease_id, "extension_requested", &lessee);
+
+        env.events().publish(
+            (Symbol::new(&env, "lease_extension_requested"),),
+            (lease_id, extension_id, additional_duration_seconds),
+        );
+
+        Ok(extension_id)
+    }
+
+    /// Approve a pending extension request (lessor only)
+    pub fn approve_lease_extension(
+        env: Env,
+        lease_id: u64,
+        extension_id: u64,
+        lessor: Address,
+    ) -> Result<(), LeaseError> {
+        lessor.require_auth();
+
+        let mut lease = get_lease(&env, lease_id).ok_or(LeaseError::LeaseNotFound)?;
+        if lease.lessor != lessor {
+            return Err(LeaseError::Unauthorized);
+        }
+        if lease.status != LeaseState::ExtensionRequested
+            || lease.pending_extension_id != Some(extension_id)
+        {
+            return Err(LeaseError::ExtensionNotPending);
+        }
+        if lease.streaming {
+            return Err(LeaseError::StreamingExtensionUnsupported);
+        }
+
+        let mut request =
+            get_lease_extension(&env, extension_id).ok_or(LeaseError::ExtensionNotFound)?;
+        if env.ledger().timestamp() > request.requested_at + LEASE_EXTENSION_REQUEST_TTL_SECONDS {
+            return Err(LeaseError::ExtensionExpired);
+        }
+
+        request.approved = true;
+        set_lease_extension(&env, &request);
+
+        lease.end_time += request.additional_duration_seconds;
+        lease.duration_seconds += request.additional_duration_seconds;
+        lease.status = LeaseState::Active;
+        lease.pending_extension_id = None;
+        set_lease(&env, &lease);
+        Self::record_lease_history(&env, lease_id, "extension_approved", &lessor);
+
+        env.events().publish(
+            (Symbol::new(&env, "lease_extended"),),
+            (lease_id, extension_id, lease.end_time),
+        );
+
+        Ok(())
+    }
+
+    /// Refresh an expired, unapproved extension request so the lessor can
+    /// still approve it (lessee only). Allowed until the request is swept by
+    /// `cleanup_expired_extensions`.
+    pub fn renew_extension_request(env: Env, lease_id: u64, extension_id: u64, lessee: Address) {
+        lessee.require_auth();
+
+        let lease = get_lease(&env, lease_id).expect("Lease not found");
+        assert!(lease.lessee == lessee, "Unauthorized");
+        assert!(
+            lease.status == LeaseState::ExtensionRequested
+                && lease.pending_extension_id == Some(extension_id),
+            "Extension not pending"
+        );
+
+        let mut request = get_lease_extension(&env, extension_id).expect("Extension not found");
+        let now = env.ledger().timestamp();
+        assert!(!request.approved, "Extension already approved");
+        assert!(
+            now > request.requested_at + LEASE_EXTENSION_REQUEST_TTL_SECONDS,
+            "Extension request not expired"
+        );
+
+        request.requested_at = now;
+        set_lease_extension(&env, &request);
+        Self::record_lease_history(&env, lease_id, "extension_renewed", &lessee);
+
+        env.events().publish(
+            (Symbol::new(&env, "lease_extension_renewed"),),
+            (lease_id, extension_id, now),
+        );
+    }
+
+    /// Expire extension requests left unapproved past their TTL and grace
+    /// period, returning their leases to Active. Callable by anyone.
+    ///
+    /// Visits lease IDs `start_id..start_id + limit` (at most
+    /// `MAX_EXTENSION_CLEANUP_BATCH` per call) and returns how many expired.
+    pub fn cleanup_expired_extensions(env: Env, start_id: u64, limit: u32) -> u32 {
+        let now = env.ledger().timestamp();
+        let mut expired = 0u32;
+
+        let start_id = start_id.max(1);
+        let limit = limit.min(MAX_EXTENSION_CLEANUP_BATCH) as u64;
+        let end_id = get_lease_counter(&env).min(start_id.saturating_add(limit) - 1);
+        for lease_id in start_id..=end_id {
+            let Some(mut lease) = get_lease(&env, lease_id) else {
+                continue;
+            };
+            if lease.status != LeaseState::ExtensionRequested {
+                continue;
+            }
+            let Some(extension_id) = lease.pending_extension_id else {
+                continue;
+            };
+            let Some(request) = get_lease_extension(&env, extension_id) else {
+                continue;
+            };
+            if now
+                <= request.requested_at
+                    + LEASE_EXTENSION_REQUEST_TTL_SECONDS
+                    + LEASE_EXTENSION_GRACE_PERIOD_SECONDS
+            {
+                continue;
+            }
+
+            lease.status = LeaseState::Active;
+            lease.pending_extension_id = None;
+            set_lease(&env, &lease);
+            Self::record_lease_history(
+                &env,
+                lease_id,
+                "extension_expired",
+                &env.current_contract_address(),
+            );
+
+            env.events().publish(
+                (Symbol::new(&env, "lease_extension_expired"),),
+                (lease_id, extension_id),
+            );
+            expired += 1;
+        }
+
+        expired
+    }
+
+    /// Record the lessee's consent to auto-renew at the next expiry
+    pub fn set_renewal_consent(env: Env, lease_id: u64, lessee: Address, consent: bool) {
+        lessee.require_auth();
+
+        let mut lease = get_lease(&env, lease_id).expect("Lease not found");
+        assert!(lease.lessee == lessee, "Unauthorized");
+        assert!(lease.status != LeaseState::Terminated, "Lease has ended");
+
+        lease.lessee_consent_for_renewal = consent;
+        set_lease(&env, &lease);
+    }
+
+    /// Withdraw the vested, unclaimed portion of a streaming lease (lessor only)
+    pub fn claim_lease_revenue(env: Env, lease_id: u64, lessor: Address) -> i128 {
+        lessor.require_auth();
+
+        let mut lease = get_lease(&env, lease_id).expect("Lease not found");
+        assert!(lease.lessor == lessor, "Unauthorized");
+        assert!(lease.streaming, "Lease is not streaming");
+
+        let claimable = Self::lease_vested_amount(&env, &lease) - lease.claimed_amount;
+        if claimable > 0 {
+            let token_client = token::Client::new(&env, &get_payment_token(&env));
+            Self::pay_lessor(
+                &env,
+                &token_client,
+                &env.current_contract_address(),
+                lease.agent_id,
+                &lessor,
+                claimable,
+            );
+
+            lease.claimed_amount += claimable;
+            set_lease(&env, &lease);
+
+            env.events().publish(
+                (Symbol::new(&env, "lease_revenue_claimed"),),
+                (lease_id, lessor, claimable),
+            );
+        }
+
+        claimable
+    }
+
+    /// End a lease before its term (lessee only).
+    ///
+    /// A penalty on the unused value is taken from the deposit and paid to the
+    /// lessor; the rest of the deposit and, in streaming mode, the unvested
+    /// lease value are refunded to the lessee.
+    pub fn early_termination(env: Env, lease_id: u64, lessee: Address) -> Result<(), LeaseError> {
+        lessee.require_auth();
+
+        let mut lease = get_lease(&env, lease_id).ok_or(LeaseError::LeaseNotFound)?;
+        if lease.lessee != lessee {
+            return Err(LeaseError::Unauthorized);
+        }
+        if lease.status == LeaseState::Terminated {
+            return Err(LeaseError::AlreadyTerminated);
+        }
+
+        let now = env.ledger().timestamp();
+        if now >= lease.end_time {
+            return Err(LeaseError::LeaseEnded);
+        }
+
+        let config = get_lease_config(&env);
+        let remaining_value =
+            (lease.total_value * (lease.end_time - now) as i128) / lease.duration_seconds as i128;
+        let penalty = ((remaining_value * config.early_termination_penalty_bps as i128) / 10000)
+            .min(lease.deposit_amount);
+
+        let token_client = token::Client::new(&env, &get_payment_token(&env));
+        let mut refund = lease.deposit_amount - penalty;
+        if penalty > 0 {
+            token_client.transfer(&env.current_contract_address(), &lease.lessor, &penalty);
+        }
+        if lease.streaming {
+            refund += Self::settle_streamed_value(&env, &token_client, &mut lease);
+        }
+        if refund > 0 {
+            token_client.transfer(&env.current_contract_address(), &lessee, &refund);
+        }
+
+        lease.status = LeaseState::Terminated;
+        set_lease(&env, &lease);
+        Self::reactivate_lease_listing(&env, lease.listing_id);
+        Self::record_lease_history(&env, lease_id, "terminated_early", &lessee);
+
+        env.events().publish(
+            (Symbol::new(&env, "lease_terminated"),),
+            (lease_id, penalty, refund),
+        );
+
+        Ok(())
+    }
+
+    /// Settle a lease whose term has ended.
+    ///
+    /// If auto-renew is set and the lessee consented, a new term is charged and
+    /// started; otherwise the deposit is refunded and the listing reopens.
+    pub fn settle_lease_expiry(env: Env, lease_id: u64) -> Result<(), LeaseError> {
+        let mut lease = get_lease(&env, lease_id).ok_or(LeaseError::LeaseNotFound)?;
+        if lease.status == LeaseState::Terminated {
+            return Err(LeaseError::AlreadyTerminated);
+        }
+
+        let now = env.ledger().timestamp();
+        if now < lease.end_time {
+            return Err(LeaseError::NotYetExpired);
+        }
+
+        let token_client = token::Client::new(&env, &get_payment_token(&env));
+        let mut refund = 0;
+        if lease.streaming {
+            refund += Self::settle_streamed_value(&env, &token_client, &mut lease);
+        }
+
+        if lease.auto_renew && lease.lessee_consent_for_renewal {
+            if refund > 0 {
+                token_client.transfer(&env.current_contract_address(), &lease.lessee, &refund);
+            }
+
+            if lease.streaming {
+                token_client.transfer(
+                    &lease.lessee,
+                    &env.current_contract_address(),
+                    &lease.total_value,
+                );
+            } else {
+                Self::pay_lessor(
+                    &env,
+                    &token_client,
+                    &lease.lessee,
+                    lease.agent_id,
+                    &lease.lessor,
+                    lease.total_value,
+                );
+            }
+
+            lease.start_time = now;
+            lease.end_time = now + lease.duration_seconds;
+            lease.claimed_amount = 0;
+            lease.lessee_consent_for_renewal = false;
+            lease.status = LeaseState::Renewed;
+            lease.pending_extension_id = None;
+            set_lease(&env, &lease);
+            Self::record_lease_history(&env, lease_id, "renewed", &lease.lessee.clone());
+
+            env.events().publish(
+                (Symbol::new(&env, "lease_renewed"),),
+                (lease_id, lease.end_time),
+            );
+            return Ok(());
+        }
+
+        refund += lease.deposit_amount;
+        if refund > 0 {
+            token_client.transfer(&env.current_contract_address(), &lease.lessee, &refund);
+        }
+
+        lease.status = LeaseState::Terminated;
+        set_lease(&env, &lease);
+        Self::reactivate_lease_listing(&env, lease.listing_id);
+        Self::record_lease_history(&env, lease_id, "expired", &env.current_contract_address());
+
+        env.events()
+            .publish((Symbol::new(&env, "lease_ended"),), (lease_id, refund));
+
+        Ok(())
+    }
+
+    /// Settle an expired lease, keeping part of the deposit for documented
+    /// damage (lessor only).
+    ///
+    /// The deduction is capped at `max_deduction_bps` of the deposit; the rest
+    /// of the deposit is refunded to the lessee and the reason is recorded in
+    /// the lease history. Leases due to auto-renew must use
+    /// `settle_lease_expiry`.
+    pub fn settle_lease_with_deduction(
+        env: Env,
+        lease_id: u64,
+        lessor: Address,
+        deduction_amount: i128,
+        reason: String,
+    ) {
+        lessor.require_auth();
+
+        let mut lease = get_lease(&env, lease_id).expect("Lease not found");
+        assert!(lease.lessor == lessor, "Unauthorized");
+        assert!(
+            lease.status != LeaseState::Terminated,
+            "Lease already ended"
+        );
+        assert!(
+            env.ledger().timestamp() >= lease.end_time,
+            "Lease not yet expired"
+        );
+        assert!(
+            !(lease.auto_renew && lease.lessee_consent_for_renewal),
+            "Lease is set to renew"
+        );
+
+        let max_deduction =
+            (lease.deposit_amount * get_max_lease_deduction_bps(&env) as i128) / 10000;
+        assert!(deduction_amount >= 0, "Invalid deduction");
+        assert!(deduction_amount <= max_deduction, "Deduction exceeds cap");
+
+        let token_client = token::Client::new(&env, &get_payment_token(&env));
+        let mut refund = lease.deposit_amount - deduction_amount;
+        if lease.streaming {
+            refund += Self::settle_streamed_value(&env, &token_client, &mut lease);
+        }
+        if deduction_amount > 0 {
+            token_client.transfer(&env.current_contract_address(), &lessor, &deduction_amount);
+        }
+        if refund > 0 {
+            token_client.transfer(&env.current_contract_address(), &lease.lessee, &refund);
+        }
+
+        lease.status = LeaseState::Terminated;
+        set_lease(&env, &lease);
+        Self::reactivate_lease_listing(&env, lease.listing_id);
+        add_lease_history(
+            &env,
+            lease_id,
+            &LeaseHistoryEntry {
+                lease_id,
+                action: String::from_str(&env, "expired_with_deduction"),
+                actor: lessor,
+                timestamp: env.ledger().timestamp(),
+                details: Some(reason),
+            },
+        );
+
+        env.events()
+            .publish((Symbol::new(&env, "lease_ended"),), (lease_id, refund));
+        env.events().publish(
+            (Symbol::new(&env, "lease_deposit_deducted"),),
+            (lease_id, deduction_amount),
+        );
+    }
+
+    /// Get a lease by ID
+    pub fn get_lease_by_id(env: Env, lease_id: u64) -> Option<LeaseData> {
+        get_lease(&env, lease_id)
+    }
+
+    /// Summarise a lease for the lessee: its state, seconds left in the term,
+    /// whether an auto-renewal is due, and any pending extension request
+    pub fn get_lease_status_view(
+        env: Env,
+        lease_id: u64,
+    ) -> Result<(LeaseState, u64, bool, Option<u64>), LeaseError> {
+        let lease = get_lease(&env, lease_id).ok_or(LeaseError::LeaseNotFound)?;
+        let now = env.ledger().timestamp();
+
+        let remaining_seconds = lease.end_time.saturating_sub(now);
+        let renewal_due = lease.auto_renew && now >= lease.end_time;
+
+        Ok((
+            lease.status,
+            remaining_seconds,
+            renewal_due,
+            lease.pending_extension_id,
+        ))
+    }
+
+    /// Get active leases where `address` is the lessee or the lessor
+    pub fn get_active_leases(env: Env, address: Address) -> Vec<LeaseData> {
+        let mut result = Vec::new(&env);
+        for id in Self::lease_ids_for(&env, &address).iter() {
+            if let Some(lease) = get_lease(&env, id) {
+                if lease.status == LeaseState::Active {
+                    result.push_back(lease);
+                }
+            }
+        }
+        result
+    }
+
+    /// Page through all leases where `address` is the lessee or the lessor,
+    /// optionally restricted to one status. `start` indexes the filtered list.
+    pub fn get_leases(
+        env: Env,
+        address: Address,
+        status_filter: Option<LeaseState>,
+        start: u32,
+        limit: u32,
+    ) -> Vec<LeaseData> {
+        let mut result = Vec::new(&env);
+        let mut matched = 0u32;
+        for id in Self::lease_ids_for(&env, &address).iter() {
+            if result.len() >= limit {
+                break;
+            }
+            if let Some(lease) = get_lease(&env, id) {
+                if status_filter.is_some_and(|status| lease.status != status) {
+                    continue;
+                }
+                if matched >= start {
+                    result.push_back(lease);
+                }
+                matched += 1;
+            }
+        }
+        result
+    }
+
+    /// Get the history of a lease
+    pub fn get_lease_history(env: Env, lease_id: u64) -> Vec<LeaseHistoryEntry> {
+        get_lease_history(&env, lease_id)
+    }
+
+    /// Lease IDs where `address` is the lessee or the lessor, without duplicates
+    fn lease_ids_for(env: &Env, address: &Address) -> Vec<u64> {
+        let mut ids = get_lessee_leases(env, address);
+        for id in get_lessor_leases(env, address).iter() {
+            if !ids.contains(id) {
+                ids.push_back(id);
+            }
+        }
+        ids
+    }
+
+    /// Portion of a streaming lease's value vested to the lessor so far
+    fn lease_vested_amount(env: &Env, lease: &LeaseData) -> i128 {
+        if lease.status == LeaseState::Terminated {
+            return lease.claimed_amount;
+        }
+
+        let now = env.ledger().timestamp().min(lease.end_time);
+        let elapsed = now.saturating_sub(lease.start_time);
+        if elapsed >= lease.duration_seconds {
+            return lease.total_value;
+        }
+        (lease.total_value * elapsed as i128) / lease.duration_seconds as i128
+    }
+
+    /// Pay the lessor any vested, unclaimed value of a streaming lease and
+    /// return the unvested remainder owed back to the lessee
+    fn settle_streamed_value(
+        env: &Env,
+        token_client: &token::Client,
+        lease: &mut LeaseData,
+    ) -> i128 {
+        // Never let settlement fall below what the lessor already claimed
+        let settled = Self::lease_vested_amount(env, lease).max(lease.claimed_amount);
+        let unclaimed = settled - lease.claimed_amount;
+        if unclaimed > 0 {
+            Self::pay_lessor(
+                env,
+                token_client,
+                &env.current_contract_address(),
+                lease.agent_id,
+                &lease.lessor,
+                unclaimed,
+            );
+        }
+        lease.claimed_amount = settled;
+        lease.total_value - settled
+    }
+
+    /// Pay lease value from `from` to the lessor, less the platform fee
+    fn pay_lessor(
+        env: &Env,
+        token_client: &token::Client,
+        from: &Address,
+        agent_id: u64,
+        lessor: &Address,
+        amount: i128,
+    ) {
+        let platform_fee = Self::collect_platform_fee(env, token_client, from, agent_id, amount);
+        token_client.transfer(from, lessor, &(amount - platform_fee));
+    }
+
+    fn reactivate_lease_listing(env: &Env, listing_id: u64) {
+        if is_listing_cancelled(env, listing_id) {
+            return;
+        }
+        if let Some(mut listing) = get_listing(env, listing_id) {
+            listing.active = true;
+            set_listing(env, &listing);
+        }
+    }
+
+    fn record_lease_history(env: &Env, lease_id: u64, action: &str, actor: &Address) {
+        let entry = LeaseHistoryEntry {
+            lease_id,
+            action: String::from_str(env, action),
+            actor: actor.clone(),
+            timestamp: env.ledger().timestamp(),
+            details: None,
+        };
+        add_lease_history(env, lease_id, &entry);
+    }
+
+    // ============ ATOMIC TRANSACTION SUPPORT ============
+
+    /// Prepare atomic transaction step
+    pub fn prepare_atomic_step(
+        env: Env,
+        transaction_id: u64,
+        step_id: u32,
+        function: Symbol,
+        args: Vec<Val>,
+    ) -> bool {
+        MarketplaceAtomicSupport::prepare_step(&env, transaction_id, step_id, &function, &args)
+    }
+
+    /// Commit atomic transaction step
+    pub fn commit_atomic_step(
+        env: Env,
+        transaction_id: u64,
+        step_id: u32,
         function: Symbol,
         args: Vec<Val>,
     ) -> Val {
@@ -1512,8 +3423,7 @@ impl Marketplace {
     pub fn unlock_listing(env: Env, listing_id: u64) -> bool {
         // This is called as a rollback function, so we don't need transaction context
         // Just unlock the listing if it exists
-        let listing_key = (Symbol::new(&env, "listing"), listing_id);
-        if env.storage().instance().has(&listing_key) {
+        if get_listing(&env, listing_id).is_some() {
             // In atomic implementation, this would remove the lock
             // For now, just return success
             true
@@ -1523,7 +3433,7 @@ impl Marketplace {
     }
 
     /// Refund from escrow (rollback function)
-    pub fn refund_from_escrow(env: Env, buyer: Address, amount: i128) -> bool {
+    pub fn refund_from_escrow(_env: Env, _buyer: Address, _amount: i128) -> bool {
         // In a real implementation, this would refund tokens from escrow
         // For now, just return success
         true
@@ -1532,10 +3442,9 @@ impl Marketplace {
     /// Revert sale (rollback function)
     pub fn revert_sale(env: Env, listing_id: u64) -> bool {
         // Reactivate the listing
-        let listing_key = (Symbol::new(&env, "listing"), listing_id);
-        if let Some(mut listing) = env.storage().instance().get::<_, Listing>(&listing_key) {
+        if let Some(mut listing) = get_listing(&env, listing_id) {
             listing.active = true;
-            env.storage().instance().set(&listing_key, &listing);
+            set_listing(&env, &listing);
             true
         } else {
             false
@@ -1548,7 +3457,7 @@ impl Marketplace {
     }
 
     /// Delete lease record (rollback function)
-    pub fn delete_lease_record(env: Env, listing_id: u64) -> bool {
+    pub fn delete_lease_record(_env: Env, _listing_id: u64) -> bool {
         // In a real implementation, this would delete the lease record
         // For now, just return success
         true
@@ -1558,5 +3467,53 @@ impl Marketplace {
 //#[cfg(test)]
 //mod test_approval;
 
+#[cfg(test)]
+mod test_approval_escrow;
+#[cfg(test)]
+mod test_atomic_resolve;
+#[cfg(test)]
+mod test_auction_extension;
+#[cfg(test)]
+mod test_auction_relist;
+#[cfg(test)]
+mod test_bid_fee;
+#[cfg(test)]
+mod test_bid_increment;
+#[cfg(test)]
+mod test_capability_search;
+#[cfg(test)]
+mod test_defaults;
+#[cfg(test)]
+mod test_dispute_hold;
+#[cfg(test)]
+mod test_dutch_auction;
 #[cfg(test)]
 mod test_dynamic_fees;
+#[cfg(test)]
+mod test_emergency_refund;
+#[cfg(test)]
+mod test_errors;
+#[cfg(test)]
+mod test_escrow_lock;
+#[cfg(test)]
+mod test_fractional_listing;
+#[cfg(test)]
+mod test_lease;
+#[cfg(test)]
+mod test_lease_errors;
+#[cfg(test)]
+mod test_listing_cap;
+#[cfg(test)]
+mod test_listing_migration;
+#[cfg(test)]
+mod test_oracle_threshold;
+#[cfg(test)]
+mod test_pause;
+#[cfg(test)]
+mod test_platform_fee;
+#[cfg(test)]
+mod test_private_listing;
+#[cfg(test)]
+mod test_royalty_cap;
+#[cfg(test)]
+mod test_utils;
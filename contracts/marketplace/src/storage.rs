@@ -1,4 +1,5 @@
-use soroban_sdk::{contracttype, Address, Env, String, Vec};
+use soroban_sdk::{contracttype, Address, Env, IntoVal, String, Symbol, Val, Vec};
+use stellai_lib::Listing;
 
 #[derive(Clone)]
 #[contracttype]
@@ -11,6 +12,7 @@ pub enum DataKey {
     ApprovalConfig,
     ApprovalCounter,
     Approval(u64),
+    ApprovalEscrow(u64),       // approval_id -> buyer funds held
     ApprovalHistory(u64, u64), // (approval_id, history_index)
     FeeAdjustmentParams,
     CurrentFeeStructure,
@@ -19,6 +21,34 @@ pub enum DataKey {
     OracleSubscriptions,
     LastOracleUpdate,
     FeeTransitionState,
+    GovernanceContract,
+    Paused,
+    AgentNft,
+    PlatformFeeBps,
+    FeeRecipient,
+    LeaseConfig,
+    LeaseCounter,
+    Lease(u64),
+    LeaseHistory(u64),
+    LesseeLeases(Address),
+    LessorLeases(Address),
+    LeaseExtensionCounter,
+    LeaseExtension(u64),
+    AllowedBuyers(u64), // listing_id -> private sale allow-list
+    PriceOracle,
+    ApprovalThresholdOracleKey, // feed converting prices to the threshold currency
+    DisputeWindow(u64),         // listing_id -> seconds buyer funds are held after a sale
+    SaleHold(u64),              // listing_id -> held sale proceeds
+    FractionalListingCounter,
+    FractionalListing(u64),
+    Shares(u64, Address),     // (fractional listing_id, holder) -> shares owned
+    MaxLeaseDeductionBps,     // cap on deposit a lessor may keep for damage
+    LeasePricePerSecond(u64), // listing_id -> rate for duration-priced leases
+    LeaseDurationBounds(u64), // listing_id -> (min, max) lease seconds
+    SellerListingCount(Address), // seller -> number of active listings
+    MaxActiveListingsPerSeller, // 0 or unset = unlimited
+    AuctionRelistedAs(u64),   // reserve-failed auction_id -> replacement auction_id
+    ListingCancelled(u64),    // listing_id withdrawn by its seller
 }
 
 /* ---------------- ADMIN ---------------- */
@@ -33,6 +63,307 @@ pub fn require_admin(env: &Env) {
     admin.require_auth();
 }
 
+/* ---------------- PAUSE ---------------- */
+
+pub fn set_governance_contract(env: &Env, governance: &Address) {
+    env.storage()
+        .instance()
+        .set(&DataKey::GovernanceContract, governance);
+}
+
+pub fn get_governance_contract(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::GovernanceContract)
+}
+
+pub fn set_paused(env: &Env, paused: bool) {
+    env.storage().instance().set(&DataKey::Paused, &paused);
+}
+
+pub fn is_paused(env: &Env) -> bool {
+    env.storage()
+        .instance()
+        .get(&DataKey::Paused)
+        .unwrap_or(false)
+}
+
+/* ---------------- LISTINGS ---------------- */
+
+// Persistent entry TTL in ledgers (~30 days threshold, ~60 days extension)
+const LISTING_TTL_THRESHOLD: u32 = 518_400;
+const LISTING_TTL_EXTEND: u32 = 1_036_800;
+
+/// Per-listing, per-lease and per-account records grow without bound, so they
+/// live in persistent storage rather than the shared instance entry
+fn set_persistent<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+    env.storage().persistent().set(key, value);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, LISTING_TTL_THRESHOLD, LISTING_TTL_EXTEND);
+}
+
+fn listing_key(env: &Env, listing_id: u64) -> (Symbol, u64) {
+    (Symbol::new(env, "listing"), listing_id)
+}
+
+/// Listings live in persistent storage; IDs not yet migrated fall back to instance
+pub fn get_listing(env: &Env, listing_id: u64) -> Option<Listing> {
+    let key = listing_key(env, listing_id);
+    env.storage()
+        .persistent()
+        .get(&key)
+        .or_else(|| env.storage().instance().get(&key))
+}
+
+/// Write a listing to persistent storage, dropping any legacy instance copy
+pub fn set_listing(env: &Env, listing: &Listing) {
+    let key = listing_key(env, listing.listing_id);
+    env.storage().persistent().set(&key, listing);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, LISTING_TTL_THRESHOLD, LISTING_TTL_EXTEND);
+    env.storage().instance().remove(&key);
+}
+
+/// Move a legacy instance listing to persistent storage; false if none was there
+pub fn migrate_listing(env: &Env, listing_id: u64) -> bool {
+    let key = listing_key(env, listing_id);
+    match env.storage().instance().get::<_, Listing>(&key) {
+        Some(listing) => {
+            set_listing(env, &listing);
+            true
+        }
+        None => false,
+    }
+}
+
+/* ---------------- AGENT NFT ---------------- */
+
+pub fn set_agent_nft(env: &Env, agent_nft: &Address) {
+    env.storage().instance().set(&DataKey::AgentNft, agent_nft);
+}
+
+pub fn get_agent_nft(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&DataKey::AgentNft)
+        .expect("AgentNFT contract not set")
+}
+
+pub fn try_get_agent_nft(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::AgentNft)
+}
+
+/* ---------------- PRICE ORACLE ---------------- */
+
+pub fn set_price_oracle(env: &Env, oracle: &Address) {
+    env.storage().instance().set(&DataKey::PriceOracle, oracle);
+}
+
+pub fn get_price_oracle(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::PriceOracle)
+}
+
+pub fn set_approval_threshold_oracle_key(env: &Env, key: &Option<Symbol>) {
+    match key {
+        Some(key) => env
+            .storage()
+            .instance()
+            .set(&DataKey::ApprovalThresholdOracleKey, key),
+        None => env
+            .storage()
+            .instance()
+            .remove(&DataKey::ApprovalThresholdOracleKey),
+    }
+}
+
+pub fn get_approval_threshold_oracle_key(env: &Env) -> Option<Symbol> {
+    env.storage()
+        .instance()
+        .get(&DataKey::ApprovalThresholdOracleKey)
+}
+
+/* ---------------- PLATFORM FEE ---------------- */
+
+pub fn set_platform_fee(env: &Env, fee_bps: u32, recipient: &Address) {
+    env.storage()
+        .instance()
+        .set(&DataKey::PlatformFeeBps, &fee_bps);
+    env.storage()
+        .instance()
+        .set(&DataKey::FeeRecipient, recipient);
+}
+
+pub fn get_platform_fee_bps(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::PlatformFeeBps)
+        .unwrap_or(0)
+}
+
+pub fn get_fee_recipient(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::FeeRecipient)
+}
+
+/* ---------------- PRIVATE LISTINGS ---------------- */
+
+pub fn set_allowed_buyers(env: &Env, listing_id: u64, allowed_buyers: &Vec<Address>) {
+    let key = DataKey::AllowedBuyers(listing_id);
+    if allowed_buyers.is_empty() {
+        env.storage().persistent().remove(&key);
+    } else {
+        set_persistent(env, &key, allowed_buyers);
+    }
+}
+
+pub fn get_allowed_buyers(env: &Env, listing_id: u64) -> Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::AllowedBuyers(listing_id))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/* ---------------- SALE HOLDS ---------------- */
+
+/// Buyer funds held after an escrow-hold sale until released or refunded
+#[derive(Clone)]
+#[contracttype]
+pub struct SaleHold {
+    pub buyer: Address,
+    pub seller: Address,
+    pub agent_id: u64,
+    pub amount: i128,
+    /// Marketplace fee fixed at purchase time, retained on release
+    pub marketplace_fee: i128,
+    pub release_after: u64,
+    pub disputed: bool,
+}
+
+pub fn set_dispute_window(env: &Env, listing_id: u64, seconds: u64) {
+    set_persistent(env, &DataKey::DisputeWindow(listing_id), &seconds);
+}
+
+pub fn get_dispute_window(env: &Env, listing_id: u64) -> Option<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::DisputeWindow(listing_id))
+}
+
+pub fn set_lease_price_per_second(env: &Env, listing_id: u64, price_per_second: i128) {
+    set_persistent(
+        env,
+        &DataKey::LeasePricePerSecond(listing_id),
+        &price_per_second,
+    );
+}
+
+pub fn get_lease_price_per_second(env: &Env, listing_id: u64) -> Option<i128> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::LeasePricePerSecond(listing_id))
+}
+
+pub fn set_lease_duration_bounds(
+    env: &Env,
+    listing_id: u64,
+    min_seconds: Option<u64>,
+    max_seconds: Option<u64>,
+) {
+    set_persistent(
+        env,
+        &DataKey::LeaseDurationBounds(listing_id),
+        &(min_seconds, max_seconds),
+    );
+}
+
+pub fn get_lease_duration_bounds(env: &Env, listing_id: u64) -> (Option<u64>, Option<u64>) {
+    env.storage()
+        .persistent()
+        .get(&DataKey::LeaseDurationBounds(listing_id))
+        .unwrap_or((None, None))
+}
+
+pub fn set_listing_cancelled(env: &Env, listing_id: u64) {
+    set_persistent(env, &DataKey::ListingCancelled(listing_id), &true);
+}
+
+pub fn is_listing_cancelled(env: &Env, listing_id: u64) -> bool {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ListingCancelled(listing_id))
+        .unwrap_or(false)
+}
+
+pub fn set_sale_hold(env: &Env, listing_id: u64, hold: &SaleHold) {
+    set_persistent(env, &DataKey::SaleHold(listing_id), hold);
+}
+
+pub fn get_sale_hold(env: &Env, listing_id: u64) -> Option<SaleHold> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::SaleHold(listing_id))
+}
+
+pub fn remove_sale_hold(env: &Env, listing_id: u64) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::SaleHold(listing_id));
+}
+
+/* ---------------- FRACTIONAL LISTINGS ---------------- */
+
+/// Listing selling shares of an agent's future revenue rather than the NFT
+#[derive(Clone)]
+#[contracttype]
+pub struct FractionalListing {
+    pub listing_id: u64,
+    pub agent_id: u64,
+    pub seller: Address,
+    pub total_shares: u64,
+    pub available_shares: u64,
+    pub price_per_share: i128,
+    /// Buyer payments escrowed in the contract until the seller claims them
+    pub proceeds: i128,
+    pub created_at: u64,
+}
+
+pub fn increment_fractional_listing_counter(env: &Env) -> u64 {
+    let counter: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::FractionalListingCounter)
+        .unwrap_or(0)
+        + 1;
+    env.storage()
+        .instance()
+        .set(&DataKey::FractionalListingCounter, &counter);
+    counter
+}
+
+pub fn set_fractional_listing(env: &Env, listing: &FractionalListing) {
+    set_persistent(
+        env,
+        &DataKey::FractionalListing(listing.listing_id),
+        listing,
+    );
+}
+
+pub fn get_fractional_listing(env: &Env, listing_id: u64) -> Option<FractionalListing> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::FractionalListing(listing_id))
+}
+
+pub fn get_shares(env: &Env, listing_id: u64, holder: &Address) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Shares(listing_id, holder.clone()))
+        .unwrap_or(0)
+}
+
+pub fn set_shares(env: &Env, listing_id: u64, holder: &Address, shares: u64) {
+    set_persistent(env, &DataKey::Shares(listing_id, holder.clone()), &shares);
+}
+
 /* ---------------- PAYMENT TOKEN ---------------- */
 
 pub fn set_payment_token(env: &Env, token: Address) {
@@ -46,6 +377,10 @@ pub fn get_payment_token(env: &Env) -> Address {
         .unwrap()
 }
 
+pub fn try_get_payment_token(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::PaymentToken)
+}
+
 /* ---------------- ROYALTY ---------------- */
 
 #[allow(dead_code)]
@@ -89,6 +424,20 @@ pub fn get_auction(env: &Env, auction_id: u64) -> Option<stellai_lib::Auction> {
     env.storage().instance().get(&DataKey::Auction(auction_id))
 }
 
+pub fn set_auction_relisted_as(env: &Env, auction_id: u64, new_auction_id: u64) {
+    set_persistent(
+        env,
+        &DataKey::AuctionRelistedAs(auction_id),
+        &new_auction_id,
+    );
+}
+
+pub fn get_auction_relisted_as(env: &Env, auction_id: u64) -> Option<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::AuctionRelistedAs(auction_id))
+}
+
 /* ---------------- HELPERS ---------------- */
 
 #[allow(dead_code)]
@@ -108,7 +457,7 @@ pub fn get_approval_config(env: &Env) -> stellai_lib::ApprovalConfig {
     env.storage()
         .instance()
         .get(&DataKey::ApprovalConfig)
-        .unwrap_or_else(|| stellai_lib::ApprovalConfig {
+        .unwrap_or(stellai_lib::ApprovalConfig {
             threshold: stellai_lib::DEFAULT_APPROVAL_THRESHOLD,
             approvers_required: stellai_lib::DEFAULT_APPROVERS_REQUIRED,
             total_approvers: stellai_lib::DEFAULT_TOTAL_APPROVERS,
@@ -147,6 +496,20 @@ pub fn get_approval(env: &Env, approval_id: u64) -> Option<stellai_lib::Approval
         .get(&DataKey::Approval(approval_id))
 }
 
+pub fn set_approval_escrow(env: &Env, approval_id: u64, amount: i128) {
+    set_persistent(env, &DataKey::ApprovalEscrow(approval_id), &amount);
+}
+
+/// Remove and return the escrowed amount for an approval, if any
+pub fn take_approval_escrow(env: &Env, approval_id: u64) -> Option<i128> {
+    let key = DataKey::ApprovalEscrow(approval_id);
+    let amount = env.storage().persistent().get(&key);
+    if amount.is_some() {
+        env.storage().persistent().remove(&key);
+    }
+    amount
+}
+
 pub fn add_approval_history(env: &Env, approval_id: u64, history: &stellai_lib::ApprovalHistory) {
     let history_index = get_approval_history_count(env, approval_id);
     env.storage().instance().set(
@@ -196,6 +559,147 @@ pub fn delete_approval(env: &Env, approval_id: u64) {
         history_index += 1;
     }
 }
+/* ---------------- LEASE ---------------- */
+
+pub fn set_lease_config(env: &Env, config: &stellai_lib::LeaseConfig) {
+    env.storage().instance().set(&DataKey::LeaseConfig, config);
+}
+
+pub fn get_lease_config(env: &Env) -> stellai_lib::LeaseConfig {
+    env.storage()
+        .instance()
+        .get(&DataKey::LeaseConfig)
+        .unwrap_or(stellai_lib::LeaseConfig {
+            deposit_bps: stellai_lib::DEFAULT_LEASE_DEPOSIT_BPS,
+            early_termination_penalty_bps: stellai_lib::DEFAULT_EARLY_TERMINATION_PENALTY_BPS,
+        })
+}
+
+pub fn set_max_lease_deduction_bps(env: &Env, bps: u32) {
+    env.storage()
+        .instance()
+        .set(&DataKey::MaxLeaseDeductionBps, &bps);
+}
+
+pub fn get_max_lease_deduction_bps(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::MaxLeaseDeductionBps)
+        .unwrap_or(stellai_lib::DEFAULT_MAX_LEASE_DEDUCTION_BPS)
+}
+
+pub fn set_max_active_listings_per_seller(env: &Env, max: u32) {
+    env.storage()
+        .instance()
+        .set(&DataKey::MaxActiveListingsPerSeller, &max);
+}
+
+pub fn get_max_active_listings_per_seller(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::MaxActiveListingsPerSeller)
+        .unwrap_or(0)
+}
+
+pub fn get_seller_listing_count(env: &Env, seller: &Address) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::SellerListingCount(seller.clone()))
+        .unwrap_or(0)
+}
+
+pub fn set_seller_listing_count(env: &Env, seller: &Address, count: u32) {
+    set_persistent(env, &DataKey::SellerListingCount(seller.clone()), &count);
+}
+
+pub fn get_lease_counter(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::LeaseCounter)
+        .unwrap_or(0)
+}
+
+pub fn increment_lease_counter(env: &Env) -> u64 {
+    let counter = get_lease_counter(env) + 1;
+    env.storage()
+        .instance()
+        .set(&DataKey::LeaseCounter, &counter);
+    counter
+}
+
+pub fn set_lease(env: &Env, lease: &stellai_lib::LeaseData) {
+    set_persistent(env, &DataKey::Lease(lease.lease_id), lease);
+}
+
+pub fn get_lease(env: &Env, lease_id: u64) -> Option<stellai_lib::LeaseData> {
+    env.storage().persistent().get(&DataKey::Lease(lease_id))
+}
+
+pub fn add_lease_history(env: &Env, lease_id: u64, entry: &stellai_lib::LeaseHistoryEntry) {
+    let mut history = get_lease_history(env, lease_id);
+    history.push_back(entry.clone());
+    set_persistent(env, &DataKey::LeaseHistory(lease_id), &history);
+}
+
+pub fn get_lease_history(env: &Env, lease_id: u64) -> Vec<stellai_lib::LeaseHistoryEntry> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::LeaseHistory(lease_id))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+pub fn lessee_leases_append(env: &Env, lessee: &Address, lease_id: u64) {
+    let mut ids = get_lessee_leases(env, lessee);
+    ids.push_back(lease_id);
+    set_persistent(env, &DataKey::LesseeLeases(lessee.clone()), &ids);
+}
+
+pub fn get_lessee_leases(env: &Env, lessee: &Address) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::LesseeLeases(lessee.clone()))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+pub fn lessor_leases_append(env: &Env, lessor: &Address, lease_id: u64) {
+    let mut ids = get_lessor_leases(env, lessor);
+    ids.push_back(lease_id);
+    set_persistent(env, &DataKey::LessorLeases(lessor.clone()), &ids);
+}
+
+pub fn get_lessor_leases(env: &Env, lessor: &Address) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::LessorLeases(lessor.clone()))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+pub fn increment_lease_extension_counter(env: &Env) -> u64 {
+    let counter: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::LeaseExtensionCounter)
+        .unwrap_or(0)
+        + 1;
+    env.storage()
+        .instance()
+        .set(&DataKey::LeaseExtensionCounter, &counter);
+    counter
+}
+
+pub fn set_lease_extension(env: &Env, request: &stellai_lib::LeaseExtensionRequest) {
+    set_persistent(env, &DataKey::LeaseExtension(request.extension_id), request);
+}
+
+pub fn get_lease_extension(
+    env: &Env,
+    extension_id: u64,
+) -> Option<stellai_lib::LeaseExtensionRequest> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::LeaseExtension(extension_id))
+}
+
 /* ---------------- DYNAMIC FEE ADJUSTMENT ---------------- */
 
 #[derive(Clone)]
@@ -306,6 +810,7 @@ pub fn set_oracle_subscriptions(env: &Env, oracle_ids: &Vec<Address>) {
         .set(&DataKey::OracleSubscriptions, oracle_ids);
 }
 
+#[allow(dead_code)]
 pub fn get_oracle_subscriptions(env: &Env) -> Vec<Address> {
     env.storage()
         .instance()
@@ -0,0 +1,198 @@
+#![cfg(test)]
+
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, vec, Address, Env, String, Vec,
+};
+use stellai_lib::ApprovalStatus;
+
+use crate::{
+    storage,
+    test_utils::{self, funded_account},
+    MarketplaceClient,
+};
+
+const PRICE: i128 = 10_000;
+
+struct Setup {
+    env: Env,
+    client: MarketplaceClient<'static>,
+    token: token::Client<'static>,
+    admin: Address,
+    seller: Address,
+    buyer: Address,
+    approvers: Vec<Address>,
+}
+
+fn setup() -> Setup {
+    let (env, client, admin, token_admin, token) = test_utils::setup();
+    client.set_approval_config(&admin, &5_000, &2, &3, &3600);
+
+    let buyer = funded_account(&env, &token_admin, PRICE);
+
+    let approvers = vec![
+        &env,
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    ];
+
+    Setup {
+        client,
+        token,
+        admin,
+        seller: Address::generate(&env),
+        buyer,
+        approvers,
+        env,
+    }
+}
+
+fn propose(s: &Setup) -> u64 {
+    let listing_id = s.client.create_listing(&1, &s.seller, &0, &PRICE);
+    s.client.propose_sale(&listing_id, &s.buyer, &s.approvers)
+}
+
+#[test]
+fn test_propose_sale_escrows_and_reject_refunds() {
+    let s = setup();
+    let approval_id = propose(&s);
+    assert_eq!(s.token.balance(&s.buyer), 0);
+    assert_eq!(s.token.balance(&s.client.address), PRICE);
+
+    s.client.reject_sale(
+        &approval_id,
+        &s.approvers.get(0).unwrap(),
+        &String::from_str(&s.env, "price too high"),
+    );
+
+    assert_eq!(s.token.balance(&s.buyer), PRICE);
+    assert_eq!(s.token.balance(&s.client.address), 0);
+}
+
+#[test]
+fn test_expired_approval_refunds_escrow() {
+    let s = setup();
+    let approval_id = propose(&s);
+
+    s.env.ledger().with_mut(|li| li.timestamp += 3600);
+    s.client.cleanup_expired_approvals();
+
+    let approval = s.client.get_approval(&approval_id).unwrap();
+    assert!(approval.status == ApprovalStatus::Expired);
+    assert_eq!(s.token.balance(&s.buyer), PRICE);
+}
+
+#[test]
+fn test_withdraw_approval_refunds_escrow() {
+    let s = setup();
+    let approval_id = propose(&s);
+
+    s.client.withdraw_approval(&approval_id, &s.buyer);
+
+    let approval = s.client.get_approval(&approval_id).unwrap();
+    assert!(approval.status == ApprovalStatus::Withdrawn);
+    assert_eq!(s.token.balance(&s.buyer), PRICE);
+
+    // Escrow is consumed, so a second withdrawal cannot refund again
+    let result = s.client.try_withdraw_approval(&approval_id, &s.buyer);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_execute_approved_sale_pays_from_escrow() {
+    let s = setup();
+    let approval_id = propose(&s);
+
+    s.client
+        .approve_sale(&approval_id, &s.approvers.get(0).unwrap());
+    s.client
+        .approve_sale(&approval_id, &s.approvers.get(1).unwrap());
+    s.client.execute_approved_sale(&approval_id);
+
+    // Buyer was charged exactly once; 2.5% marketplace fee stays in the contract
+    assert_eq!(s.token.balance(&s.buyer), 0);
+    assert_eq!(s.token.balance(&s.seller), 9_750);
+    assert_eq!(s.token.balance(&s.client.address), 250);
+}
+
+#[test]
+fn test_execute_on_sold_listing_refunds_escrow() {
+    let s = setup();
+    let listing_id = s.client.create_listing(&1, &s.seller, &0, &PRICE);
+    let other_buyer = Address::generate(&s.env);
+    token::StellarAssetClient::new(&s.env, &s.token.address).mint(&other_buyer, &PRICE);
+
+    let first = s.client.propose_sale(&listing_id, &s.buyer, &s.approvers);
+    let second = s
+        .client
+        .propose_sale(&listing_id, &other_buyer, &s.approvers);
+    for approval_id in [first, second] {
+        s.client
+            .approve_sale(&approval_id, &s.approvers.get(0).unwrap());
+        s.client
+            .approve_sale(&approval_id, &s.approvers.get(1).unwrap());
+    }
+
+    s.client.execute_approved_sale(&first);
+    s.client.create_listing(&2, &s.seller, &0, &PRICE);
+    assert_eq!(s.client.get_seller_listing_count(&s.seller), 1);
+
+    // The listing is already sold, so the second buyer gets their escrow back
+    s.client.execute_approved_sale(&second);
+    let approval = s.client.get_approval(&second).unwrap();
+    assert!(approval.status == ApprovalStatus::Rejected);
+    assert_eq!(s.token.balance(&other_buyer), PRICE);
+    assert_eq!(s.token.balance(&s.seller), 9_750);
+    assert_eq!(s.token.balance(&s.client.address), 250);
+    assert_eq!(s.client.get_seller_listing_count(&s.seller), 1);
+}
+
+#[test]
+fn test_repair_approval_counter_prevents_id_collision() {
+    let s = setup();
+    token::StellarAssetClient::new(&s.env, &s.token.address).mint(&s.buyer, &(PRICE * 2));
+    let first = propose(&s);
+    let second = propose(&s);
+    assert_eq!((first, second), (1, 2));
+
+    // Simulate a corrupted counter that would hand out an existing ID next
+    s.env.as_contract(&s.client.address, || {
+        storage::set_approval_counter(&s.env, 1);
+    });
+
+    assert_eq!(s.client.repair_approval_counter(&s.admin, &1), (2, None));
+
+    let third = propose(&s);
+    assert_eq!(third, 3);
+    assert_eq!(s.client.get_approval(&second).unwrap().listing_id, Some(2));
+    assert_eq!(s.client.get_approval(&third).unwrap().listing_id, Some(3));
+}
+
+#[test]
+fn test_repair_approval_counter_resumes_across_calls() {
+    let s = setup();
+    token::StellarAssetClient::new(&s.env, &s.token.address).mint(&s.buyer, &PRICE);
+    let first = propose(&s);
+
+    // Spread copies of the approval over more IDs than one call inspects
+    s.env.as_contract(&s.client.address, || {
+        let approval = storage::get_approval(&s.env, first).unwrap();
+        for approval_id in (90..=630u64).step_by(90) {
+            let mut copy = approval.clone();
+            copy.approval_id = approval_id;
+            storage::set_approval(&s.env, &copy);
+        }
+        storage::set_approval_counter(&s.env, 1);
+    });
+
+    assert_eq!(
+        s.client.repair_approval_counter(&s.admin, &1),
+        (450, Some(501))
+    );
+    assert_eq!(
+        s.client.repair_approval_counter(&s.admin, &501),
+        (630, None)
+    );
+    assert_eq!(propose(&s), 631);
+}
@@ -0,0 +1,164 @@
+#![cfg(test)]
+
+use soroban_sdk::{
+    contract, contractimpl, symbol_short,
+    testutils::{Address as _, Ledger},
+    token, Address, Env, String, Vec,
+};
+use stellai_lib::{Agent, AuctionStatus, AuctionType};
+
+use crate::{
+    test_utils::{self, funded_account},
+    MarketplaceClient,
+};
+
+// AgentNFT stand-in whose `transfer_agent` can be made to trap
+#[contract]
+pub struct MockTransferNFT;
+
+#[contractimpl]
+impl MockTransferNFT {
+    pub fn set_owner(env: Env, agent_id: u64, owner: Address) {
+        env.storage().instance().set(&agent_id, &owner);
+    }
+
+    pub fn set_fail_transfers(env: Env, fail: bool) {
+        env.storage().instance().set(&symbol_short!("fail"), &fail);
+    }
+
+    pub fn get_agent(env: Env, agent_id: u64) -> Agent {
+        Agent {
+            id: agent_id,
+            owner: Self::owner_of(env.clone(), agent_id),
+            name: String::from_str(&env, "agent"),
+            model_hash: String::from_str(&env, "hash"),
+            metadata_cid: String::from_str(&env, "cid"),
+            capabilities: Vec::new(&env),
+            evolution_level: 0,
+            created_at: 0,
+            updated_at: 0,
+            nonce: 0,
+            escrow_locked: false,
+            escrow_holder: None,
+        }
+    }
+
+    pub fn get_max_royalty(_env: Env) -> u32 {
+        stellai_lib::DEFAULT_MAX_ROYALTY_BPS
+    }
+
+    pub fn owner_of(env: Env, agent_id: u64) -> Address {
+        env.storage().instance().get(&agent_id).unwrap()
+    }
+
+    pub fn transfer_agent(env: Env, agent_id: u64, from: Address, to: Address) {
+        let fail: bool = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("fail"))
+            .unwrap_or(false);
+        if fail {
+            panic!("transfer trapped");
+        }
+        from.require_auth();
+        env.storage().instance().set(&agent_id, &to);
+    }
+}
+
+struct Setup {
+    env: Env,
+    client: MarketplaceClient<'static>,
+    nft: MockTransferNFTClient<'static>,
+    token: token::Client<'static>,
+    seller: Address,
+    bidder: Address,
+    auction_id: u64,
+}
+
+// Ended English auction with a 10_000 winning bid on agent 1
+fn setup() -> Setup {
+    let (env, client, admin, token_admin, token) = test_utils::setup();
+
+    let seller = Address::generate(&env);
+    let bidder = funded_account(&env, &token_admin, 10_000);
+    let nft = MockTransferNFTClient::new(&env, &env.register(MockTransferNFT, ()));
+    nft.set_owner(&1, &seller);
+    client.set_agent_nft(&admin, &nft.address);
+    client.set_royalty(&1, &seller, &Address::generate(&env), &1000);
+
+    let auction_id = client.create_auction(
+        &1,
+        &seller,
+        &AuctionType::English,
+        &1000,
+        &5_000,
+        &3600,
+        &0,
+        &None,
+        &None,
+        &None,
+    );
+    client.place_bid(&auction_id, &bidder, &10_000);
+    env.ledger().with_mut(|li| li.timestamp += 3601);
+
+    Setup {
+        client,
+        nft,
+        token,
+        seller,
+        bidder,
+        auction_id,
+        env,
+    }
+}
+
+#[test]
+fn test_atomic_resolve_transfers_agent_and_pays_out() {
+    let s = setup();
+
+    let status = s.client.resolve_auction_atomic(&s.auction_id);
+    assert_eq!(status, AuctionStatus::Won);
+    assert_eq!(s.nft.owner_of(&1), s.bidder);
+
+    // 250 marketplace fee and 1000 royalty come out of the 10_000 bid
+    assert_eq!(s.token.balance(&s.bidder), 0);
+    assert_eq!(s.token.balance(&s.seller), 8_750);
+}
+
+#[test]
+fn test_atomic_resolve_refunds_bidder_when_transfer_fails() {
+    let s = setup();
+    s.nft.set_fail_transfers(&true);
+
+    let status = s.client.resolve_auction_atomic(&s.auction_id);
+    assert_eq!(status, AuctionStatus::Ended);
+    assert_eq!(s.nft.owner_of(&1), s.seller);
+
+    assert_eq!(s.token.balance(&s.bidder), 10_000);
+    assert_eq!(s.token.balance(&s.seller), 0);
+    assert_eq!(s.token.balance(&s.client.address), 0);
+}
+
+#[test]
+fn test_atomic_resolve_without_winning_bid_matches_resolve() {
+    let s = setup();
+    let auction_id = s.client.create_auction(
+        &1,
+        &s.seller,
+        &AuctionType::English,
+        &1000,
+        &5_000,
+        &3600,
+        &0,
+        &None,
+        &None,
+        &None,
+    );
+    s.env.ledger().with_mut(|li| li.timestamp += 3601);
+
+    assert_eq!(
+        s.client.resolve_auction_atomic(&auction_id),
+        AuctionStatus::Ended
+    );
+    assert_eq!(s.nft.owner_of(&1), s.seller);
+}
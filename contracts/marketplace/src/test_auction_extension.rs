@@ -0,0 +1,84 @@
+#![cfg(test)]
+
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, Env,
+};
+use stellai_lib::AuctionType;
+
+use crate::{test_utils::setup, MarketplaceClient};
+
+fn create_english_auction(env: &Env, client: &MarketplaceClient) -> u64 {
+    client.create_auction(
+        &1,
+        &Address::generate(env),
+        &AuctionType::English,
+        &100,
+        &0,
+        &3600,
+        &500,
+        &None,
+        &None,
+        &None,
+    )
+}
+
+// Moves the ledger into the final minute of the auction and places a bid
+fn snipe(
+    env: &Env,
+    client: &MarketplaceClient,
+    token_admin: &token::StellarAssetClient,
+    auction_id: u64,
+    amount: i128,
+) {
+    let end_time = client.get_auction(&auction_id).unwrap().end_time;
+    env.ledger().with_mut(|li| li.timestamp = end_time - 60);
+    let bidder = Address::generate(env);
+    token_admin.mint(&bidder, &amount);
+    client.place_bid(&auction_id, &bidder, &amount);
+}
+
+#[test]
+fn test_late_bid_extends_and_counts() {
+    let (env, client, _, token_admin, _) = setup();
+    let auction_id = create_english_auction(&env, &client);
+
+    // An early bid does not extend
+    let bidder = Address::generate(&env);
+    token_admin.mint(&bidder, &100);
+    client.place_bid(&auction_id, &bidder, &100);
+    let auction = client.get_auction(&auction_id).unwrap();
+    assert_eq!(auction.extension_count, 0);
+    assert_eq!(auction.end_time, 3600);
+
+    snipe(&env, &client, &token_admin, auction_id, 200);
+    let auction = client.get_auction(&auction_id).unwrap();
+    assert_eq!(auction.extension_count, 1);
+    assert_eq!(auction.end_time, 3900);
+
+    snipe(&env, &client, &token_admin, auction_id, 300);
+    let auction = client.get_auction(&auction_id).unwrap();
+    assert_eq!(auction.extension_count, 2);
+    assert_eq!(auction.end_time, 4200);
+}
+
+#[test]
+fn test_extension_cap_stops_further_extensions() {
+    let (env, client, _, token_admin, _) = setup();
+    let auction_id = create_english_auction(&env, &client);
+
+    let mut amount = 100;
+    for _ in 0..10 {
+        snipe(&env, &client, &token_admin, auction_id, amount);
+        amount *= 2;
+    }
+    let capped = client.get_auction(&auction_id).unwrap();
+    assert_eq!(capped.extension_count, 10);
+
+    // The bid is still accepted, but the end time no longer moves
+    snipe(&env, &client, &token_admin, auction_id, amount);
+    let auction = client.get_auction(&auction_id).unwrap();
+    assert_eq!(auction.extension_count, 10);
+    assert_eq!(auction.end_time, capped.end_time);
+    assert_eq!(auction.highest_bid, amount);
+}
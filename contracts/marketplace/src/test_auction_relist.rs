@@ -0,0 +1,141 @@
+#![cfg(test)]
+
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, Env,
+};
+use stellai_lib::{AuctionStatus, AuctionType};
+
+use crate::{
+    test_utils::{self, funded_account},
+    MarketplaceClient,
+};
+
+struct Setup {
+    env: Env,
+    client: MarketplaceClient<'static>,
+    token: token::Client<'static>,
+    seller: Address,
+    bidder: Address,
+}
+
+fn setup() -> Setup {
+    let (env, client, _, token_admin, token) = test_utils::setup();
+    client.set_royalty(
+        &1,
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &1000,
+    );
+
+    let bidder = funded_account(&env, &token_admin, 10_000);
+
+    Setup {
+        client,
+        token,
+        seller: Address::generate(&env),
+        bidder,
+        env,
+    }
+}
+
+// English auction with a 5_000 reserve that has received one 2_000 bid
+fn auction_with_bid(s: &Setup) -> u64 {
+    let auction_id = s.client.create_auction(
+        &1,
+        &s.seller,
+        &AuctionType::English,
+        &100,
+        &5_000,
+        &3600,
+        &500,
+        &Some(10),
+        &None,
+        &None,
+    );
+    s.client.place_bid(&auction_id, &s.bidder, &2_000);
+    auction_id
+}
+
+#[test]
+fn test_relist_reserve_failed_auction() {
+    let s = setup();
+    let auction_id = auction_with_bid(&s);
+    s.env.ledger().with_mut(|li| li.timestamp += 3601);
+    s.client.resolve_auction(&auction_id);
+    assert_eq!(s.token.balance(&s.bidder), 10_000);
+
+    let new_id = s
+        .client
+        .relist_auction(&auction_id, &s.seller, &7200, &1_500);
+    assert_ne!(new_id, auction_id);
+
+    let relisted = s.client.get_auction(&new_id).unwrap();
+    assert_eq!(relisted.agent_id, 1);
+    assert_eq!(relisted.seller, s.seller);
+    assert_eq!(relisted.status, AuctionStatus::Active);
+    assert_eq!(relisted.reserve_price, 1_500);
+    assert_eq!(relisted.highest_bidder, None);
+    assert_eq!(relisted.highest_bid, 0);
+    assert_eq!(relisted.min_increment_absolute, 10);
+    assert_eq!(relisted.end_time, s.env.ledger().timestamp() + 7200);
+
+    // The lower reserve lets the same bid win this time
+    s.client.place_bid(&new_id, &s.bidder, &2_000);
+    s.env.ledger().with_mut(|li| li.timestamp += 7201);
+    s.client.resolve_auction(&new_id);
+    assert_eq!(
+        s.client.get_auction(&new_id).unwrap().status,
+        AuctionStatus::Won
+    );
+}
+
+#[test]
+#[should_panic(expected = "Auction did not fail its reserve")]
+fn test_relist_rejects_won_auction() {
+    let s = setup();
+    let auction_id = s.client.create_auction(
+        &1,
+        &s.seller,
+        &AuctionType::English,
+        &100,
+        &1_000,
+        &3600,
+        &500,
+        &None,
+        &None,
+        &None,
+    );
+    s.client.place_bid(&auction_id, &s.bidder, &2_000);
+    s.env.ledger().with_mut(|li| li.timestamp += 3601);
+    s.client.resolve_auction(&auction_id);
+
+    s.client.relist_auction(&auction_id, &s.seller, &3600, &500);
+}
+
+#[test]
+#[should_panic(expected = "Auction already relisted")]
+fn test_relist_only_once() {
+    let s = setup();
+    let auction_id = auction_with_bid(&s);
+    s.env.ledger().with_mut(|li| li.timestamp += 3601);
+    s.client.resolve_auction(&auction_id);
+
+    s.client
+        .relist_auction(&auction_id, &s.seller, &3600, &1_500);
+    s.client
+        .relist_auction(&auction_id, &s.seller, &3600, &1_500);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_relist_requires_seller() {
+    let s = setup();
+    let auction_id = auction_with_bid(&s);
+    s.env.ledger().with_mut(|li| li.timestamp += 3601);
+    s.client.resolve_auction(&auction_id);
+
+    let stranger = Address::generate(&s.env);
+    s.client
+        .relist_auction(&auction_id, &stranger, &3600, &1_500);
+}
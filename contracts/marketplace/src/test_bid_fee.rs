@@ -0,0 +1,114 @@
+#![cfg(test)]
+
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, Env,
+};
+use stellai_lib::AuctionType;
+
+use crate::{
+    test_utils::{self, funded_account},
+    MarketplaceClient,
+};
+
+struct Setup {
+    env: Env,
+    client: MarketplaceClient<'static>,
+    token: token::Client<'static>,
+    seller: Address,
+    first_bidder: Address,
+    second_bidder: Address,
+}
+
+fn setup() -> Setup {
+    let (env, client, _, token_admin, token) = test_utils::setup();
+    client.set_royalty(
+        &1,
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &1000,
+    );
+
+    let first_bidder = funded_account(&env, &token_admin, 10_000);
+    let second_bidder = funded_account(&env, &token_admin, 10_000);
+
+    Setup {
+        client,
+        token,
+        seller: Address::generate(&env),
+        first_bidder,
+        second_bidder,
+        env,
+    }
+}
+
+fn create_auction(s: &Setup, bid_fee: Option<i128>) -> u64 {
+    s.client.create_auction(
+        &1,
+        &s.seller,
+        &AuctionType::English,
+        &100,
+        &0,
+        &3600,
+        &0,
+        &None,
+        &None,
+        &bid_fee,
+    )
+}
+
+#[test]
+fn test_bid_fee_charged_on_each_bid() {
+    let s = setup();
+    let auction_id = create_auction(&s, Some(50));
+
+    s.client.place_bid(&auction_id, &s.first_bidder, &1_000);
+    s.client.place_bid(&auction_id, &s.second_bidder, &2_000);
+
+    // The outbid bidder gets the bid back but not the fee
+    assert_eq!(s.token.balance(&s.first_bidder), 9_950);
+    assert_eq!(s.token.balance(&s.second_bidder), 7_950);
+    assert_eq!(s.token.balance(&s.client.address), 2_100);
+
+    let auction = s.client.get_auction(&auction_id).unwrap();
+    assert_eq!(auction.bid_fee, 50);
+    assert_eq!(auction.accumulated_bid_fees, 100);
+}
+
+#[test]
+fn test_bid_fees_paid_to_seller_on_resolution() {
+    let s = setup();
+    let auction_id = create_auction(&s, Some(50));
+
+    s.client.place_bid(&auction_id, &s.first_bidder, &1_000);
+    s.client.place_bid(&auction_id, &s.second_bidder, &2_000);
+    s.env.ledger().with_mut(|li| li.timestamp += 3601);
+    s.client.resolve_auction(&auction_id);
+
+    // 2000 less 50 marketplace fee and 200 royalty, plus both bid fees
+    assert_eq!(s.token.balance(&s.seller), 1_850);
+    assert_eq!(
+        s.client
+            .get_auction(&auction_id)
+            .unwrap()
+            .accumulated_bid_fees,
+        0
+    );
+}
+
+#[test]
+fn test_zero_bid_fee_preserves_bidding() {
+    let s = setup();
+    let auction_id = create_auction(&s, None);
+
+    s.client.place_bid(&auction_id, &s.first_bidder, &1_000);
+    s.client.place_bid(&auction_id, &s.second_bidder, &2_000);
+
+    assert_eq!(s.token.balance(&s.first_bidder), 10_000);
+    assert_eq!(s.token.balance(&s.second_bidder), 8_000);
+    assert_eq!(s.token.balance(&s.client.address), 2_000);
+
+    s.env.ledger().with_mut(|li| li.timestamp += 3601);
+    s.client.resolve_auction(&auction_id);
+    assert_eq!(s.token.balance(&s.seller), 1_750);
+}
@@ -0,0 +1,58 @@
+#![cfg(test)]
+
+use soroban_sdk::{testutils::Address as _, Address};
+use stellai_lib::AuctionType;
+
+use crate::test_utils::{funded_account, setup};
+
+#[test]
+fn test_absolute_floor_dominates_low_priced_auction() {
+    let (env, client, _, token_admin, _) = setup();
+    let seller = Address::generate(&env);
+    let bidder = || funded_account(&env, &token_admin, 1_000_000);
+    // 5% of a 100 bid is 5, below the absolute floor of 10
+    let auction_id = client.create_auction(
+        &1,
+        &seller,
+        &AuctionType::English,
+        &50,
+        &0,
+        &3600,
+        &500,
+        &Some(10),
+        &None,
+        &None,
+    );
+
+    client.place_bid(&auction_id, &bidder(), &100);
+
+    let low = client.try_place_bid(&auction_id, &bidder(), &109);
+    assert!(low.is_err());
+    client.place_bid(&auction_id, &bidder(), &110);
+}
+
+#[test]
+fn test_bps_increment_dominates_high_priced_auction() {
+    let (env, client, _, token_admin, _) = setup();
+    let seller = Address::generate(&env);
+    let bidder = || funded_account(&env, &token_admin, 1_000_000);
+    // 5% of a 100_000 bid is 5_000, well above the default floor of 1
+    let auction_id = client.create_auction(
+        &1,
+        &seller,
+        &AuctionType::English,
+        &1000,
+        &0,
+        &3600,
+        &500,
+        &None,
+        &None,
+        &None,
+    );
+
+    client.place_bid(&auction_id, &bidder(), &100_000);
+
+    let low = client.try_place_bid(&auction_id, &bidder(), &104_999);
+    assert!(low.is_err());
+    client.place_bid(&auction_id, &bidder(), &105_000);
+}
@@ -0,0 +1,51 @@
+#![cfg(test)]
+
+use soroban_sdk::{testutils::Address as _, Address, Env, String, Vec};
+
+use crate::test_utils::setup_with_agent_nft;
+
+fn capabilities(env: &Env, names: &[&str]) -> Vec<String> {
+    let mut caps = Vec::new(env);
+    for name in names {
+        caps.push_back(String::from_str(env, name));
+    }
+    caps
+}
+
+#[test]
+fn test_get_listings_by_capability_filters_agents() {
+    let (env, client, agent_nft) = setup_with_agent_nft();
+    let seller = Address::generate(&env);
+
+    agent_nft.set_agent(&1, &seller, &capabilities(&env, &["vision", "nlp"]), &false);
+    agent_nft.set_agent(&2, &seller, &capabilities(&env, &["trading"]), &false);
+    agent_nft.set_agent(&3, &seller, &capabilities(&env, &["vision"]), &false);
+
+    let first = client.create_listing(&1, &seller, &0, &1000);
+    client.create_listing(&2, &seller, &0, &1000);
+    let third = client.create_listing(&3, &seller, &0, &1000);
+
+    let vision = client.get_listings_by_capability(&String::from_str(&env, "vision"), &1, &10);
+    assert_eq!(vision.len(), 2);
+    assert_eq!(vision.get(0).unwrap().listing_id, first);
+    assert_eq!(vision.get(1).unwrap().listing_id, third);
+
+    let audio = client.get_listings_by_capability(&String::from_str(&env, "audio"), &1, &10);
+    assert_eq!(audio.len(), 0);
+}
+
+#[test]
+fn test_get_listings_by_capability_skips_inactive_and_respects_limit() {
+    let (env, client, agent_nft) = setup_with_agent_nft();
+    let seller = Address::generate(&env);
+
+    for agent_id in 1..=3u64 {
+        agent_nft.set_agent(&agent_id, &seller, &capabilities(&env, &["vision"]), &false);
+        client.create_listing(&agent_id, &seller, &0, &1000);
+    }
+    client.cancel_listing(&1, &seller);
+
+    let vision = client.get_listings_by_capability(&String::from_str(&env, "vision"), &1, &1);
+    assert_eq!(vision.len(), 1);
+    assert_eq!(vision.get(0).unwrap().listing_id, 2);
+}
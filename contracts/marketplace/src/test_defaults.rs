@@ -0,0 +1,47 @@
+#![cfg(test)]
+
+use soroban_sdk::{testutils::Address as _, Address};
+use stellai_lib::{
+    DEFAULT_APPROVAL_THRESHOLD, DEFAULT_APPROVAL_TTL_SECONDS, DEFAULT_APPROVERS_REQUIRED,
+    DEFAULT_EARLY_TERMINATION_PENALTY_BPS, DEFAULT_LEASE_DEPOSIT_BPS, DEFAULT_TOTAL_APPROVERS,
+};
+
+use crate::test_utils::setup_without_token;
+
+#[test]
+fn test_defaults_before_configuration() {
+    let (_env, client, _admin) = setup_without_token();
+
+    assert_eq!(client.get_payment_token_address(), None);
+
+    let (approval, lease) = client.get_defaults();
+    assert_eq!(approval.threshold, DEFAULT_APPROVAL_THRESHOLD);
+    assert_eq!(approval.approvers_required, DEFAULT_APPROVERS_REQUIRED);
+    assert_eq!(approval.total_approvers, DEFAULT_TOTAL_APPROVERS);
+    assert_eq!(approval.ttl_seconds, DEFAULT_APPROVAL_TTL_SECONDS);
+    assert_eq!(lease.deposit_bps, DEFAULT_LEASE_DEPOSIT_BPS);
+    assert_eq!(
+        lease.early_termination_penalty_bps,
+        DEFAULT_EARLY_TERMINATION_PENALTY_BPS
+    );
+}
+
+#[test]
+fn test_getters_reflect_set_values() {
+    let (env, client, admin) = setup_without_token();
+
+    let token = Address::generate(&env);
+    client.set_payment_token(&admin, &token);
+    client.set_approval_config(&admin, &5_000, &3, &5, &3600);
+    client.set_lease_config(&admin, &500, &1500);
+
+    assert_eq!(client.get_payment_token_address(), Some(token));
+
+    let (approval, lease) = client.get_defaults();
+    assert_eq!(approval.threshold, 5_000);
+    assert_eq!(approval.approvers_required, 3);
+    assert_eq!(approval.total_approvers, 5);
+    assert_eq!(approval.ttl_seconds, 3600);
+    assert_eq!(lease.deposit_bps, 500);
+    assert_eq!(lease.early_termination_penalty_bps, 1500);
+}
@@ -0,0 +1,181 @@
+#![cfg(test)]
+
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+
+use crate::{test_utils::setup, MarketplaceError};
+
+const WINDOW: u64 = 3600;
+
+fn advance(env: &Env, seconds: u64) {
+    let now = env.ledger().timestamp();
+    env.ledger().with_mut(|li| li.timestamp = now + seconds);
+}
+
+#[test]
+fn test_release_after_window_pays_seller() {
+    let (env, client, _, token_admin, token) = setup();
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    token_admin.mint(&buyer, &1000);
+
+    let listing_id = client.create_escrow_listing(&seller, &1, &0, &1000, &WINDOW);
+    client.buy_agent(&listing_id, &buyer);
+
+    // Funds sit with the marketplace while the window is open
+    assert_eq!(token.balance(&seller), 0);
+    assert_eq!(token.balance(&client.address), 1000);
+    assert_eq!(
+        client.try_release_funds(&listing_id),
+        Err(Ok(MarketplaceError::DisputeWindowOpen))
+    );
+
+    advance(&env, WINDOW);
+    client.release_funds(&listing_id);
+
+    // Default 2.5% marketplace fee is retained
+    assert_eq!(token.balance(&seller), 975);
+    assert_eq!(token.balance(&client.address), 25);
+    assert!(client.get_sale_hold(&listing_id).is_none());
+    assert_eq!(
+        client.try_release_funds(&listing_id),
+        Err(Ok(MarketplaceError::NoHeldFunds))
+    );
+}
+
+#[test]
+fn test_dispute_blocks_release() {
+    let (env, client, _, token_admin, token) = setup();
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    token_admin.mint(&buyer, &1000);
+
+    let listing_id = client.create_escrow_listing(&seller, &1, &0, &1000, &WINDOW);
+    client.buy_agent(&listing_id, &buyer);
+    client.raise_dispute(&listing_id, &buyer);
+    assert!(client.get_sale_hold(&listing_id).unwrap().disputed);
+
+    advance(&env, WINDOW);
+    assert_eq!(
+        client.try_release_funds(&listing_id),
+        Err(Ok(MarketplaceError::SaleDisputed))
+    );
+    assert_eq!(token.balance(&seller), 0);
+}
+
+#[test]
+fn test_dispute_after_window_or_by_stranger_rejected() {
+    let (env, client, _, token_admin, _) = setup();
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    token_admin.mint(&buyer, &1000);
+
+    let listing_id = client.create_escrow_listing(&seller, &1, &0, &1000, &WINDOW);
+    client.buy_agent(&listing_id, &buyer);
+
+    assert_eq!(
+        client.try_raise_dispute(&listing_id, &Address::generate(&env)),
+        Err(Ok(MarketplaceError::Unauthorized))
+    );
+
+    advance(&env, WINDOW);
+    assert_eq!(
+        client.try_raise_dispute(&listing_id, &buyer),
+        Err(Ok(MarketplaceError::DisputeWindowClosed))
+    );
+}
+
+#[test]
+fn test_admin_resolution_refunds_buyer() {
+    let (env, client, admin, token_admin, token) = setup();
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    token_admin.mint(&buyer, &1000);
+
+    let listing_id = client.create_escrow_listing(&seller, &1, &0, &1000, &WINDOW);
+    client.buy_agent(&listing_id, &buyer);
+    client.raise_dispute(&listing_id, &buyer);
+
+    assert_eq!(
+        client.try_resolve_dispute(&Address::generate(&env), &listing_id, &true),
+        Err(Ok(MarketplaceError::Unauthorized))
+    );
+
+    client.resolve_dispute(&admin, &listing_id, &true);
+
+    assert_eq!(token.balance(&buyer), 1000);
+    assert_eq!(token.balance(&seller), 0);
+    assert!(client.get_sale_hold(&listing_id).is_none());
+    assert!(client.get_listing(&listing_id).unwrap().active);
+}
+
+#[test]
+fn test_admin_resolution_can_pay_seller() {
+    let (env, client, admin, token_admin, token) = setup();
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    token_admin.mint(&buyer, &1000);
+
+    let listing_id = client.create_escrow_listing(&seller, &1, &0, &1000, &WINDOW);
+    client.buy_agent(&listing_id, &buyer);
+    client.raise_dispute(&listing_id, &buyer);
+    client.resolve_dispute(&admin, &listing_id, &false);
+
+    assert_eq!(token.balance(&seller), 975);
+    assert!(!client.get_listing(&listing_id).unwrap().active);
+}
+
+#[test]
+fn test_invalid_dispute_window_rejected() {
+    let (env, client, ..) = setup();
+    let seller = Address::generate(&env);
+
+    assert_eq!(
+        client.try_create_escrow_listing(&seller, &1, &0, &1000, &0),
+        Err(Ok(MarketplaceError::InvalidDisputeWindow))
+    );
+}
+
+#[test]
+fn test_refunded_dispute_counts_toward_listing_cap() {
+    let (env, client, admin, token_admin, _) = setup();
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    token_admin.mint(&buyer, &1000);
+    client.set_max_listings_per_seller(&admin, &1);
+
+    let listing_id = client.create_escrow_listing(&seller, &1, &0, &1000, &WINDOW);
+    client.buy_agent(&listing_id, &buyer);
+    client.raise_dispute(&listing_id, &buyer);
+    client.resolve_dispute(&admin, &listing_id, &true);
+
+    assert!(client.get_listing(&listing_id).unwrap().active);
+    assert_eq!(client.get_seller_listing_count(&seller), 1);
+    assert_eq!(
+        client.try_create_listing(&2, &seller, &0, &1000),
+        Err(Ok(MarketplaceError::ListingCapReached))
+    );
+}
+
+#[test]
+fn test_refunded_dispute_stays_closed_when_seller_at_cap() {
+    let (env, client, admin, token_admin, token) = setup();
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    token_admin.mint(&buyer, &1000);
+    client.set_max_listings_per_seller(&admin, &1);
+
+    let listing_id = client.create_escrow_listing(&seller, &1, &0, &1000, &WINDOW);
+    client.buy_agent(&listing_id, &buyer);
+    client.raise_dispute(&listing_id, &buyer);
+
+    // The held sale freed the seller's slot, which a new listing now uses
+    client.create_listing(&2, &seller, &0, &1000);
+    client.resolve_dispute(&admin, &listing_id, &true);
+
+    assert_eq!(token.balance(&buyer), 1000);
+    assert!(!client.get_listing(&listing_id).unwrap().active);
+    assert_eq!(client.get_seller_listing_count(&seller), 1);
+}
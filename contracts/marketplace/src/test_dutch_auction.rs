@@ -0,0 +1,173 @@
+#![cfg(test)]
+
+use soroban_sdk::{
+    contract, contractimpl,
+    testutils::{Address as _, Ledger},
+    token, Address, Env, Symbol,
+};
+use stellai_lib::{Auction, AuctionStatus, AuctionType, OracleData};
+
+use crate::{storage, test_utils, MarketplaceClient};
+
+fn auction(env: &Env, client: &MarketplaceClient, auction_id: u64) -> Auction {
+    env.as_contract(&client.address, || {
+        storage::get_auction(env, auction_id).unwrap()
+    })
+}
+
+// Stands in for the Oracle contract's `get_data` feed
+#[contract]
+pub struct MockPriceOracle;
+
+#[contractimpl]
+impl MockPriceOracle {
+    pub fn set_price(env: Env, key: Symbol, value: i128, timestamp: u64) {
+        let data = OracleData {
+            key: key.clone(),
+            value,
+            timestamp,
+            provider: env.current_contract_address(),
+            signature: None,
+            source: None,
+        };
+        env.storage().instance().set(&key, &data);
+    }
+
+    pub fn get_data(env: Env, key: Symbol) -> Option<OracleData> {
+        env.storage().instance().get(&key)
+    }
+}
+
+fn setup() -> (
+    Env,
+    MarketplaceClient<'static>,
+    token::StellarAssetClient<'static>,
+    Address,
+    u64,
+) {
+    let (env, client, _, token_admin, _) = test_utils::setup();
+
+    let seller = Address::generate(&env);
+    client.set_royalty(&1, &seller, &Address::generate(&env), &0);
+    let auction_id = client.create_auction(
+        &1,
+        &seller,
+        &AuctionType::Dutch,
+        &2000,
+        &1000,
+        &1000,
+        &0,
+        &None,
+        &None,
+        &None,
+    );
+
+    (env, client, token_admin, seller, auction_id)
+}
+
+#[test]
+fn test_accept_dutch_price_before_end() {
+    let (env, client, token_admin, _seller, auction_id) = setup();
+    let buyer = Address::generate(&env);
+    token_admin.mint(&buyer, &2000);
+
+    env.ledger().with_mut(|li| li.timestamp += 500);
+    client.accept_dutch_price(&auction_id, &buyer);
+
+    let auction = auction(&env, &client, auction_id);
+    assert_eq!(auction.status, AuctionStatus::Won);
+    assert_eq!(auction.highest_bid, 1500);
+}
+
+#[test]
+#[should_panic(expected = "Auction expired")]
+fn test_accept_dutch_price_after_end_rejected() {
+    let (env, client, token_admin, _seller, auction_id) = setup();
+    let buyer = Address::generate(&env);
+    token_admin.mint(&buyer, &2000);
+
+    env.ledger().with_mut(|li| li.timestamp += 1000);
+    client.accept_dutch_price(&auction_id, &buyer);
+}
+
+#[test]
+fn test_expire_dutch_auction_without_buyer() {
+    let (env, client, _token_admin, _seller, auction_id) = setup();
+
+    assert!(client.try_expire_dutch_auction(&auction_id).is_err());
+
+    env.ledger().with_mut(|li| li.timestamp += 1000);
+    client.expire_dutch_auction(&auction_id);
+
+    let auction = auction(&env, &client, auction_id);
+    assert_eq!(auction.status, AuctionStatus::Ended);
+}
+
+fn oracle_setup() -> (
+    Env,
+    MarketplaceClient<'static>,
+    token::StellarAssetClient<'static>,
+    MockPriceOracleClient<'static>,
+    u64,
+) {
+    let (env, client, admin, token_admin, _) = test_utils::setup();
+    env.ledger().with_mut(|li| li.timestamp = 10_000);
+
+    let oracle_id = env.register(MockPriceOracle, ());
+    client.set_price_oracle(&admin, &oracle_id);
+
+    let seller = Address::generate(&env);
+    client.set_royalty(&1, &seller, &Address::generate(&env), &0);
+    let auction_id = client.create_auction(
+        &1,
+        &seller,
+        &AuctionType::Dutch,
+        &2000,
+        &1000,
+        &1000,
+        &0,
+        &None,
+        &Some(Symbol::new(&env, "agent_price")),
+        &None,
+    );
+
+    (
+        env.clone(),
+        client,
+        token_admin,
+        MockPriceOracleClient::new(&env, &oracle_id),
+        auction_id,
+    )
+}
+
+#[test]
+fn test_oracle_priced_dutch_auction_pays_oracle_price() {
+    let (env, client, token_admin, oracle, auction_id) = oracle_setup();
+    let buyer = Address::generate(&env);
+    token_admin.mint(&buyer, &5000);
+
+    // Well off the linear curve, which would give 2000 at this point
+    oracle.set_price(&Symbol::new(&env, "agent_price"), &1750, &10_000);
+    assert_eq!(client.calculate_dutch_price(&auction_id), 1750);
+
+    client.accept_dutch_price(&auction_id, &buyer);
+
+    let auction = auction(&env, &client, auction_id);
+    assert_eq!(auction.status, AuctionStatus::Won);
+    assert_eq!(auction.highest_bid, 1750);
+    assert_eq!(
+        token::Client::new(&env, &token_admin.address).balance(&buyer),
+        3250
+    );
+}
+
+#[test]
+#[should_panic(expected = "Stale oracle price")]
+fn test_oracle_priced_dutch_auction_rejects_stale_price() {
+    let (env, client, token_admin, oracle, auction_id) = oracle_setup();
+    let buyer = Address::generate(&env);
+    token_admin.mint(&buyer, &5000);
+
+    oracle.set_price(&Symbol::new(&env, "agent_price"), &1750, &9_000);
+    client.accept_dutch_price(&auction_id, &buyer);
+}
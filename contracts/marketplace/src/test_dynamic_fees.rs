@@ -4,7 +4,7 @@ use super::*;
 use soroban_sdk::{testutils::Address as _, Address, Env, Vec};
 
 fn create_marketplace_contract(env: &Env) -> Address {
-    env.register_contract(None, Marketplace)
+    env.register(Marketplace, ())
 }
 
 fn setup_marketplace_with_fee_adjustment(
@@ -219,7 +219,7 @@ fn test_fee_transition_mechanism() {
 
         // Fee should still be reasonable (transition in progress)
         let transitioning_fee = Marketplace::get_current_marketplace_fee(env.clone());
-        assert!(transitioning_fee >= 50 && transitioning_fee <= 1000);
+        assert!((50..=1000).contains(&transitioning_fee));
     });
 }
 
@@ -0,0 +1,97 @@
+#![cfg(test)]
+
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, Env,
+};
+use stellai_lib::{AuctionStatus, AuctionType};
+
+use crate::{
+    storage,
+    test_utils::{self, funded_account},
+    MarketplaceClient,
+};
+
+struct Setup {
+    env: Env,
+    client: MarketplaceClient<'static>,
+    token: token::Client<'static>,
+    admin: Address,
+    seller: Address,
+    bidder: Address,
+    auction_id: u64,
+}
+
+// English auction with a 5_000 bid, ended but not yet resolved
+fn setup() -> Setup {
+    let (env, client, admin, token_admin, token) = test_utils::setup();
+
+    let seller = Address::generate(&env);
+    let bidder = funded_account(&env, &token_admin, 5_000);
+
+    let auction_id = client.create_auction(
+        &1,
+        &seller,
+        &AuctionType::English,
+        &1000,
+        &0,
+        &3600,
+        &0,
+        &None,
+        &None,
+        &None,
+    );
+    client.place_bid(&auction_id, &bidder, &5_000);
+    env.ledger().with_mut(|li| li.timestamp += 3600);
+
+    Setup {
+        client,
+        token,
+        admin,
+        seller,
+        bidder,
+        auction_id,
+        env,
+    }
+}
+
+fn auction_status(s: &Setup) -> AuctionStatus {
+    s.env.as_contract(&s.client.address, || {
+        storage::get_auction(&s.env, s.auction_id).unwrap().status
+    })
+}
+
+#[test]
+fn test_admin_refunds_stuck_auction() {
+    let s = setup();
+    // No royalty info is set, so resolution cannot complete
+    assert!(s.client.try_resolve_auction(&s.auction_id).is_err());
+
+    s.client.emergency_refund_bid(&s.auction_id, &s.admin);
+
+    assert_eq!(s.token.balance(&s.bidder), 5_000);
+    assert_eq!(s.token.balance(&s.client.address), 0);
+    assert_eq!(auction_status(&s), AuctionStatus::Ended);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_non_admin_cannot_refund() {
+    let s = setup();
+    s.client.emergency_refund_bid(&s.auction_id, &s.bidder);
+}
+
+#[test]
+fn test_resolvable_auction_unaffected() {
+    let s = setup();
+    s.client
+        .set_royalty(&1, &s.seller, &Address::generate(&s.env), &0);
+
+    s.client.resolve_auction(&s.auction_id);
+    assert_eq!(auction_status(&s), AuctionStatus::Won);
+
+    // Once resolved there is nothing left to refund
+    let result = s.client.try_emergency_refund_bid(&s.auction_id, &s.admin);
+    assert!(result.is_err());
+    assert_eq!(s.token.balance(&s.bidder), 0);
+}
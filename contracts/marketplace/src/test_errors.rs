@@ -0,0 +1,78 @@
+#![cfg(test)]
+
+use soroban_sdk::{testutils::Address as _, vec, Address};
+
+use crate::{test_utils::setup, MarketplaceError};
+
+#[test]
+fn test_create_listing_error_codes() {
+    let (env, client, admin, ..) = setup();
+    let seller = Address::generate(&env);
+
+    assert_eq!(
+        client.try_create_listing(&0, &seller, &0, &1000),
+        Err(Ok(MarketplaceError::InvalidAgentId))
+    );
+    assert_eq!(
+        client.try_create_listing(&1, &seller, &3, &1000),
+        Err(Ok(MarketplaceError::InvalidListingType))
+    );
+    assert_eq!(
+        client.try_create_listing(&1, &seller, &0, &0),
+        Err(Ok(MarketplaceError::PriceNotPositive))
+    );
+
+    client.set_paused(&admin, &true);
+    assert_eq!(
+        client.try_create_listing(&1, &seller, &0, &1000),
+        Err(Ok(MarketplaceError::Paused))
+    );
+}
+
+#[test]
+fn test_buy_agent_error_codes() {
+    let (env, client, ..) = setup();
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    assert_eq!(
+        client.try_buy_agent(&0, &buyer),
+        Err(Ok(MarketplaceError::InvalidListingId))
+    );
+    assert_eq!(
+        client.try_buy_agent(&42, &buyer),
+        Err(Ok(MarketplaceError::ListingNotFound))
+    );
+
+    let listing_id = client.create_listing(&1, &seller, &0, &1000);
+    client.cancel_listing(&listing_id, &seller);
+    assert_eq!(
+        client.try_buy_agent(&listing_id, &buyer),
+        Err(Ok(MarketplaceError::ListingInactive))
+    );
+}
+
+#[test]
+fn test_cancel_listing_by_non_seller_unauthorized() {
+    let (env, client, ..) = setup();
+    let seller = Address::generate(&env);
+    let listing_id = client.create_listing(&1, &seller, &0, &1000);
+
+    assert_eq!(
+        client.try_cancel_listing(&listing_id, &Address::generate(&env)),
+        Err(Ok(MarketplaceError::Unauthorized))
+    );
+    assert!(client.get_listing(&listing_id).unwrap().active);
+}
+
+#[test]
+fn test_propose_sale_below_threshold_not_required() {
+    let (env, client, ..) = setup();
+    let seller = Address::generate(&env);
+    let listing_id = client.create_listing(&1, &seller, &0, &1000);
+
+    assert_eq!(
+        client.try_propose_sale(&listing_id, &Address::generate(&env), &vec![&env, seller]),
+        Err(Ok(MarketplaceError::ApprovalNotRequired))
+    );
+}
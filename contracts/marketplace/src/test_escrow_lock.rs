@@ -0,0 +1,49 @@
+#![cfg(test)]
+
+use soroban_sdk::{testutils::Address as _, Address, Vec};
+use stellai_lib::AuctionType;
+
+use crate::{test_utils::setup_with_agent_nft, MarketplaceError};
+
+#[test]
+fn test_list_unlocked_agent() {
+    let (env, client, agent_nft) = setup_with_agent_nft();
+    let seller = Address::generate(&env);
+    agent_nft.set_agent(&1, &seller, &Vec::new(&env), &false);
+
+    let listing_id = client.create_listing(&1, &seller, &0, &1000);
+    assert!(client.get_listing(&listing_id).unwrap().active);
+}
+
+#[test]
+fn test_list_escrow_locked_agent_rejected() {
+    let (env, client, agent_nft) = setup_with_agent_nft();
+    let seller = Address::generate(&env);
+    agent_nft.set_agent(&1, &seller, &Vec::new(&env), &true);
+
+    assert_eq!(
+        client.try_create_listing(&1, &seller, &0, &1000),
+        Err(Ok(MarketplaceError::AgentEscrowLocked))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #10)")]
+fn test_auction_escrow_locked_agent_panics() {
+    let (env, client, agent_nft) = setup_with_agent_nft();
+    let seller = Address::generate(&env);
+    agent_nft.set_agent(&1, &seller, &Vec::new(&env), &true);
+
+    client.create_auction(
+        &1,
+        &seller,
+        &AuctionType::English,
+        &1000,
+        &500,
+        &3600,
+        &500,
+        &None,
+        &None,
+        &None,
+    );
+}
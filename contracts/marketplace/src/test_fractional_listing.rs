@@ -0,0 +1,77 @@
+#![cfg(test)]
+
+use soroban_sdk::{testutils::Address as _, Address};
+
+use crate::{
+    test_utils::{funded_account, setup},
+    MarketplaceError,
+};
+
+#[test]
+fn test_partial_purchases_by_multiple_buyers() {
+    let (env, client, _, token_admin, token) = setup();
+    let seller = Address::generate(&env);
+    let alice = funded_account(&env, &token_admin, 10_000);
+    let bob = funded_account(&env, &token_admin, 10_000);
+
+    let listing_id = client.create_fractional_listing(&seller, &1, &100, &10);
+    client.buy_shares(&listing_id, &alice, &30);
+    client.buy_shares(&listing_id, &bob, &20);
+    client.buy_shares(&listing_id, &alice, &5);
+
+    assert_eq!(client.get_shares(&listing_id, &alice), 35);
+    assert_eq!(client.get_shares(&listing_id, &bob), 20);
+    assert_eq!(token.balance(&alice), 10_000 - 350);
+    assert_eq!(token.balance(&client.address), 550);
+
+    let listing = client.get_fractional_listing(&listing_id).unwrap();
+    assert_eq!(listing.available_shares, 45);
+    assert_eq!(listing.proceeds, 550);
+
+    assert_eq!(client.claim_share_proceeds(&listing_id, &seller), 550);
+    assert_eq!(token.balance(&seller), 550);
+}
+
+#[test]
+fn test_selling_out_closes_listing() {
+    let (env, client, _, token_admin, _) = setup();
+    let seller = Address::generate(&env);
+    let buyer = funded_account(&env, &token_admin, 10_000);
+
+    let listing_id = client.create_fractional_listing(&seller, &1, &10, &100);
+    client.buy_shares(&listing_id, &buyer, &10);
+
+    assert_eq!(client.get_shares(&listing_id, &buyer), 10);
+    assert_eq!(
+        client
+            .get_fractional_listing(&listing_id)
+            .unwrap()
+            .available_shares,
+        0
+    );
+    assert_eq!(
+        client.try_buy_shares(&listing_id, &funded_account(&env, &token_admin, 10_000), &1),
+        Err(Ok(MarketplaceError::ListingInactive))
+    );
+}
+
+#[test]
+fn test_over_buying_rejected() {
+    let (env, client, _, token_admin, token) = setup();
+    let seller = Address::generate(&env);
+    let buyer = funded_account(&env, &token_admin, 10_000);
+
+    let listing_id = client.create_fractional_listing(&seller, &1, &10, &100);
+    client.buy_shares(&listing_id, &buyer, &6);
+
+    assert_eq!(
+        client.try_buy_shares(&listing_id, &buyer, &5),
+        Err(Ok(MarketplaceError::InsufficientShares))
+    );
+    assert_eq!(
+        client.try_buy_shares(&listing_id, &buyer, &0),
+        Err(Ok(MarketplaceError::InvalidShareAmount))
+    );
+    assert_eq!(client.get_shares(&listing_id, &buyer), 6);
+    assert_eq!(token.balance(&buyer), 10_000 - 600);
+}
@@ -2,11 +2,13 @@
 
 #![cfg(test)]
 
-use soroban_sdk::{Address, Env, Symbol, String};
-use soroban_sdk::testutils::Address as _;
-use stellai_lib::{LeaseData, LeaseState, LeaseHistoryEntry, Listing, ListingType, LISTING_COUNTER_KEY};
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::{token, vec, Address, Env, String, Symbol, Vec};
+use stellai_lib::{
+    LeaseData, LeaseHistoryEntry, LeaseState, Listing, ListingType, LISTING_COUNTER_KEY,
+};
 
-use crate::{Marketplace, MarketplaceClient, storage::*};
+use crate::{storage::*, LeaseError, Marketplace, MarketplaceClient, MarketplaceError};
 
 /// Setup env with marketplace initialized and a lease written to storage (no token needed).
 /// Call after init_contract; all storage writes run inside contract context.
@@ -56,6 +58,8 @@ fn setup_lease_in_storage(env: &Env, contract_id: &Address) -> (Address, Address
             lessee_consent_for_renewal: false,
             status: LeaseState::Active,
             pending_extension_id: None,
+            streaming: false,
+            claimed_amount: 0,
         };
         set_lease(env, &lease);
         lessee_leases_append(env, &lessee, lease_id);
@@ -78,7 +82,7 @@ fn setup_lease_in_storage(env: &Env, contract_id: &Address) -> (Address, Address
 fn test_lease_config_default() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register_contract(None, Marketplace);
+    let contract_id = env.register(Marketplace, ());
     let client = MarketplaceClient::new(&env, &contract_id);
     let admin = Address::generate(&env);
 
@@ -96,7 +100,7 @@ fn test_lease_config_default() {
 fn test_set_lease_config() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register_contract(None, Marketplace);
+    let contract_id = env.register(Marketplace, ());
     let client = MarketplaceClient::new(&env, &contract_id);
     let admin = Address::generate(&env);
 
@@ -112,7 +116,7 @@ fn test_set_lease_config() {
 fn test_get_lease_by_id_and_active_leases() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register_contract(None, Marketplace);
+    let contract_id = env.register(Marketplace, ());
     let client = MarketplaceClient::new(&env, &contract_id);
     let admin = Address::generate(&env);
     client.init_contract(&admin);
@@ -137,7 +141,7 @@ fn test_get_lease_by_id_and_active_leases() {
 fn test_lease_extension_request_and_approve() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register_contract(None, Marketplace);
+    let contract_id = env.register(Marketplace, ());
     let client = MarketplaceClient::new(&env, &contract_id);
     let admin = Address::generate(&env);
     client.init_contract(&admin);
@@ -165,7 +169,7 @@ fn test_lease_extension_request_and_approve() {
 fn test_lease_history() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register_contract(None, Marketplace);
+    let contract_id = env.register(Marketplace, ());
     let client = MarketplaceClient::new(&env, &contract_id);
     let admin = Address::generate(&env);
     client.init_contract(&admin);
@@ -173,12 +177,531 @@ fn test_lease_history() {
 
     let history_before = client.get_lease_history(&lease_id);
     assert_eq!(history_before.len(), 1);
-    assert_eq!(history_before.get(0).unwrap().action, String::from_str(&env, "initiated"));
+    assert_eq!(
+        history_before.get(0).unwrap().action,
+        String::from_str(&env, "initiated")
+    );
 
     client.request_lease_extension(&lease_id, &lessee, &3600);
 
     let history = client.get_lease_history(&lease_id);
     assert!(history.len() >= 2);
-    assert_eq!(history.get(0).unwrap().action, String::from_str(&env, "initiated"));
-    assert_eq!(history.get(1).unwrap().action, String::from_str(&env, "extension_requested"));
+    assert_eq!(
+        history.get(0).unwrap().action,
+        String::from_str(&env, "initiated")
+    );
+    assert_eq!(
+        history.get(1).unwrap().action,
+        String::from_str(&env, "extension_requested")
+    );
+}
+
+/// Setup env with marketplace, payment token and an active Lease listing priced at 10_000.
+fn setup_lease_listing(
+    env: &Env,
+) -> (
+    MarketplaceClient<'_>,
+    token::Client<'_>,
+    Address,
+    Address,
+    u64,
+) {
+    let contract_id = env.register(Marketplace, ());
+    let client = MarketplaceClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.init_contract(&admin);
+
+    let token_id = env
+        .register_stellar_asset_contract_v2(Address::generate(env))
+        .address();
+    client.set_payment_token(&admin, &token_id);
+
+    let lessor = Address::generate(env);
+    let lessee = Address::generate(env);
+    token::StellarAssetClient::new(env, &token_id).mint(&lessee, &11_000);
+
+    let listing_id = client.create_listing(&10, &lessor, &1, &10_000);
+    (
+        client,
+        token::Client::new(env, &token_id),
+        lessor,
+        lessee,
+        listing_id,
+    )
+}
+
+#[test]
+fn test_streaming_lease_claim_partway() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, lessor, lessee, listing_id) = setup_lease_listing(&env);
+
+    let lease_id = client.initiate_lease(&listing_id, &lessee, &1000, &false, &true);
+    // Lease value and 10% deposit are both held by the contract
+    assert_eq!(token.balance(&client.address), 11_000);
+    assert_eq!(token.balance(&lessor), 0);
+
+    env.ledger().with_mut(|li| li.timestamp += 250);
+    assert_eq!(client.claim_lease_revenue(&lease_id, &lessor), 2_500);
+    assert_eq!(token.balance(&lessor), 2_500);
+
+    // Nothing new vests without time passing
+    assert_eq!(client.claim_lease_revenue(&lease_id, &lessor), 0);
+
+    env.ledger().with_mut(|li| li.timestamp += 250);
+    assert_eq!(client.claim_lease_revenue(&lease_id, &lessor), 2_500);
+    assert_eq!(
+        client.get_lease_by_id(&lease_id).unwrap().claimed_amount,
+        5_000
+    );
+}
+
+#[test]
+fn test_streaming_lease_early_termination_refunds_unvested() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, lessor, lessee, listing_id) = setup_lease_listing(&env);
+
+    let lease_id = client.initiate_lease(&listing_id, &lessee, &1000, &false, &true);
+    env.ledger().with_mut(|li| li.timestamp += 200);
+    client.claim_lease_revenue(&lease_id, &lessor);
+
+    env.ledger().with_mut(|li| li.timestamp += 200);
+    client.early_termination(&lease_id, &lessee);
+
+    // 40% vested to the lessor; penalty is 20% of the unused 6_000, capped at the 1_000 deposit
+    assert_eq!(token.balance(&lessor), 4_000 + 1_000);
+    assert_eq!(token.balance(&lessee), 6_000);
+    assert_eq!(token.balance(&client.address), 0);
+
+    let lease = client.get_lease_by_id(&lease_id).unwrap();
+    assert!(lease.status == LeaseState::Terminated);
+    assert!(client.get_listing(&listing_id).unwrap().active);
+
+    // Vesting stops once the lease is terminated
+    env.ledger().with_mut(|li| li.timestamp += 1000);
+    assert_eq!(client.claim_lease_revenue(&lease_id, &lessor), 0);
+}
+
+#[test]
+fn test_streaming_lease_rejects_extension() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _, lessee, listing_id) = setup_lease_listing(&env);
+
+    let lease_id = client.initiate_lease(&listing_id, &lessee, &1000, &false, &true);
+    assert_eq!(
+        client.try_request_lease_extension(&lease_id, &lessee, &1000),
+        Err(Ok(LeaseError::StreamingExtensionUnsupported))
+    );
+}
+
+#[test]
+fn test_streaming_settlement_never_drops_below_claimed() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, lessor, lessee, listing_id) = setup_lease_listing(&env);
+
+    let lease_id = client.initiate_lease(&listing_id, &lessee, &1000, &false, &true);
+    env.ledger().with_mut(|li| li.timestamp += 500);
+    assert_eq!(client.claim_lease_revenue(&lease_id, &lessor), 5_000);
+
+    // A lease whose schedule was stretched after the claim now vests less
+    // than the lessor already took
+    env.as_contract(&client.address, || {
+        let mut lease = get_lease(&env, lease_id).unwrap();
+        lease.duration_seconds = 2000;
+        lease.end_time = lease.start_time + 2000;
+        set_lease(&env, &lease);
+    });
+    env.ledger().with_mut(|li| li.timestamp += 100);
+    client.early_termination(&lease_id, &lessee);
+
+    // Only the unclaimed value is refunded, so no more than the 11_000
+    // deposited ever leaves the contract
+    assert_eq!(token.balance(&lessee) + token.balance(&lessor), 11_000);
+    assert_eq!(token.balance(&client.address), 0);
+}
+
+#[test]
+fn test_streaming_lease_expiry_pays_lessor_and_refunds_deposit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, lessor, lessee, listing_id) = setup_lease_listing(&env);
+
+    let lease_id = client.initiate_lease(&listing_id, &lessee, &1000, &false, &true);
+    env.ledger().with_mut(|li| li.timestamp += 1000);
+    client.settle_lease_expiry(&lease_id);
+
+    assert_eq!(token.balance(&lessor), 10_000);
+    assert_eq!(token.balance(&lessee), 1_000);
+    assert_eq!(token.balance(&client.address), 0);
+}
+
+#[test]
+fn test_cancelled_lease_listing_stays_closed_after_lease_ends() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _token, lessor, lessee, listing_id) = setup_lease_listing(&env);
+
+    let lease_id = client.initiate_lease(&listing_id, &lessee, &1000, &false, &false);
+    client.cancel_listing(&listing_id, &lessor);
+
+    env.ledger().with_mut(|li| li.timestamp += 1000);
+    client.settle_lease_expiry(&lease_id);
+
+    assert!(!client.get_listing(&listing_id).unwrap().active);
+    assert_eq!(
+        client.try_initiate_lease(&listing_id, &lessee, &1000, &false, &false),
+        Err(Ok(LeaseError::ListingInactive))
+    );
+}
+
+#[test]
+fn test_upfront_lease_pays_lessor_and_holds_deposit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, lessor, lessee, listing_id) = setup_lease_listing(&env);
+
+    let lease_id = client.initiate_lease(&listing_id, &lessee, &1000, &false, &false);
+    assert_eq!(token.balance(&lessor), 10_000);
+    assert_eq!(token.balance(&client.address), 1_000);
+
+    let result = client.try_claim_lease_revenue(&lease_id, &lessor);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_get_leases_filters_by_status_and_pages() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, lessor, lessee, first_listing) = setup_lease_listing(&env);
+    token::StellarAssetClient::new(&env, &token.address).mint(&lessee, &22_000);
+
+    let second_listing = client.create_listing(&11, &lessor, &1, &10_000);
+    let third_listing = client.create_listing(&12, &lessor, &1, &10_000);
+    let active = client.initiate_lease(&first_listing, &lessee, &1000, &false, &false);
+    let extending = client.initiate_lease(&second_listing, &lessee, &1000, &false, &false);
+    let terminated = client.initiate_lease(&third_listing, &lessee, &1000, &false, &false);
+    client.request_lease_extension(&extending, &lessee, &500);
+    client.early_termination(&terminated, &lessee);
+
+    let ids = |leases: Vec<LeaseData>| {
+        let mut ids = Vec::new(&env);
+        for lease in leases.iter() {
+            ids.push_back(lease.lease_id);
+        }
+        ids
+    };
+
+    let all = client.get_leases(&lessee, &None, &0, &10);
+    assert_eq!(ids(all), vec![&env, active, extending, terminated]);
+
+    let filtered = client.get_leases(&lessee, &Some(LeaseState::Active), &0, &10);
+    assert_eq!(ids(filtered), vec![&env, active]);
+    let filtered = client.get_leases(&lessee, &Some(LeaseState::ExtensionRequested), &0, &10);
+    assert_eq!(ids(filtered), vec![&env, extending]);
+    let filtered = client.get_leases(&lessor, &Some(LeaseState::Terminated), &0, &10);
+    assert_eq!(ids(filtered), vec![&env, terminated]);
+    assert_eq!(
+        client
+            .get_leases(&lessee, &Some(LeaseState::Renewed), &0, &10)
+            .len(),
+        0
+    );
+
+    let page = client.get_leases(&lessee, &None, &1, &1);
+    assert_eq!(ids(page), vec![&env, extending]);
+    assert_eq!(client.get_leases(&lessee, &None, &3, &10).len(), 0);
+}
+
+#[test]
+fn test_renew_expired_extension_request_then_approve() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(Marketplace, ());
+    let client = MarketplaceClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.init_contract(&admin);
+    let (lessor, lessee, lease_id, _) = setup_lease_in_storage(&env, &contract_id);
+
+    let extension_id = client.request_lease_extension(&lease_id, &lessee, &3600);
+
+    // Renewing is only possible once the request has expired
+    let early = client.try_renew_extension_request(&lease_id, &extension_id, &lessee);
+    assert!(early.is_err());
+
+    env.ledger()
+        .with_mut(|li| li.timestamp += stellai_lib::LEASE_EXTENSION_REQUEST_TTL_SECONDS + 1);
+    let expired = client.try_approve_lease_extension(&lease_id, &extension_id, &lessor);
+    assert!(expired.is_err());
+
+    client.renew_extension_request(&lease_id, &extension_id, &lessee);
+    client.approve_lease_extension(&lease_id, &extension_id, &lessor);
+
+    let lease = client.get_lease_by_id(&lease_id).unwrap();
+    assert!(lease.status == LeaseState::Active);
+    assert_eq!(lease.duration_seconds, 86400 * 30 + 3600);
+}
+
+#[test]
+fn test_cleanup_expires_extension_requests_after_grace_period() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(Marketplace, ());
+    let client = MarketplaceClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.init_contract(&admin);
+    let (_lessor, lessee, lease_id, _) = setup_lease_in_storage(&env, &contract_id);
+
+    let extension_id = client.request_lease_extension(&lease_id, &lessee, &3600);
+
+    // Still within the grace period: nothing is swept
+    env.ledger()
+        .with_mut(|li| li.timestamp += stellai_lib::LEASE_EXTENSION_REQUEST_TTL_SECONDS + 1);
+    assert_eq!(client.cleanup_expired_extensions(&1, &10), 0);
+
+    env.ledger()
+        .with_mut(|li| li.timestamp += stellai_lib::LEASE_EXTENSION_GRACE_PERIOD_SECONDS);
+    // A batch that does not cover the lease leaves it pending
+    assert_eq!(client.cleanup_expired_extensions(&(lease_id + 1), &10), 0);
+    assert_eq!(client.cleanup_expired_extensions(&lease_id, &1), 1);
+
+    let lease = client.get_lease_by_id(&lease_id).unwrap();
+    assert!(lease.status == LeaseState::Active);
+    assert_eq!(lease.pending_extension_id, None);
+    let renew = client.try_renew_extension_request(&lease_id, &extension_id, &lessee);
+    assert!(renew.is_err());
+}
+
+fn marketplace_admin(env: &Env, client: &MarketplaceClient) -> Address {
+    env.as_contract(&client.address, || {
+        env.storage().instance().get(&DataKey::Admin).unwrap()
+    })
+}
+
+#[test]
+fn test_lease_expiry_refunds_full_deposit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, lessor, lessee, listing_id) = setup_lease_listing(&env);
+
+    let lease_id = client.initiate_lease(&listing_id, &lessee, &1000, &false, &false);
+    env.ledger().with_mut(|li| li.timestamp += 1000);
+    client.settle_lease_expiry(&lease_id);
+
+    assert_eq!(token.balance(&lessor), 10_000);
+    assert_eq!(token.balance(&lessee), 1_000);
+}
+
+#[test]
+fn test_settle_with_deduction_within_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, lessor, lessee, listing_id) = setup_lease_listing(&env);
+    client.set_max_deduction_bps(&marketplace_admin(&env, &client), &3000);
+
+    let lease_id = client.initiate_lease(&listing_id, &lessee, &1000, &false, &false);
+    env.ledger().with_mut(|li| li.timestamp += 1000);
+    let reason = String::from_str(&env, "corrupted model weights");
+    client.settle_lease_with_deduction(&lease_id, &lessor, &300, &reason);
+
+    // 300 of the 1_000 deposit goes to the lessor, the rest back to the lessee
+    assert_eq!(token.balance(&lessor), 10_300);
+    assert_eq!(token.balance(&lessee), 700);
+    assert_eq!(token.balance(&client.address), 0);
+
+    let lease = client.get_lease_by_id(&lease_id).unwrap();
+    assert!(lease.status == LeaseState::Terminated);
+    assert!(client.get_listing(&listing_id).unwrap().active);
+
+    let history = client.get_lease_history(&lease_id);
+    let last = history.get(history.len() - 1).unwrap();
+    assert_eq!(
+        last.action,
+        String::from_str(&env, "expired_with_deduction")
+    );
+    assert_eq!(last.details, Some(reason));
+}
+
+#[test]
+#[should_panic(expected = "Deduction exceeds cap")]
+fn test_settle_with_deduction_over_cap_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _token, lessor, lessee, listing_id) = setup_lease_listing(&env);
+    client.set_max_deduction_bps(&marketplace_admin(&env, &client), &3000);
+
+    let lease_id = client.initiate_lease(&listing_id, &lessee, &1000, &false, &false);
+    env.ledger().with_mut(|li| li.timestamp += 1000);
+    client.settle_lease_with_deduction(&lease_id, &lessor, &301, &String::from_str(&env, "damage"));
+}
+
+#[test]
+fn test_prorated_lease_cost_scales_with_duration() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_client, lessor, lessee, _) = setup_lease_listing(&env);
+    let short_listing = client.create_prorated_lease_listing(&lessor, &11, &5);
+    let long_listing = client.create_prorated_lease_listing(&lessor, &12, &5);
+    assert_eq!(client.get_lease_price_per_second(&short_listing), Some(5));
+
+    let short = client.initiate_lease(&short_listing, &lessee, &100, &false, &false);
+    let long = client.initiate_lease(&long_listing, &lessee, &1000, &false, &false);
+
+    let short_value = client.get_lease_by_id(&short).unwrap().total_value;
+    let long_value = client.get_lease_by_id(&long).unwrap().total_value;
+    assert_eq!(short_value, 500);
+    assert_eq!(long_value, 5000);
+    // Both lease values plus their 10% deposits
+    assert_eq!(token_client.balance(&lessee), 11_000 - 550 - 5500);
+    assert_eq!(token_client.balance(&lessor), 5500);
+}
+
+#[test]
+fn test_flat_lease_price_ignores_duration() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _, lessee, listing_id) = setup_lease_listing(&env);
+    assert_eq!(client.get_lease_price_per_second(&listing_id), None);
+
+    let lease_id = client.initiate_lease(&listing_id, &lessee, &10, &false, &false);
+    assert_eq!(
+        client.get_lease_by_id(&lease_id).unwrap().total_value,
+        10_000
+    );
+}
+
+#[test]
+fn test_prorated_lease_rejects_excessive_duration() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, lessor, lessee, _) = setup_lease_listing(&env);
+    let listing_id = client.create_prorated_lease_listing(&lessor, &11, &5);
+
+    let too_long = stellai_lib::MAX_DURATION_DAYS * 86400 + 1;
+    assert_eq!(
+        client.try_initiate_lease(&listing_id, &lessee, &too_long, &false, &false),
+        Err(Ok(LeaseError::InvalidDuration))
+    );
+
+    let huge_rate = client.create_prorated_lease_listing(&lessor, &12, &(i128::MAX / 2));
+    assert_eq!(
+        client.try_initiate_lease(&huge_rate, &lessee, &3, &false, &false),
+        Err(Ok(LeaseError::PriceOverflow))
+    );
+}
+
+#[test]
+fn test_lease_status_view_mid_term() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _, lessee, listing_id) = setup_lease_listing(&env);
+    let lease_id = client.initiate_lease(&listing_id, &lessee, &1000, &false, &false);
+
+    env.ledger().with_mut(|li| li.timestamp += 400);
+    assert_eq!(
+        client.get_lease_status_view(&lease_id),
+        (LeaseState::Active, 600, false, None)
+    );
+    assert_eq!(
+        client.try_get_lease_status_view(&99),
+        Err(Ok(LeaseError::LeaseNotFound))
+    );
+}
+
+#[test]
+fn test_lease_status_view_expired_auto_renew() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _, lessee, listing_id) = setup_lease_listing(&env);
+    let lease_id = client.initiate_lease(&listing_id, &lessee, &1000, &true, &false);
+
+    env.ledger().with_mut(|li| li.timestamp += 1500);
+    assert_eq!(
+        client.get_lease_status_view(&lease_id),
+        (LeaseState::Active, 0, true, None)
+    );
+}
+
+#[test]
+fn test_lease_status_view_pending_extension() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _, lessee, listing_id) = setup_lease_listing(&env);
+    let lease_id = client.initiate_lease(&listing_id, &lessee, &1000, &true, &false);
+    let extension_id = client.request_lease_extension(&lease_id, &lessee, &500);
+
+    assert_eq!(
+        client.get_lease_status_view(&lease_id),
+        (
+            LeaseState::ExtensionRequested,
+            1000,
+            false,
+            Some(extension_id)
+        )
+    );
+}
+
+#[test]
+fn test_bounded_lease_accepts_durations_in_range() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, lessor, lessee, _) = setup_lease_listing(&env);
+    let listing_id = client.create_lease_listing(&lessor, &11, &100, &Some(600), &Some(3600));
+    assert_eq!(
+        client.get_lease_duration_bounds(&listing_id),
+        (Some(600), Some(3600))
+    );
+
+    let lease_id = client.initiate_lease(&listing_id, &lessee, &600, &false, &false);
+    assert_eq!(
+        client.get_lease_by_id(&lease_id).unwrap().duration_seconds,
+        600
+    );
+
+    let max_listing = client.create_lease_listing(&lessor, &12, &100, &Some(600), &Some(3600));
+    client.initiate_lease(&max_listing, &lessee, &3600, &false, &false);
+}
+
+#[test]
+fn test_bounded_lease_rejects_durations_out_of_range() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, lessor, lessee, _) = setup_lease_listing(&env);
+    let listing_id = client.create_lease_listing(&lessor, &11, &100, &Some(600), &Some(3600));
+
+    assert_eq!(
+        client.try_initiate_lease(&listing_id, &lessee, &599, &false, &false),
+        Err(Ok(LeaseError::InvalidDuration))
+    );
+    assert_eq!(
+        client.try_initiate_lease(&listing_id, &lessee, &3601, &false, &false),
+        Err(Ok(LeaseError::InvalidDuration))
+    );
+
+    // Only a minimum: long leases stay allowed
+    let min_only = client.create_lease_listing(&lessor, &12, &100, &Some(600), &None);
+    assert_eq!(
+        client.try_initiate_lease(&min_only, &lessee, &10, &false, &false),
+        Err(Ok(LeaseError::InvalidDuration))
+    );
+    client.initiate_lease(&min_only, &lessee, &86400, &false, &false);
+}
+
+#[test]
+fn test_lease_listing_bounds_validated() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, lessor, _, listing_id) = setup_lease_listing(&env);
+    assert_eq!(client.get_lease_duration_bounds(&listing_id), (None, None));
+
+    assert_eq!(
+        client.try_create_lease_listing(&lessor, &11, &100, &Some(3600), &Some(600)),
+        Err(Ok(MarketplaceError::InvalidLeaseBounds))
+    );
+    assert_eq!(
+        client.try_create_lease_listing(&lessor, &11, &100, &None, &Some(0)),
+        Err(Ok(MarketplaceError::InvalidLeaseBounds))
+    );
 }
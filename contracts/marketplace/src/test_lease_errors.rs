@@ -0,0 +1,179 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::{token, Address, Env};
+use stellai_lib::{LEASE_EXTENSION_REQUEST_TTL_SECONDS, MAX_DURATION_DAYS};
+
+use crate::{LeaseError, Marketplace, MarketplaceClient};
+
+const DURATION: u64 = 1000;
+
+/// Marketplace with a funded lessee and an active Lease listing priced at 10_000
+fn setup(env: &Env) -> (MarketplaceClient<'_>, Address, Address, Address, u64) {
+    env.mock_all_auths();
+    let contract_id = env.register(Marketplace, ());
+    let client = MarketplaceClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.init_contract(&admin);
+
+    let token_id = env
+        .register_stellar_asset_contract_v2(Address::generate(env))
+        .address();
+    client.set_payment_token(&admin, &token_id);
+
+    let lessor = Address::generate(env);
+    let lessee = Address::generate(env);
+    token::StellarAssetClient::new(env, &token_id).mint(&lessee, &100_000);
+
+    let listing_id = client.create_listing(&10, &lessor, &1, &10_000);
+    (client, admin, lessor, lessee, listing_id)
+}
+
+#[test]
+fn test_initiate_lease_error_codes() {
+    let env = Env::default();
+    let (client, admin, lessor, lessee, listing_id) = setup(&env);
+
+    assert_eq!(
+        client.try_initiate_lease(&99, &lessee, &DURATION, &false, &false),
+        Err(Ok(LeaseError::ListingNotFound))
+    );
+
+    let sale_listing = client.create_listing(&11, &lessor, &0, &10_000);
+    assert_eq!(
+        client.try_initiate_lease(&sale_listing, &lessee, &DURATION, &false, &false),
+        Err(Ok(LeaseError::NotLeaseListing))
+    );
+    assert_eq!(
+        client.try_initiate_lease(&listing_id, &lessor, &DURATION, &false, &false),
+        Err(Ok(LeaseError::SelfLease))
+    );
+    assert_eq!(
+        client.try_initiate_lease(&listing_id, &lessee, &0, &false, &false),
+        Err(Ok(LeaseError::InvalidDuration))
+    );
+    let too_long = MAX_DURATION_DAYS * 86400 + 1;
+    assert_eq!(
+        client.try_initiate_lease(&listing_id, &lessee, &too_long, &false, &false),
+        Err(Ok(LeaseError::InvalidDuration))
+    );
+
+    client.set_paused(&admin, &true);
+    assert_eq!(
+        client.try_initiate_lease(&listing_id, &lessee, &DURATION, &false, &false),
+        Err(Ok(LeaseError::Paused))
+    );
+    client.set_paused(&admin, &false);
+
+    client.initiate_lease(&listing_id, &lessee, &DURATION, &false, &false);
+    assert_eq!(
+        client.try_initiate_lease(&listing_id, &lessee, &DURATION, &false, &false),
+        Err(Ok(LeaseError::ListingInactive))
+    );
+}
+
+#[test]
+fn test_lease_extension_error_codes() {
+    let env = Env::default();
+    let (client, _admin, lessor, lessee, listing_id) = setup(&env);
+    let lease_id = client.initiate_lease(&listing_id, &lessee, &DURATION, &false, &false);
+
+    assert_eq!(
+        client.try_request_lease_extension(&99, &lessee, &100),
+        Err(Ok(LeaseError::LeaseNotFound))
+    );
+    assert_eq!(
+        client.try_request_lease_extension(&lease_id, &lessor, &100),
+        Err(Ok(LeaseError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_request_lease_extension(&lease_id, &lessee, &0),
+        Err(Ok(LeaseError::InvalidDuration))
+    );
+    assert_eq!(
+        client.try_approve_lease_extension(&lease_id, &1, &lessor),
+        Err(Ok(LeaseError::ExtensionNotPending))
+    );
+
+    let extension_id = client.request_lease_extension(&lease_id, &lessee, &100);
+    assert_eq!(
+        client.try_request_lease_extension(&lease_id, &lessee, &100),
+        Err(Ok(LeaseError::LeaseNotActive))
+    );
+    assert_eq!(
+        client.try_approve_lease_extension(&lease_id, &extension_id, &lessee),
+        Err(Ok(LeaseError::Unauthorized))
+    );
+
+    env.ledger()
+        .with_mut(|li| li.timestamp += LEASE_EXTENSION_REQUEST_TTL_SECONDS + 1);
+    assert_eq!(
+        client.try_approve_lease_extension(&lease_id, &extension_id, &lessor),
+        Err(Ok(LeaseError::ExtensionExpired))
+    );
+}
+
+#[test]
+fn test_request_extension_after_end_rejected() {
+    let env = Env::default();
+    let (client, _admin, _lessor, lessee, listing_id) = setup(&env);
+    let lease_id = client.initiate_lease(&listing_id, &lessee, &DURATION, &false, &false);
+
+    env.ledger().with_mut(|li| li.timestamp += DURATION);
+    assert_eq!(
+        client.try_request_lease_extension(&lease_id, &lessee, &100),
+        Err(Ok(LeaseError::LeaseEnded))
+    );
+}
+
+#[test]
+fn test_early_termination_error_codes() {
+    let env = Env::default();
+    let (client, _admin, lessor, lessee, listing_id) = setup(&env);
+    let lease_id = client.initiate_lease(&listing_id, &lessee, &DURATION, &false, &false);
+
+    assert_eq!(
+        client.try_early_termination(&99, &lessee),
+        Err(Ok(LeaseError::LeaseNotFound))
+    );
+    assert_eq!(
+        client.try_early_termination(&lease_id, &lessor),
+        Err(Ok(LeaseError::Unauthorized))
+    );
+
+    client.early_termination(&lease_id, &lessee);
+    assert_eq!(
+        client.try_early_termination(&lease_id, &lessee),
+        Err(Ok(LeaseError::AlreadyTerminated))
+    );
+
+    let second = client.initiate_lease(&listing_id, &lessee, &DURATION, &false, &false);
+    env.ledger().with_mut(|li| li.timestamp += DURATION);
+    assert_eq!(
+        client.try_early_termination(&second, &lessee),
+        Err(Ok(LeaseError::LeaseEnded))
+    );
+}
+
+#[test]
+fn test_settle_lease_expiry_error_codes() {
+    let env = Env::default();
+    let (client, _admin, _lessor, lessee, listing_id) = setup(&env);
+    let lease_id = client.initiate_lease(&listing_id, &lessee, &DURATION, &false, &false);
+
+    assert_eq!(
+        client.try_settle_lease_expiry(&99),
+        Err(Ok(LeaseError::LeaseNotFound))
+    );
+    assert_eq!(
+        client.try_settle_lease_expiry(&lease_id),
+        Err(Ok(LeaseError::NotYetExpired))
+    );
+
+    env.ledger().with_mut(|li| li.timestamp += DURATION);
+    client.settle_lease_expiry(&lease_id);
+    assert_eq!(
+        client.try_settle_lease_expiry(&lease_id),
+        Err(Ok(LeaseError::AlreadyTerminated))
+    );
+}
@@ -0,0 +1,80 @@
+#![cfg(test)]
+
+use soroban_sdk::{testutils::Address as _, token, Address};
+
+use crate::{test_utils::setup_without_token, MarketplaceError};
+
+#[test]
+fn test_seller_can_list_up_to_cap() {
+    let (env, client, admin) = setup_without_token();
+    let seller = Address::generate(&env);
+    client.set_max_listings_per_seller(&admin, &2);
+
+    client.create_listing(&1, &seller, &0, &1000);
+    client.create_listing(&2, &seller, &0, &1000);
+    assert_eq!(client.get_seller_listing_count(&seller), 2);
+
+    assert_eq!(
+        client.try_create_listing(&3, &seller, &0, &1000),
+        Err(Ok(MarketplaceError::ListingCapReached))
+    );
+
+    // The cap is per seller
+    let other = Address::generate(&env);
+    client.create_listing(&3, &other, &0, &1000);
+    assert_eq!(client.get_seller_listing_count(&other), 1);
+}
+
+#[test]
+fn test_cancel_and_sale_free_listing_slots() {
+    let (env, client, admin) = setup_without_token();
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.set_payment_token(&admin, &token_id);
+    token::StellarAssetClient::new(&env, &token_id).mint(&buyer, &1000);
+    client.set_max_listings_per_seller(&admin, &2);
+
+    let first = client.create_listing(&1, &seller, &0, &1000);
+    let second = client.create_listing(&2, &seller, &0, &1000);
+
+    client.cancel_listing(&first, &seller);
+    // Cancelling twice does not free a second slot
+    client.cancel_listing(&first, &seller);
+    assert_eq!(client.get_seller_listing_count(&seller), 1);
+    client.create_listing(&3, &seller, &0, &1000);
+
+    client.buy_agent(&second, &buyer);
+    assert_eq!(client.get_seller_listing_count(&seller), 1);
+    client.create_listing(&4, &seller, &0, &1000);
+    assert_eq!(client.get_seller_listing_count(&seller), 2);
+}
+
+#[test]
+fn test_raising_cap_allows_more_listings() {
+    let (env, client, admin) = setup_without_token();
+    let seller = Address::generate(&env);
+    assert_eq!(client.get_max_listings_per_seller(), 0);
+    client.set_max_listings_per_seller(&admin, &1);
+
+    client.create_listing(&1, &seller, &0, &1000);
+    assert_eq!(
+        client.try_create_listing(&2, &seller, &0, &1000),
+        Err(Ok(MarketplaceError::ListingCapReached))
+    );
+
+    client.set_max_listings_per_seller(&admin, &3);
+    client.create_listing(&2, &seller, &0, &1000);
+    client.create_listing(&3, &seller, &0, &1000);
+    assert_eq!(client.get_seller_listing_count(&seller), 3);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_set_listing_cap_requires_admin() {
+    let (env, client, _) = setup_without_token();
+    let stranger = Address::generate(&env);
+    client.set_max_listings_per_seller(&stranger, &1);
+}
@@ -0,0 +1,98 @@
+#![cfg(test)]
+
+use soroban_sdk::{contract, contractimpl, testutils::Address as _, Address, Env, Symbol};
+use stellai_lib::OracleData;
+
+use crate::{
+    test_utils::{self, funded_account},
+    MarketplaceClient, MarketplaceError,
+};
+
+// Listing price in token units; the threshold is 10_000 reference units
+const PRICE: i128 = 5_000;
+
+// Stands in for the Oracle contract reporting a token/reference exchange rate
+#[contract]
+pub struct MockRateOracle;
+
+#[contractimpl]
+impl MockRateOracle {
+    pub fn set_rate(env: Env, key: Symbol, value: i128) {
+        let data = OracleData {
+            key: key.clone(),
+            value,
+            timestamp: env.ledger().timestamp(),
+            provider: env.current_contract_address(),
+            signature: None,
+            source: None,
+        };
+        env.storage().instance().set(&key, &data);
+    }
+
+    pub fn get_data(env: Env, key: Symbol) -> Option<OracleData> {
+        env.storage().instance().get(&key)
+    }
+}
+
+fn setup() -> (
+    Env,
+    MarketplaceClient<'static>,
+    Address,
+    MockRateOracleClient<'static>,
+    Address,
+) {
+    let (env, client, admin, token_admin, _) = test_utils::setup();
+    client.set_approval_config(&admin, &10_000, &2, &3, &3600);
+
+    let buyer = funded_account(&env, &token_admin, PRICE);
+
+    let oracle_id = env.register(MockRateOracle, ());
+    client.set_price_oracle(&admin, &oracle_id);
+
+    (
+        env.clone(),
+        client,
+        admin,
+        MockRateOracleClient::new(&env, &oracle_id),
+        buyer,
+    )
+}
+
+#[test]
+fn test_low_rate_keeps_sale_below_threshold() {
+    let (env, client, admin, oracle, buyer) = setup();
+    let key = Symbol::new(&env, "xlm_usd");
+    client.set_approval_threshold_oracle(&admin, &Some(key.clone()));
+    oracle.set_rate(&key, &10_000_000); // 1.0 -> 5_000 reference units
+
+    let listing_id = client.create_listing(&1, &Address::generate(&env), &0, &PRICE);
+    client.buy_agent(&listing_id, &buyer);
+
+    assert!(!client.get_listing(&listing_id).unwrap().active);
+}
+
+#[test]
+fn test_high_rate_pushes_same_price_over_threshold() {
+    let (env, client, admin, oracle, buyer) = setup();
+    let key = Symbol::new(&env, "xlm_usd");
+    client.set_approval_threshold_oracle(&admin, &Some(key.clone()));
+    oracle.set_rate(&key, &30_000_000); // 3.0 -> 15_000 reference units
+
+    let listing_id = client.create_listing(&1, &Address::generate(&env), &0, &PRICE);
+    assert_eq!(
+        client.try_buy_agent(&listing_id, &buyer),
+        Err(Ok(MarketplaceError::ApprovalRequired))
+    );
+}
+
+#[test]
+fn test_fixed_threshold_used_without_oracle_key() {
+    let (env, client, _admin, oracle, buyer) = setup();
+    // A rate is published but the threshold is not tied to it
+    oracle.set_rate(&Symbol::new(&env, "xlm_usd"), &30_000_000);
+
+    let listing_id = client.create_listing(&1, &Address::generate(&env), &0, &PRICE);
+    client.buy_agent(&listing_id, &buyer);
+
+    assert!(!client.get_listing(&listing_id).unwrap().active);
+}
@@ -0,0 +1,69 @@
+#![cfg(test)]
+
+use soroban_sdk::{contract, contractimpl, testutils::Address as _, Address, Env};
+
+use crate::{test_utils::setup_without_token, MarketplaceClient};
+
+// Stands in for the Governance contract executing an EmergencyPause proposal
+#[contract]
+pub struct MockGovernance;
+
+#[contractimpl]
+impl MockGovernance {
+    pub fn emergency_pause(env: Env, marketplace: Address, paused: bool) {
+        MarketplaceClient::new(&env, &marketplace)
+            .set_paused(&env.current_contract_address(), &paused);
+    }
+}
+
+fn setup() -> (Env, MarketplaceClient<'static>, Address, Address) {
+    let (env, client, admin) = setup_without_token();
+
+    let governance = env.register(MockGovernance, ());
+    client.set_governance_contract(&admin, &governance);
+
+    (env, client, admin, governance)
+}
+
+#[test]
+fn test_governance_pause_blocks_create_listing() {
+    let (env, client, _admin, governance) = setup();
+    let seller = Address::generate(&env);
+
+    MockGovernanceClient::new(&env, &governance).emergency_pause(&client.address, &true);
+    assert!(client.is_paused());
+
+    let result = client.try_create_listing(&1, &seller, &0, &1000);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_governance_unpause_restores_create_listing() {
+    let (env, client, _admin, governance) = setup();
+    let seller = Address::generate(&env);
+    let gov_client = MockGovernanceClient::new(&env, &governance);
+
+    gov_client.emergency_pause(&client.address, &true);
+    gov_client.emergency_pause(&client.address, &false);
+    assert!(!client.is_paused());
+
+    let listing_id = client.create_listing(&1, &seller, &0, &1000);
+    assert!(client.get_listing(&listing_id).is_some());
+}
+
+#[test]
+fn test_admin_can_pause() {
+    let (_env, client, admin, _governance) = setup();
+
+    client.set_paused(&admin, &true);
+    assert!(client.is_paused());
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_unauthorized_pause_rejected() {
+    let (env, client, _admin, _governance) = setup();
+    let stranger = Address::generate(&env);
+
+    client.set_paused(&stranger, &true);
+}
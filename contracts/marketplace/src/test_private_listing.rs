@@ -0,0 +1,67 @@
+#![cfg(test)]
+
+use soroban_sdk::{testutils::Address as _, vec, Address, Vec};
+
+use crate::{test_utils::setup, MarketplaceError};
+
+#[test]
+fn test_allowed_buyer_can_buy_private_listing() {
+    let (env, client, _, token_admin, _) = setup();
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    token_admin.mint(&buyer, &1000);
+
+    let listing_id =
+        client.create_private_listing(&seller, &1, &0, &1000, &vec![&env, buyer.clone()]);
+    client.buy_agent(&listing_id, &buyer);
+
+    assert!(!client.get_listing(&listing_id).unwrap().active);
+}
+
+#[test]
+fn test_non_allowed_buyer_rejected() {
+    let (env, client, _, token_admin, _) = setup();
+    let seller = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    token_admin.mint(&stranger, &1000);
+
+    let listing_id =
+        client.create_private_listing(&seller, &1, &0, &1000, &vec![&env, Address::generate(&env)]);
+    assert_eq!(
+        client.try_buy_agent(&listing_id, &stranger),
+        Err(Ok(MarketplaceError::BuyerNotAllowed))
+    );
+}
+
+#[test]
+fn test_public_listing_and_cleared_allow_list() {
+    let (env, client, _, token_admin, _) = setup();
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    token_admin.mint(&buyer, &2000);
+
+    let public_id = client.create_private_listing(&seller, &1, &0, &1000, &Vec::new(&env));
+    client.buy_agent(&public_id, &buyer);
+
+    // Clearing the allow-list opens a private listing to everyone
+    let private_id =
+        client.create_private_listing(&seller, &2, &0, &1000, &vec![&env, Address::generate(&env)]);
+    assert!(client.try_buy_agent(&private_id, &buyer).is_err());
+    client.update_allowed_buyers(&private_id, &seller, &Vec::new(&env));
+    assert_eq!(client.get_allowed_buyers(&private_id).len(), 0);
+    client.buy_agent(&private_id, &buyer);
+}
+
+#[test]
+fn test_propose_sale_rejects_non_allowed_buyer() {
+    let (env, client, ..) = setup();
+    let seller = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    let listing_id =
+        client.create_private_listing(&seller, &1, &0, &1000, &vec![&env, Address::generate(&env)]);
+    assert_eq!(
+        client.try_propose_sale(&listing_id, &stranger, &vec![&env, seller.clone()]),
+        Err(Ok(MarketplaceError::BuyerNotAllowed))
+    );
+}
@@ -0,0 +1,48 @@
+#![cfg(test)]
+
+use soroban_sdk::{contract, contractimpl, testutils::Address as _, Address, Env};
+
+use crate::test_utils::setup_without_token;
+
+// Stands in for the AgentNFT contract's configurable royalty cap
+#[contract]
+pub struct MockRoyaltyCap;
+
+#[contractimpl]
+impl MockRoyaltyCap {
+    pub fn set_max_royalty(env: Env, bps: u32) {
+        env.storage().instance().set(&0u32, &bps);
+    }
+
+    pub fn get_max_royalty(env: Env) -> u32 {
+        env.storage().instance().get(&0u32).unwrap()
+    }
+}
+
+#[test]
+#[should_panic(expected = "Royalty fee exceeds maximum")]
+fn test_royalty_above_default_cap_rejected() {
+    let (env, client, _admin) = setup_without_token();
+    let creator = Address::generate(&env);
+
+    client.set_royalty(&1, &creator, &creator, &1001);
+}
+
+#[test]
+fn test_royalty_cap_follows_agent_nft() {
+    let (env, client, admin) = setup_without_token();
+    let creator = Address::generate(&env);
+
+    let cap_id = env.register(MockRoyaltyCap, ());
+    let cap = MockRoyaltyCapClient::new(&env, &cap_id);
+    cap.set_max_royalty(&2000);
+    client.set_agent_nft(&admin, &cap_id);
+
+    client.set_royalty(&1, &creator, &creator, &2000);
+    assert_eq!(client.get_royalty(&1).unwrap().fee, 2000);
+
+    cap.set_max_royalty(&500);
+    assert!(client
+        .try_set_royalty(&2, &creator, &creator, &600)
+        .is_err());
+}
@@ -0,0 +1,99 @@
+//! Fixtures shared by the marketplace test modules.
+
+#![cfg(test)]
+
+use soroban_sdk::{
+    contract, contractimpl, testutils::Address as _, token, Address, Env, String, Vec,
+};
+use stellai_lib::Agent;
+
+use crate::{Marketplace, MarketplaceClient};
+
+/// Register and initialize a marketplace without a payment token
+pub fn setup_without_token() -> (Env, MarketplaceClient<'static>, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(Marketplace, ());
+    let client = MarketplaceClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.init_contract(&admin);
+    (env, client, admin)
+}
+
+/// Register and initialize a marketplace with a Stellar asset as payment
+/// token, returning (env, client, admin, token admin client, token client)
+pub fn setup() -> (
+    Env,
+    MarketplaceClient<'static>,
+    Address,
+    token::StellarAssetClient<'static>,
+    token::Client<'static>,
+) {
+    let (env, client, admin) = setup_without_token();
+    let token_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.set_payment_token(&admin, &token_id);
+
+    (
+        env.clone(),
+        client,
+        admin,
+        token::StellarAssetClient::new(&env, &token_id),
+        token::Client::new(&env, &token_id),
+    )
+}
+
+/// A new account holding `amount` of the payment token
+pub fn funded_account(env: &Env, token_admin: &token::StellarAssetClient, amount: i128) -> Address {
+    let account = Address::generate(env);
+    token_admin.mint(&account, &amount);
+    account
+}
+
+// Minimal AgentNFT exposing `get_agent` with configurable capabilities and escrow lock
+#[contract]
+pub struct MockAgentNFT;
+
+#[contractimpl]
+impl MockAgentNFT {
+    pub fn set_agent(
+        env: Env,
+        agent_id: u64,
+        owner: Address,
+        capabilities: Vec<String>,
+        escrow_locked: bool,
+    ) {
+        let agent = Agent {
+            id: agent_id,
+            owner: owner.clone(),
+            name: String::from_str(&env, "agent"),
+            model_hash: String::from_str(&env, "hash"),
+            metadata_cid: String::from_str(&env, "cid"),
+            capabilities,
+            evolution_level: 0,
+            created_at: 0,
+            updated_at: 0,
+            nonce: 0,
+            escrow_locked,
+            escrow_holder: if escrow_locked { Some(owner) } else { None },
+        };
+        env.storage().instance().set(&agent_id, &agent);
+    }
+
+    pub fn get_agent(env: Env, agent_id: u64) -> Agent {
+        env.storage()
+            .instance()
+            .get(&agent_id)
+            .expect("Agent not found in mock")
+    }
+}
+
+/// Register a marketplace wired to a `MockAgentNFT`
+pub fn setup_with_agent_nft() -> (Env, MarketplaceClient<'static>, MockAgentNFTClient<'static>) {
+    let (env, client, admin) = setup_without_token();
+    let agent_nft_id = env.register(MockAgentNFT, ());
+    let agent_nft = MockAgentNFTClient::new(&env, &agent_nft_id);
+    client.set_agent_nft(&admin, &agent_nft_id);
+    (env, client, agent_nft)
+}
@@ -1,4 +1,5 @@
 #![no_std]
+#![allow(clippy::too_many_arguments)]
 
 extern crate alloc;
 
@@ -8,12 +9,8 @@ mod tests;
 mod testutils;
 mod types;
 
-use alloc::vec::Vec as StdVec;
-use core::convert::TryInto;
-use soroban_sdk::xdr::{self, Limited, Limits, WriteXdr};
-use soroban_sdk::{
-    contract, contractimpl, Address, Bytes, BytesN, Env, IntoVal, String, Symbol, Val, Vec,
-};
+use soroban_sdk::xdr::ToXdr;
+use soroban_sdk::{contract, contractimpl, Address, Bytes, BytesN, Env, String, Symbol, Val, Vec};
 use stellai_lib::{
     audit::{create_audit_log, OperationType},
     OracleData, ADMIN_KEY, PROVIDER_LIST_KEY,
@@ -21,6 +18,11 @@ use stellai_lib::{
 
 pub use types::*;
 
+const MAX_RELAY_BATCH_SIZE: u32 = 10;
+// Submissions older than this are ignored by aggregated reads
+const SUBMISSION_MAX_AGE_SECONDS: u64 = 300;
+const DEFAULT_MIN_PROVIDERS: u32 = 1;
+
 #[contract]
 pub struct Oracle;
 
@@ -56,6 +58,11 @@ impl Oracle {
         }
     }
 
+    /// Check whether an address is a registered data provider
+    pub fn is_provider(env: Env, provider: Address) -> bool {
+        Self::is_authorized_provider(&env, &provider)
+    }
+
     fn is_authorized_provider(env: &Env, provider: &Address) -> bool {
         let providers: Vec<Address> = env
             .storage()
@@ -91,6 +98,10 @@ impl Oracle {
         env.storage()
             .instance()
             .set(&Symbol::new(&env, PROVIDER_LIST_KEY), &providers);
+        env.storage().instance().set(
+            &DataKey::ProviderInfo(provider.clone()),
+            &(env.ledger().timestamp(), 0u64),
+        );
 
         env.events().publish(
             (Symbol::new(&env, "provider_registered"),),
@@ -117,6 +128,17 @@ impl Oracle {
         };
 
         env.storage().instance().set(&key, &oracle_data);
+        env.storage().instance().set(
+            &DataKey::Submission(key.clone(), provider.clone()),
+            &oracle_data,
+        );
+
+        let info_key = DataKey::ProviderInfo(provider.clone());
+        let (registered_at, submissions): (u64, u64) =
+            env.storage().instance().get(&info_key).unwrap_or((0, 0));
+        env.storage()
+            .instance()
+            .set(&info_key, &(registered_at, submissions.saturating_add(1)));
 
         env.events().publish(
             (Symbol::new(&env, "data_submitted"),),
@@ -146,6 +168,92 @@ impl Oracle {
         env.storage().instance().get(&key)
     }
 
+    /// Set how many fresh provider submissions an aggregated read of `key` needs (admin only)
+    pub fn set_min_providers(env: Env, admin: Address, key: Symbol, min_providers: u32) {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin);
+
+        if min_providers == 0 {
+            panic!("Minimum providers must be positive");
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::MinProviders(key.clone()), &min_providers);
+
+        env.events().publish(
+            (Symbol::new(&env, "min_providers_set"),),
+            (admin, key, min_providers),
+        );
+    }
+
+    pub fn get_min_providers(env: Env, key: Symbol) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::MinProviders(key))
+            .unwrap_or(DEFAULT_MIN_PROVIDERS)
+    }
+
+    /// Median of the fresh submissions for `key` across registered providers
+    ///
+    /// Returns `None` when fewer than the key's `min_providers` have submitted
+    /// within the freshness window.
+    pub fn get_aggregated_data(env: Env, key: Symbol) -> Option<i128> {
+        let now = env.ledger().timestamp();
+        let mut values: Vec<i128> = Vec::new(&env);
+
+        for provider in Self::get_providers(env.clone()).iter() {
+            let submission: Option<OracleData> = env
+                .storage()
+                .instance()
+                .get(&DataKey::Submission(key.clone(), provider));
+            if let Some(data) = submission {
+                if now.saturating_sub(data.timestamp) > SUBMISSION_MAX_AGE_SECONDS {
+                    continue;
+                }
+                // Insert in sorted position
+                let mut index = 0;
+                while index < values.len() && values.get(index).unwrap() < data.value {
+                    index += 1;
+                }
+                values.insert(index, data.value);
+            }
+        }
+
+        if values.is_empty() || values.len() < Self::get_min_providers(env.clone(), key) {
+            return None;
+        }
+
+        let mid = values.len() / 2;
+        if values.len() % 2 == 1 {
+            values.get(mid)
+        } else {
+            let low = values.get(mid - 1).unwrap();
+            let high = values.get(mid).unwrap();
+            Some(low + (high - low) / 2)
+        }
+    }
+
+    /// Like `get_aggregated_data`, but panics when the quorum is not met
+    pub fn get_aggregated_data_required(env: Env, key: Symbol) -> i128 {
+        Self::get_aggregated_data(env, key).unwrap_or_else(|| panic!("Insufficient providers"))
+    }
+
+    /// List all registered data providers
+    pub fn get_providers(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&Symbol::new(&env, PROVIDER_LIST_KEY))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Get a provider's registration time and number of submissions
+    pub fn get_provider_info(env: Env, provider: Address) -> Option<(u64, u64)> {
+        env.storage()
+            .instance()
+            .get(&DataKey::ProviderInfo(provider))
+    }
+
     pub fn deregister_provider(env: Env, admin: Address, provider: Address) {
         admin.require_auth();
         Self::verify_admin(&env, &admin);
@@ -174,6 +282,9 @@ impl Oracle {
         env.storage()
             .instance()
             .set(&Symbol::new(&env, PROVIDER_LIST_KEY), &updated_providers);
+        env.storage()
+            .instance()
+            .remove(&DataKey::ProviderInfo(provider.clone()));
 
         env.events().publish(
             (Symbol::new(&env, "provider_deregistered"),),
@@ -217,9 +328,8 @@ impl Oracle {
         env.storage()
             .instance()
             .remove(&DataKey::Oracle(oracle_pubkey.clone()));
-        env.storage()
-            .instance()
-            .remove(&DataKey::OracleNonce(oracle_pubkey.clone()));
+        // The relay nonce is kept so re-registering the key cannot replay
+        // messages it signed before
 
         env.events().publish(
             (Symbol::new(&env, "oracle_key_deregistered"),),
@@ -227,6 +337,44 @@ impl Oracle {
         );
     }
 
+    /// Replace an oracle key with a new one, carrying the relay nonce over
+    ///
+    /// The new key continues from the old key's last used nonce, and the old
+    /// key's nonce is kept so re-registering it later cannot replay messages
+    /// it already signed.
+    pub fn rotate_oracle_key(
+        env: Env,
+        admin: Address,
+        old_pubkey: BytesN<32>,
+        new_pubkey: BytesN<32>,
+    ) {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin);
+
+        if !Self::is_approved_oracle_key(&env, &old_pubkey) {
+            panic!("Oracle key not found");
+        }
+        if Self::is_approved_oracle_key(&env, &new_pubkey) {
+            panic!("Oracle key already registered");
+        }
+
+        let nonce = Self::get_oracle_nonce(&env, &old_pubkey)
+            .max(Self::get_oracle_nonce(&env, &new_pubkey));
+
+        env.storage()
+            .instance()
+            .remove(&DataKey::Oracle(old_pubkey.clone()));
+        env.storage()
+            .instance()
+            .set(&DataKey::Oracle(new_pubkey.clone()), &true);
+        Self::set_oracle_nonce(&env, &new_pubkey, nonce);
+
+        env.events().publish(
+            (Symbol::new(&env, "oracle_key_rotated"),),
+            (admin, old_pubkey, new_pubkey, nonce),
+        );
+    }
+
     pub fn is_registered_oracle_key(env: Env, oracle_pubkey: BytesN<32>) -> bool {
         Self::is_approved_oracle_key(&env, &oracle_pubkey)
     }
@@ -244,6 +392,69 @@ impl Oracle {
             .set(&DataKey::OracleNonce(oracle_pubkey.clone()), &nonce);
     }
 
+    /// Allow relayed calls to `function` on `contract` (admin only)
+    pub fn allow_relay_target(env: Env, admin: Address, contract: Address, function: Symbol) {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin);
+
+        env.storage().instance().set(
+            &DataKey::RelayTarget(contract.clone(), function.clone()),
+            &true,
+        );
+
+        env.events().publish(
+            (Symbol::new(&env, "relay_target_allowed"),),
+            (admin, contract, function),
+        );
+    }
+
+    /// Stop relaying calls to `function` on `contract` (admin only)
+    pub fn disallow_relay_target(env: Env, admin: Address, contract: Address, function: Symbol) {
+        admin.require_auth();
+        Self::verify_admin(&env, &admin);
+
+        if !Self::is_relay_target_allowed(env.clone(), contract.clone(), function.clone()) {
+            panic!("Relay target not found");
+        }
+
+        env.storage()
+            .instance()
+            .remove(&DataKey::RelayTarget(contract.clone(), function.clone()));
+
+        env.events().publish(
+            (Symbol::new(&env, "relay_target_disallowed"),),
+            (admin, contract, function),
+        );
+    }
+
+    pub fn is_relay_target_allowed(env: Env, contract: Address, function: Symbol) -> bool {
+        env.storage()
+            .instance()
+            .get::<_, bool>(&DataKey::RelayTarget(contract, function))
+            .unwrap_or(false)
+    }
+
+    fn require_relay_target(env: &Env, contract: &Address, function: &Symbol) {
+        if !Self::is_relay_target_allowed(env.clone(), contract.clone(), function.clone()) {
+            panic!("Relay target not allowed");
+        }
+    }
+
+    fn check_relay_preconditions(env: &Env, oracle_pubkey: &BytesN<32>, nonce: u64, deadline: u64) {
+        if !Self::is_approved_oracle_key(env, oracle_pubkey) {
+            panic!("Oracle not approved");
+        }
+
+        if env.ledger().timestamp() > deadline {
+            panic!("Signature expired");
+        }
+
+        let stored_nonce = Self::get_oracle_nonce(env, oracle_pubkey);
+        if nonce <= stored_nonce {
+            panic!("Invalid nonce: replay protection triggered");
+        }
+    }
+
     fn build_relay_message(env: &Env, req: &RelayRequest) -> Bytes {
         // Simplified implementation - just create a hash from the deadline and nonce
         let deadline_bytes = req.deadline.to_be_bytes();
@@ -256,6 +467,9 @@ impl Oracle {
         Bytes::from_array(env, &hash.to_array())
     }
 
+    /// Relay a call signed by an approved oracle key
+    ///
+    /// The oracle signs sha256(deadline || nonce), both encoded big-endian.
     pub fn relay_signed(
         env: Env,
         oracle_pubkey: BytesN<32>,
@@ -266,18 +480,8 @@ impl Oracle {
         deadline: u64,
         signature: BytesN<64>,
     ) -> Val {
-        if !Self::is_approved_oracle_key(&env, &oracle_pubkey) {
-            panic!("Oracle not approved");
-        }
-
-        if env.ledger().timestamp() > deadline {
-            panic!("Signature expired");
-        }
-
-        let stored_nonce = Self::get_oracle_nonce(&env, &oracle_pubkey);
-        if nonce <= stored_nonce {
-            panic!("Invalid nonce: replay protection triggered");
-        }
+        Self::check_relay_preconditions(&env, &oracle_pubkey, nonce, deadline);
+        Self::require_relay_target(&env, &target_contract, &function);
 
         let req = RelayRequest {
             relay_contract: env.current_contract_address(),
@@ -304,4 +508,56 @@ impl Oracle {
 
         result
     }
+
+    /// Relay several calls under a single signature and nonce
+    ///
+    /// The oracle signs the XDR encoding of the whole `RelayBatchRequest`.
+    /// Calls run in order; if any of them traps the entire relay reverts.
+    pub fn relay_signed_batch(
+        env: Env,
+        oracle_pubkey: BytesN<32>,
+        calls: Vec<(Address, Symbol, Vec<Val>)>,
+        nonce: u64,
+        deadline: u64,
+        signature: BytesN<64>,
+    ) -> Vec<Val> {
+        if calls.is_empty() {
+            panic!("Batch is empty");
+        }
+        if calls.len() > MAX_RELAY_BATCH_SIZE {
+            panic!("Batch size exceeds maximum");
+        }
+
+        Self::check_relay_preconditions(&env, &oracle_pubkey, nonce, deadline);
+        for (target_contract, function, _) in calls.iter() {
+            Self::require_relay_target(&env, &target_contract, &function);
+        }
+
+        let req = RelayBatchRequest {
+            relay_contract: env.current_contract_address(),
+            oracle_pubkey: oracle_pubkey.clone(),
+            calls: calls.clone(),
+            nonce,
+            deadline,
+        };
+
+        let message = req.to_xdr(&env);
+        env.crypto()
+            .ed25519_verify(&oracle_pubkey, &message, &signature);
+
+        Self::set_oracle_nonce(&env, &oracle_pubkey, nonce);
+
+        let mut results = Vec::new(&env);
+        for (target_contract, function, args) in calls.iter() {
+            let result: Val = env.invoke_contract(&target_contract, &function, args);
+            results.push_back(result);
+        }
+
+        env.events().publish(
+            (Symbol::new(&env, "batch_relayed"),),
+            (oracle_pubkey, calls.len(), nonce),
+        );
+
+        results
+    }
 }
@@ -1,11 +1,13 @@
 #![cfg(test)]
 
-use crate::{Oracle, OracleClient, RelayRequest};
-use ed25519_dalek::SigningKey;
+extern crate std;
+
+use crate::{Oracle, OracleClient, RelayBatchRequest, RelayRequest};
+use ed25519_dalek::{Signer, SigningKey};
 use soroban_sdk::testutils::{Address as _, Ledger};
 use soroban_sdk::xdr::{self, Limited, Limits, WriteXdr};
 use soroban_sdk::{
-    contract, contractimpl, symbol_short, Address, BytesN, Env, Symbol, TryIntoVal, Val, Vec,
+    contract, contractimpl, symbol_short, Address, Bytes, BytesN, Env, Symbol, TryIntoVal, Val, Vec,
 };
 
 #[contract]
@@ -23,23 +25,35 @@ impl Receiver {
     }
 }
 
+// The oracle signs sha256(deadline || nonce), both encoded big-endian
 fn build_signed_payload(
+    env: &Env,
+    nonce: u64,
+    deadline: u64,
+    signing_key: &SigningKey,
+) -> BytesN<64> {
+    let mut message = [0u8; 16];
+    message[..8].copy_from_slice(&deadline.to_be_bytes());
+    message[8..].copy_from_slice(&nonce.to_be_bytes());
+    let digest = env.crypto().sha256(&Bytes::from_array(env, &message));
+
+    let sig = signing_key.sign(&digest.to_array());
+    BytesN::from_array(env, &sig.to_bytes())
+}
+
+fn build_signed_batch(
     env: &Env,
     oracle_contract: &Address,
     oracle_pubkey: &BytesN<32>,
-    target_contract: &Address,
-    function: &Symbol,
-    args: &Vec<Val>,
+    calls: &Vec<(Address, Symbol, Vec<Val>)>,
     nonce: u64,
     deadline: u64,
     signing_key: &SigningKey,
 ) -> BytesN<64> {
-    let req = RelayRequest {
+    let req = RelayBatchRequest {
         relay_contract: oracle_contract.clone(),
         oracle_pubkey: oracle_pubkey.clone(),
-        target_contract: target_contract.clone(),
-        function: function.clone(),
-        args: args.clone(),
+        calls: calls.clone(),
         nonce,
         deadline,
     };
@@ -65,12 +79,13 @@ fn setup() -> (
     let env = Env::default();
     env.mock_all_auths();
 
-    let oracle_contract_id = env.register_contract(None, Oracle);
+    let oracle_contract_id = env.register(Oracle, ());
     let oracle_client = OracleClient::new(&env, &oracle_contract_id);
     let admin = Address::generate(&env);
     oracle_client.init_contract(&admin);
 
-    let receiver_id = env.register_contract(None, Receiver);
+    let receiver_id = env.register(Receiver, ());
+    oracle_client.allow_relay_target(&admin, &receiver_id, &Symbol::new(&env, "ping"));
 
     let sk = SigningKey::from_bytes(&[7u8; 32]);
     let pk_bytes: [u8; 32] = sk.verifying_key().to_bytes();
@@ -88,17 +103,7 @@ fn test_relay_signed_success_forwards_payload() {
     let args: Vec<Val> = (123u32,).try_into_val(&env).unwrap();
     let nonce = 1u64;
     let deadline = env.ledger().timestamp() + 100;
-    let signature = build_signed_payload(
-        &env,
-        &oracle.address,
-        &pk,
-        &receiver_id,
-        &function,
-        &args,
-        nonce,
-        deadline,
-        &sk,
-    );
+    let signature = build_signed_payload(&env, nonce, deadline, &sk);
 
     let res = oracle.relay_signed(
         &pk,
@@ -127,18 +132,32 @@ fn test_relay_signed_rejects_unapproved_oracle() {
     let args: Vec<Val> = (1u32,).try_into_val(&env).unwrap();
     let nonce = 1u64;
     let deadline = env.ledger().timestamp() + 100;
-    let signature = build_signed_payload(
-        &env,
-        &oracle.address,
+    let signature = build_signed_payload(&env, nonce, deadline, &sk);
+
+    oracle.relay_signed(
         &pk,
         &receiver_id,
         &function,
         &args,
-        nonce,
-        deadline,
-        &sk,
+        &nonce,
+        &deadline,
+        &signature,
     );
+}
+
+#[test]
+#[should_panic]
+fn test_relay_signed_rejects_bad_signature() {
+    let (env, oracle, admin, pk, _sk, receiver_id) = setup();
+    oracle.register_oracle_key(&admin, &pk);
 
+    let function = Symbol::new(&env, "ping");
+    let args: Vec<Val> = (1u32,).try_into_val(&env).unwrap();
+    let nonce = 1u64;
+    let deadline = env.ledger().timestamp() + 100;
+
+    // Wrong signature
+    let signature = BytesN::from_array(&env, &[0u8; 64]);
     oracle.relay_signed(
         &pk,
         &receiver_id,
@@ -152,8 +171,8 @@ fn test_relay_signed_rejects_unapproved_oracle() {
 
 #[test]
 #[should_panic]
-fn test_relay_signed_rejects_bad_signature() {
-    let (env, oracle, admin, pk, _sk, receiver_id) = setup();
+fn test_relay_signed_rejects_signature_over_request_xdr() {
+    let (env, oracle, admin, pk, sk, receiver_id) = setup();
     oracle.register_oracle_key(&admin, &pk);
 
     let function = Symbol::new(&env, "ping");
@@ -161,8 +180,23 @@ fn test_relay_signed_rejects_bad_signature() {
     let nonce = 1u64;
     let deadline = env.ledger().timestamp() + 100;
 
-    // Wrong signature
-    let signature = BytesN::from_array(&env, &[0u8; 64]);
+    // Signing anything other than sha256(deadline || nonce) must not verify
+    let req = RelayRequest {
+        relay_contract: oracle.address.clone(),
+        oracle_pubkey: pk.clone(),
+        target_contract: receiver_id.clone(),
+        function: function.clone(),
+        args: args.clone(),
+        nonce,
+        deadline,
+    };
+    let scval: xdr::ScVal = req.try_into().unwrap();
+    let mut buf: std::vec::Vec<u8> = std::vec::Vec::new();
+    scval
+        .write_xdr(&mut Limited::new(&mut buf, Limits::none()))
+        .unwrap();
+    let signature = BytesN::from_array(&env, &sk.sign(&buf).to_bytes());
+
     oracle.relay_signed(
         &pk,
         &receiver_id,
@@ -184,17 +218,39 @@ fn test_relay_signed_prevents_replay() {
     let args: Vec<Val> = (5u32,).try_into_val(&env).unwrap();
     let nonce = 1u64;
     let deadline = env.ledger().timestamp() + 100;
-    let signature = build_signed_payload(
-        &env,
-        &oracle.address,
+    let signature = build_signed_payload(&env, nonce, deadline, &sk);
+
+    oracle.relay_signed(
         &pk,
         &receiver_id,
         &function,
         &args,
-        nonce,
-        deadline,
-        &sk,
+        &nonce,
+        &deadline,
+        &signature,
     );
+    oracle.relay_signed(
+        &pk,
+        &receiver_id,
+        &function,
+        &args,
+        &nonce,
+        &deadline,
+        &signature,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Invalid nonce: replay protection triggered")]
+fn test_reregistered_oracle_key_cannot_replay() {
+    let (env, oracle, admin, pk, sk, receiver_id) = setup();
+    oracle.register_oracle_key(&admin, &pk);
+
+    let function = Symbol::new(&env, "ping");
+    let args: Vec<Val> = (5u32,).try_into_val(&env).unwrap();
+    let nonce = 1u64;
+    let deadline = env.ledger().timestamp() + 100;
+    let signature = build_signed_payload(&env, nonce, deadline, &sk);
 
     oracle.relay_signed(
         &pk,
@@ -205,6 +261,10 @@ fn test_relay_signed_prevents_replay() {
         &deadline,
         &signature,
     );
+
+    oracle.deregister_oracle_key(&admin, &pk);
+    oracle.register_oracle_key(&admin, &pk);
+
     oracle.relay_signed(
         &pk,
         &receiver_id,
@@ -226,27 +286,257 @@ fn test_relay_signed_rejects_expired_deadline() {
     let args: Vec<Val> = (1u32,).try_into_val(&env).unwrap();
     let nonce = 1u64;
     let deadline = env.ledger().timestamp();
-    let signature = build_signed_payload(
-        &env,
-        &oracle.address,
+    let signature = build_signed_payload(&env, nonce, deadline, &sk);
+
+    // Move ledger time forward
+    env.ledger().set_timestamp(deadline + 1);
+    oracle.relay_signed(
         &pk,
         &receiver_id,
         &function,
         &args,
-        nonce,
-        deadline,
-        &sk,
+        &nonce,
+        &deadline,
+        &signature,
     );
+}
 
-    // Move ledger time forward
-    env.ledger().set_timestamp(deadline + 1);
+#[test]
+#[should_panic(expected = "Oracle not approved")]
+fn test_rotate_oracle_key_rejects_old_key() {
+    let (env, oracle, admin, pk, sk, receiver_id) = setup();
+    oracle.register_oracle_key(&admin, &pk);
+
+    let new_sk = SigningKey::from_bytes(&[9u8; 32]);
+    let new_pk = BytesN::from_array(&env, &new_sk.verifying_key().to_bytes());
+    oracle.rotate_oracle_key(&admin, &pk, &new_pk);
+    assert!(!oracle.is_registered_oracle_key(&pk));
+    assert!(oracle.is_registered_oracle_key(&new_pk));
+
+    let function = Symbol::new(&env, "ping");
+    let args: Vec<Val> = (1u32,).try_into_val(&env).unwrap();
+    let deadline = env.ledger().timestamp() + 100;
+    let signature = build_signed_payload(&env, 1, deadline, &sk);
     oracle.relay_signed(
         &pk,
         &receiver_id,
         &function,
         &args,
-        &nonce,
+        &1,
+        &deadline,
+        &signature,
+    );
+}
+
+#[test]
+fn test_rotate_oracle_key_carries_nonce_over() {
+    let (env, oracle, admin, pk, sk, receiver_id) = setup();
+    oracle.register_oracle_key(&admin, &pk);
+
+    let function = Symbol::new(&env, "ping");
+    let args: Vec<Val> = (1u32,).try_into_val(&env).unwrap();
+    let deadline = env.ledger().timestamp() + 100;
+    let signature = build_signed_payload(&env, 5, deadline, &sk);
+    oracle.relay_signed(
+        &pk,
+        &receiver_id,
+        &function,
+        &args,
+        &5,
+        &deadline,
+        &signature,
+    );
+
+    let new_sk = SigningKey::from_bytes(&[9u8; 32]);
+    let new_pk = BytesN::from_array(&env, &new_sk.verifying_key().to_bytes());
+    oracle.rotate_oracle_key(&admin, &pk, &new_pk);
+
+    // Nonces up to the old key's last one stay consumed
+    let stale = build_signed_payload(&env, 5, deadline, &new_sk);
+    let result = oracle.try_relay_signed(
+        &new_pk,
+        &receiver_id,
+        &function,
+        &args,
+        &5,
+        &deadline,
+        &stale,
+    );
+    assert!(result.is_err());
+
+    let fresh = build_signed_payload(&env, 6, deadline, &new_sk);
+    oracle.relay_signed(
+        &new_pk,
+        &receiver_id,
+        &function,
+        &args,
+        &6,
+        &deadline,
+        &fresh,
+    );
+}
+
+fn ping_calls(
+    env: &Env,
+    receiver_id: &Address,
+    inputs: &[u32],
+) -> Vec<(Address, Symbol, Vec<Val>)> {
+    let mut calls = Vec::new(env);
+    for input in inputs {
+        let args: Vec<Val> = (*input,).try_into_val(env).unwrap();
+        calls.push_back((receiver_id.clone(), Symbol::new(env, "ping"), args));
+    }
+    calls
+}
+
+#[test]
+fn test_relay_signed_batch_executes_all_calls() {
+    let (env, oracle, admin, pk, sk, receiver_id) = setup();
+    oracle.register_oracle_key(&admin, &pk);
+
+    let calls = ping_calls(&env, &receiver_id, &[10, 20, 30]);
+    let deadline = env.ledger().timestamp() + 100;
+    let signature = build_signed_batch(&env, &oracle.address, &pk, &calls, 1, deadline, &sk);
+
+    let results = oracle.relay_signed_batch(&pk, &calls, &1, &deadline, &signature);
+    assert_eq!(results.len(), 3);
+    let first: u32 = results.get(0).unwrap().try_into_val(&env).unwrap();
+    let last: u32 = results.get(2).unwrap().try_into_val(&env).unwrap();
+    assert_eq!(first, 11);
+    assert_eq!(last, 31);
+
+    let stored: Option<u32> =
+        env.invoke_contract(&receiver_id, &Symbol::new(&env, "last"), Vec::new(&env));
+    assert_eq!(stored, Some(30));
+}
+
+#[test]
+#[should_panic]
+fn test_relay_signed_batch_rejects_tampered_calls() {
+    let (env, oracle, admin, pk, sk, receiver_id) = setup();
+    oracle.register_oracle_key(&admin, &pk);
+
+    let calls = ping_calls(&env, &receiver_id, &[10, 20]);
+    let deadline = env.ledger().timestamp() + 100;
+    let signature = build_signed_batch(&env, &oracle.address, &pk, &calls, 1, deadline, &sk);
+
+    let tampered = ping_calls(&env, &receiver_id, &[10, 99]);
+    oracle.relay_signed_batch(&pk, &tampered, &1, &deadline, &signature);
+}
+
+#[test]
+#[should_panic(expected = "Relay target not allowed")]
+fn test_relay_signed_rejects_unlisted_target() {
+    let (env, oracle, admin, pk, sk, receiver_id) = setup();
+    oracle.register_oracle_key(&admin, &pk);
+
+    let function = Symbol::new(&env, "ping");
+    oracle.disallow_relay_target(&admin, &receiver_id, &function);
+    assert!(!oracle.is_relay_target_allowed(&receiver_id, &function));
+
+    let args: Vec<Val> = (1u32,).try_into_val(&env).unwrap();
+    let deadline = env.ledger().timestamp() + 100;
+    let signature = build_signed_payload(&env, 1, deadline, &sk);
+    oracle.relay_signed(
+        &pk,
+        &receiver_id,
+        &function,
+        &args,
+        &1,
         &deadline,
         &signature,
     );
 }
+
+#[test]
+#[should_panic(expected = "Caller is not admin")]
+fn test_allow_relay_target_requires_admin() {
+    let (env, oracle, _admin, _pk, _sk, receiver_id) = setup();
+    let outsider = Address::generate(&env);
+    oracle.allow_relay_target(&outsider, &receiver_id, &Symbol::new(&env, "last"));
+}
+
+#[test]
+fn test_provider_listing_and_stats() {
+    let (env, oracle, admin, _pk, _sk, _receiver_id) = setup();
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+
+    env.ledger().set_timestamp(1_000);
+    oracle.register_provider(&admin, &first);
+    env.ledger().set_timestamp(2_000);
+    oracle.register_provider(&admin, &second);
+
+    oracle.submit_data(&first, &symbol_short!("btc"), &100);
+    oracle.submit_data(&first, &symbol_short!("eth"), &50);
+    oracle.submit_data(&second, &symbol_short!("btc"), &101);
+
+    let providers = oracle.get_providers();
+    assert_eq!(providers.len(), 2);
+    assert_eq!(providers.get(0).unwrap(), first);
+    assert_eq!(providers.get(1).unwrap(), second);
+
+    assert_eq!(oracle.get_provider_info(&first), Some((1_000, 2)));
+    assert_eq!(oracle.get_provider_info(&second), Some((2_000, 1)));
+
+    oracle.deregister_provider(&admin, &second);
+    assert_eq!(oracle.get_providers().len(), 1);
+    assert_eq!(oracle.get_provider_info(&second), None);
+}
+
+fn register_providers(
+    env: &Env,
+    oracle: &OracleClient,
+    admin: &Address,
+    count: u32,
+) -> Vec<Address> {
+    let mut providers = Vec::new(env);
+    for _ in 0..count {
+        let provider = Address::generate(env);
+        oracle.register_provider(admin, &provider);
+        providers.push_back(provider);
+    }
+    providers
+}
+
+#[test]
+fn test_aggregated_data_returns_median_when_quorum_met() {
+    let (env, oracle, admin, _pk, _sk, _receiver_id) = setup();
+    let providers = register_providers(&env, &oracle, &admin, 3);
+    let key = symbol_short!("btc");
+    oracle.set_min_providers(&admin, &key, &3);
+
+    oracle.submit_data(&providers.get(0).unwrap(), &key, &120);
+    oracle.submit_data(&providers.get(1).unwrap(), &key, &100);
+    oracle.submit_data(&providers.get(2).unwrap(), &key, &110);
+
+    assert_eq!(oracle.get_aggregated_data(&key), Some(110));
+    assert_eq!(oracle.get_aggregated_data_required(&key), 110);
+}
+
+#[test]
+fn test_aggregated_data_none_below_quorum() {
+    let (env, oracle, admin, _pk, _sk, _receiver_id) = setup();
+    let providers = register_providers(&env, &oracle, &admin, 3);
+    let key = symbol_short!("btc");
+    oracle.set_min_providers(&admin, &key, &3);
+
+    oracle.submit_data(&providers.get(0).unwrap(), &key, &120);
+    oracle.submit_data(&providers.get(1).unwrap(), &key, &100);
+
+    assert_eq!(oracle.get_aggregated_data(&key), None);
+    assert!(oracle.try_get_aggregated_data_required(&key).is_err());
+}
+
+#[test]
+fn test_aggregated_data_default_quorum_uses_single_submission() {
+    let (env, oracle, admin, _pk, _sk, _receiver_id) = setup();
+    let providers = register_providers(&env, &oracle, &admin, 1);
+    let key = symbol_short!("btc");
+
+    assert_eq!(oracle.get_min_providers(&key), 1);
+    assert_eq!(oracle.get_aggregated_data(&key), None);
+
+    oracle.submit_data(&providers.get(0).unwrap(), &key, &100);
+    assert_eq!(oracle.get_aggregated_data(&key), Some(100));
+}
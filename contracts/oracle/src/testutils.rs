@@ -1,4 +1,5 @@
 #![cfg(any(test, feature = "testutils"))]
+#![allow(dead_code)]
 
 // use soroban_sdk::{Address, Bytes, Env, String};
 use soroban_sdk::{Address, Bytes, Env};
@@ -1,11 +1,13 @@
-#![no_std]
-
 use soroban_sdk::{contracttype, Address, BytesN, Symbol, Val, Vec};
 
 #[contracttype]
 pub enum DataKey {
     Oracle(BytesN<32>),
     OracleNonce(BytesN<32>),
+    RelayTarget(Address, Symbol),
+    ProviderInfo(Address),
+    Submission(Symbol, Address),
+    MinProviders(Symbol),
 }
 
 #[contracttype]
@@ -19,3 +21,13 @@ pub struct RelayRequest {
     pub nonce: u64,
     pub deadline: u64,
 }
+
+#[contracttype]
+#[derive(Clone)]
+pub struct RelayBatchRequest {
+    pub relay_contract: Address,
+    pub oracle_pubkey: BytesN<32>,
+    pub calls: Vec<(Address, Symbol, Vec<Val>)>,
+    pub nonce: u64,
+    pub deadline: u64,
+}
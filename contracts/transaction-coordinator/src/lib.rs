@@ -1,4 +1,5 @@
 #![no_std]
+#![allow(clippy::too_many_arguments)]
 
 #[cfg(test)]
 mod integration_test;
@@ -8,23 +9,31 @@ mod workflows;
 
 use soroban_sdk::{
     contract, contracterror, contractimpl, contracttype, panic_with_error, symbol_short, Address,
-    Env, String, Symbol, Vec,
+    BytesN, Env, String, Symbol, Vec,
 };
 use stellai_lib::{
     atomic::AtomicTransactionUtils, AtomicTransaction, TransactionEvent, TransactionJournalEntry,
-    TransactionStatus, TransactionStep, MAX_TRANSACTION_STEPS, TRANSACTION_TIMEOUT_SECONDS,
+    TransactionStatus, TransactionStep, MAX_HISTORY_QUERY_LIMIT, MAX_SUB_TRANSACTION_DEPTH,
+    MAX_TRANSACTION_STEPS, MAX_TRANSACTION_STEPS_CEILING, MAX_TRANSACTION_TIMEOUT_SECONDS,
+    TRANSACTION_TIMEOUT_SECONDS,
 };
 
 pub use workflows::AtomicAgentSaleWorkflow;
 
+// Read-only function a step contract exposes for pre-step state snapshots
+const STATE_SNAPSHOT_FUNCTION: &str = "state_snapshot";
+
 #[derive(Clone)]
 #[contracttype]
 pub enum DataKey {
     Admin,
     TransactionCounter,
     Transaction(u64),
-    Journal(u64, u32),  // (transaction_id, step_id)
-    PreparedSteps(u64), // Track prepared steps per transaction
+    Journal(u64, u32),                   // (transaction_id, step_id)
+    PreparedSteps(u64),                  // Track prepared steps per transaction
+    IdempotencyKey(Address, BytesN<32>), // (initiator, client key) -> transaction_id
+    InitiatorTransactions(Address),      // Transaction IDs created by an initiator
+    MaxSteps,                            // Admin-configured cap on steps per transaction
 }
 
 #[contracterror]
@@ -60,15 +69,69 @@ impl TransactionCoordinator {
         env.storage()
             .instance()
             .set(&DataKey::TransactionCounter, &0u64);
+        env.storage()
+            .instance()
+            .set(&DataKey::MaxSteps, &MAX_TRANSACTION_STEPS);
 
         env.events().publish((symbol_short!("init"),), admin);
     }
 
+    /// Set the maximum number of steps per transaction (admin only)
+    ///
+    /// The value is clamped to `1..=MAX_TRANSACTION_STEPS_CEILING`.
+    pub fn set_max_steps(env: Env, admin: Address, max: u32) {
+        admin.require_auth();
+
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotInitialized));
+        if admin != stored_admin {
+            panic_with_error!(&env, Error::Unauthorized);
+        }
+
+        let max = max.clamp(1, MAX_TRANSACTION_STEPS_CEILING);
+        env.storage().instance().set(&DataKey::MaxSteps, &max);
+
+        env.events()
+            .publish((Symbol::new(&env, "max_steps_set"),), max);
+    }
+
+    /// Get the maximum number of steps per transaction
+    pub fn get_max_steps(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::MaxSteps)
+            .unwrap_or(MAX_TRANSACTION_STEPS)
+    }
+
     /// Create a new atomic transaction
-    pub fn create_transaction(env: Env, initiator: Address, steps: Vec<TransactionStep>) -> u64 {
+    ///
+    /// `timeout_seconds` overrides the default `TRANSACTION_TIMEOUT_SECONDS`
+    /// and is clamped to `MAX_TRANSACTION_TIMEOUT_SECONDS`. When an
+    /// `idempotency_key` is supplied and the same initiator has used it
+    /// before, the existing transaction ID is returned instead of creating a
+    /// duplicate. Keys are scoped per initiator, so one caller cannot claim
+    /// another's key.
+    pub fn create_transaction(
+        env: Env,
+        initiator: Address,
+        steps: Vec<TransactionStep>,
+        timeout_seconds: Option<u64>,
+        idempotency_key: Option<BytesN<32>>,
+    ) -> u64 {
         initiator.require_auth();
 
-        if steps.len() == 0 || steps.len() > MAX_TRANSACTION_STEPS as u32 {
+        if let Some(key) = &idempotency_key {
+            if let Some(existing_id) =
+                Self::get_tx_by_idempotency_key(env.clone(), initiator.clone(), key.clone())
+            {
+                return existing_id;
+            }
+        }
+
+        if steps.is_empty() || steps.len() > Self::get_max_steps(env.clone()) {
             panic_with_error!(&env, Error::TooManySteps);
         }
 
@@ -80,12 +143,22 @@ impl TransactionCoordinator {
             .unwrap_or(0);
         let transaction_id = counter + 1;
 
-        let deadline = env.ledger().timestamp() + TRANSACTION_TIMEOUT_SECONDS;
+        let timeout = timeout_seconds
+            .unwrap_or(TRANSACTION_TIMEOUT_SECONDS)
+            .min(MAX_TRANSACTION_TIMEOUT_SECONDS);
+        let deadline = env.ledger().timestamp() + timeout;
+
+        // Resolve the dependency order once; execution reuses it
+        let execution_order = AtomicTransactionUtils::resolve_execution_order(&env, &steps);
+        if execution_order.len() != steps.len() {
+            panic_with_error!(&env, Error::CircularDependency);
+        }
 
         let transaction = AtomicTransaction {
             transaction_id,
             initiator: initiator.clone(),
             steps: steps.clone(),
+            execution_order,
             status: TransactionStatus::Initiated,
             created_at: env.ledger().timestamp(),
             deadline,
@@ -106,6 +179,23 @@ impl TransactionCoordinator {
         env.storage()
             .instance()
             .set(&DataKey::TransactionCounter, &transaction_id);
+        if let Some(key) = idempotency_key {
+            env.storage().instance().set(
+                &DataKey::IdempotencyKey(initiator.clone(), key),
+                &transaction_id,
+            );
+        }
+
+        let initiator_key = DataKey::InitiatorTransactions(initiator);
+        let mut initiator_transactions: Vec<u64> = env
+            .storage()
+            .instance()
+            .get(&initiator_key)
+            .unwrap_or_else(|| Vec::new(&env));
+        initiator_transactions.push_back(transaction_id);
+        env.storage()
+            .instance()
+            .set(&initiator_key, &initiator_transactions);
 
         // Initialize prepared steps tracking
         env.storage().instance().set(
@@ -126,7 +216,7 @@ impl TransactionCoordinator {
     pub fn execute_transaction(env: Env, transaction_id: u64, executor: Address) -> bool {
         executor.require_auth();
 
-        let mut transaction: AtomicTransaction = env
+        let transaction: AtomicTransaction = env
             .storage()
             .instance()
             .get(&DataKey::Transaction(transaction_id))
@@ -137,9 +227,20 @@ impl TransactionCoordinator {
             panic_with_error!(&env, Error::Unauthorized);
         }
 
+        Self::run_transaction(env, transaction_id, transaction, 0)
+    }
+
+    /// Run the two-phase commit for a transaction at the given nesting depth
+    fn run_transaction(
+        env: Env,
+        transaction_id: u64,
+        mut transaction: AtomicTransaction,
+        depth: u32,
+    ) -> bool {
         // Check timeout
         if AtomicTransactionUtils::is_transaction_timed_out(&env, &transaction) {
             transaction.status = TransactionStatus::TimedOut;
+            transaction.failure_reason = Some(String::from_str(&env, "Transaction timed out"));
             env.storage()
                 .instance()
                 .set(&DataKey::Transaction(transaction_id), &transaction);
@@ -153,8 +254,7 @@ impl TransactionCoordinator {
             .instance()
             .set(&DataKey::Transaction(transaction_id), &transaction);
 
-        let execution_order =
-            AtomicTransactionUtils::resolve_execution_order(&env, &transaction.steps);
+        let execution_order = transaction.execution_order.clone();
         let mut prepared_steps = Vec::new(&env);
 
         for step_id in execution_order.iter() {
@@ -174,13 +274,14 @@ impl TransactionCoordinator {
                     }
                 }
                 if !found {
+                    Self::record_failure(&env, &mut transaction, "prepare", step_id);
                     Self::rollback_transaction(&env, transaction_id, &prepared_steps);
                     return false;
                 }
             }
 
             // Prepare step
-            let prepare_success = Self::prepare_step(&env, transaction_id, &step);
+            let prepare_success = Self::prepare_step(&env, &transaction, &step, depth);
 
             if prepare_success {
                 prepared_steps.push_back(step_id);
@@ -200,6 +301,7 @@ impl TransactionCoordinator {
                     false,
                     Some("Step preparation failed"),
                 );
+                Self::record_failure(&env, &mut transaction, "prepare", step_id);
                 Self::rollback_transaction(&env, transaction_id, &prepared_steps);
                 return false;
             }
@@ -227,7 +329,7 @@ impl TransactionCoordinator {
                 .find(|s| s.step_id == step_id)
                 .unwrap();
 
-            let commit_success = Self::commit_step(&env, transaction_id, &step);
+            let commit_success = Self::commit_step(&env, transaction_id, &step, depth);
 
             if commit_success {
                 executed_steps.push_back(step_id);
@@ -247,6 +349,7 @@ impl TransactionCoordinator {
                     false,
                     Some("Step commit failed"),
                 );
+                Self::record_failure(&env, &mut transaction, "commit", step_id);
                 Self::rollback_transaction(&env, transaction_id, &executed_steps);
                 return false;
             }
@@ -272,6 +375,34 @@ impl TransactionCoordinator {
             .get(&DataKey::Transaction(transaction_id))
     }
 
+    /// Look up the transaction `initiator` created with an idempotency key
+    pub fn get_tx_by_idempotency_key(env: Env, initiator: Address, key: BytesN<32>) -> Option<u64> {
+        env.storage()
+            .instance()
+            .get(&DataKey::IdempotencyKey(initiator, key))
+    }
+
+    /// List transaction IDs created by `initiator`, oldest first, paginated
+    /// from index `start` (limit capped at `MAX_HISTORY_QUERY_LIMIT`)
+    pub fn get_transactions_by_initiator(
+        env: Env,
+        initiator: Address,
+        start: u32,
+        limit: u32,
+    ) -> Vec<u64> {
+        let ids: Vec<u64> = env
+            .storage()
+            .instance()
+            .get(&DataKey::InitiatorTransactions(initiator))
+            .unwrap_or_else(|| Vec::new(&env));
+
+        let start = start.min(ids.len());
+        let end = start
+            .saturating_add(limit.min(MAX_HISTORY_QUERY_LIMIT))
+            .min(ids.len());
+        ids.slice(start..end)
+    }
+
     /// Get transaction status
     pub fn get_transaction_status(env: Env, transaction_id: u64) -> Option<TransactionStatus> {
         env.storage()
@@ -281,21 +412,121 @@ impl TransactionCoordinator {
     }
 
     /// Prepare a single step
-    fn prepare_step(env: &Env, transaction_id: u64, step: &TransactionStep) -> bool {
+    fn prepare_step(
+        env: &Env,
+        transaction: &AtomicTransaction,
+        step: &TransactionStep,
+        depth: u32,
+    ) -> bool {
+        let transaction_id = transaction.transaction_id;
+
+        if let Some(child_id) = step.sub_transaction {
+            if let Err(reason) = Self::check_sub_transaction(env, transaction, child_id, depth) {
+                Self::create_journal_entry(
+                    env,
+                    transaction_id,
+                    step.step_id,
+                    "prepare",
+                    false,
+                    Some(reason),
+                );
+                return false;
+            }
+        }
+
+        // Capture the step contract's state before anything is committed
+        let snapshot = if step.capture_snapshot {
+            match env.try_invoke_contract::<String, soroban_sdk::Error>(
+                &step.contract,
+                &Symbol::new(env, STATE_SNAPSHOT_FUNCTION),
+                Vec::new(env),
+            ) {
+                Ok(Ok(snapshot)) => Some(snapshot),
+                _ => {
+                    Self::create_journal_entry(
+                        env,
+                        transaction_id,
+                        step.step_id,
+                        "prepare",
+                        false,
+                        Some("State snapshot failed"),
+                    );
+                    return false;
+                }
+            }
+        } else {
+            None
+        };
+
         // For now, return true as a placeholder since we can't easily convert complex types to Val
         // In a real implementation, this would need proper serialization
         Self::create_journal_entry(env, transaction_id, step.step_id, "prepare", true, None);
+        if let Some(snapshot) = snapshot {
+            Self::attach_snapshot(env, transaction_id, step.step_id, snapshot);
+        }
         true
     }
 
     /// Commit a single step
-    fn commit_step(env: &Env, transaction_id: u64, step: &TransactionStep) -> bool {
+    fn commit_step(env: &Env, transaction_id: u64, step: &TransactionStep, depth: u32) -> bool {
+        if let Some(child_id) = step.sub_transaction {
+            let child: AtomicTransaction = env
+                .storage()
+                .instance()
+                .get(&DataKey::Transaction(child_id))
+                .unwrap();
+            let committed = Self::run_transaction(env.clone(), child_id, child, depth + 1);
+            Self::create_journal_entry(
+                env,
+                transaction_id,
+                step.step_id,
+                "commit",
+                committed,
+                if committed {
+                    None
+                } else {
+                    Some("Sub-transaction failed")
+                },
+            );
+            return committed;
+        }
+
         // For now, return true as a placeholder since we can't easily convert complex types to Val
         // In a real implementation, this would need proper serialization
         Self::create_journal_entry(env, transaction_id, step.step_id, "commit", true, None);
         true
     }
 
+    /// Check that a sub-transaction step may run its child at this depth
+    fn check_sub_transaction(
+        env: &Env,
+        parent: &AtomicTransaction,
+        child_id: u64,
+        depth: u32,
+    ) -> Result<(), &'static str> {
+        if depth + 1 > MAX_SUB_TRANSACTION_DEPTH {
+            return Err("Sub-transaction depth exceeded");
+        }
+        if child_id == parent.transaction_id {
+            return Err("Transaction cannot nest itself");
+        }
+
+        let child: AtomicTransaction = env
+            .storage()
+            .instance()
+            .get(&DataKey::Transaction(child_id))
+            .ok_or("Sub-transaction not found")?;
+
+        if child.initiator != parent.initiator {
+            return Err("Sub-transaction initiator mismatch");
+        }
+        if child.status != TransactionStatus::Initiated {
+            return Err("Sub-transaction not executable");
+        }
+
+        Ok(())
+    }
+
     /// Rollback transaction by undoing executed steps in reverse order
     fn rollback_transaction(env: &Env, transaction_id: u64, executed_steps: &Vec<u32>) -> bool {
         let mut transaction: AtomicTransaction = env
@@ -320,6 +551,20 @@ impl TransactionCoordinator {
                 .find(|s| s.step_id == step_id)
                 .unwrap();
 
+            // Undo a committed sub-transaction as a unit
+            if let Some(child_id) = step.sub_transaction {
+                let child: AtomicTransaction = env
+                    .storage()
+                    .instance()
+                    .get(&DataKey::Transaction(child_id))
+                    .unwrap();
+                if child.status == TransactionStatus::Committed {
+                    Self::rollback_transaction(env, child_id, &child.executed_steps);
+                }
+                Self::create_journal_entry(env, transaction_id, step_id, "rollback", true, None);
+                continue;
+            }
+
             if let (Some(_rollback_contract), Some(_rollback_function)) =
                 (&step.rollback_contract, &step.rollback_function)
             {
@@ -343,7 +588,55 @@ impl TransactionCoordinator {
         rollback_success
     }
 
+    /// Persist why a transaction failed, e.g. "commit failed at step 2"
+    fn record_failure(env: &Env, transaction: &mut AtomicTransaction, phase: &str, step_id: u32) {
+        let mut buf = [0u8; 48];
+        let mut len = 0;
+        for part in [phase.as_bytes(), b" failed at step "] {
+            buf[len..len + part.len()].copy_from_slice(part);
+            len += part.len();
+        }
+
+        let mut digits = [0u8; 10];
+        let mut count = 0;
+        let mut n = step_id;
+        loop {
+            digits[count] = b'0' + (n % 10) as u8;
+            count += 1;
+            n /= 10;
+            if n == 0 {
+                break;
+            }
+        }
+        for i in (0..count).rev() {
+            buf[len] = digits[i];
+            len += 1;
+        }
+
+        transaction.failure_reason = Some(String::from_bytes(env, &buf[..len]));
+        env.storage().instance().set(
+            &DataKey::Transaction(transaction.transaction_id),
+            transaction,
+        );
+    }
+
+    /// Store a pre-step state snapshot on the step's journal entry
+    fn attach_snapshot(env: &Env, transaction_id: u64, step_id: u32, snapshot: String) {
+        let key = DataKey::Journal(transaction_id, step_id);
+        if let Some(mut entry) = env
+            .storage()
+            .instance()
+            .get::<DataKey, TransactionJournalEntry>(&key)
+        {
+            entry.state_snapshot = Some(snapshot);
+            env.storage().instance().set(&key, &entry);
+        }
+    }
+
     /// Create journal entry for audit trail
+    ///
+    /// Each step keeps only its latest entry; a captured snapshot is carried
+    /// over so later commit/rollback entries still hold the pre-step state.
     fn create_journal_entry(
         env: &Env,
         transaction_id: u64,
@@ -352,19 +645,23 @@ impl TransactionCoordinator {
         success: bool,
         error_message: Option<&str>,
     ) {
+        let key = DataKey::Journal(transaction_id, step_id);
+        let state_snapshot = env
+            .storage()
+            .instance()
+            .get::<DataKey, TransactionJournalEntry>(&key)
+            .and_then(|previous| previous.state_snapshot);
         let entry = TransactionJournalEntry {
             transaction_id,
             step_id,
-            action: String::from_str(&env, action),
+            action: String::from_str(env, action),
             timestamp: env.ledger().timestamp(),
             success,
-            error_message: error_message.map(|s| String::from_str(&env, s)),
-            state_snapshot: None,
+            error_message: error_message.map(|s| String::from_str(env, s)),
+            state_snapshot,
         };
 
-        env.storage()
-            .instance()
-            .set(&DataKey::Journal(transaction_id, step_id), &entry);
+        env.storage().instance().set(&key, &entry);
     }
 
     /// Emit transaction event
@@ -377,10 +674,10 @@ impl TransactionCoordinator {
     ) {
         let _event = TransactionEvent {
             transaction_id,
-            event_type: String::from_str(&env, event_type),
+            event_type: String::from_str(env, event_type),
             step_id,
             timestamp: env.ledger().timestamp(),
-            details: details.map(|s| String::from_str(&env, s)),
+            details: details.map(|s| String::from_str(env, s)),
         };
 
         env.events().publish(
@@ -390,11 +687,28 @@ impl TransactionCoordinator {
     }
 
     /// Get transaction journal for audit
-    pub fn get_transaction_journal(env: Env, _transaction_id: u64) -> Vec<TransactionJournalEntry> {
-        let journal = Vec::new(&env);
+    ///
+    /// Returns the latest entry for the transaction itself (step 0) followed by
+    /// the latest entry of each step, in step order.
+    pub fn get_transaction_journal(env: Env, transaction_id: u64) -> Vec<TransactionJournalEntry> {
+        let mut journal = Vec::new(&env);
+        let Some(transaction) = Self::get_transaction(env.clone(), transaction_id) else {
+            return journal;
+        };
 
-        // This is a simplified version - in practice, you'd iterate through stored journal entries
-        // For now, return empty vector as journal entries are stored but not easily queryable
+        let mut step_ids = Vec::from_array(&env, [0u32]);
+        for step in transaction.steps.iter() {
+            step_ids.push_back(step.step_id);
+        }
+        for step_id in step_ids.iter() {
+            if let Some(entry) = env
+                .storage()
+                .instance()
+                .get(&DataKey::Journal(transaction_id, step_id))
+            {
+                journal.push_back(entry);
+            }
+        }
 
         journal
     }
@@ -3,7 +3,7 @@
 use super::*;
 use soroban_sdk::{
     testutils::{Address as _, Ledger},
-    Address, Env, Vec,
+    Address, Env, String, Vec,
 };
 use stellai_lib::{TransactionStatus, TransactionStep};
 
@@ -69,10 +69,12 @@ fn test_create_transaction() {
             rollback_args: Some(Vec::new(&env)),
             executed: false,
             result: None,
+            sub_transaction: None,
+            capture_snapshot: false,
         }],
     );
 
-    let tx_id = client.create_transaction(&user, &steps);
+    let tx_id = client.create_transaction(&user, &steps, &None, &None);
     assert_eq!(tx_id, 1);
 
     let transaction = client.get_transaction(&tx_id).unwrap();
@@ -92,7 +94,7 @@ fn test_create_transaction_empty_steps() {
     client.initialize(&admin);
 
     let empty_steps = Vec::new(&env);
-    client.create_transaction(&user, &empty_steps);
+    client.create_transaction(&user, &empty_steps, &None, &None);
 }
 
 #[test]
@@ -118,6 +120,8 @@ fn test_transaction_with_dependencies() {
                 rollback_args: Some(Vec::new(&env)),
                 executed: false,
                 result: None,
+                sub_transaction: None,
+                capture_snapshot: false,
             },
             TransactionStep {
                 step_id: 2,
@@ -130,11 +134,13 @@ fn test_transaction_with_dependencies() {
                 rollback_args: Some(Vec::new(&env)),
                 executed: false,
                 result: None,
+                sub_transaction: None,
+                capture_snapshot: false,
             },
         ],
     );
 
-    let tx_id = client.create_transaction(&user, &steps);
+    let tx_id = client.create_transaction(&user, &steps, &None, &None);
     let transaction = client.get_transaction(&tx_id).unwrap();
 
     assert_eq!(transaction.steps.len(), 2);
@@ -163,10 +169,12 @@ fn test_transaction_status_queries() {
             rollback_args: None,
             executed: false,
             result: None,
+            sub_transaction: None,
+            capture_snapshot: false,
         }],
     );
 
-    let tx_id = client.create_transaction(&user, &steps);
+    let tx_id = client.create_transaction(&user, &steps, &None, &None);
 
     let status = client.get_transaction_status(&tx_id).unwrap();
     assert_eq!(status, TransactionStatus::Initiated);
@@ -233,6 +241,8 @@ fn test_transaction_validation() {
             rollback_args: None,
             executed: false,
             result: None,
+            sub_transaction: None,
+            capture_snapshot: false,
         }],
     );
 
@@ -240,6 +250,7 @@ fn test_transaction_validation() {
         transaction_id: 1,
         initiator: user.clone(),
         steps: valid_steps,
+        execution_order: Vec::new(&env),
         status: TransactionStatus::Initiated,
         created_at: 0,
         deadline: 300,
@@ -255,6 +266,7 @@ fn test_transaction_validation() {
         transaction_id: 2,
         initiator: user.clone(),
         steps: Vec::new(&env),
+        execution_order: Vec::new(&env),
         status: TransactionStatus::Initiated,
         created_at: 0,
         deadline: 300,
@@ -286,6 +298,8 @@ fn test_execution_order_resolution() {
                 rollback_args: None,
                 executed: false,
                 result: None,
+                sub_transaction: None,
+                capture_snapshot: false,
             },
             TransactionStep {
                 step_id: 1,
@@ -298,6 +312,8 @@ fn test_execution_order_resolution() {
                 rollback_args: None,
                 executed: false,
                 result: None,
+                sub_transaction: None,
+                capture_snapshot: false,
             },
             TransactionStep {
                 step_id: 2,
@@ -310,6 +326,8 @@ fn test_execution_order_resolution() {
                 rollback_args: None,
                 executed: false,
                 result: None,
+                sub_transaction: None,
+                capture_snapshot: false,
             },
         ],
     );
@@ -318,8 +336,8 @@ fn test_execution_order_resolution() {
 
     // Should execute in dependency order: 1, then 2 and 3 (which both depend on 1)
     assert_eq!(execution_order.get(0).unwrap(), 1);
-    assert!(execution_order.contains(&2));
-    assert!(execution_order.contains(&3));
+    assert!(execution_order.contains(2));
+    assert!(execution_order.contains(3));
 }
 
 #[test]
@@ -335,6 +353,7 @@ fn test_timeout_detection() {
         transaction_id: 1,
         initiator: user.clone(),
         steps: Vec::new(&env),
+        execution_order: Vec::new(&env),
         status: TransactionStatus::Initiated,
         created_at: 0,
         deadline: 50, // Deadline in the past
@@ -352,6 +371,7 @@ fn test_timeout_detection() {
         transaction_id: 2,
         initiator: user,
         steps: Vec::new(&env),
+        execution_order: Vec::new(&env),
         status: TransactionStatus::Initiated,
         created_at: 0,
         deadline: 200, // Deadline in the future
@@ -365,3 +385,365 @@ fn test_timeout_detection() {
         &not_expired_tx
     ));
 }
+
+fn single_step(env: &Env) -> Vec<TransactionStep> {
+    let mock_contract = create_mock_contract(env);
+    Vec::from_array(
+        env,
+        [TransactionStep {
+            step_id: 1,
+            contract: mock_contract,
+            function: Symbol::new(env, "test_function"),
+            args: Vec::new(env),
+            depends_on: None,
+            rollback_contract: None,
+            rollback_function: None,
+            rollback_args: None,
+            executed: false,
+            result: None,
+            sub_transaction: None,
+            capture_snapshot: false,
+        }],
+    )
+}
+
+#[test]
+fn test_custom_short_timeout_times_out_early() {
+    let (env, admin, user) = create_test_env();
+    let contract_id = create_test_contract(&env);
+    let client = TransactionCoordinatorClient::new(&env, &contract_id);
+    client.initialize(&admin);
+
+    let tx_id = client.create_transaction(&user, &single_step(&env), &Some(60), &None);
+
+    // Well within the default 300s timeout, but past the custom 60s one
+    env.ledger().with_mut(|li| li.timestamp += 61);
+    assert!(!client.execute_transaction(&tx_id, &user));
+    assert_eq!(
+        client.get_transaction_status(&tx_id).unwrap(),
+        TransactionStatus::TimedOut
+    );
+}
+
+#[test]
+fn test_custom_long_timeout_outlives_default() {
+    let (env, admin, user) = create_test_env();
+    let contract_id = create_test_contract(&env);
+    let client = TransactionCoordinatorClient::new(&env, &contract_id);
+    client.initialize(&admin);
+
+    let tx_id = client.create_transaction(&user, &single_step(&env), &Some(1000), &None);
+
+    env.ledger()
+        .with_mut(|li| li.timestamp += TRANSACTION_TIMEOUT_SECONDS + 100);
+    assert!(client.execute_transaction(&tx_id, &user));
+    assert_eq!(
+        client.get_transaction_status(&tx_id).unwrap(),
+        TransactionStatus::Committed
+    );
+}
+
+#[test]
+fn test_excessive_timeout_is_clamped() {
+    let (env, admin, user) = create_test_env();
+    let contract_id = create_test_contract(&env);
+    let client = TransactionCoordinatorClient::new(&env, &contract_id);
+    client.initialize(&admin);
+
+    let tx_id = client.create_transaction(&user, &single_step(&env), &Some(100_000), &None);
+
+    let transaction = client.get_transaction(&tx_id).unwrap();
+    assert_eq!(
+        transaction.deadline,
+        transaction.created_at + MAX_TRANSACTION_TIMEOUT_SECONDS
+    );
+
+    let default_id = client.create_transaction(&user, &single_step(&env), &None, &None);
+    let transaction = client.get_transaction(&default_id).unwrap();
+    assert_eq!(
+        transaction.deadline,
+        transaction.created_at + TRANSACTION_TIMEOUT_SECONDS
+    );
+}
+
+#[test]
+fn test_idempotency_key_returns_existing_transaction() {
+    let (env, admin, user) = create_test_env();
+    let contract_id = create_test_contract(&env);
+    let client = TransactionCoordinatorClient::new(&env, &contract_id);
+    client.initialize(&admin);
+
+    let key = BytesN::from_array(&env, &[7u8; 32]);
+    let first = client.create_transaction(&user, &single_step(&env), &None, &Some(key.clone()));
+    let retry = client.create_transaction(&user, &single_step(&env), &None, &Some(key.clone()));
+
+    assert_eq!(first, retry);
+    assert_eq!(client.get_tx_by_idempotency_key(&user, &key), Some(first));
+    // The retry must not have consumed a new ID
+    assert!(client.get_transaction(&(first + 1)).is_none());
+}
+
+#[test]
+fn test_distinct_idempotency_keys_create_distinct_transactions() {
+    let (env, admin, user) = create_test_env();
+    let contract_id = create_test_contract(&env);
+    let client = TransactionCoordinatorClient::new(&env, &contract_id);
+    client.initialize(&admin);
+
+    let key_a = BytesN::from_array(&env, &[1u8; 32]);
+    let key_b = BytesN::from_array(&env, &[2u8; 32]);
+    let tx_a = client.create_transaction(&user, &single_step(&env), &None, &Some(key_a.clone()));
+    let tx_b = client.create_transaction(&user, &single_step(&env), &None, &Some(key_b.clone()));
+
+    assert_ne!(tx_a, tx_b);
+    assert_eq!(client.get_tx_by_idempotency_key(&user, &key_a), Some(tx_a));
+    assert_eq!(client.get_tx_by_idempotency_key(&user, &key_b), Some(tx_b));
+    assert_eq!(
+        client.get_tx_by_idempotency_key(&user, &BytesN::from_array(&env, &[3u8; 32])),
+        None
+    );
+}
+
+#[test]
+fn test_idempotency_keys_are_scoped_per_initiator() {
+    let (env, admin, user) = create_test_env();
+    let contract_id = create_test_contract(&env);
+    let client = TransactionCoordinatorClient::new(&env, &contract_id);
+    client.initialize(&admin);
+
+    let other = Address::generate(&env);
+    let key = BytesN::from_array(&env, &[7u8; 32]);
+    let first = client.create_transaction(&user, &single_step(&env), &None, &Some(key.clone()));
+    let second = client.create_transaction(&other, &single_step(&env), &None, &Some(key.clone()));
+
+    // Reusing another initiator's key creates a fresh transaction
+    assert_ne!(first, second);
+    assert_eq!(client.get_transaction(&second).unwrap().initiator, other);
+    assert_eq!(client.get_tx_by_idempotency_key(&user, &key), Some(first));
+    assert_eq!(client.get_tx_by_idempotency_key(&other, &key), Some(second));
+}
+
+fn step_with_child(env: &Env, step_id: u32, sub_transaction: Option<u64>) -> TransactionStep {
+    let mut step = single_step(env).get(0).unwrap();
+    step.step_id = step_id;
+    step.sub_transaction = sub_transaction;
+    step
+}
+
+#[test]
+fn test_commit_failure_records_failing_step() {
+    let (env, admin, user) = create_test_env();
+    let contract_id = create_test_contract(&env);
+    let client = TransactionCoordinatorClient::new(&env, &contract_id);
+    client.initialize(&admin);
+
+    // Step 2 commits a child that will have expired by then
+    let child_id = client.create_transaction(&user, &single_step(&env), &Some(1), &None);
+    let steps = Vec::from_array(
+        &env,
+        [
+            step_with_child(&env, 1, None),
+            step_with_child(&env, 2, Some(child_id)),
+        ],
+    );
+    let tx_id = client.create_transaction(&user, &steps, &None, &None);
+    env.ledger().with_mut(|li| li.timestamp += 2);
+
+    assert!(!client.execute_transaction(&tx_id, &user));
+
+    let transaction = client.get_transaction(&tx_id).unwrap();
+    assert_eq!(transaction.status, TransactionStatus::RolledBack);
+    assert_eq!(
+        transaction.failure_reason,
+        Some(String::from_str(&env, "commit failed at step 2"))
+    );
+
+    let child = client.get_transaction(&child_id).unwrap();
+    assert_eq!(
+        child.failure_reason,
+        Some(String::from_str(&env, "Transaction timed out"))
+    );
+}
+
+#[test]
+fn test_prepare_failure_records_failing_step() {
+    let (env, admin, user) = create_test_env();
+    let contract_id = create_test_contract(&env);
+    let client = TransactionCoordinatorClient::new(&env, &contract_id);
+    client.initialize(&admin);
+
+    // Step 12 references a transaction that does not exist
+    let steps = Vec::from_array(&env, [step_with_child(&env, 12, Some(99))]);
+    let tx_id = client.create_transaction(&user, &steps, &None, &None);
+
+    assert!(!client.execute_transaction(&tx_id, &user));
+    assert_eq!(
+        client.get_transaction(&tx_id).unwrap().failure_reason,
+        Some(String::from_str(&env, "prepare failed at step 12"))
+    );
+}
+
+fn step_depending_on(env: &Env, step_id: u32, depends_on: Option<u32>) -> TransactionStep {
+    let mut step = step_with_child(env, step_id, None);
+    step.depends_on = depends_on;
+    step
+}
+
+/// Steps 2 and 3 fan out from 1 and step 4 joins back after 2, given out of order
+fn diamond_steps(env: &Env) -> Vec<TransactionStep> {
+    Vec::from_array(
+        env,
+        [
+            step_depending_on(env, 4, Some(2)),
+            step_depending_on(env, 3, Some(1)),
+            step_depending_on(env, 2, Some(1)),
+            step_depending_on(env, 1, None),
+        ],
+    )
+}
+
+#[test]
+fn test_execution_order_is_stored_at_creation() {
+    let (env, admin, user) = create_test_env();
+    let contract_id = create_test_contract(&env);
+    let client = TransactionCoordinatorClient::new(&env, &contract_id);
+    client.initialize(&admin);
+
+    let steps = diamond_steps(&env);
+    let tx_id = client.create_transaction(&user, &steps, &None, &None);
+
+    let stored = client.get_transaction(&tx_id).unwrap().execution_order;
+    assert_eq!(
+        stored,
+        AtomicTransactionUtils::resolve_execution_order(&env, &steps)
+    );
+    assert_eq!(stored, Vec::from_array(&env, [1u32, 3, 2, 4]));
+}
+
+#[test]
+fn test_execution_uses_stored_order() {
+    let (env, admin, user) = create_test_env();
+    let contract_id = create_test_contract(&env);
+    let client = TransactionCoordinatorClient::new(&env, &contract_id);
+    client.initialize(&admin);
+
+    let tx_id = client.create_transaction(&user, &diamond_steps(&env), &None, &None);
+
+    // Swap in another valid order; execution must follow it, not re-resolve
+    let custom_order = Vec::from_array(&env, [1u32, 2, 4, 3]);
+    env.as_contract(&contract_id, || {
+        let key = DataKey::Transaction(tx_id);
+        let mut transaction: AtomicTransaction = env.storage().instance().get(&key).unwrap();
+        transaction.execution_order = custom_order.clone();
+        env.storage().instance().set(&key, &transaction);
+    });
+
+    assert!(client.execute_transaction(&tx_id, &user));
+    let transaction = client.get_transaction(&tx_id).unwrap();
+    assert_eq!(transaction.prepared_steps, custom_order);
+    assert_eq!(transaction.executed_steps, custom_order);
+}
+
+#[test]
+fn test_get_transactions_by_initiator_lists_in_order() {
+    let (env, admin, user) = create_test_env();
+    let contract_id = create_test_contract(&env);
+    let client = TransactionCoordinatorClient::new(&env, &contract_id);
+    client.initialize(&admin);
+
+    let other = Address::generate(&env);
+    let first = client.create_transaction(&user, &single_step(&env), &None, &None);
+    let foreign = client.create_transaction(&other, &single_step(&env), &None, &None);
+    let second = client.create_transaction(&user, &single_step(&env), &None, &None);
+    let third = client.create_transaction(&user, &single_step(&env), &None, &None);
+
+    assert_eq!(
+        client.get_transactions_by_initiator(&user, &0, &10),
+        Vec::from_array(&env, [first, second, third])
+    );
+    assert_eq!(
+        client.get_transactions_by_initiator(&user, &1, &1),
+        Vec::from_array(&env, [second])
+    );
+    assert_eq!(
+        client.get_transactions_by_initiator(&other, &0, &10),
+        Vec::from_array(&env, [foreign])
+    );
+    assert_eq!(
+        client.get_transactions_by_initiator(&user, &5, &10).len(),
+        0
+    );
+}
+
+#[test]
+fn test_get_transactions_by_initiator_empty() {
+    let (env, admin, _user) = create_test_env();
+    let contract_id = create_test_contract(&env);
+    let client = TransactionCoordinatorClient::new(&env, &contract_id);
+    client.initialize(&admin);
+
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        client
+            .get_transactions_by_initiator(&stranger, &0, &10)
+            .len(),
+        0
+    );
+}
+
+fn independent_steps(env: &Env, count: u32) -> Vec<TransactionStep> {
+    let mut steps = Vec::new(env);
+    for step_id in 1..=count {
+        steps.push_back(step_with_child(env, step_id, None));
+    }
+    steps
+}
+
+#[test]
+fn test_transaction_at_max_steps_succeeds() {
+    let (env, admin, user) = create_test_env();
+    let contract_id = create_test_contract(&env);
+    let client = TransactionCoordinatorClient::new(&env, &contract_id);
+    client.initialize(&admin);
+    assert_eq!(client.get_max_steps(), stellai_lib::MAX_TRANSACTION_STEPS);
+
+    client.set_max_steps(&admin, &4);
+    let tx_id = client.create_transaction(&user, &independent_steps(&env, 4), &None, &None);
+    assert_eq!(client.get_transaction(&tx_id).unwrap().steps.len(), 4);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #10)")]
+fn test_transaction_over_max_steps_rejected() {
+    let (env, admin, user) = create_test_env();
+    let contract_id = create_test_contract(&env);
+    let client = TransactionCoordinatorClient::new(&env, &contract_id);
+    client.initialize(&admin);
+
+    client.set_max_steps(&admin, &4);
+    client.create_transaction(&user, &independent_steps(&env, 5), &None, &None);
+}
+
+#[test]
+fn test_raising_max_steps_allows_larger_transactions() {
+    let (env, admin, user) = create_test_env();
+    let contract_id = create_test_contract(&env);
+    let client = TransactionCoordinatorClient::new(&env, &contract_id);
+    client.initialize(&admin);
+
+    let steps = independent_steps(&env, stellai_lib::MAX_TRANSACTION_STEPS + 5);
+    assert!(client
+        .try_create_transaction(&user, &steps, &None, &None)
+        .is_err());
+
+    client.set_max_steps(&admin, &20);
+    let tx_id = client.create_transaction(&user, &steps, &None, &None);
+    assert!(client.execute_transaction(&tx_id, &user));
+
+    // Requests above the hard ceiling are clamped
+    client.set_max_steps(&admin, &1000);
+    assert_eq!(
+        client.get_max_steps(),
+        stellai_lib::MAX_TRANSACTION_STEPS_CEILING
+    );
+}
@@ -1,5 +1,7 @@
 use soroban_sdk::{Address, Env, String, Symbol, Vec};
-use stellai_lib::{AtomicTransaction, TransactionStatus, TransactionStep};
+use stellai_lib::{
+    atomic::AtomicTransactionUtils, AtomicTransaction, TransactionStatus, TransactionStep,
+};
 
 /// Atomic Agent Sale Workflow
 ///
@@ -44,6 +46,8 @@ impl AtomicAgentSaleWorkflow {
             rollback_args: Some(Vec::new(env)),
             executed: false,
             result: None,
+            sub_transaction: None,
+            capture_snapshot: false,
         };
         steps.push_back(step1);
 
@@ -59,6 +63,8 @@ impl AtomicAgentSaleWorkflow {
             rollback_args: Some(Vec::new(env)),
             executed: false,
             result: None,
+            sub_transaction: None,
+            capture_snapshot: false,
         };
         steps.push_back(step2);
 
@@ -74,6 +80,8 @@ impl AtomicAgentSaleWorkflow {
             rollback_args: Some(Vec::new(env)),
             executed: false,
             result: None,
+            sub_transaction: None,
+            capture_snapshot: false,
         };
         steps.push_back(step3);
 
@@ -89,6 +97,8 @@ impl AtomicAgentSaleWorkflow {
             rollback_args: Some(Vec::new(env)),
             executed: false,
             result: None,
+            sub_transaction: None,
+            capture_snapshot: false,
         };
         steps.push_back(step4);
 
@@ -104,12 +114,15 @@ impl AtomicAgentSaleWorkflow {
             rollback_args: None,
             executed: false,
             result: None,
+            sub_transaction: None,
+            capture_snapshot: false,
         };
         steps.push_back(step5);
 
         AtomicTransaction {
             transaction_id,
             initiator,
+            execution_order: AtomicTransactionUtils::resolve_execution_order(env, &steps),
             steps,
             status: TransactionStatus::Initiated,
             created_at: env.ledger().timestamp(),
@@ -148,6 +161,8 @@ impl AtomicAgentSaleWorkflow {
             rollback_args: Some(Vec::new(env)),
             executed: false,
             result: None,
+            sub_transaction: None,
+            capture_snapshot: false,
         };
         steps.push_back(step1);
 
@@ -163,6 +178,8 @@ impl AtomicAgentSaleWorkflow {
             rollback_args: Some(Vec::new(env)),
             executed: false,
             result: None,
+            sub_transaction: None,
+            capture_snapshot: false,
         };
         steps.push_back(step2);
 
@@ -178,6 +195,8 @@ impl AtomicAgentSaleWorkflow {
             rollback_args: Some(Vec::new(env)),
             executed: false,
             result: None,
+            sub_transaction: None,
+            capture_snapshot: false,
         };
         steps.push_back(step3);
 
@@ -194,6 +213,8 @@ impl AtomicAgentSaleWorkflow {
                 rollback_args: Some(Vec::new(env)),
                 executed: false,
                 result: None,
+                sub_transaction: None,
+                capture_snapshot: false,
             };
             steps.push_back(step4);
 
@@ -209,6 +230,8 @@ impl AtomicAgentSaleWorkflow {
                 rollback_args: None,
                 executed: false,
                 result: None,
+                sub_transaction: None,
+                capture_snapshot: false,
             };
             steps.push_back(step5);
         } else {
@@ -224,6 +247,8 @@ impl AtomicAgentSaleWorkflow {
                 rollback_args: None,
                 executed: false,
                 result: None,
+                sub_transaction: None,
+                capture_snapshot: false,
             };
             steps.push_back(step4);
         }
@@ -259,6 +284,8 @@ impl AtomicAgentSaleWorkflow {
             rollback_args: Some(Vec::new(env)),
             executed: false,
             result: None,
+            sub_transaction: None,
+            capture_snapshot: false,
         };
         steps.push_back(step1);
 
@@ -274,6 +301,8 @@ impl AtomicAgentSaleWorkflow {
             rollback_args: Some(Vec::new(env)),
             executed: false,
             result: None,
+            sub_transaction: None,
+            capture_snapshot: false,
         };
         steps.push_back(step2);
 
@@ -289,6 +318,8 @@ impl AtomicAgentSaleWorkflow {
             rollback_args: Some(Vec::new(env)),
             executed: false,
             result: None,
+            sub_transaction: None,
+            capture_snapshot: false,
         };
         steps.push_back(step3);
 
@@ -304,6 +335,8 @@ impl AtomicAgentSaleWorkflow {
             rollback_args: None,
             executed: false,
             result: None,
+            sub_transaction: None,
+            capture_snapshot: false,
         };
         steps.push_back(step4);
 
@@ -335,6 +368,8 @@ impl AtomicAgentSaleWorkflow {
             rollback_args: Some(Vec::new(env)),
             executed: false,
             result: None,
+            sub_transaction: None,
+            capture_snapshot: false,
         };
         steps.push_back(step1);
 
@@ -350,6 +385,8 @@ impl AtomicAgentSaleWorkflow {
             rollback_args: Some(Vec::new(env)),
             executed: false,
             result: None,
+            sub_transaction: None,
+            capture_snapshot: false,
         };
         steps.push_back(step2);
 
@@ -365,6 +402,8 @@ impl AtomicAgentSaleWorkflow {
             rollback_args: Some(Vec::new(env)),
             executed: false,
             result: None,
+            sub_transaction: None,
+            capture_snapshot: false,
         };
         steps.push_back(step3);
 
@@ -380,6 +419,8 @@ impl AtomicAgentSaleWorkflow {
             rollback_args: None,
             executed: false,
             result: None,
+            sub_transaction: None,
+            capture_snapshot: false,
         };
         steps.push_back(step4);
 
@@ -411,6 +452,8 @@ impl AtomicAgentSaleWorkflow {
             rollback_args: Some(Vec::new(env)),
             executed: false,
             result: None,
+            sub_transaction: None,
+            capture_snapshot: false,
         };
         steps.push_back(step1);
 
@@ -426,12 +469,15 @@ impl AtomicAgentSaleWorkflow {
             rollback_args: Some(Vec::new(env)),
             executed: false,
             result: None,
+            sub_transaction: None,
+            capture_snapshot: false,
         };
         steps.push_back(step2);
 
         AtomicTransaction {
             transaction_id,
             initiator,
+            execution_order: AtomicTransactionUtils::resolve_execution_order(env, &steps),
             steps,
             status: TransactionStatus::Initiated,
             created_at: env.ledger().timestamp(),
@@ -466,6 +512,8 @@ impl AtomicAgentSaleWorkflow {
             rollback_args: Some(Vec::new(env)),
             executed: false,
             result: None,
+            sub_transaction: None,
+            capture_snapshot: false,
         };
         steps.push_back(step1);
 
@@ -481,6 +529,8 @@ impl AtomicAgentSaleWorkflow {
             rollback_args: Some(Vec::new(env)),
             executed: false,
             result: None,
+            sub_transaction: None,
+            capture_snapshot: false,
         };
         steps.push_back(step2);
 
@@ -496,12 +546,15 @@ impl AtomicAgentSaleWorkflow {
             rollback_args: Some(Vec::new(env)),
             executed: false,
             result: None,
+            sub_transaction: None,
+            capture_snapshot: false,
         };
         steps.push_back(step3);
 
         AtomicTransaction {
             transaction_id,
             initiator,
+            execution_order: AtomicTransactionUtils::resolve_execution_order(env, &steps),
             steps,
             status: TransactionStatus::Initiated,
             created_at: env.ledger().timestamp(),
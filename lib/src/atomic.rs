@@ -46,11 +46,12 @@ pub struct AtomicTransactionUtils;
 impl AtomicTransactionUtils {
     /// Validate transaction structure and dependencies
     pub fn validate_transaction(transaction: &AtomicTransaction) -> Result<(), &'static str> {
-        if transaction.steps.len() == 0 {
+        if transaction.steps.is_empty() {
             return Err("Transaction must have at least one step");
         }
 
-        if transaction.steps.len() > crate::MAX_TRANSACTION_STEPS as u32 {
+        // Contracts enforce their own configured cap, at most this ceiling
+        if transaction.steps.len() > crate::MAX_TRANSACTION_STEPS_CEILING {
             return Err("Too many transaction steps");
         }
 
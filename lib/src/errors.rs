@@ -25,4 +25,10 @@ pub enum ContractError {
     OracleError = 15,
     RateLimitExceeded = 16,
     InvalidRoyaltyFee = 17,
+    AgentEscrowLocked = 18,
+    StaleNonce = 19,
+    NonSequentialNonce = 20,
+    OperatorExpired = 21,
+    AgentPaused = 22,
+    ContractPaused = 23,
 }
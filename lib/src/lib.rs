@@ -4,7 +4,7 @@ pub mod audit;
 pub mod audit_helpers;
 pub mod errors;
 
-use soroban_sdk::{contracttype, symbol_short, Address, Bytes, String, Symbol, Val, Vec};
+use soroban_sdk::{contracttype, symbol_short, Address, Bytes, BytesN, String, Symbol, Val, Vec};
 
 /// Oracle data entry
 #[derive(Clone, Debug)]
@@ -74,6 +74,8 @@ pub struct EvolutionRequest {
     pub agent_id: u64,
     pub owner: Address,
     pub stake_amount: i128,
+    /// Token the stake was escrowed in (None if nothing was escrowed)
+    pub stake_token: Option<Address>,
     pub status: EvolutionStatus,
     pub created_at: u64,
     pub completed_at: Option<u64>,
@@ -87,6 +89,7 @@ pub enum EvolutionStatus {
     InProgress = 1,
     Completed = 2,
     Failed = 3,
+    Cancelled = 4,
 }
 
 /// Royalty information for marketplace transactions
@@ -148,7 +151,17 @@ pub struct Auction {
     pub start_time: u64,
     pub end_time: u64,
     pub min_bid_increment_bps: u32,
+    /// Absolute minimum bid increment in token units (floor for the bps increment)
+    pub min_increment_absolute: i128,
+    /// Oracle feed key that prices a Dutch auction instead of the linear curve
+    pub oracle_key: Option<Symbol>,
     pub status: AuctionStatus,
+    /// Number of times a late bid has pushed back `end_time`
+    pub extension_count: u32,
+    /// Non-refundable fee charged on every English auction bid
+    pub bid_fee: i128,
+    /// Bid fees collected so far, paid to the seller on resolution
+    pub accumulated_bid_fees: i128,
     // pub dutch_config: Option<DutchAuctionConfig>, // Temporarily commented out
 }
 
@@ -172,6 +185,7 @@ pub enum ApprovalStatus {
     Rejected = 2,
     Expired = 3,
     Executed = 4,
+    Withdrawn = 5,
 }
 
 /// Multi-signature approval for high-value agent sales
@@ -204,10 +218,15 @@ pub struct ApprovalHistory {
     pub reason: Option<String>,
 }
 
+/// Oracle attestation that an evolution request has been completed off-chain
+#[derive(Clone, Debug)]
+#[contracttype]
 pub struct EvolutionAttestation {
     pub request_id: u64,
     pub agent_id: u64,
     pub oracle_provider: Address,
+    /// Ed25519 key the oracle signed the attestation digest with
+    pub oracle_pubkey: BytesN<32>,
     pub new_model_hash: String,
     pub attestation_data: Bytes,
     pub signature: Bytes,
@@ -220,11 +239,13 @@ pub struct EvolutionAttestation {
 pub const ADMIN_KEY: &str = "admin";
 pub const MAX_STRING_LENGTH: u32 = 256;
 pub const MAX_ROYALTY_FEE: u32 = 10000;
+pub const DEFAULT_MAX_ROYALTY_BPS: u32 = 1000; // 10% until an admin changes the cap
 pub const MAX_DATA_SIZE: u32 = 65536;
 pub const MAX_HISTORY_SIZE: u32 = 1000;
 pub const MAX_HISTORY_QUERY_LIMIT: u32 = 500;
 pub const DEFAULT_RATE_LIMIT_OPERATIONS: u32 = 100;
 pub const DEFAULT_RATE_LIMIT_WINDOW_SECONDS: u64 = 60;
+pub const DEFAULT_MAX_OPERATOR_DURATION_SECONDS: u64 = 90 * 86400; // 90 days
 pub const MAX_CAPABILITIES: usize = 32;
 pub const MAX_ROYALTY_PERCENTAGE: u32 = 10000; // 100%
 pub const MIN_ROYALTY_PERCENTAGE: u32 = 0;
@@ -250,6 +271,7 @@ pub const AGENT_COUNTER_KEY: &str = "agent_counter";
 pub const AGENT_KEY_PREFIX: &str = "agent_";
 pub const AGENT_LEASE_STATUS_PREFIX: &str = "agent_lease_";
 pub const APPROVED_MINTERS_KEY: &str = "approved_minters";
+pub const APPROVED_ESCROWS_KEY: &str = "approved_escrows";
 pub const IMPLEMENTATION_KEY: Symbol = symbol_short!("impl_key");
 pub const UPGRADE_HISTORY_KEY: Symbol = symbol_short!("up_hist");
 pub const IS_PAUSED_KEY: Symbol = symbol_short!("is_paused");
@@ -269,7 +291,7 @@ pub const DEFAULT_APPROVAL_TTL_SECONDS: u64 = 604800; // 7 days
 // ---------------------------------------------------------------------------
 
 /// State of a lease in its lifecycle.
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[contracttype]
 #[repr(u32)]
 pub enum LeaseState {
@@ -303,6 +325,10 @@ pub struct LeaseData {
     pub status: LeaseState,
     /// If status == ExtensionRequested, the pending extension id.
     pub pending_extension_id: Option<u64>,
+    /// Streaming mode: the contract holds `total_value` and the lessor claims it as it vests.
+    pub streaming: bool,
+    /// Portion of `total_value` already paid out to the lessor in streaming mode.
+    pub claimed_amount: i128,
 }
 
 /// A request to extend an active lease by additional duration.
@@ -328,10 +354,22 @@ pub struct LeaseHistoryEntry {
     pub details: Option<String>,
 }
 
+/// Marketplace-wide lease configuration.
+#[derive(Clone)]
+#[contracttype]
+pub struct LeaseConfig {
+    /// Deposit taken on top of the lease value, in bps of `total_value`.
+    pub deposit_bps: u32,
+    /// Penalty on early termination, in bps of the remaining (unused) value.
+    pub early_termination_penalty_bps: u32,
+}
+
 // Lease config: basis points (bps). 1000 bps = 10%.
 pub const DEFAULT_LEASE_DEPOSIT_BPS: u32 = 1000; // 10% of lease value
 pub const DEFAULT_EARLY_TERMINATION_PENALTY_BPS: u32 = 2000; // 20% of remaining value
+pub const DEFAULT_MAX_LEASE_DEDUCTION_BPS: u32 = 5000; // 50% of the deposit for damage
 pub const LEASE_EXTENSION_REQUEST_TTL_SECONDS: u64 = 604_800; // 7 days
+pub const LEASE_EXTENSION_GRACE_PERIOD_SECONDS: u64 = 259_200; // 3 days after TTL to renew
 
 // ---------------------------------------------------------------------------
 // Atomic Transaction Framework
@@ -367,6 +405,8 @@ pub struct TransactionStep {
     pub rollback_args: Option<Vec<Val>>,
     pub executed: bool,
     pub result: Option<String>, // Store result as string for dependent steps
+    pub sub_transaction: Option<u64>, // Commit executes this nested transaction atomically
+    pub capture_snapshot: bool, // Prepare journals the contract's `state_snapshot()` output
 }
 
 /// Atomic transaction containing multiple coordinated steps
@@ -376,9 +416,10 @@ pub struct AtomicTransaction {
     pub transaction_id: u64,
     pub initiator: Address,
     pub steps: Vec<TransactionStep>,
+    pub execution_order: Vec<u32>, // Dependency-resolved step order, fixed at creation
     pub status: TransactionStatus,
     pub created_at: u64,
-    pub deadline: u64,            // 5-minute timeout
+    pub deadline: u64,            // created_at + timeout (default 5 minutes)
     pub prepared_steps: Vec<u32>, // Track which steps are prepared
     pub executed_steps: Vec<u32>, // Track execution order for rollback
     pub failure_reason: Option<String>,
@@ -410,7 +451,10 @@ pub struct TransactionEvent {
 
 // Transaction constants
 pub const TRANSACTION_TIMEOUT_SECONDS: u64 = 300; // 5 minutes
-pub const MAX_TRANSACTION_STEPS: u32 = 10; // Prevent DoS
+pub const MAX_TRANSACTION_TIMEOUT_SECONDS: u64 = 3600; // 1 hour cap on custom timeouts
+pub const MAX_SUB_TRANSACTION_DEPTH: u32 = 3; // Nesting limit for sub-transaction steps
+pub const MAX_TRANSACTION_STEPS: u32 = 10; // Prevent DoS (default per-transaction cap)
+pub const MAX_TRANSACTION_STEPS_CEILING: u32 = 30; // Upper bound for configured step caps
 pub const TRANSACTION_COUNTER_KEY: &str = "tx_counter";
 pub const TRANSACTION_KEY_PREFIX: &str = "tx_";
 pub const TRANSACTION_JOURNAL_KEY_PREFIX: &str = "tx_journal_";